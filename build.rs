@@ -1,8 +1,50 @@
 use std::io::Result;
 
+/// The messages of a verbose log, which get serde support in their proto3 JSON form. Nested
+/// messages (`Platform.Property`) match through their parent.
+const SERDE_MESSAGES: &[&str] = &[
+    ".tools.protos.Digest",
+    ".tools.protos.File",
+    ".tools.protos.EnvironmentVariable",
+    ".tools.protos.Platform",
+    ".tools.protos.SpawnMetrics",
+    ".tools.protos.SpawnExec",
+];
+
+/// `google.protobuf.Duration` fields of `SpawnMetrics`, which prost-types cannot serialize.
+const DURATION_FIELDS: &[&str] = &[
+    "total_time",
+    "parse_time",
+    "network_time",
+    "fetch_time",
+    "queue_time",
+    "setup_time",
+    "upload_time",
+    "execution_wall_time",
+    "process_outputs_time",
+    "retry_time",
+    "time_limit",
+];
+
 fn main() -> Result<()> {
     // Configure prost to generate basic protobuf support
     let mut config = prost_build::Config::new();
+    for message in SERDE_MESSAGES {
+        config.type_attribute(
+            message,
+            "#[derive(serde::Serialize, serde::Deserialize)] #[serde(rename_all = \"camelCase\", default)]",
+        );
+    }
+    for field in DURATION_FIELDS {
+        config.field_attribute(
+            format!(".tools.protos.SpawnMetrics.{}", field),
+            "#[serde(with = \"crate::proto::json::duration\", skip_serializing_if = \"Option::is_none\")]",
+        );
+    }
+    config.field_attribute(
+        ".tools.protos.SpawnMetrics.start_time",
+        "#[serde(with = \"crate::proto::json::timestamp\", skip_serializing_if = \"Option::is_none\")]",
+    );
     config.compile_protos(&["spawn.proto"], &["."])?;
     
     println!("cargo:rerun-if-changed=spawn.proto");
@@ -0,0 +1,24 @@
+#![no_main]
+
+use bzl_exec_log_parser::parsers::{parse_compact_bytes, ParseLimits, ParsedLog};
+use libfuzzer_sys::fuzz_target;
+
+/// Well above what fuzz inputs hold, but low enough that a zstd bomb stays within the fuzzer's
+/// memory limit.
+const LIMITS: ParseLimits = ParseLimits {
+    max_entries: None,
+    max_entry_bytes: None,
+    max_decompressed_bytes: Some(64 << 20),
+    max_memory_bytes: Some(256 << 20),
+};
+
+fuzz_target!(|data: &[u8]| {
+    // Random bytes are rarely a valid zstd frame, so the input is also compressed to reach the
+    // entry parser and the reconstruction of spawns from it.
+    let _ = parse_compact_bytes(data, &LIMITS);
+    let compressed = zstd::encode_all(data, 1).expect("compressing in memory does not fail");
+    let parsed = parse_compact_bytes(&compressed, &LIMITS);
+    if let Ok(ParsedLog { error: Some(error), .. }) = parsed {
+        assert!(error.offset <= data.len());
+    }
+});
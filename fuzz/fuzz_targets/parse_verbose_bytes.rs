@@ -0,0 +1,20 @@
+#![no_main]
+
+use bzl_exec_log_parser::parsers::{parse_verbose_bytes, ParseLimits, ParsedLog};
+use libfuzzer_sys::fuzz_target;
+
+/// Well above what fuzz inputs hold, but low enough that a zstd bomb stays within the fuzzer's
+/// memory limit.
+const LIMITS: ParseLimits = ParseLimits {
+    max_entries: None,
+    max_entry_bytes: None,
+    max_decompressed_bytes: Some(64 << 20),
+    max_memory_bytes: Some(256 << 20),
+};
+
+fuzz_target!(|data: &[u8]| {
+    let parsed = parse_verbose_bytes(data, &LIMITS);
+    if let Ok(ParsedLog { error: Some(error), .. }) = parsed {
+        assert!(error.offset <= data.len());
+    }
+});
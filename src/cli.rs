@@ -1,15 +1,110 @@
-use clap::Parser;
+use crate::render::{NumberFormat, OLDEST_REPORT_FORMAT_VERSION, REPORT_FORMAT_VERSION};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::fmt;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "bzl-exec-log-analyzer")]
 #[command(about = "Analyzes Bazel execution logs to extract performance metrics")]
 #[command(version)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+// Lets flags given on the command line replace those of a saved query (`--run`).
+#[command(args_override_self = true)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Path to the Bazel execution log file (auto-detects format)
-    #[arg(help = "Path to the Bazel execution log file")]
-    pub file: PathBuf,
+    #[arg(help = "Path to the Bazel execution log file", required_unless_present_any = ["list_reports", "emit_schema"])]
+    pub file: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub reports: ReportArgs,
+
+    /// List the report names accepted by --only and exit
+    #[arg(long)]
+    pub list_reports: bool,
+
+    /// Also write the hermeticity findings as SARIF to this file, for code-scanning annotations
+    #[arg(long, value_name = "FILE")]
+    pub sarif: Option<PathBuf>,
+
+    /// Triage a broken build: lead with the failed spawns in full and restrict every report to
+    /// them and the other spawns of their targets
+    #[arg(long)]
+    pub failures_only: bool,
 
+    #[command(flatten)]
+    pub filters: FilterArgs,
+
+    /// Print the JSON Schema of the `--output json` document and exit
+    #[arg(long)]
+    pub emit_schema: bool,
+
+    /// Structure version of json, yaml, msgpack, cbor and csv output; the previous version stays
+    /// supported so consumers can upgrade at their own pace
+    #[arg(long, default_value_t = REPORT_FORMAT_VERSION, value_parser = format_version_parser())]
+    pub format_version: u32,
+
+    /// Output format of the report
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// Print only a one-line summary with status icons, for CI status descriptions and chat
+    /// messages
+    #[arg(long, conflicts_with = "output")]
+    pub summary_line: bool,
+
+    /// Write the report straight to the terminal instead of through $PAGER (or `less -R`) when
+    /// it is taller than the terminal
+    #[arg(long)]
+    pub no_pager: bool,
+
+    /// Run a saved query from the configuration file; flags given here override its flags
+    #[arg(long, value_name = "NAME")]
+    pub run: Option<String>,
+
+    /// Configuration file with saved queries [default: .bzl-exec-log-analyzer.toml in the
+    /// working directory]
+    #[arg(long, value_name = "FILE")]
+    pub config_file: Option<PathBuf>,
+
+    /// Thousands and decimal separators of the formats read by people: a locale such as `de_DE`,
+    /// `auto` to follow LC_ALL, LC_NUMERIC or LANG, or `none` for ungrouped numbers
+    #[arg(long, global = true, value_name = "LOCALE", default_value = "en", value_parser = number_locale)]
+    pub number_locale: NumberFormat,
+
+    /// Write only ASCII characters, replacing bars, icons and non-ASCII labels, for CI log
+    /// processors that mangle anything else
+    #[arg(long, global = true)]
+    pub ascii: bool,
+}
+
+fn number_locale(value: &str) -> Result<NumberFormat, String> {
+    if value == "auto" {
+        return Ok(NumberFormat::from_environment());
+    }
+    NumberFormat::for_locale(value)
+        .ok_or_else(|| format!("unknown locale '{}'; use e.g. en_US, de_DE, fr_FR, auto or none", value))
+}
+
+/// The runner string Bazel uses for remote cache hits.
+pub const DEFAULT_CACHE_HIT_RUNNERS: &[&str] = &["remote cache hit"];
+
+/// Mnemonics of the trivial filesystem actions Bazel runs to lay out outputs and runfiles.
+pub const DEFAULT_BOOKKEEPING_MNEMONICS: &[&str] = &[
+    "SymlinkTree",
+    "Middleman",
+    "FileWrite",
+    "SourceSymlinkManifest",
+    "TemplateExpand",
+    "ExecutableSymlink",
+];
+
+/// Report selection and tuning flags, shared by the analysis and the exports built from it.
+#[derive(Args)]
+pub struct ReportArgs {
     /// Number of slowest actions to display in the report
     #[arg(short, long, default_value_t = 10)]
     pub top_n: usize,
@@ -18,6 +113,15 @@ pub struct Cli {
     #[arg(long, default_value_t = true)]
     pub cache_metrics: bool,
 
+    /// Runner string Bazel records for remote cache hits (repeatable); set it for Bazel versions
+    /// and forks that word it differently
+    #[arg(
+        long = "cache-hit-runner",
+        value_name = "RUNNER",
+        default_values = DEFAULT_CACHE_HIT_RUNNERS.iter().copied()
+    )]
+    pub cache_hit_runners: Vec<String>,
+
     /// Display a detailed breakdown of action phase timings for slowest actions
     #[arg(long)]
     pub phase_timings: bool,
@@ -26,7 +130,7 @@ pub struct Cli {
     #[arg(long)]
     pub input_analysis: bool,
 
-    /// Display a report on actions that failed or were retried
+    /// Display a report on actions that failed or were retried, and on the first failure's aftermath
     #[arg(long)]
     pub retries: bool,
 
@@ -38,7 +142,7 @@ pub struct Cli {
     #[arg(long)]
     pub output_analysis: bool,
 
-    /// Display a report on actions with the highest memory usage relative to their limit
+    /// Display actions with the highest memory usage relative to their limit and the headroom per mnemonic
     #[arg(long)]
     pub memory_analysis: bool,
 
@@ -49,4 +153,796 @@ pub struct Cli {
     /// Display a report on actions with the longest queue times
     #[arg(long)]
     pub queue_analysis: bool,
-}
\ No newline at end of file
+
+    /// Target 95th percentile remote queue time for the executor pool estimate of --queue-analysis,
+    /// in seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = 1.0)]
+    pub queue_target: f64,
+
+    /// Display a report on remotable mnemonics that fell back to local execution
+    #[arg(long)]
+    pub remote_fallback: bool,
+
+    /// Display per-worker action counts and durations to spot slow or overloaded executors
+    #[arg(long)]
+    pub worker_skew: bool,
+
+    /// Display a report on the container images (`container-image` platform property) used by spawns
+    #[arg(long)]
+    pub container_images: bool,
+
+    /// Display action counts and queue times per execution pool (`Pool` platform property)
+    #[arg(long)]
+    pub pool_utilization: bool,
+
+    /// Display a report on command lines: longest ones, common executables and per-mnemonic patterns
+    #[arg(long)]
+    pub command_lines: bool,
+
+    /// Display a report on environment sizes per action and mnemonic, the largest variables and likely secrets
+    #[arg(long)]
+    pub environment_size: bool,
+
+    /// Display peak and average parallelism, job-limit plateaus and --jobs recommendations
+    #[arg(long)]
+    pub concurrency: bool,
+
+    /// Display the time before the first action (with --bep) and idle gaps between actions
+    #[arg(long)]
+    pub start_latency: bool,
+
+    /// Display actions killed by a signal (137 SIGKILL/OOM, 139 SIGSEGV, ...) with their memory estimates and limits
+    #[arg(long)]
+    pub signals: bool,
+
+    /// Display the cache hit rate over time, bucketed by action start time
+    #[arg(long)]
+    pub cache_timeline: bool,
+
+    /// Width of the time buckets used by --cache-timeline, in seconds
+    #[arg(long, default_value_t = 60)]
+    pub bucket_seconds: u64,
+
+    /// Estimate the download volume `--remote_download_toplevel`/`minimal` would have avoided
+    #[arg(long)]
+    pub download_savings: bool,
+
+    /// Display hermeticity findings: absolute paths in command lines and unsandboxed local actions
+    #[arg(long)]
+    pub hermeticity: bool,
+
+    /// Display spawns per build configuration and actions built under more than one configuration
+    #[arg(long)]
+    pub configurations: bool,
+
+    /// Display how much build time goes to IDE and lint aspects and exec-configuration tools
+    #[arg(long)]
+    pub attribution: bool,
+
+    /// Display a per-mnemonic execution strategy recommendation (remote, worker, local-sandbox)
+    #[arg(long)]
+    pub strategies: bool,
+
+    /// Display configured spawn timeouts by mnemonic against actual execution times
+    #[arg(long)]
+    pub timeouts: bool,
+
+    /// Display actions that read workspace status (stamping) and the rebuild cascade they cause
+    #[arg(long)]
+    pub stamping: bool,
+
+    /// Display targets with an unusually high number of spawns (retries, shards, coverage)
+    #[arg(long)]
+    pub fan_out: bool,
+
+    /// Display coverage post-processing spawns and their share of the build
+    #[arg(long)]
+    pub coverage: bool,
+
+    /// Display a heatmap of execution time per mnemonic across the build timeline
+    #[arg(long)]
+    pub heatmap: bool,
+
+    /// Display how few of the slowest actions account for most of the execution time
+    #[arg(long)]
+    pub time_concentration: bool,
+
+    /// Display actions with the longest output fetch times and the most bytes fetched
+    #[arg(long)]
+    pub fetch_analysis: bool,
+
+    /// Display actions whose input staging and sandbox setup rival their execution time
+    #[arg(long)]
+    pub setup_hotspots: bool,
+
+    /// Display actions and time per mnemonic within each package
+    #[arg(long)]
+    pub package_mnemonics: bool,
+
+    /// Roll packages up to this many directory levels in the package report, e.g. 1 counts
+    /// //third_party/protobuf under //third_party
+    #[arg(long, value_name = "LEVELS")]
+    pub package_depth: Option<usize>,
+
+    /// Display build cost per rule class, inferred from mnemonics and output paths
+    #[arg(long)]
+    pub rule_classes: bool,
+
+    /// Display an audit of genrule and shell actions: cost, cacheability and non-hermetic commands
+    #[arg(long)]
+    pub genrule_audit: bool,
+
+    /// Display mnemonics with very many short actions and the fixed overhead they pay per action
+    #[arg(long)]
+    pub action_explosion: bool,
+
+    /// Display spawns without a target label, grouped by their outputs, and the time they leave
+    /// unattributed
+    #[arg(long)]
+    pub unlabeled: bool,
+
+    /// Bazel JSON trace profile (`--profile`, optionally gzipped) to join with the log by target
+    #[arg(long, value_name = "FILE")]
+    pub profile: Option<PathBuf>,
+
+    /// Build Event Protocol JSON file (`--build_event_json_file`) of the same invocation
+    #[arg(long, value_name = "FILE")]
+    pub bep: Option<PathBuf>,
+
+    /// CSV of server-side timings per action digest from the remote execution backend
+    #[arg(long, value_name = "CSV")]
+    pub server_timings: Option<PathBuf>,
+
+    /// Run only the named report (repeatable); ignores the other report flags
+    #[arg(long, value_name = "REPORT")]
+    pub only: Vec<String>,
+}
+
+/// Transformations and filters applied to spawns before any report runs.
+#[derive(Args)]
+pub struct FilterArgs {
+    /// Keep paths as recorded instead of turning backslashes into slashes and folding the host CPU
+    /// of `bazel-out/<cpu>-<mode>` directories into its architecture
+    #[arg(long)]
+    pub raw_paths: bool,
+
+    /// Leave coverage post-processing spawns (coverage report, lcov merging) out of every report
+    #[arg(long)]
+    pub exclude_coverage: bool,
+
+    /// Rewrite bzlmod canonical repository names in labels (`@@rules_foo~1.2//...`) to apparent
+    /// names (`@rules_foo//...`), so one repository is not split across rows
+    #[arg(long)]
+    pub canonicalize_labels: bool,
+
+    /// Rename a repository in labels, after canonicalization (repeatable)
+    #[arg(long, value_name = "FROM=TO", value_parser = parse_repo_mapping)]
+    pub map_repo: Vec<(String, String)>,
+
+    /// Keep the bookkeeping mnemonics (see --bookkeeping-mnemonics) in the reports
+    #[arg(long)]
+    pub include_bookkeeping: bool,
+
+    /// Mnemonics of trivial filesystem actions left out of the reports unless
+    /// --include-bookkeeping is given
+    #[arg(
+        long,
+        value_name = "MNEMONICS",
+        value_delimiter = ',',
+        default_values = DEFAULT_BOOKKEEPING_MNEMONICS.iter().copied()
+    )]
+    pub bookkeeping_mnemonics: Vec<String>,
+
+    /// Leave out spawns that took less than this in total, e.g. `5ms`, `1.5s` or `2m` (a bare
+    /// number is seconds)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub min_duration: Option<Duration>,
+
+    /// Leave out spawns whose inputs and outputs together are smaller than this many bytes
+    #[arg(long, value_name = "BYTES")]
+    pub min_bytes: Option<i64>,
+
+    /// Keep only spawns that ran on these kinds of runner (repeatable, comma-separated)
+    #[arg(long, value_name = "KIND", value_delimiter = ',')]
+    pub runner: Vec<RunnerKind>,
+
+    /// Leave out spawns that ran on these kinds of runner (repeatable, comma-separated)
+    #[arg(long, value_name = "KIND", value_delimiter = ',')]
+    pub exclude_runner: Vec<RunnerKind>,
+
+    /// Only keep spawns with these mnemonics (repeatable or comma-separated)
+    #[arg(long, value_name = "MNEMONIC", value_delimiter = ',')]
+    pub mnemonic: Vec<String>,
+
+    /// Keep only spawns whose status matches this glob, e.g. `NON_ZERO_EXIT` or `*TIMEOUT*`
+    /// (case-insensitive; successful spawns have the status `SUCCESS`)
+    #[arg(long, value_name = "GLOB")]
+    pub status: Option<String>,
+
+    /// Keep only spawns that exited with one of these codes (repeatable, comma-separated), e.g.
+    /// 137 for processes killed by the OOM killer
+    #[arg(long, value_name = "CODE", value_delimiter = ',', allow_negative_numbers = true)]
+    pub exit_code: Vec<i32>,
+
+    /// Keep only spawns whose outputs came from a cache
+    #[arg(long, conflicts_with = "misses_only")]
+    pub hits_only: bool,
+
+    /// Keep only spawns that missed the cache and executed, e.g. to see which inputs matter for
+    /// execution cost
+    #[arg(long)]
+    pub misses_only: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Compare two execution logs of the same build
+    Diff(DiffArgs),
+    /// Compare two groups of logs as the arms of an experiment, per mnemonic with confidence intervals
+    Ab(AbArgs),
+    /// Show every recorded detail of the actions matching a pattern
+    Show(ShowArgs),
+    /// Export the spawns of a log for loading into other tools
+    Export(ExportArgs),
+    /// Follow a log while Bazel writes it, with a live summary of the build so far
+    Watch(WatchArgs),
+    /// Suggest .bazelrc flags backed by evidence from the log, as comments to review
+    SuggestRc(SuggestRcArgs),
+    /// Print just the key numbers of a log (counts, hit rates, duration percentiles, bytes)
+    Stats(StatsArgs),
+    /// Analyze a directory of execution logs from many builds
+    Corpus(CorpusArgs),
+    /// Check that a log is readable and complete, and how to fix the build flags if it is not
+    Doctor(DoctorArgs),
+    /// Rewrite a log in the other format, verbose to compact or compact to verbose
+    Convert(ConvertArgs),
+    /// Merge several logs into one, e.g. the attempts of a retried CI job
+    Merge(MergeArgs),
+    /// Split a log into one smaller log per package or mnemonic
+    Split(SplitArgs),
+    /// Write the analysis report as text, JSON, HTML, CSV and a Markdown summary in one go
+    Report(Box<BundleArgs>),
+    /// Run a SQL query over the spawns, outputs and environment variables of a log
+    #[cfg(feature = "sql")]
+    Sql(SqlArgs),
+}
+
+#[derive(Args)]
+pub struct DiffArgs {
+    /// Path to the baseline execution log
+    pub old: PathBuf,
+
+    /// Path to the execution log to compare against the baseline
+    pub new: PathBuf,
+
+    /// Number of rows to display in each section of the diff
+    #[arg(short, long, default_value_t = 10)]
+    pub top_n: usize,
+
+    /// Match actions by their paths as recorded, without normalizing separators and `bazel-out`
+    /// directories (see the report's --raw-paths)
+    #[arg(long)]
+    pub raw_paths: bool,
+
+    /// Only report actions whose time changed by more than this, either a duration such as
+    /// `100ms` or a percentage of the baseline time such as `10%` (repeatable; every floor given
+    /// must be exceeded)
+    #[arg(long, value_name = "DURATION|PERCENT", value_parser = parse_diff_threshold)]
+    pub diff_threshold: Vec<DiffThreshold>,
+
+    /// Structure version of json, yaml, msgpack, cbor and csv output; the previous version stays
+    /// supported so consumers can upgrade at their own pace
+    #[arg(long, default_value_t = REPORT_FORMAT_VERSION, value_parser = format_version_parser())]
+    pub format_version: u32,
+
+    /// Output format of the diff
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct AbArgs {
+    /// Execution logs of the baseline arm, at least two
+    #[arg(value_name = "A_LOGS", required = true, num_args = 2..)]
+    pub a: Vec<PathBuf>,
+
+    /// Execution logs of the experiment arm, at least two, after `--`
+    #[arg(value_name = "B_LOGS", last = true, required = true, num_args = 2..)]
+    pub b: Vec<PathBuf>,
+
+    /// Number of mnemonics to display
+    #[arg(short, long, default_value_t = 20)]
+    pub top_n: usize,
+
+    /// Structure version of json, yaml, msgpack, cbor and csv output; the previous version stays
+    /// supported so consumers can upgrade at their own pace
+    #[arg(long, default_value_t = REPORT_FORMAT_VERSION, value_parser = format_version_parser())]
+    pub format_version: u32,
+
+    /// Output format of the comparison
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct ShowArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// Substring of the target label, an output path or the action digest, or a spawn ID to look for
+    pub pattern: String,
+
+    /// Maximum number of matching actions to display
+    #[arg(long, default_value_t = 5)]
+    pub limit: usize,
+
+    /// Structure version of json, yaml, msgpack, cbor and csv output; the previous version stays
+    /// supported so consumers can upgrade at their own pace
+    #[arg(long, default_value_t = REPORT_FORMAT_VERSION, value_parser = format_version_parser())]
+    pub format_version: u32,
+
+    /// Output format of the details
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct WatchArgs {
+    /// Path to the execution log Bazel is writing (compact or verbose)
+    pub file: PathBuf,
+
+    /// Seconds between refreshes
+    #[arg(long, default_value_t = 2.0)]
+    pub interval: f64,
+
+    /// Number of mnemonics to display (with --delta, of the slowest newly executed actions)
+    #[arg(short, long, default_value_t = 10)]
+    pub top_n: usize,
+
+    /// Print only what changed since the previous refresh as plain lines instead of drawing a
+    /// screen, for CI logs and other non-terminal output
+    #[arg(long)]
+    pub delta: bool,
+}
+
+#[derive(Args)]
+pub struct ResourcesArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// File to write the estimates to (defaults to stdout)
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct CorpusArgs {
+    #[command(subcommand)]
+    pub analysis: CorpusAnalysis,
+}
+
+#[derive(Subcommand)]
+pub enum CorpusAnalysis {
+    /// How many builds hit each action cache entry, and how many cache writes were never read
+    Reuse(LogsDirArgs),
+    /// Which output paths change digest from build to build and which stay stable
+    Churn(LogsDirArgs),
+    /// How much action times vary across repeated builds of the same commit, and which actions vary most
+    Noise(LogsDirArgs),
+}
+
+#[derive(Args)]
+pub struct LogsDirArgs {
+    /// Directory of execution logs, one per build; file names must sort in build order
+    #[arg(long)]
+    pub logs_dir: PathBuf,
+
+    /// Number of rows to display in each table
+    #[arg(short, long, default_value_t = 10)]
+    pub top_n: usize,
+
+    /// Structure version of json, yaml, msgpack, cbor and csv output; the previous version stays
+    /// supported so consumers can upgrade at their own pace
+    #[arg(long, default_value_t = REPORT_FORMAT_VERSION, value_parser = format_version_parser())]
+    pub format_version: u32,
+
+    /// Output format of the report
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+}
+
+#[cfg(feature = "sql")]
+#[derive(Args)]
+pub struct SqlArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// SQLite query over the `spawns`, `outputs` and `env` tables
+    pub query: String,
+
+    /// Structure version of json, yaml, msgpack, cbor and csv output; the previous version stays
+    /// supported so consumers can upgrade at their own pace
+    #[arg(long, default_value_t = REPORT_FORMAT_VERSION, value_parser = format_version_parser())]
+    pub format_version: u32,
+
+    /// Output format of the query result
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct DigestMapArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// File to write the mapping to (defaults to stdout)
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+
+    /// Format of the mapping
+    #[arg(long, value_enum, default_value = "csv")]
+    pub format: DigestMapFormat,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum DigestMapFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// Newline-delimited JSON, one object per output
+    Ndjson,
+}
+
+#[derive(Args)]
+pub struct StatsArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// Print the numbers as a JSON object instead of one `name: value` line each
+    #[arg(long)]
+    pub json: bool,
+
+    #[command(flatten)]
+    pub filters: FilterArgs,
+}
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// Output format of the diagnosis
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct ConvertArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// Path of the log to write
+    pub out: PathBuf,
+
+    /// Format of the written log (defaults to the format the input is not in)
+    #[arg(long, value_enum)]
+    pub to: Option<LogFormatArg>,
+}
+
+#[derive(Args)]
+pub struct BundleArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// Directory to write the report files to, created if missing
+    #[arg(long)]
+    pub out_dir: PathBuf,
+
+    #[command(flatten)]
+    pub reports: ReportArgs,
+
+    #[command(flatten)]
+    pub filters: FilterArgs,
+
+    /// Lead with the failed spawns in full and restrict every report to them and the other spawns
+    /// of their targets
+    #[arg(long)]
+    pub failures_only: bool,
+
+    /// Structure version of the JSON and CSV files
+    #[arg(long, default_value_t = REPORT_FORMAT_VERSION, value_parser = format_version_parser())]
+    pub format_version: u32,
+}
+
+#[derive(Args)]
+pub struct MergeArgs {
+    /// Paths to the execution logs to merge, earliest attempt first
+    #[arg(required = true)]
+    pub logs: Vec<PathBuf>,
+
+    /// Path of the merged log to write
+    #[arg(short, long)]
+    pub out: PathBuf,
+
+    /// Format of the merged log (defaults to the format of the first log)
+    #[arg(long, value_enum)]
+    pub to: Option<LogFormatArg>,
+
+    /// Keep one attempt of each action found more than once (by action digest); without it every
+    /// spawn is kept and the duplicates are only reported
+    #[arg(long, value_enum)]
+    pub dedup: Option<DedupPolicy>,
+
+    /// Number of mnemonics to display in the duplicate work table
+    #[arg(short, long, default_value_t = 10)]
+    pub top_n: usize,
+
+    /// Output format of the merge summary
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DedupPolicy {
+    /// The attempt that started first
+    First,
+    /// The attempt that started last, usually the one of the retry that passed
+    Last,
+    /// The attempt that took the least time
+    Fastest,
+}
+
+#[derive(Args)]
+pub struct SplitArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// What to group the actions by, one log per group
+    #[arg(long, value_enum)]
+    pub by: SplitBy,
+
+    /// Directory to write the logs to, created if missing
+    #[arg(long)]
+    pub out_dir: PathBuf,
+
+    /// Roll packages up to this many directory levels, e.g. 1 puts //third_party/protobuf in
+    /// the log of //third_party
+    #[arg(long, value_name = "LEVELS")]
+    pub package_depth: Option<usize>,
+
+    /// Output format of the list of logs written
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SplitBy {
+    /// The package of the action's target label
+    Package,
+    /// The action's mnemonic
+    Mnemonic,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum LogFormatArg {
+    /// Length-delimited `SpawnExec` messages, as `--execution_log_binary_file` writes
+    Verbose,
+    /// zstd-compressed entries, as `--execution_log_compact_file` writes
+    Compact,
+}
+
+#[derive(Args)]
+pub struct SuggestRcArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// Write the flags under `build:<CONFIG>` so they only apply with `--config=<CONFIG>`
+    #[arg(long)]
+    pub config: Option<String>,
+
+    /// Build Event Protocol JSON file (`--build_event_json_file`) of the same invocation, for the
+    /// `--jobs` it ran with
+    #[arg(long, value_name = "FILE")]
+    pub bep: Option<PathBuf>,
+
+    /// File to write the suggestions to (defaults to stdout)
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+
+    /// Runner string Bazel records for remote cache hits (repeatable)
+    #[arg(
+        long = "cache-hit-runner",
+        value_name = "RUNNER",
+        default_values = DEFAULT_CACHE_HIT_RUNNERS.iter().copied()
+    )]
+    pub cache_hit_runners: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    #[command(subcommand)]
+    pub format: ExportFormat,
+}
+
+#[derive(Subcommand)]
+pub enum ExportFormat {
+    /// Newline-delimited JSON with one row per spawn, ready for `bq load`
+    Ndjson(NdjsonArgs),
+    /// Per-mnemonic memory and duration estimates as JSON, for tuning `resource_set` and
+    /// `--local_resources`
+    Resources(ResourcesArgs),
+    /// Action digest to output path, digest and size, for joining against remote cache (CAS)
+    /// access logs
+    DigestMap(DigestMapArgs),
+    /// The reconstructed spawns as MessagePack, for notebooks and other tools to load without
+    /// parsing the log again
+    Msgpack(MsgpackArgs),
+    /// An Excel workbook with one sheet per enabled report
+    #[cfg(feature = "xlsx")]
+    Xlsx(XlsxArgs),
+}
+
+#[derive(Args)]
+pub struct NdjsonArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// File to write the rows to (defaults to stdout)
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+
+    /// Also write the matching BigQuery table schema to this file
+    #[arg(long)]
+    pub schema: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct MsgpackArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// File to write the spawns to (defaults to stdout)
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+}
+
+#[cfg(feature = "xlsx")]
+#[derive(Args)]
+pub struct XlsxArgs {
+    /// Path to the Bazel execution log file
+    pub file: PathBuf,
+
+    /// File to write the workbook to
+    #[arg(short, long)]
+    pub out: PathBuf,
+
+    #[command(flatten)]
+    pub reports: ReportArgs,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    /// Plain-text tables for the terminal
+    Text,
+    /// Pretty-printed JSON with raw values, for scripts and dashboards
+    Json,
+    /// YAML with the same structure as the JSON output
+    Yaml,
+    /// Binary MessagePack with the same structure as the JSON output
+    Msgpack,
+    /// Binary CBOR with the same structure as the JSON output
+    Cbor,
+    /// GitHub-flavored Markdown, for PR comments and CI job summaries
+    Markdown,
+    /// One CSV table per report section, with raw values
+    Csv,
+    /// A self-contained HTML page, suitable for linking from CI
+    Html,
+    /// A Slack Block Kit message, ready to post to an incoming webhook
+    Slack,
+}
+
+/// How a spawn's result was produced, classified from the free-form runner string.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum RunnerKind {
+    /// Outputs downloaded from a remote cache
+    RemoteCache,
+    /// Outputs copied from the disk cache
+    DiskCache,
+    /// Executed remotely
+    Remote,
+    /// Executed in a persistent worker
+    Worker,
+    /// Executed locally in a sandbox
+    Sandbox,
+    /// Executed locally without a sandbox
+    Local,
+    /// Any runner not recognized as one of the above
+    Other,
+}
+
+impl RunnerKind {
+    /// The name used on the command line, e.g. `remote-cache`.
+    pub fn name(self) -> &'static str {
+        match self {
+            RunnerKind::RemoteCache => "remote-cache",
+            RunnerKind::DiskCache => "disk-cache",
+            RunnerKind::Remote => "remote",
+            RunnerKind::Worker => "worker",
+            RunnerKind::Sandbox => "sandbox",
+            RunnerKind::Local => "local",
+            RunnerKind::Other => "other",
+        }
+    }
+}
+
+fn format_version_parser() -> clap::builder::RangedI64ValueParser<u32> {
+    clap::value_parser!(u32).range(i64::from(OLDEST_REPORT_FORMAT_VERSION)..=i64::from(REPORT_FORMAT_VERSION))
+}
+
+fn parse_repo_mapping(value: &str) -> Result<(String, String), String> {
+    let (from, to) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected FROM=TO, got '{}'", value))?;
+    let name = |repo: &str| repo.trim_start_matches('@').to_string();
+    Ok((name(from), name(to)))
+}
+
+/// A floor below which `diff` treats a change in an action's time as noise.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiffThreshold {
+    Duration(Duration),
+    /// Percent of the baseline time.
+    Percent(f64),
+}
+
+impl DiffThreshold {
+    /// Whether going from `old` to `new` seconds changes the time by more than the floor, in
+    /// either direction.
+    pub fn exceeded_by(&self, old: f64, new: f64) -> bool {
+        let change = (new - old).abs();
+        match *self {
+            DiffThreshold::Duration(floor) => change > floor.as_secs_f64(),
+            DiffThreshold::Percent(floor) => (old <= 0.0 && change > 0.0) || change / old * 100.0 > floor,
+        }
+    }
+}
+
+impl fmt::Display for DiffThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffThreshold::Duration(floor) => write!(f, "{:?}", floor),
+            DiffThreshold::Percent(floor) => write!(f, "{}%", floor),
+        }
+    }
+}
+
+fn parse_diff_threshold(value: &str) -> Result<DiffThreshold, String> {
+    match value.trim().strip_suffix('%') {
+        Some(percent) => percent
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|percent| *percent >= 0.0)
+            .map(DiffThreshold::Percent)
+            .ok_or_else(|| format!("expected a percentage such as 10%, got '{}'", value)),
+        None => parse_duration(value).map(DiffThreshold::Duration),
+    }
+}
+
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("expected a duration such as 500ms, 2s or 1.5m, got '{}'", value))?;
+    let seconds = match unit {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" | "min" => number * 60.0,
+        "h" => number * 3600.0,
+        _ => return Err(format!("unknown duration unit '{}' (use ms, s, m or h)", unit)),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("invalid duration '{}': {}", value, e))
+}
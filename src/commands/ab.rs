@@ -0,0 +1,206 @@
+//! `ab`: compares two groups of logs as the arms of an experiment, e.g. builds with and without a
+//! remote executor or with two toolchain versions, so a change is judged on its spread over many
+//! builds instead of one noisy pair.
+//!
+//! Each build is one observation: per mnemonic, the summed time of its executed spawns. The arms
+//! are compared with Welch's t-test, which does not assume they vary equally.
+
+use crate::cli::AbArgs;
+use crate::commands::analyze::{percentage, total_time};
+use crate::parsers::parse_log_file;
+use crate::proto::SpawnExec;
+use crate::render::{renderer_for, Column, Document, Section, Table, Value};
+use crate::AppResult;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::PathBuf;
+
+/// Row label of the summed time of every executed spawn.
+const ALL_MNEMONICS: &str = "(all executed)";
+
+/// Two-sided 95% critical values of Student's t distribution for 1 to 30 degrees of freedom.
+const T_975: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160, 2.145, 2.131, 2.120,
+    2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+
+/// The critical value for `df` degrees of freedom, rounded down to be conservative; past the
+/// table, `1.96 + 2.4 / df` is within 0.002 of it.
+fn t_critical(df: f64) -> f64 {
+    match df.floor() as usize {
+        0 => T_975[0],
+        df @ 1..=30 => T_975[df - 1],
+        _ => 1.96 + 2.4 / df,
+    }
+}
+
+/// One mnemonic's per-build times in both arms.
+struct Comparison {
+    mnemonic: String,
+    a: Vec<f64>,
+    b: Vec<f64>,
+}
+
+/// Mean difference (B minus A) with its 95% confidence interval.
+struct Difference {
+    delta: f64,
+    low: f64,
+    high: f64,
+}
+
+impl Difference {
+    /// Whether the interval leaves out zero.
+    fn is_significant(&self) -> bool {
+        self.low > 0.0 || self.high < 0.0
+    }
+}
+
+pub(crate) fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+pub(crate) fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    } else {
+        sorted[middle]
+    }
+}
+
+/// Sample variance; callers pass at least two values.
+pub(crate) fn variance(values: &[f64]) -> f64 {
+    let mean = mean(values);
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64
+}
+
+/// Welch's confidence interval for the difference of the means of `b` and `a`.
+fn welch(a: &[f64], b: &[f64]) -> Difference {
+    let delta = mean(b) - mean(a);
+    let (se_a, se_b) = (variance(a) / a.len() as f64, variance(b) / b.len() as f64);
+    let se = (se_a + se_b).sqrt();
+    if se == 0.0 {
+        // Every build of each arm took the same time: the difference is exact.
+        return Difference {
+            delta,
+            low: delta,
+            high: delta,
+        };
+    }
+    let df = (se_a + se_b).powi(2) / (se_a.powi(2) / (a.len() - 1) as f64 + se_b.powi(2) / (b.len() - 1) as f64);
+    let margin = t_critical(df) * se;
+    Difference {
+        delta,
+        low: delta - margin,
+        high: delta + margin,
+    }
+}
+
+/// Summed time of the executed spawns of each mnemonic in one build, and of all of them.
+fn times_by_mnemonic(spawns: &[SpawnExec]) -> BTreeMap<&str, f64> {
+    let mut times = BTreeMap::from([(ALL_MNEMONICS, 0.0)]);
+    for spawn in spawns.iter().filter(|s| !s.cache_hit) {
+        let seconds = total_time(spawn).as_secs_f64();
+        *times.entry(spawn.mnemonic.as_str()).or_default() += seconds;
+        *times.entry(ALL_MNEMONICS).or_default() += seconds;
+    }
+    times
+}
+
+fn compare(a: &[Vec<SpawnExec>], b: &[Vec<SpawnExec>]) -> Vec<Comparison> {
+    let a_times: Vec<_> = a.iter().map(|spawns| times_by_mnemonic(spawns)).collect();
+    let b_times: Vec<_> = b.iter().map(|spawns| times_by_mnemonic(spawns)).collect();
+    let mnemonics: BTreeSet<&str> = a_times.iter().chain(&b_times).flat_map(|times| times.keys().copied()).collect();
+    // A build without a mnemonic spent no time on it.
+    let per_build = |arm: &[BTreeMap<&str, f64>], mnemonic| {
+        arm.iter().map(|times| times.get(mnemonic).copied().unwrap_or(0.0)).collect()
+    };
+    mnemonics
+        .into_iter()
+        .map(|mnemonic| Comparison {
+            mnemonic: mnemonic.to_string(),
+            a: per_build(&a_times, mnemonic),
+            b: per_build(&b_times, mnemonic),
+        })
+        .collect()
+}
+
+fn parse_arm(paths: &[PathBuf]) -> AppResult<Vec<Vec<SpawnExec>>> {
+    paths.iter().map(|path| parse_log_file(path)).collect()
+}
+
+pub fn run_ab(args: AbArgs) -> AppResult<()> {
+    let a = parse_arm(&args.a)?;
+    let b = parse_arm(&args.b)?;
+    let mut comparisons: Vec<(Comparison, Difference)> = compare(&a, &b)
+        .into_iter()
+        .map(|comparison| {
+            let difference = welch(&comparison.a, &comparison.b);
+            (comparison, difference)
+        })
+        .collect();
+    // The overall row first, then significant changes, each by the size of the change.
+    comparisons.sort_by(|(x, dx), (y, dy)| {
+        (y.mnemonic == ALL_MNEMONICS)
+            .cmp(&(x.mnemonic == ALL_MNEMONICS))
+            .then(dy.is_significant().cmp(&dx.is_significant()))
+            .then(dy.delta.abs().total_cmp(&dx.delta.abs()))
+    });
+
+    let mut document = Document::new("A/B Comparison");
+    document.format_version = args.format_version;
+    document.push_metadata(Column::integer("a_builds", "A builds"), a.len());
+    document.push_metadata(Column::integer("b_builds", "B builds"), b.len());
+
+    let significant = comparisons
+        .iter()
+        .filter(|(c, d)| c.mnemonic != ALL_MNEMONICS && d.is_significant())
+        .count();
+    let mut section = Section::new("mnemonic_differences", "Execution Time by Mnemonic");
+    section.push_text(
+        "Note: Each build counts once, with the summed time of the mnemonic's executed spawns. Delta is B minus A; \
+         a change is significant when its 95% confidence interval (Welch's t-test) leaves out zero.",
+    );
+    section.push_field(Column::integer("significant", "Significant changes"), significant);
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::seconds("a_mean", "A Mean", 2),
+        Column::seconds("b_mean", "B Mean", 2),
+        Column::seconds("delta", "Delta", 2).signed(),
+        Column::percent("delta_share", "Delta %", 1).signed(),
+        Column::seconds("ci_low", "95% CI Low", 2).signed(),
+        Column::seconds("ci_high", "95% CI High", 2).signed(),
+        Column::seconds("a_median", "A Median", 2),
+        Column::seconds("b_median", "B Median", 2),
+        Column::seconds("median_delta", "Median Delta", 2).signed(),
+        Column::text("significant", "Significant"),
+    ]);
+    for (comparison, difference) in comparisons.iter().take(args.top_n + 1) {
+        let a_mean = mean(&comparison.a);
+        let (a_median, b_median) = (median(&comparison.a), median(&comparison.b));
+        table.push_row(vec![
+            comparison.mnemonic.as_str().into(),
+            a_mean.into(),
+            mean(&comparison.b).into(),
+            difference.delta.into(),
+            if a_mean > 0.0 {
+                percentage(difference.delta, a_mean).into()
+            } else {
+                Value::Null
+            },
+            difference.low.into(),
+            difference.high.into(),
+            a_median.into(),
+            b_median.into(),
+            (b_median - a_median).into(),
+            if difference.is_significant() { "yes" } else { "no" }.into(),
+        ]);
+    }
+    section.push_table(table);
+    document.sections.push(section);
+
+    renderer_for(args.output).render(&document, &mut io::stdout().lock())?;
+    Ok(())
+}
@@ -1,22 +1,75 @@
-use crate::cli::Cli;
-use crate::proto::exec_log_entry::{self as compact, Type as CompactEntryType};
-use crate::proto::{ExecLogEntry, SpawnExec};
+use crate::cli::{Cli, FilterArgs, OutputFormat, ReportArgs, RunnerKind, DEFAULT_BOOKKEEPING_MNEMONICS};
+use crate::correlate::profile;
+use crate::correlate::bep::{BuildEvents, Configuration};
+use crate::commands::show::{network_bytes, spawn_section};
+use crate::correlate::SideInputs;
+use crate::{filters, pager};
+use crate::findings::{hermeticity_findings, is_absolute_path, is_expected_absolute_path, write_sarif, RULES};
+use crate::parsers::parse_log_file_with_warnings;
+use crate::warnings::Warnings;
+use crate::proto::{SpawnExec, SpawnMetrics};
+use crate::rows::spawn_id;
+use crate::render::{
+    renderer_for, report_schema, styled, ByteUnit, Column, Document, Section, SummaryLineRenderer, Table, Value,
+};
 use crate::{AppError, AppResult};
-use prost::Message;
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
+use std::io::{self, BufWriter, Write};
 use std::path::Path;
 use std::time::Duration;
-use zstd::stream::decode_all;
 
 /// Helper to convert prost's Duration to std's Duration
-fn to_std_duration(prost_duration: &prost_types::Duration) -> Duration {
+pub(crate) fn to_std_duration(prost_duration: &prost_types::Duration) -> Duration {
     Duration::new(
         prost_duration.seconds.try_into().unwrap_or(0),
         prost_duration.nanos.try_into().unwrap_or(0),
     )
 }
 
+/// The stable ID of each listed spawn, only in machine-readable output, for joining report rows
+/// with exports and other reports.
+pub(crate) fn spawn_id_column() -> Column {
+    Column::text("spawn_id", "Spawn ID").machine_only()
+}
+
+/// Reads one phase duration from a spawn's metrics, defaulting to zero when absent.
+pub(crate) fn phase_duration(
+    spawn: &SpawnExec,
+    phase: fn(&SpawnMetrics) -> Option<&prost_types::Duration>,
+) -> Duration {
+    spawn
+        .metrics
+        .as_ref()
+        .and_then(phase)
+        .map(to_std_duration)
+        .unwrap_or_default()
+}
+
+pub(crate) fn total_time(spawn: &SpawnExec) -> Duration {
+    phase_duration(spawn, |m| m.total_time.as_ref())
+}
+
+/// Sum of the digest sizes of a spawn's outputs.
+pub(crate) fn output_bytes(spawn: &SpawnExec) -> i64 {
+    spawn
+        .actual_outputs
+        .iter()
+        .filter_map(|file| file.digest.as_ref())
+        .map(|digest| digest.size_bytes)
+        .sum()
+}
+
+/// Percentage of `part` in `whole`, or zero when `whole` is zero.
+pub(crate) fn percentage(part: f64, whole: f64) -> f64 {
+    if whole > 0.0 {
+        part / whole * 100.0
+    } else {
+        0.0
+    }
+}
+
 #[derive(Default)]
 struct MnemonicMetrics {
     count: u64,
@@ -30,942 +83,1773 @@ struct ExecutionTimings {
     total_duration: Duration,
 }
 
+impl ExecutionTimings {
+    fn average_seconds(&self) -> f64 {
+        if self.count > 0 {
+            self.total_duration.as_secs_f64() / self.count as f64
+        } else {
+            0.0
+        }
+    }
+}
+
 #[derive(Default)]
 struct MnemonicExecutionStats {
     remote: ExecutionTimings,
     local: ExecutionTimings,
 }
 
-/// An enum to hold different types of compact log entries for reconstruction.
-enum StoredEntry {
-    File(compact::File),
-    Directory(compact::Directory),
+/// The last component of a path with `/` or `\` separators.
+pub(crate) fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
 }
 
-pub fn run_analyze(args: Cli) -> AppResult<()> {
-    let spawns = parse_log_file(&args.file)?;
-
-    if spawns.is_empty() {
-        println!("Execution log is empty or contains no spawn actions. No metrics to report.");
-        return Ok(());
+/// The name of a program from its path, without the `.exe` of Windows executables.
+fn program_name(path: &str) -> &str {
+    let name = file_name(path);
+    match name.len().checked_sub(4) {
+        Some(stem) if name.is_char_boundary(stem) && name[stem..].eq_ignore_ascii_case(".exe") => &name[..stem],
+        _ => name,
     }
-    println!(
-        "Successfully parsed and reconstructed {} spawn entries from the log.",
-        spawns.len()
-    );
+}
 
-    // --- Print Main Report ---
-    print_main_report(&spawns, &args);
+/// Whether a path is one of the workspace status files stamped actions read.
+pub(crate) fn is_workspace_status_file(path: &str) -> bool {
+    path.ends_with("volatile-status.txt") || path.ends_with("stable-status.txt")
+}
 
-    // --- Optional Reports ---
-    if args.cache_metrics {
-        print_cache_performance_report(&spawns);
-    }
-    if args.phase_timings {
-        print_phase_timings_report(&spawns, args.top_n);
-    }
-    if args.input_analysis {
-        print_input_analysis_report(&spawns, args.top_n);
-    }
-    if args.retries {
-        print_retries_and_failures_report(&spawns);
-    }
+/// Whether a spawn is coverage post-processing: the coverage report action, lcov merging or
+/// Bazel's coverage output generator.
+pub(crate) fn is_coverage_spawn(spawn: &SpawnExec) -> bool {
+    const COMMAND_MARKERS: &[&str] = &["lcov_merger", "LcovMerger", "CoverageOutputGenerator", "collect_coverage"];
+    spawn.mnemonic.contains("Coverage")
+        || spawn
+            .actual_outputs
+            .iter()
+            .any(|f| f.path.ends_with("coverage.dat") || f.path.ends_with("_coverage_report.dat"))
+        || spawn
+            .command_args
+            .iter()
+            .take(2)
+            .any(|arg| COMMAND_MARKERS.iter().any(|marker| arg.contains(marker)))
+}
 
-    // --- NEW REPORTS ---
-    if args.aggregate_phases {
-        print_aggregate_phases_report(&spawns);
-    }
-    if args.output_analysis {
-        print_output_analysis_report(&spawns, args.top_n);
+/// Returns true for runners that execute on the local machine (sandboxed, workers or unsandboxed).
+pub(crate) fn is_local_runner(runner: &str) -> bool {
+    ["sandbox", "local", "worker", "standalone", "processwrapper"]
+        .iter()
+        .any(|kind| runner.contains(kind))
+}
+
+/// Classifies the runner a spawn's result came from.
+pub(crate) fn runner_kind(spawn: &SpawnExec) -> RunnerKind {
+    let runner = spawn.runner.as_str();
+    if spawn.cache_hit {
+        if runner.contains("disk") { RunnerKind::DiskCache } else { RunnerKind::RemoteCache }
+    } else if runner.contains("remote") {
+        RunnerKind::Remote
+    } else if runner.contains("worker") {
+        RunnerKind::Worker
+    } else if runner.contains("sandbox") {
+        RunnerKind::Sandbox
+    } else if is_local_runner(runner) {
+        RunnerKind::Local
+    } else {
+        RunnerKind::Other
     }
-    if args.memory_analysis {
-        print_memory_analysis_report(&spawns, args.top_n);
+}
+
+/// Share of spawns above which a runner string the reports do not recognize is worth a warning.
+const UNRECOGNIZED_RUNNER_SHARE: f64 = 1.0;
+
+/// Records runner strings that are common in the log but that the reports cannot classify,
+/// most likely because a Bazel version or fork words them differently.
+pub(crate) fn check_runners(spawns: &[SpawnExec], cache_hit_runners: &[String], warnings: &mut Warnings) {
+    let mut counts: BTreeMap<&str, (u64, bool)> = BTreeMap::new();
+    for spawn in spawns {
+        let unknown_cache_hit =
+            runner_kind(spawn) == RunnerKind::RemoteCache && !cache_hit_runners.contains(&spawn.runner);
+        if unknown_cache_hit || runner_kind(spawn) == RunnerKind::Other {
+            let entry = counts.entry(&spawn.runner).or_default();
+            entry.0 += 1;
+            entry.1 |= unknown_cache_hit;
+        }
     }
-    if args.execution_comparison {
-        print_execution_comparison_report(&spawns);
+    for (runner, (count, cache_hit)) in counts {
+        if percentage(count as f64, spawns.len() as f64) < UNRECOGNIZED_RUNNER_SHARE {
+            continue;
+        }
+        if cache_hit {
+            warnings.push_many(
+                "uncounted_cache_hit_runner",
+                "Cache hits whose runner is not counted as a remote cache hit; pass it with --cache-hit-runner if it is one",
+                count,
+                format!("'{}' ({} spawns)", runner, count),
+            );
+        } else {
+            warnings.push_many(
+                "unrecognized_runner",
+                "Spawns with a runner string the reports do not recognize",
+                count,
+                format!("'{}' ({} spawns)", runner, count),
+            );
+        }
     }
-    if args.queue_analysis {
-        print_queue_analysis_report(&spawns, args.top_n);
+}
+
+/// Share of spawns without metrics above which the log is taken to have been recorded without them.
+const MISSING_METRICS_SHARE: f64 = 50.0;
+
+/// Whether a spawn has no timings, because the log was written without spawn metrics.
+pub(crate) fn lacks_metrics(spawn: &SpawnExec) -> bool {
+    spawn.metrics.is_none() || total_time(spawn).is_zero()
+}
+
+/// How many spawns lack metrics, if enough do that the timing reports would only show zeros.
+pub(crate) fn missing_metrics(spawns: &[SpawnExec]) -> Option<usize> {
+    let missing = spawns.iter().filter(|s| lacks_metrics(s)).count();
+    (missing > 0 && percentage(missing as f64, spawns.len() as f64) >= MISSING_METRICS_SHARE).then_some(missing)
+}
+
+/// Leaves out the reports built from spawn metrics when the log mostly lacks them, and says
+/// prominently which flag records them.
+pub(crate) fn skip_untimed_reports(
+    selected: Vec<&'static Report>,
+    spawns: &[SpawnExec],
+    warnings: &mut Warnings,
+) -> Vec<&'static Report> {
+    let Some(missing) = missing_metrics(spawns) else {
+        return selected;
+    };
+    let (skipped, kept): (Vec<_>, Vec<_>) = selected.into_iter().partition(|report| report.needs_metrics);
+    let skipped: Vec<&str> = skipped.iter().map(|report| report.name).collect();
+    eprintln!(
+        "Warning: {} of {} spawns have no execution metrics, so their timings would all read zero. \
+         Re-run the build with --execution_log_spawn_metrics to record them.",
+        missing,
+        spawns.len()
+    );
+    if !skipped.is_empty() {
+        eprintln!("Skipping the timing reports: {}.", skipped.join(", "));
     }
+    warnings.push_many(
+        "missing_metrics",
+        "Spawns without execution metrics; record them with --execution_log_spawn_metrics",
+        missing as u64,
+        if skipped.is_empty() {
+            format!("{} of {} spawns", missing, spawns.len())
+        } else {
+            format!("{} of {} spawns; skipped {}", missing, spawns.len(), skipped.join(", "))
+        },
+    );
+    kept
+}
 
-    Ok(())
+#[derive(Default)]
+struct WorkerStats {
+    count: u64,
+    total_duration: Duration,
+    /// Sum of each action's duration relative to its mnemonic's average, used to normalize for action mix.
+    relative_duration_sum: f64,
 }
 
-/// Parses the log file, auto-detecting the format (compact or verbose).
-fn parse_log_file(path: &Path) -> AppResult<Vec<SpawnExec>> {
-    let raw_bytes = fs::read(path)?;
+#[derive(Default)]
+struct PoolStats {
+    count: u64,
+    executed: u64,
+    total_execution: Duration,
+    total_queue: Duration,
+    max_queue: Duration,
+}
 
-    // 1. Try parsing as a zstd-compressed compact log first.
-    if let Ok(decompressed) = decode_all(raw_bytes.as_slice()) {
-        if let Ok(spawns) = parse_compact_log(&decompressed) {
-            println!("Detected zstd-compressed compact log format.");
-            return Ok(spawns);
-        }
+#[derive(Default)]
+struct CommandLineStats {
+    count: u64,
+    total_argc: usize,
+    max_argc: usize,
+    max_bytes: usize,
+    params_file_count: u64,
+}
+
+#[derive(Default)]
+struct CacheHitCounts {
+    count: u64,
+    cache_hits: u64,
+}
+
+#[derive(Default)]
+struct EnvironmentStats {
+    count: u64,
+    total_bytes: usize,
+    max_bytes: usize,
+}
+
+/// A report that `run_analyze` can produce, selectable by name with `--only`.
+pub struct Report {
+    /// Stable name, matching the report's command-line flag.
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Whether the report is enabled by the command-line flags when `--only` is not used.
+    enabled: fn(&ReportArgs) -> bool,
+    /// Whether the report is built from spawn metrics alone, so that it would show nothing but
+    /// zeros for a log recorded without them.
+    needs_metrics: bool,
+    build: fn(&[SpawnExec], &ReportArgs, &SideInputs) -> Vec<Section>,
+}
+
+impl Report {
+    pub(crate) fn sections(&self, spawns: &[SpawnExec], args: &ReportArgs, inputs: &SideInputs) -> Vec<Section> {
+        (self.build)(spawns, args, inputs)
     }
+}
+
+/// Every report, in display order.
+pub const REPORTS: &[Report] = &[
+    Report {
+        name: "summary",
+        description: "Overall summary, slowest actions and the breakdown by mnemonic",
+        enabled: |_| true,
+        needs_metrics: false,
+        build: |spawns, args, _| main_report_sections(spawns, args.top_n),
+    },
+    Report {
+        name: "cache-metrics",
+        description: "Remote cache download volume, throughput percentiles and slowest fetches",
+        enabled: |args| args.cache_metrics,
+        needs_metrics: false,
+        build: |spawns, args, _| cache_performance_sections(spawns, &args.cache_hit_runners, args.top_n),
+    },
+    Report {
+        name: "phase-timings",
+        description: "Phase breakdown of the slowest executed actions",
+        enabled: |args| args.phase_timings,
+        needs_metrics: true,
+        build: |spawns, args, _| vec![phase_timings_section(spawns, args.top_n)],
+    },
+    Report {
+        name: "input-analysis",
+        description: "Actions with the largest inputs, including params files",
+        enabled: |args| args.input_analysis,
+        needs_metrics: false,
+        build: |spawns, args, _| vec![input_analysis_section(spawns, args.top_n)],
+    },
+    Report {
+        name: "retries",
+        description: "Actions that failed or were retried, and what the first failure took down with it",
+        enabled: |args| args.retries,
+        needs_metrics: false,
+        build: |spawns, args, _| {
+            let mut sections = vec![retries_and_failures_section(spawns)];
+            sections.extend(retry_time_sections(spawns, args.top_n));
+            sections.extend(first_failure_section(spawns, args.top_n));
+            sections
+        },
+    },
+    Report {
+        name: "aggregate-phases",
+        description: "Total time spent in each execution phase",
+        enabled: |args| args.aggregate_phases,
+        needs_metrics: true,
+        build: |spawns, _, _| vec![aggregate_phases_section(spawns)],
+    },
+    Report {
+        name: "output-analysis",
+        description: "Actions with the largest outputs",
+        enabled: |args| args.output_analysis,
+        needs_metrics: false,
+        build: |spawns, args, _| vec![output_analysis_section(spawns, args.top_n)],
+    },
+    Report {
+        name: "memory-analysis",
+        description: "Actions with the highest memory usage relative to their limit, and headroom per mnemonic",
+        enabled: |args| args.memory_analysis,
+        needs_metrics: true,
+        build: |spawns, args, _| vec![memory_analysis_section(spawns, args.top_n), memory_headroom_section(spawns)],
+    },
+    Report {
+        name: "execution-comparison",
+        description: "Remote vs. local execution times by mnemonic",
+        enabled: |args| args.execution_comparison,
+        needs_metrics: true,
+        build: |spawns, _, _| vec![execution_comparison_section(spawns)],
+    },
+    Report {
+        name: "queue-analysis",
+        description: "Actions with the longest queue times",
+        enabled: |args| args.queue_analysis,
+        needs_metrics: true,
+        build: |spawns, args, _| {
+            vec![queue_analysis_section(spawns, args.top_n), queue_pressure_section(spawns, args.queue_target)]
+        },
+    },
+    Report {
+        name: "remote-fallback",
+        description: "Remotable mnemonics that fell back to local execution",
+        enabled: |args| args.remote_fallback,
+        needs_metrics: false,
+        build: |spawns, _, _| vec![remote_fallback_section(spawns)],
+    },
+    Report {
+        name: "worker-skew",
+        description: "Per-worker execution times, normalized for action mix",
+        enabled: |args| args.worker_skew,
+        needs_metrics: true,
+        build: |spawns, args, _| vec![worker_skew_section(spawns, args.top_n)],
+    },
+    Report {
+        name: "container-images",
+        description: "Container images used by actions",
+        enabled: |args| args.container_images,
+        needs_metrics: false,
+        build: |spawns, args, _| vec![container_image_section(spawns, args.top_n)],
+    },
+    Report {
+        name: "pool-utilization",
+        description: "Actions, execution and queue times by remote execution pool",
+        enabled: |args| args.pool_utilization,
+        needs_metrics: true,
+        build: |spawns, _, _| vec![pool_utilization_section(spawns)],
+    },
+    Report {
+        name: "command-lines",
+        description: "Longest command lines, common executables and per-mnemonic patterns",
+        enabled: |args| args.command_lines,
+        needs_metrics: false,
+        build: |spawns, args, _| command_line_sections(spawns, args.top_n),
+    },
+    Report {
+        name: "environment-size",
+        description: "Environment sizes per action and mnemonic, the largest variables and likely secrets",
+        enabled: |args| args.environment_size,
+        needs_metrics: false,
+        build: |spawns, args, _| environment_size_sections(spawns, args.top_n),
+    },
+    Report {
+        name: "download-savings",
+        description: "Download volume minimal/top-level download modes would have avoided",
+        enabled: |args| args.download_savings,
+        needs_metrics: false,
+        build: |spawns, args, _| vec![download_savings_section(spawns, &args.cache_hit_runners, args.top_n)],
+    },
+    Report {
+        name: "hermeticity",
+        description: "Targets whose actions use absolute paths or ran locally without a sandbox",
+        enabled: |args| args.hermeticity,
+        needs_metrics: false,
+        build: |spawns, args, _| vec![hermeticity_section(spawns, args.top_n)],
+    },
+    Report {
+        name: "configurations",
+        description: "Spawns per build configuration and actions built under more than one configuration",
+        enabled: |args| args.configurations,
+        needs_metrics: false,
+        build: |spawns, args, inputs| configuration_sections(spawns, inputs, args.top_n),
+    },
+    Report {
+        name: "attribution",
+        description: "Build time spent on IDE and lint aspects and exec-configuration tools versus primary compilation",
+        enabled: |args| args.attribution,
+        needs_metrics: false,
+        build: |spawns, args, inputs| vec![attribution_section(spawns, inputs, args.top_n)],
+    },
+    Report {
+        name: "strategies",
+        description: "Recommended execution strategy (remote, worker, local-sandbox) per mnemonic with supporting numbers",
+        enabled: |args| args.strategies,
+        needs_metrics: false,
+        build: |spawns, _, _| vec![strategy_section(spawns)],
+    },
+    Report {
+        name: "timeouts",
+        description: "Configured spawn timeouts by mnemonic against actual execution times",
+        enabled: |args| args.timeouts,
+        needs_metrics: true,
+        build: |spawns, args, _| vec![timeout_section(spawns, args.top_n)],
+    },
+    Report {
+        name: "stamping",
+        description: "Actions reading workspace status (stamping) and the downstream rebuild cascade they cause",
+        enabled: |args| args.stamping,
+        needs_metrics: false,
+        build: |spawns, args, _| vec![stamping_section(spawns, args.top_n)],
+    },
+    Report {
+        name: "fan-out",
+        description: "Targets with an unusually high number of spawns (retries, shards, coverage)",
+        enabled: |args| args.fan_out,
+        needs_metrics: false,
+        build: |spawns, args, _| vec![fan_out_section(spawns, args.top_n)],
+    },
+    Report {
+        name: "coverage",
+        description: "Coverage post-processing spawns (coverage report, lcov merging) and their share of the build",
+        enabled: |args| args.coverage,
+        needs_metrics: false,
+        build: |spawns, _, _| vec![coverage_section(spawns)],
+    },
+    Report {
+        name: "heatmap",
+        description: "Execution time per mnemonic across the build timeline, as a heatmap",
+        enabled: |args| args.heatmap,
+        needs_metrics: true,
+        build: |spawns, args, _| vec![heatmap_section(spawns, args.top_n)],
+    },
+    Report {
+        name: "time-concentration",
+        description: "How few of the slowest actions account for most of the execution time",
+        enabled: |args| args.time_concentration,
+        needs_metrics: true,
+        build: |spawns, _, _| vec![time_concentration_section(spawns)],
+    },
+    Report {
+        name: "fetch-analysis",
+        description: "Actions with the longest output fetch times and the most bytes fetched",
+        enabled: |args| args.fetch_analysis,
+        needs_metrics: true,
+        build: |spawns, args, _| fetch_analysis_sections(spawns, args.top_n),
+    },
+    Report {
+        name: "setup-hotspots",
+        description: "Actions whose input staging and sandbox setup rival their execution, by mnemonic and input count",
+        enabled: |args| args.setup_hotspots,
+        needs_metrics: true,
+        build: |spawns, args, _| setup_hotspot_sections(spawns, args.top_n),
+    },
+    Report {
+        name: "package-mnemonics",
+        description: "Actions and time per mnemonic within each package, collapsible per package in HTML and Markdown",
+        enabled: |args| args.package_mnemonics,
+        needs_metrics: false,
+        build: |spawns, args, _| package_mnemonic_sections(spawns, args.package_depth, args.top_n),
+    },
+    Report {
+        name: "rule-classes",
+        description: "Build cost per rule class, inferred from mnemonics and output paths",
+        enabled: |args| args.rule_classes,
+        needs_metrics: false,
+        build: |spawns, _, _| vec![rule_class_section(spawns)],
+    },
+    Report {
+        name: "genrule-audit",
+        description: "Cost, cacheability and non-hermetic command patterns of genrule and shell actions",
+        enabled: |args| args.genrule_audit,
+        needs_metrics: false,
+        build: |spawns, args, _| genrule_audit_sections(spawns, args.top_n),
+    },
+    Report {
+        name: "action-explosion",
+        description: "Mnemonics with very many short actions (e.g. codegen) and the fixed overhead they pay per action",
+        enabled: |args| args.action_explosion,
+        needs_metrics: true,
+        build: |spawns, _, _| vec![action_explosion_section(spawns)],
+    },
+    Report {
+        name: "invocation",
+        description: "Invocation details from the Build Event Protocol file (--bep) and spawns per requested target",
+        enabled: |args| args.bep.is_some(),
+        needs_metrics: false,
+        build: |spawns, args, inputs| vec![invocation_section(spawns, inputs, args.top_n)],
+    },
+    Report {
+        name: "profile",
+        description: "Action time from the JSON profile (--profile) split into spawn time and time outside the spawn",
+        enabled: |args| args.profile.is_some(),
+        needs_metrics: false,
+        build: |spawns, args, inputs| vec![profile_correlation_section(spawns, inputs, args.top_n)],
+    },
+    Report {
+        name: "server-timings",
+        description: "Client-observed versus server-reported times of remote executions (--server-timings)",
+        enabled: |args| args.server_timings.is_some(),
+        needs_metrics: true,
+        build: |spawns, args, inputs| vec![server_timings_section(spawns, inputs, args.top_n)],
+    },
+    Report {
+        name: "cache-timeline",
+        description: "Cache hit rate over time, bucketed by action start time",
+        enabled: |args| args.cache_timeline,
+        needs_metrics: true,
+        build: |spawns, args, _| vec![cache_timeline_section(spawns, args.bucket_seconds)],
+    },
+    Report {
+        name: "concurrency",
+        description: "Peak and average parallelism, job-limit plateaus and --jobs and executor pool recommendations",
+        enabled: |args| args.concurrency,
+        needs_metrics: true,
+        build: |spawns, args, inputs| vec![concurrency_section(spawns, args.top_n, inputs.bep.as_ref())],
+    },
+    Report {
+        name: "start-latency",
+        description: "Time before the first action and after the last (with --bep), and idle gaps between actions",
+        enabled: |args| args.start_latency,
+        needs_metrics: true,
+        build: |spawns, args, inputs| vec![start_latency_section(spawns, inputs, args.top_n)],
+    },
+    Report {
+        name: "signals",
+        description: "Actions killed by a signal (137 SIGKILL/OOM, 139 SIGSEGV, ...) with their memory estimates and limits",
+        enabled: |args| args.signals,
+        needs_metrics: false,
+        build: |spawns, args, _| vec![signal_section(spawns, args.top_n)],
+    },
+    Report {
+        name: "unlabeled",
+        description: "Spawns without a target label, grouped by their outputs, and the time left unattributed",
+        enabled: |args| args.unlabeled,
+        needs_metrics: false,
+        build: |spawns, args, _| unlabeled_sections(spawns, args.top_n),
+    },
+];
 
-    // 2. Fallback to parsing as an uncompressed verbose log.
-    println!("Could not parse as compact log. Falling back to verbose log format.");
-    parse_verbose_log(&raw_bytes)
+fn find_report(name: &str) -> AppResult<&'static Report> {
+    REPORTS.iter().find(|r| r.name == name).ok_or_else(|| {
+        let names: Vec<&str> = REPORTS.iter().map(|r| r.name).collect();
+        AppError::Analysis(format!(
+            "Unknown report '{}'. Available reports: {}",
+            name,
+            names.join(", ")
+        ))
+    })
 }
 
-/// Parses the verbose execution log format (length-delimited SpawnExec protos).
-fn parse_verbose_log(content: &[u8]) -> AppResult<Vec<SpawnExec>> {
-    let mut decoded_spawns = Vec::new();
-    let mut cursor = content;
+/// The reports named by `--only`, or the ones enabled by their flags when it is not used.
+pub(crate) fn selected_reports(args: &ReportArgs) -> AppResult<Vec<&'static Report>> {
+    if args.only.is_empty() {
+        Ok(REPORTS.iter().filter(|r| (r.enabled)(args)).collect())
+    } else {
+        args.only.iter().map(|name| find_report(name)).collect()
+    }
+}
 
-    while !cursor.is_empty() {
-        match SpawnExec::decode_length_delimited(&mut cursor) {
-            Ok(spawn) => decoded_spawns.push(spawn),
-            Err(e) => {
-                return Err(AppError::LogParsing(format!("Failed to parse verbose protobuf message: {}. The log file might be corrupt or in the wrong format.", e)));
-            }
-        }
+fn print_report_list() {
+    let width = REPORTS.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    for report in REPORTS {
+        println!("{:<width$}  {}", report.name, report.description, width = width);
     }
-    Ok(decoded_spawns)
 }
 
-/// Parses the compact execution log format and reconstructs SpawnExec messages.
-fn parse_compact_log(content: &[u8]) -> AppResult<Vec<SpawnExec>> {
-    let mut cursor = content;
-    let mut stored_entries: HashMap<u32, StoredEntry> = HashMap::new();
-    let mut reconstructed_spawns = Vec::new();
+/// Parses `file` and builds the analysis report the top-level command prints, also writing the
+/// hermeticity findings to `sarif` when given. `None` when the log has no spawns.
+pub(crate) fn analysis_document(
+    file: &Path,
+    reports: &ReportArgs,
+    filters: &FilterArgs,
+    failures_only: bool,
+    sarif: Option<&Path>,
+    format_version: u32,
+) -> AppResult<Option<Document>> {
+    // Resolve report names before parsing so typos fail fast.
+    let selected = selected_reports(reports)?;
+    let inputs = SideInputs::load(reports)?;
+    let mut warnings = Warnings::new();
+    let spawns = parse_log_file_with_warnings(file, &mut warnings)?;
+
+    if spawns.is_empty() {
+        eprintln!("Execution log is empty or contains no spawn actions. No metrics to report.");
+        return Ok(None);
+    }
+    eprintln!(
+        "Successfully parsed and reconstructed {} spawn entries from the log.",
+        spawns.len()
+    );
 
-    while !cursor.is_empty() {
-        let entry = ExecLogEntry::decode_length_delimited(&mut cursor)?;
-        let id = entry.id;
+    let mut document = Document::new("Bazel Execution Log Analysis Report");
+    document.format_version = format_version;
+    document.push_metadata(Column::text("log_file", "Log file"), file.display().to_string());
+    let bookkeeping = filters::bookkeeping_rollup(&spawns, filters);
+    let spawns = filters::apply(spawns, filters, &mut document);
+    check_runners(&spawns, &reports.cache_hit_runners, &mut warnings);
+    let spawns = if failures_only {
+        let failed_targets: BTreeSet<&str> = spawns
+            .iter()
+            .filter(|s| is_failed(s) && !s.target_label.is_empty())
+            .map(|s| s.target_label.as_str())
+            .collect();
+        let context: Vec<SpawnExec> = spawns
+            .iter()
+            .filter(|s| is_failed(s) || failed_targets.contains(s.target_label.as_str()))
+            .cloned()
+            .collect();
+        eprintln!(
+            "Restricting the reports to failed spawns and their targets: {} of {} spawns.",
+            context.len(),
+            spawns.len()
+        );
+        document.push_metadata(Column::integer("triaged_spawns", "Spawns (failures and their targets)"), context.len());
+        document.sections.extend(failure_triage_sections(&spawns, reports.top_n));
+        context
+    } else {
+        spawns
+    };
+    for report in skip_untimed_reports(selected, &spawns, &mut warnings) {
+        document.sections.extend(report.sections(&spawns, reports, &inputs));
+    }
+    if let Some(line) = bookkeeping.summary_line(filters.include_bookkeeping)
+        && let Some(summary) = document.sections.iter_mut().find(|section| section.id == "overall_summary")
+    {
+        summary.push_text(line);
+    }
 
-        match entry.r#type {
-            Some(CompactEntryType::Spawn(s)) => {
-                let spawn_exec = reconstruct_spawn_exec(s, &stored_entries);
-                reconstructed_spawns.push(spawn_exec);
-            }
-            Some(CompactEntryType::File(f)) if id != 0 => {
-                stored_entries.insert(id, StoredEntry::File(f));
-            }
-            Some(CompactEntryType::Directory(d)) if id != 0 => {
-                stored_entries.insert(id, StoredEntry::Directory(d));
-            }
-            // Ignore other entry types for now as they are not needed for the analysis.
-            _ => {}
-        }
+    if let Some(sarif_path) = sarif {
+        let findings = hermeticity_findings(&spawns);
+        let mut out = BufWriter::new(fs::File::create(sarif_path)?);
+        write_sarif(&findings, &mut out)?;
+        out.flush()?;
+        eprintln!("Wrote {} findings to {}.", findings.len(), sarif_path.display());
     }
-    Ok(reconstructed_spawns)
-}
-
-/// Converts a compact `Spawn` entry into a verbose `SpawnExec` using stored file/dir info.
-fn reconstruct_spawn_exec(
-    spawn: compact::Spawn,
-    stored_entries: &HashMap<u32, StoredEntry>,
-) -> SpawnExec {
-    let mut actual_outputs = Vec::new();
-    for output in spawn.outputs {
-        if let Some(compact::output::Type::OutputId(id)) = output.r#type {
-            if let Some(entry) = stored_entries.get(&id) {
-                match entry {
-                    StoredEntry::File(f) => {
-                        actual_outputs.push(crate::proto::File {
-                            path: f.path.clone(),
-                            digest: f.digest.clone(),
-                            symlink_target_path: String::new(),
-                            is_tool: false,
-                        });
-                    }
-                    StoredEntry::Directory(d) => {
-                        // The verbose format represents directories as a single File entry with a path.
-                        // We will omit the digest as it's not directly available/needed for metrics.
-                        actual_outputs.push(crate::proto::File {
-                            path: d.path.clone(),
-                            digest: None,
-                            symlink_target_path: String::new(),
-                            is_tool: false,
-                        });
-                    }
-                }
-            }
-        }
+
+    if !warnings.is_empty() {
+        document.sections.push(warnings.section());
     }
 
-    SpawnExec {
-        command_args: spawn.args,
-        environment_variables: spawn.env_vars,
-        platform: spawn.platform,
-        inputs: vec![],         // Not reconstructed as it's not used in analysis
-        listed_outputs: vec![], // Not reconstructed as it's not used in analysis
-        remotable: spawn.remotable,
-        cacheable: spawn.cacheable,
-        timeout_millis: spawn.timeout_millis,
-        mnemonic: spawn.mnemonic,
-        actual_outputs,
-        runner: spawn.runner,
-        cache_hit: spawn.cache_hit,
-        status: spawn.status,
-        exit_code: spawn.exit_code,
-        remote_cacheable: spawn.remote_cacheable,
-        target_label: spawn.target_label,
-        digest: spawn.digest,
-        metrics: spawn.metrics,
+    Ok(Some(document))
+}
+
+pub fn run_analyze(args: Cli) -> AppResult<()> {
+    if args.list_reports {
+        print_report_list();
+        return Ok(());
+    }
+    if args.emit_schema {
+        serde_json::to_writer_pretty(io::stdout().lock(), &report_schema(args.format_version)).map_err(io::Error::from)?;
+        println!();
+        return Ok(());
+    }
+    // Resolve report names before parsing so typos fail fast.
+    if args.summary_line && !selected_reports(&args.reports)?.iter().any(|report| report.name == "summary") {
+        return Err(AppError::Analysis("--summary-line needs the summary report; add it to --only.".to_string()));
     }
+
+    let Some(file) = args.file.as_deref() else {
+        return Err(AppError::Analysis("No execution log file was given.".to_string()));
+    };
+    let Some(document) = analysis_document(
+        file,
+        &args.reports,
+        &args.filters,
+        args.failures_only,
+        args.sarif.as_deref(),
+        args.format_version,
+    )?
+    else {
+        return Ok(());
+    };
+
+    let mut rendered = Vec::new();
+    let renderer = if args.summary_line { styled(Box::new(SummaryLineRenderer)) } else { renderer_for(args.output) };
+    renderer.render(&document, &mut rendered)?;
+    let binary = matches!(args.output, OutputFormat::Msgpack | OutputFormat::Cbor);
+    pager::write_paged(&rendered, args.no_pager || binary)?;
+    Ok(())
 }
 
 // --- ANALYSIS AND REPORTING FUNCTIONS ---
 
-fn print_main_report(spawns: &[SpawnExec], args: &Cli) {
+fn main_report_sections(spawns: &[SpawnExec], top_n: usize) -> Vec<Section> {
     let total_actions = spawns.len();
     let cache_hits = spawns.iter().filter(|s| s.cache_hit).count();
 
+    let mut summary = Section::new("overall_summary", "Overall Summary");
+    summary.push_field(Column::integer("total_actions", "Total Actions"), total_actions);
+    summary.push_field(Column::integer("cache_hits", "Cache Hits"), cache_hits);
+    summary.push_field(
+        Column::percent("cache_hit_rate", "Cache Hit Rate", 2),
+        percentage(cache_hits as f64, total_actions as f64),
+    );
+    let timed = missing_metrics(spawns).is_none();
+    if timed {
+        summary.push_field(
+            Column::seconds("execution_time", "Execution Time", 2),
+            spawns.iter().filter(|s| !s.cache_hit).map(total_time).sum::<Duration>(),
+        );
+    }
+    summary.push_field(
+        Column::integer("failed_actions", "Failed Actions"),
+        spawns.iter().filter(|s| is_failed(s)).count(),
+    );
+    if !timed {
+        summary.push_text(
+            "Warning: most spawns have no execution metrics, so timings are left out. Re-run the build with \
+             --execution_log_spawn_metrics to record them.",
+        );
+        return vec![summary, mnemonic_counts_section(spawns)];
+    }
+
     let mut slowest_actions: Vec<&SpawnExec> = spawns.iter().collect();
-    slowest_actions.sort_by_key(|s| {
-        s.metrics
-            .as_ref()
-            .and_then(|m| m.total_time.as_ref())
-            .map(to_std_duration)
-            .unwrap_or_default()
-    });
-    slowest_actions.reverse();
+    slowest_actions.sort_by_key(|s| std::cmp::Reverse(total_time(s)));
+
+    let build_time = BuildTime::of(spawns);
+    let mut slowest = Section::new("slowest_actions", format!("Top {} Slowest Actions", top_n));
+    let mut table = Table::new(
+        [
+            vec![Column::seconds("total_time", "Time", 3)],
+            build_time.columns(),
+            vec![Column::text("mnemonic", "Mnemonic"), Column::text("target", "Target"), spawn_id_column()],
+        ]
+        .concat(),
+    );
+    for spawn in slowest_actions.iter().take(top_n) {
+        table.push_row(
+            [
+                vec![total_time(spawn).into()],
+                build_time.shares(total_time(spawn).as_secs_f64()),
+                vec![(&spawn.mnemonic).into(), display_label(spawn).into(), spawn_id(spawn).into()],
+            ]
+            .concat(),
+        );
+    }
+    slowest.push_table(table);
 
-    let mut mnemonic_metrics: HashMap<String, MnemonicMetrics> = HashMap::new();
+    let mut mnemonic_metrics: HashMap<&str, MnemonicMetrics> = HashMap::new();
     for spawn in spawns {
-        let metrics = mnemonic_metrics.entry(spawn.mnemonic.clone()).or_default();
+        let metrics = mnemonic_metrics.entry(&spawn.mnemonic).or_default();
         metrics.count += 1;
         if spawn.cache_hit {
             metrics.cache_hits += 1;
         }
-        if let Some(m) = spawn.metrics.as_ref().and_then(|m| m.total_time.as_ref()) {
-            metrics.total_duration += to_std_duration(m);
-        }
+        metrics.total_duration += total_time(spawn);
     }
+    let mut sorted_mnemonics: Vec<_> = mnemonic_metrics.into_iter().collect();
+    sorted_mnemonics.sort_by_key(|(name, metrics)| (std::cmp::Reverse(metrics.total_duration), *name));
 
-    println!("========================================");
-    println!(" Bazel Execution Log Analysis Report");
-    println!("========================================");
-    println!("Log file: {}\n", args.file.display());
-    println!("--- Overall Summary ---");
-    println!("Total Actions: {}", total_actions);
-    println!(
-        "Cache Hits: {} ({:.2}%)",
-        cache_hits,
-        (cache_hits as f64 / total_actions as f64) * 100.0
+    let mut by_mnemonic = Section::new("mnemonics", "Analysis by Mnemonic");
+    let mut table = Table::new(
+        [
+            vec![
+                Column::text("mnemonic", "Mnemonic"),
+                Column::integer("count", "Count"),
+                Column::percent("cache_hit_rate", "Cache Hits", 1),
+                Column::seconds("total_time", "Total Time", 2),
+                Column::seconds("avg_time", "Avg Time", 3),
+            ],
+            build_time.columns(),
+        ]
+        .concat(),
     );
-    println!();
-    println!("--- Top {} Slowest Actions ---", args.top_n);
-    println!("{:<10} | {:<25} | {}", "Time", "Mnemonic", "Target");
-    println!("---------------------------------------------------------------------------------");
-    for spawn in slowest_actions.iter().take(args.top_n) {
-        let duration = spawn
-            .metrics
-            .as_ref()
-            .and_then(|m| m.total_time.as_ref())
-            .map(to_std_duration)
-            .unwrap_or_default();
-
-        println!(
-            "{:<10.3}s | {:<25} | {}",
-            duration.as_secs_f64(),
-            spawn.mnemonic,
-            spawn.target_label
+    for (mnemonic, metrics) in sorted_mnemonics {
+        let seconds = metrics.total_duration.as_secs_f64();
+        table.push_row(
+            [
+                vec![
+                    mnemonic.into(),
+                    metrics.count.into(),
+                    percentage(metrics.cache_hits as f64, metrics.count as f64).into(),
+                    metrics.total_duration.into(),
+                    (seconds / metrics.count as f64).into(),
+                ],
+                build_time.shares(seconds),
+            ]
+            .concat(),
         );
     }
-    println!();
-    println!("--- Analysis by Mnemonic ---");
-
-    // Calculate column widths based on actual data
-    let mut sorted_mnemonics: Vec<_> = mnemonic_metrics.iter().collect();
-    sorted_mnemonics.sort_by_key(|(_, metrics)| metrics.total_duration);
-    sorted_mnemonics.reverse();
-
-    let mnemonic_width = sorted_mnemonics
-        .iter()
-        .map(|(name, _)| name.len())
-        .max()
-        .unwrap_or(8)
-        .max(8); // "Mnemonic" header
-
-    let count_width = sorted_mnemonics
-        .iter()
-        .map(|(_, metrics)| metrics.count.to_string().len())
-        .max()
-        .unwrap_or(5)
-        .max(5); // "Count" header
+    by_mnemonic.push_table(table);
+    by_mnemonic.push_text(
+        "Note: Shares are of the summed time of all spawns and, with start times, of the build's wall time; \
+         running in parallel, a mnemonic can take more than 100% of the wall time.",
+    );
 
-    let cache_hits_width = sorted_mnemonics
-        .iter()
-        .map(|(_, metrics)| {
-            format!(
-                "{:.1}%",
-                (metrics.cache_hits as f64 / metrics.count as f64) * 100.0
-            )
-            .len()
-        })
-        .max()
-        .unwrap_or(10)
-        .max(10); // "Cache Hits" header
+    vec![summary, slowest, by_mnemonic]
+}
 
-    let total_time_width = sorted_mnemonics
-        .iter()
-        .map(|(_, metrics)| format!("{:.2}s", metrics.total_duration.as_secs_f64()).len())
-        .max()
-        .unwrap_or(10)
-        .max(10); // "Total Time" header
+/// The breakdown by mnemonic without the time columns, for logs without spawn metrics.
+fn mnemonic_counts_section(spawns: &[SpawnExec]) -> Section {
+    let mut counts: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+    for spawn in spawns {
+        let entry = counts.entry(&spawn.mnemonic).or_default();
+        entry.0 += 1;
+        entry.1 += u64::from(spawn.cache_hit);
+    }
+    let mut sorted: Vec<_> = counts.into_iter().collect();
+    sorted.sort_by_key(|(name, (count, _))| (std::cmp::Reverse(*count), *name));
 
-    let avg_time_width = sorted_mnemonics
-        .iter()
-        .map(|(_, metrics)| {
-            let avg_time = if metrics.count > 0 {
-                metrics.total_duration.as_secs_f64() / metrics.count as f64
-            } else {
-                0.0
-            };
-            format!("{:.3}s", avg_time).len()
-        })
-        .max()
-        .unwrap_or(8)
-        .max(8); // "Avg Time" header
-
-    // Print header
-    println!(
-        "{:<width1$} | {:>width2$} | {:>width3$} | {:>width4$} | {:>width5$}",
-        "Mnemonic",
-        "Count",
-        "Cache Hits",
-        "Total Time",
-        "Avg Time",
-        width1 = mnemonic_width,
-        width2 = count_width,
-        width3 = cache_hits_width,
-        width4 = total_time_width,
-        width5 = avg_time_width
-    );
+    let mut section = Section::new("mnemonics", "Analysis by Mnemonic");
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("count", "Count"),
+        Column::percent("cache_hit_rate", "Cache Hits", 1),
+    ]);
+    for (mnemonic, (count, cache_hits)) in sorted {
+        table.push_row(vec![
+            mnemonic.into(),
+            count.into(),
+            percentage(cache_hits as f64, count as f64).into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
 
-    // Print separator line
-    let separator_width =
-        mnemonic_width + count_width + cache_hits_width + total_time_width + avg_time_width + 12; // 12 for " | " separators
-    println!("{}", "-".repeat(separator_width));
+/// Fetches shorter than this are too noisy to derive a meaningful throughput from.
+const MIN_FETCH_SECONDS: f64 = 0.001;
 
-    // Print data rows
-    for (mnemonic, metrics) in sorted_mnemonics {
-        let avg_time = if metrics.count > 0 {
-            metrics.total_duration.as_secs_f64() / metrics.count as f64
-        } else {
-            0.0
-        };
-        println!(
-            "{:<width1$} | {:>width2$} | {:>width3$.1}% | {:>width4$.2}s | {:>width5$.3}s",
-            mnemonic,
-            metrics.count,
-            (metrics.cache_hits as f64 / metrics.count as f64) * 100.0,
-            metrics.total_duration.as_secs_f64(),
-            avg_time,
-            width1 = mnemonic_width,
-            width2 = count_width,
-            width3 = cache_hits_width - 1, // -1 for the % symbol
-            width4 = total_time_width - 1, // -1 for the s suffix
-            width5 = avg_time_width - 1    // -1 for the s suffix
-        );
+/// Nearest-rank percentile of an ascending slice, or `None` if it is empty.
+pub(crate) fn percentile(sorted: &[f64], pct: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
     }
-    println!();
+    let rank = (pct / 100.0 * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
 }
 
-fn print_cache_performance_report(spawns: &[SpawnExec]) {
+fn cache_performance_sections(spawns: &[SpawnExec], cache_hit_runners: &[String], top_n: usize) -> Vec<Section> {
+    let mut section = Section::new("cache_performance", "Remote Cache Performance");
+
     let mut total_bytes_downloaded: i64 = 0;
     let mut total_fetch_time = Duration::ZERO;
-    let mut remote_cache_hit_count = 0;
+    let mut remote_cache_hit_count: u64 = 0;
+    // (bytes, fetch seconds, MB/s, spawn) for every hit with a measurable fetch.
+    let mut fetches: Vec<(i64, f64, f64, &SpawnExec)> = Vec::new();
 
     for spawn in spawns {
-        if spawn.runner == "remote cache hit" {
+        if cache_hit_runners.contains(&spawn.runner) {
             remote_cache_hit_count += 1;
-            let bytes_for_spawn: i64 = spawn
-                .actual_outputs
-                .iter()
-                .filter_map(|file| file.digest.as_ref())
-                .map(|digest| digest.size_bytes)
-                .sum();
-            total_bytes_downloaded += bytes_for_spawn;
-            if let Some(fetch_duration) = spawn.metrics.as_ref().and_then(|m| m.fetch_time.as_ref())
-            {
-                total_fetch_time += to_std_duration(fetch_duration);
+            let bytes = output_bytes(spawn);
+            let fetch_time = phase_duration(spawn, |m| m.fetch_time.as_ref());
+            total_bytes_downloaded += bytes;
+            total_fetch_time += fetch_time;
+            let fetch_seconds = fetch_time.as_secs_f64();
+            if bytes > 0 && fetch_seconds > MIN_FETCH_SECONDS {
+                fetches.push((bytes, fetch_seconds, bytes as f64 / 1_000_000.0 / fetch_seconds, spawn));
             }
         }
     }
 
-    println!("--- Remote Cache Performance ---");
     if remote_cache_hit_count == 0 {
-        println!("No remote cache hits found in the log.");
-        println!();
-        return;
+        section.push_text("No remote cache hits found in the log.");
+        return vec![section];
     }
-    let total_mb_downloaded = total_bytes_downloaded as f64 / 1_000_000.0;
     let total_fetch_seconds = total_fetch_time.as_secs_f64();
-    println!("Remote Cache Hits Count: {}", remote_cache_hit_count);
-    println!("Total Data Downloaded: {:.2} MB", total_mb_downloaded);
-    println!(
-        "Total Time Fetching from Cache: {:.2}s",
-        total_fetch_seconds
+    section.push_field(
+        Column::integer("remote_cache_hits", "Remote Cache Hits Count"),
+        remote_cache_hit_count,
     );
-    if total_fetch_seconds > 0.001 {
-        let download_rate_mbps = total_mb_downloaded / total_fetch_seconds;
-        println!("Average Download Rate: {:.2} MB/s", download_rate_mbps);
-    } else {
-        println!("Average Download Rate: N/A (total fetch time is negligible)");
+    section.push_field(
+        Column::bytes("bytes_downloaded", "Total Data Downloaded", ByteUnit::DecimalMegabytes, 2),
+        total_bytes_downloaded,
+    );
+    section.push_field(
+        Column::seconds("fetch_time", "Total Time Fetching from Cache", 2),
+        total_fetch_time,
+    );
+    let rate = (total_fetch_seconds > MIN_FETCH_SECONDS)
+        .then(|| total_bytes_downloaded as f64 / 1_000_000.0 / total_fetch_seconds);
+    section.push_field(
+        Column::float("download_rate_mb_per_second", "Average Download Rate (MB/s)", 2),
+        rate,
+    );
+    if rate.is_none() {
+        section.push_text("Average download rate is N/A (total fetch time is negligible).");
     }
-    println!();
-}
 
-fn print_phase_timings_report(spawns: &[SpawnExec], top_n: usize) {
-    println!("--- Top {} Slowest Actions (Phase Timings) ---", top_n);
-    println!("Note: This report excludes cache hits as phase timings are most relevant for executed actions.");
+    if fetches.is_empty() {
+        return vec![section];
+    }
+    let mut rates: Vec<f64> = fetches.iter().map(|(_, _, rate, _)| *rate).collect();
+    rates.sort_by(f64::total_cmp);
+    for (key, title, pct) in [
+        ("download_rate_p10", "Per-Fetch Rate p10 (MB/s)", 10.0),
+        ("download_rate_p50", "Per-Fetch Rate p50 (MB/s)", 50.0),
+        ("download_rate_p90", "Per-Fetch Rate p90 (MB/s)", 90.0),
+    ] {
+        section.push_field(Column::float(key, title, 2), percentile(&rates, pct));
+    }
+    section.push_text(format!(
+        "Per-fetch rates cover {} hits with outputs and a fetch time over {}ms.",
+        fetches.len(),
+        MIN_FETCH_SECONDS * 1000.0
+    ));
+
+    let mut slowest = Section::new(
+        "slowest_fetches",
+        format!("Top {} Slowest Cache Fetches (by Throughput)", top_n),
+    );
+    fetches.sort_by(|a, b| a.2.total_cmp(&b.2));
+    let mut table = Table::new(vec![
+        Column::float("download_rate_mb_per_second", "MB/s", 2),
+        Column::bytes("bytes", "Size", ByteUnit::DecimalMegabytes, 2),
+        Column::seconds("fetch_time", "Fetch Time", 3),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
+    for (bytes, fetch_seconds, rate, spawn) in fetches.iter().take(top_n) {
+        table.push_row(vec![
+            (*rate).into(),
+            (*bytes).into(),
+            (*fetch_seconds).into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    slowest.push_table(table);
+    slowest.push_text("Hint: A low p50 points at a slow CAS or network; a healthy p50 with a long total fetch time points at a few huge artifacts.");
+    vec![section, slowest]
+}
 
-    let mut non_cache_hits: Vec<&SpawnExec> = spawns.iter().filter(|s| !s.cache_hit).collect();
-    non_cache_hits.sort_by_key(|s| {
-        s.metrics
-            .as_ref()
-            .and_then(|m| m.total_time.as_ref())
-            .map(to_std_duration)
-            .unwrap_or_default()
-    });
-    non_cache_hits.reverse();
+/// Width of the per-action composition bar in the phase timings report.
+const COMPOSITION_WIDTH: usize = 20;
 
-    if non_cache_hits.is_empty() {
-        println!("No executed actions found (all were cache hits).");
-        println!();
-        return;
-    }
-
-    // Calculate column widths based on actual data
-    let actions_to_display = non_cache_hits.iter().take(top_n);
-    
-    let total_width = actions_to_display.clone()
-        .map(|s| {
-            let total = s.metrics.as_ref()
-                .and_then(|m| m.total_time.as_ref())
-                .map(to_std_duration)
-                .unwrap_or_default();
-            format!("{:.2}s", total.as_secs_f64()).len()
-        })
-        .max()
-        .unwrap_or(5)
-        .max(5); // "Total" header
-
-    let queue_width = actions_to_display.clone()
-        .map(|s| {
-            let queue = s.metrics.as_ref()
-                .and_then(|m| m.queue_time.as_ref())
-                .map(to_std_duration)
-                .unwrap_or_default();
-            format!("{:.2}s", queue.as_secs_f64()).len()
-        })
-        .max()
-        .unwrap_or(5)
-        .max(5); // "Queue" header
-
-    let setup_width = actions_to_display.clone()
-        .map(|s| {
-            let setup = s.metrics.as_ref()
-                .and_then(|m| m.setup_time.as_ref())
-                .map(to_std_duration)
-                .unwrap_or_default();
-            format!("{:.2}s", setup.as_secs_f64()).len()
-        })
-        .max()
-        .unwrap_or(5)
-        .max(5); // "Setup" header
-
-    let upload_width = actions_to_display.clone()
-        .map(|s| {
-            let upload = s.metrics.as_ref()
-                .and_then(|m| m.upload_time.as_ref())
-                .map(to_std_duration)
-                .unwrap_or_default();
-            format!("{:.2}s", upload.as_secs_f64()).len()
-        })
-        .max()
-        .unwrap_or(6)
-        .max(6); // "Upload" header
+/// Symbols of the composition bar in plain-text output, one per phase in table order.
+const COMPOSITION_SYMBOLS: &[char] = &['Q', 'S', 'U', 'E', 'F'];
 
-    let execute_width = actions_to_display.clone()
-        .map(|s| {
-            let execution = s.metrics.as_ref()
-                .and_then(|m| m.execution_wall_time.as_ref())
-                .map(to_std_duration)
-                .unwrap_or_default();
-            format!("{:.2}s", execution.as_secs_f64()).len()
-        })
-        .max()
-        .unwrap_or(7)
-        .max(7); // "Execute" header
-
-    let fetch_width = actions_to_display.clone()
-        .map(|s| {
-            let fetch = s.metrics.as_ref()
-                .and_then(|m| m.fetch_time.as_ref())
-                .map(to_std_duration)
-                .unwrap_or_default();
-            format!("{:.2}s", fetch.as_secs_f64()).len()
-        })
-        .max()
-        .unwrap_or(5)
-        .max(5); // "Fetch" header
-
-    // Print header
-    println!(
-        "{:>width1$} | {:>width2$} | {:>width3$} | {:>width4$} | {:>width5$} | {:>width6$} | {}",
-        "Total", "Queue", "Setup", "Upload", "Execute", "Fetch", "Target",
-        width1 = total_width,
-        width2 = queue_width,
-        width3 = setup_width,
-        width4 = upload_width,
-        width5 = execute_width,
-        width6 = fetch_width
+fn phase_timings_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new(
+        "phase_timings",
+        format!("Top {} Slowest Actions (Phase Timings)", top_n),
     );
-    
-    // Print separator line
-    let separator_width = total_width + queue_width + setup_width + upload_width + execute_width + fetch_width + 18 + 6; // separators + "Target"
-    println!("{}", "-".repeat(separator_width));
+    section.push_text("Note: This report excludes cache hits as phase timings are most relevant for executed actions.");
 
-    for spawn in non_cache_hits.iter().take(top_n) {
-        if let Some(metrics) = spawn.metrics.as_ref() {
-            let total = metrics.total_time.as_ref().map(to_std_duration).unwrap_or_default();
-            let queue = metrics.queue_time.as_ref().map(to_std_duration).unwrap_or_default();
-            let setup = metrics.setup_time.as_ref().map(to_std_duration).unwrap_or_default();
-            let upload = metrics.upload_time.as_ref().map(to_std_duration).unwrap_or_default();
-            let execution = metrics.execution_wall_time.as_ref().map(to_std_duration).unwrap_or_default();
-            let fetch = metrics.fetch_time.as_ref().map(to_std_duration).unwrap_or_default();
-
-            // Calculate overhead for display
-            let overhead_pct = if total.as_secs_f64() > 0.0 {
-                (total - execution).as_secs_f64() / total.as_secs_f64() * 100.0
-            } else {
-                0.0
-            };
+    let mut non_cache_hits: Vec<&SpawnExec> = spawns
+        .iter()
+        .filter(|s| !s.cache_hit && s.metrics.is_some())
+        .collect();
+    if non_cache_hits.is_empty() {
+        section.push_text("No executed actions found (all were cache hits).");
+        return section;
+    }
+    non_cache_hits.sort_by_key(|s| std::cmp::Reverse(total_time(s)));
 
-            println!(
-                "{:>width1$.2}s | {:>width2$.2}s | {:>width3$.2}s | {:>width4$.2}s | {:>width5$.2}s | {:>width6$.2}s | {}",
-                total.as_secs_f64(),
-                queue.as_secs_f64(),
-                setup.as_secs_f64(),
-                upload.as_secs_f64(),
-                execution.as_secs_f64(),
-                fetch.as_secs_f64(),
-                spawn.target_label,
-                width1 = total_width - 1, // -1 for 's' suffix
-                width2 = queue_width - 1,
-                width3 = setup_width - 1,
-                width4 = upload_width - 1,
-                width5 = execute_width - 1,
-                width6 = fetch_width - 1
-            );
-            println!("  └ Overhead: {:.1}%", overhead_pct);
-        }
+    let mut table = Table::new(vec![
+        Column::seconds("total_time", "Total", 2),
+        Column::seconds("queue_time", "Queue", 2),
+        Column::seconds("setup_time", "Setup", 2),
+        Column::seconds("upload_time", "Upload", 2),
+        Column::seconds("execution_time", "Execute", 2),
+        Column::seconds("fetch_time", "Fetch", 2),
+        Column::percent("overhead", "Overhead", 1),
+        Column::bar("composition", "Composition", COMPOSITION_WIDTH, COMPOSITION_SYMBOLS),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
+    for spawn in non_cache_hits.iter().take(top_n) {
+        let total = total_time(spawn);
+        let execution = phase_duration(spawn, |m| m.execution_wall_time.as_ref());
+        // Overhead is everything that is not the action's own execution.
+        let overhead_pct = percentage(total.saturating_sub(execution).as_secs_f64(), total.as_secs_f64());
+        let phases = [
+            phase_duration(spawn, |m| m.queue_time.as_ref()),
+            phase_duration(spawn, |m| m.setup_time.as_ref()),
+            phase_duration(spawn, |m| m.upload_time.as_ref()),
+            execution,
+            phase_duration(spawn, |m| m.fetch_time.as_ref()),
+        ];
+        let composition = phases
+            .iter()
+            .map(|phase| if total.is_zero() { 0.0 } else { phase.as_secs_f64() / total.as_secs_f64() })
+            .collect();
+        table.push_row(vec![
+            total.into(),
+            phases[0].into(),
+            phases[1].into(),
+            phases[2].into(),
+            execution.into(),
+            phases[4].into(),
+            overhead_pct.into(),
+            Value::Bar(composition),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
     }
-    println!();
+    section.push_table(table);
+    section.push_text("Note: In the composition bar Q = queue, S = setup, U = upload, E = execute and F = fetch; the rest is time outside these phases.");
+    section
 }
 
-fn print_input_analysis_report(spawns: &[SpawnExec], top_n: usize) {
-    println!("--- Top {} Actions by Input Size ---", top_n);
-
-    let mut sorted_by_size = spawns.to_vec();
-    sorted_by_size.sort_by_key(|s| s.metrics.as_ref().map_or(0, |m| m.input_bytes));
-    sorted_by_size.reverse();
+fn input_analysis_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new("input_analysis", format!("Top {} Actions by Input Size", top_n));
 
     // Filter out actions with no input data
-    let actions_with_inputs: Vec<_> = sorted_by_size
+    let mut actions_with_inputs: Vec<(&SpawnExec, &SpawnMetrics)> = spawns
         .iter()
-        .filter(|s| s.metrics.as_ref().map_or(false, |m| m.input_bytes > 0))
+        .filter_map(|s| s.metrics.as_ref().map(|m| (s, m)))
+        .filter(|(_, m)| m.input_bytes > 0)
         .collect();
 
     if actions_with_inputs.is_empty() {
-        println!("No actions with input size data found in the log.");
-        println!();
-        return;
+        section.push_text("No actions with input size data found in the log.");
+        return section;
     }
+    actions_with_inputs.sort_by_key(|(_, m)| std::cmp::Reverse(m.input_bytes));
 
-    // Calculate column widths based on actual data
-    let actions_to_display = actions_with_inputs.iter().take(top_n);
-    
-    let size_width = actions_to_display.clone()
-        .map(|s| {
-            let size_mb = s.metrics.as_ref().unwrap().input_bytes as f64 / 1_048_576.0;
-            format!("{:.2}MB", size_mb).len()
-        })
-        .max()
-        .unwrap_or(10)
-        .max(10); // "Input Size" header
-
-    let files_width = actions_to_display.clone()
-        .map(|s| s.metrics.as_ref().unwrap().input_files.to_string().len())
-        .max()
-        .unwrap_or(11)
-        .max(11); // "Input Files" header
-
-    // Print header
-    println!(
-        "{:>width1$} | {:>width2$} | {}",
-        "Input Size", "Input Files", "Target",
-        width1 = size_width,
-        width2 = files_width
-    );
-    
-    // Print separator line
-    let separator_width = size_width + files_width + 6 + 6; // separators + "Target"
-    println!("{}", "-".repeat(separator_width));
-
-    for spawn in actions_with_inputs.iter().take(top_n) {
-        if let Some(metrics) = spawn.metrics.as_ref() {
-            println!(
-                "{:>width1$.2}MB | {:>width2$} | {}",
-                metrics.input_bytes as f64 / 1_048_576.0,
-                metrics.input_files,
-                spawn.target_label,
-                width1 = size_width - 2, // -2 for "MB" suffix
-                width2 = files_width
-            );
+    let mut table = Table::new(vec![
+        Column::bytes("input_bytes", "Input Size", ByteUnit::Megabytes, 2),
+        Column::integer("input_files", "Input Files"),
+        Column::text("params_files", "Params File"),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
+    for (spawn, metrics) in actions_with_inputs.iter().take(top_n) {
+        table.push_row(vec![
+            metrics.input_bytes.into(),
+            metrics.input_files.into(),
+            describe_params_files(spawn).into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    section.push_table(table);
+
+    // Summarize params file usage across the whole log, not only the displayed rows.
+    let mut referencing = 0;
+    let mut found = 0;
+    let mut found_bytes: i64 = 0;
+    for spawn in spawns {
+        let params = referenced_params_files(spawn);
+        if params.is_empty() {
+            continue;
+        }
+        referencing += 1;
+        for path in params {
+            if let Some(size) = params_file_size(spawn, path) {
+                found += 1;
+                found_bytes += size;
+            }
         }
     }
-    println!();
+    if referencing > 0 {
+        section.push_text(format!(
+            "{} actions reference params files; {} params files found among reconstructed inputs ({:.2}MB total).",
+            referencing,
+            found,
+            found_bytes as f64 / 1_048_576.0
+        ));
+        if spawns.iter().all(|s| s.inputs.is_empty()) {
+            section.push_text("Note: Inputs are not reconstructed for this log, so params file sizes are unknown.");
+        }
+    }
+    section
+}
+
+/// Returns the paths of params files referenced on the command line (`@file` or `--flagfile=file`).
+fn referenced_params_files(spawn: &SpawnExec) -> Vec<&str> {
+    spawn
+        .command_args
+        .iter()
+        .filter(|arg| is_params_file_arg(arg))
+        .map(|arg| {
+            arg.strip_prefix("--flagfile=")
+                .or_else(|| arg.strip_prefix('@'))
+                .unwrap_or(arg)
+        })
+        .collect()
+}
+
+/// Looks up the size of a params file among the spawn's inputs. Params paths on the command line
+/// may be relative to the exec root while inputs are not, so a suffix match is accepted too.
+fn params_file_size(spawn: &SpawnExec, params_path: &str) -> Option<i64> {
+    spawn
+        .inputs
+        .iter()
+        .find(|input| input.path == params_path || input.path.ends_with(params_path) || params_path.ends_with(&input.path))
+        .map(|input| input.digest.as_ref().map_or(0, |d| d.size_bytes))
+}
+
+/// Renders the params-file column of the input report: total size, "missing" or "-" if none are used.
+fn describe_params_files(spawn: &SpawnExec) -> String {
+    let params = referenced_params_files(spawn);
+    if params.is_empty() {
+        return "-".to_string();
+    }
+    if spawn.inputs.is_empty() {
+        return "unknown".to_string();
+    }
+    let sizes: Vec<Option<i64>> = params.iter().map(|p| params_file_size(spawn, p)).collect();
+    if sizes.iter().any(Option::is_none) {
+        return "missing".to_string();
+    }
+    format!("{:.1}KB", sizes.into_iter().flatten().sum::<i64>() as f64 / 1024.0)
 }
 
-fn print_retries_and_failures_report(spawns: &[SpawnExec]) {
-    println!("--- Actions with Failures or Retries ---");
+fn retries_and_failures_section(spawns: &[SpawnExec]) -> Section {
+    let mut section = Section::new("retries", "Actions with Failures or Retries");
 
     let problematic_spawns: Vec<_> = spawns
         .iter()
         .filter(|s| {
-            !s.status.is_empty() || s.metrics.as_ref().map_or(false, |m| {
-                m.retry_time.as_ref().map_or(false, |d| d.seconds > 0 || d.nanos > 0)
-            })
+            !s.status.is_empty() || !phase_duration(s, |m| m.retry_time.as_ref()).is_zero()
         })
         .collect();
 
     if problematic_spawns.is_empty() {
-        println!("No actions with failures or retries found.");
-    } else {
-        for spawn in problematic_spawns {
-            let retry_duration = spawn
-                .metrics
-                .as_ref()
-                .and_then(|m| m.retry_time.as_ref())
-                .map(to_std_duration)
-                .unwrap_or_default();
-            
-            println!("Target: {}", spawn.target_label);
-            if !spawn.status.is_empty() {
-                println!("  └ Status: {} (Exit Code: {})", spawn.status, spawn.exit_code);
-            }
-            if !retry_duration.is_zero() {
-                println!("  └ Time in Retries: {:.3}s", retry_duration.as_secs_f64());
-            }
-        }
+        section.push_text("No actions with failures or retries found.");
+        return section;
     }
-    println!();
+
+    let mut table = Table::new(vec![
+        Column::text("status", "Status"),
+        Column::integer("exit_code", "Exit Code"),
+        Column::seconds("retry_time", "Retry Time", 3),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
+    for spawn in problematic_spawns {
+        let retry_duration = phase_duration(spawn, |m| m.retry_time.as_ref());
+        let failed = !spawn.status.is_empty();
+        table.push_row(vec![
+            failed.then_some(&spawn.status).into(),
+            failed.then_some(spawn.exit_code).into(),
+            (!retry_duration.is_zero()).then_some(retry_duration).into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
+
+#[derive(Default)]
+struct RetryStats {
+    count: u64,
+    retried: u64,
+    total_duration: Duration,
+    retry_duration: Duration,
 }
 
-fn print_aggregate_phases_report(spawns: &[SpawnExec]) {
-    println!("--- Aggregate Phase Timings (Executed Actions) ---");
-    
-    let mut total_time = Duration::ZERO;
-    let mut total_queue = Duration::ZERO;
-    let mut total_setup = Duration::ZERO;
-    let mut total_upload = Duration::ZERO;
-    let mut total_execution = Duration::ZERO;
-    let mut total_fetch = Duration::ZERO;
-    let mut total_retry = Duration::ZERO;
-    
-    let mut executed_count = 0;
-    
+/// Retry time build-wide, per mnemonic ranked by the share of their time spent retrying, and per
+/// runner kind, which separates remote execution errors from local ones.
+fn retry_time_sections(spawns: &[SpawnExec], top_n: usize) -> Vec<Section> {
+    let retry_time = |s: &SpawnExec| phase_duration(s, |m| m.retry_time.as_ref());
+    let mut section = Section::new("retry_time", "Time Spent Retrying");
+    let mut by_mnemonic: HashMap<&str, RetryStats> = HashMap::new();
+    let mut by_runner: BTreeMap<RunnerKind, RetryStats> = BTreeMap::new();
     for spawn in spawns {
-        if !spawn.cache_hit {
-            executed_count += 1;
-            if let Some(metrics) = spawn.metrics.as_ref() {
-                if let Some(d) = metrics.total_time.as_ref() {
-                    total_time += to_std_duration(d);
-                }
-                if let Some(d) = metrics.queue_time.as_ref() {
-                    total_queue += to_std_duration(d);
-                }
-                if let Some(d) = metrics.setup_time.as_ref() {
-                    total_setup += to_std_duration(d);
-                }
-                if let Some(d) = metrics.upload_time.as_ref() {
-                    total_upload += to_std_duration(d);
-                }
-                if let Some(d) = metrics.execution_wall_time.as_ref() {
-                    total_execution += to_std_duration(d);
-                }
-                if let Some(d) = metrics.fetch_time.as_ref() {
-                    total_fetch += to_std_duration(d);
-                }
-                if let Some(d) = metrics.retry_time.as_ref() {
-                    total_retry += to_std_duration(d);
-                }
-            }
+        let retry = retry_time(spawn);
+        for stats in [by_mnemonic.entry(&spawn.mnemonic).or_default(), by_runner.entry(runner_kind(spawn)).or_default()] {
+            stats.count += 1;
+            stats.total_duration += total_time(spawn);
+            stats.retry_duration += retry;
+            stats.retried += u64::from(!retry.is_zero());
         }
     }
-    
-    if executed_count == 0 {
-        println!("No executed actions found (all were cache hits).");
-        println!();
-        return;
+    let retry_total: Duration = by_runner.values().map(|s| s.retry_duration).sum();
+    if retry_total.is_zero() {
+        section.push_text("No time was spent retrying.");
+        return vec![section];
     }
-    
-    let total_seconds = total_time.as_secs_f64();
-    
-    println!("Executed Actions: {}", executed_count);
-    println!("Total Execution Time: {:.2}s", total_seconds);
-    println!();
-    
-    println!("{:<15} | {:>10} | {:>8}", "Phase", "Time", "% of Total");
-    println!("----------------------------------------");
-    
-    let phases = [
-        ("Queue", total_queue),
-        ("Setup", total_setup),
-        ("Upload", total_upload),
-        ("Execution", total_execution),
-        ("Fetch", total_fetch),
-        ("Retry", total_retry),
-    ];
-    
-    for (name, duration) in phases {
-        let seconds = duration.as_secs_f64();
-        let percentage = if total_seconds > 0.0 {
-            (seconds / total_seconds) * 100.0
-        } else {
-            0.0
-        };
-        println!("{:<15} | {:>10.2}s | {:>7.1}%", name, seconds, percentage);
+    let total: Duration = by_runner.values().map(|s| s.total_duration).sum();
+    section.push_field(Column::seconds("retry_time", "Total Retry Time", 2), retry_total);
+    section.push_field(
+        Column::percent("retry_share", "% of Total Time", 2),
+        percentage(retry_total.as_secs_f64(), total.as_secs_f64()),
+    );
+    section.push_field(
+        Column::integer("retried_actions", "Retried Actions"),
+        by_runner.values().map(|s| s.retried).sum::<u64>(),
+    );
+
+    let columns = |name: Column| {
+        vec![
+            name,
+            Column::integer("count", "Actions"),
+            Column::integer("retried", "Retried"),
+            Column::seconds("retry_time", "Retry Time", 2),
+            Column::percent("retry_share", "% of Time Retrying", 1),
+        ]
+    };
+    let row = |name: &str, stats: &RetryStats| -> Vec<Value> {
+        vec![
+            name.into(),
+            stats.count.into(),
+            stats.retried.into(),
+            stats.retry_duration.into(),
+            percentage(stats.retry_duration.as_secs_f64(), stats.total_duration.as_secs_f64()).into(),
+        ]
+    };
+
+    let mut mnemonics = Section::new("retry_prone_mnemonics", "Retry-Prone Mnemonics");
+    let mut ranked: Vec<_> = by_mnemonic.into_iter().filter(|(_, stats)| stats.retried > 0).collect();
+    let share = |stats: &RetryStats| stats.retry_duration.as_secs_f64() / stats.total_duration.as_secs_f64().max(f64::EPSILON);
+    ranked.sort_by(|a, b| share(&b.1).total_cmp(&share(&a.1)).then(a.0.cmp(b.0)));
+    let mut table = Table::new(columns(Column::text("mnemonic", "Mnemonic")));
+    for (mnemonic, stats) in ranked.iter().take(top_n) {
+        table.push_row(row(mnemonic, stats));
     }
-    println!();
+    mnemonics.push_table(table);
+
+    let mut runners = Section::new("retry_by_runner", "Retry Time by Runner");
+    let mut table = Table::new(columns(Column::text("runner", "Runner")));
+    for (kind, stats) in by_runner.iter().filter(|(_, stats)| stats.retried > 0) {
+        table.push_row(row(kind.name(), stats));
+    }
+    runners.push_table(table);
+    runners.push_text("Hint: Retries on remote runners usually point at executor or network errors (tuned with --remote_retries); retries on local runners at flaky tools or resource exhaustion.");
+    vec![section, mnemonics, runners]
 }
 
-fn print_output_analysis_report(spawns: &[SpawnExec], top_n: usize) {
-    println!("--- Top {} Actions by Output Size ---", top_n);
-    
-    let mut size_data: Vec<(i64, &SpawnExec)> = Vec::new();
-    
-    for spawn in spawns {
-        let total_output_size: i64 = spawn
-            .actual_outputs
+/// Whether a spawn failed: it has a non-success status or a non-zero exit code.
+pub(crate) fn is_failed(spawn: &SpawnExec) -> bool {
+    !spawn.status.is_empty() || spawn.exit_code != 0
+}
+
+/// Names and usual causes of the signals that end build actions, by number.
+const SIGNALS: &[(i32, &str, &str)] = &[
+    (1, "SIGHUP", "hangup"),
+    (2, "SIGINT", "interrupted"),
+    (3, "SIGQUIT", "quit"),
+    (4, "SIGILL", "illegal instruction"),
+    (6, "SIGABRT", "aborted, e.g. a failed assertion or allocation"),
+    (7, "SIGBUS", "bus error, e.g. a truncated memory-mapped file"),
+    (8, "SIGFPE", "arithmetic error"),
+    (9, "SIGKILL", "killed, usually by the OOM killer"),
+    (11, "SIGSEGV", "segmentation fault"),
+    (13, "SIGPIPE", "broken pipe"),
+    (14, "SIGALRM", "alarm"),
+    (15, "SIGTERM", "terminated, e.g. on timeout or cancellation"),
+    (24, "SIGXCPU", "CPU time limit exceeded"),
+    (25, "SIGXFSZ", "file size limit exceeded"),
+];
+
+/// The signal that ended a spawn: exit codes above 128 are 128 plus the signal number, as shells
+/// and Bazel report them. Spawns Bazel marked out of memory count as SIGKILL.
+fn exit_signal(spawn: &SpawnExec) -> Option<i32> {
+    if spawn.status.contains("OUT_OF_MEMORY") {
+        return Some(9);
+    }
+    (129..=128 + 64).contains(&spawn.exit_code).then_some(spawn.exit_code - 128)
+}
+
+fn signal_name(signal: i32) -> (String, &'static str) {
+    match SIGNALS.iter().find(|(number, _, _)| *number == signal) {
+        Some((_, name, meaning)) => (name.to_string(), meaning),
+        None => (format!("signal {}", signal), ""),
+    }
+}
+
+/// Memory estimate over limit, when the spawn has both.
+fn memory_usage(spawn: &SpawnExec) -> Option<f64> {
+    let metrics = spawn.metrics.as_ref()?;
+    (metrics.memory_bytes_limit > 0 && metrics.memory_estimate_bytes > 0)
+        .then(|| metrics.memory_estimate_bytes as f64 / metrics.memory_bytes_limit as f64)
+}
+
+fn signal_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new("exit_signals", "Actions Killed by Signals");
+    let mut killed: Vec<(i32, &SpawnExec)> = spawns.iter().filter_map(|s| exit_signal(s).map(|signal| (signal, s))).collect();
+    if killed.is_empty() {
+        section.push_text("No actions were killed by a signal.");
+        return section;
+    }
+
+    let mut by_signal: BTreeMap<i32, (usize, usize, usize)> = BTreeMap::new();
+    for (signal, spawn) in &killed {
+        let (count, remote, local) = by_signal.entry(*signal).or_default();
+        *count += 1;
+        match runner_kind(spawn) {
+            RunnerKind::Remote => *remote += 1,
+            _ if is_local_runner(&spawn.runner) => *local += 1,
+            _ => {}
+        }
+    }
+    let mut summary = Table::new(vec![
+        Column::text("signal", "Signal"),
+        Column::integer("exit_code", "Exit Code"),
+        Column::text("meaning", "Meaning"),
+        Column::integer("actions", "Actions"),
+        Column::integer("remote", "Remote"),
+        Column::integer("local", "Local"),
+    ]);
+    for (signal, (count, remote, local)) in &by_signal {
+        let (name, meaning) = signal_name(*signal);
+        summary.push_row(vec![
+            name.into(),
+            (128 + signal).into(),
+            meaning.into(),
+            (*count).into(),
+            (*remote).into(),
+            (*local).into(),
+        ]);
+    }
+    section.push_table(summary);
+
+    // SIGKILL first, the actions closest to their memory limit first within each signal.
+    killed.sort_by(|(a, x), (b, y)| {
+        (*b == 9).cmp(&(*a == 9)).then(a.cmp(b)).then(memory_usage(y).unwrap_or(0.0).total_cmp(&memory_usage(x).unwrap_or(0.0)))
+    });
+    let mut table = Table::new(vec![
+        Column::text("signal", "Signal"),
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+        Column::text("runner", "Runner"),
+        Column::bytes("memory_estimate_bytes", "Memory Estimate", ByteUnit::Megabytes, 1),
+        Column::bytes("memory_limit_bytes", "Memory Limit", ByteUnit::Megabytes, 1),
+        Column::percent("usage", "Usage %", 1),
+        spawn_id_column(),
+    ]);
+    for (signal, spawn) in killed.iter().take(top_n) {
+        let metrics = spawn.metrics.as_ref();
+        let bytes = |value: i64| if value > 0 { Value::from(value) } else { Value::Null };
+        table.push_row(vec![
+            signal_name(*signal).0.into(),
+            spawn.mnemonic.as_str().into(),
+            display_label(spawn).into(),
+            spawn.runner.as_str().into(),
+            bytes(metrics.map_or(0, |m| m.memory_estimate_bytes)),
+            bytes(metrics.map_or(0, |m| m.memory_bytes_limit)),
+            memory_usage(spawn).map(|usage| usage * 100.0).into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    section.push_table(table);
+
+    let sigkilled: Vec<&SpawnExec> = killed.iter().filter(|(signal, _)| *signal == 9).map(|(_, s)| *s).collect();
+    let near_limit = sigkilled.iter().filter(|s| memory_usage(s).is_some_and(|usage| usage >= OOM_RISK_RATIO)).count();
+    if near_limit > 0 {
+        section.push_text(format!(
+            "Warning: {} of the {} SIGKILLed actions were estimated at {:.0}% or more of their memory limit and were \
+             most likely killed for running out of memory. Raise their limit (e.g. a `memory` exec property for \
+             remote execution or `resource_set` locally) or run fewer of them at once.",
+            near_limit,
+            sigkilled.len(),
+            OOM_RISK_RATIO * 100.0
+        ));
+    } else if !sigkilled.is_empty() {
+        section.push_text(
+            "Hint: no SIGKILLed action was estimated near its own memory limit, but the OOM killer also acts on \
+             the whole machine or container; the worker's kernel log or the remote executor's logs confirm it.",
+        );
+    }
+    section
+}
+
+/// Sections for `--failures-only`: every failed spawn, then the first `top_n` of them in full
+/// with the other spawns of the same target.
+fn failure_triage_sections(spawns: &[SpawnExec], top_n: usize) -> Vec<Section> {
+    let mut failed: Vec<&SpawnExec> = spawns.iter().filter(|s| is_failed(s)).collect();
+    let mut overview = Section::new("failures", "Failed Spawns");
+    if failed.is_empty() {
+        overview.push_text("No failed spawns found in the log.");
+        return vec![overview];
+    }
+    failed.sort_by(|a, b| start_seconds(a).unwrap_or(0.0).total_cmp(&start_seconds(b).unwrap_or(0.0)));
+
+    overview.push_field(Column::integer("failed_spawns", "Failed Spawns"), failed.len());
+    let mut table = Table::new(vec![
+        Column::float("start_time", "Start Time (Unix s)", 3),
+        Column::integer("exit_code", "Exit Code"),
+        Column::text("status", "Status"),
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
+    for spawn in &failed {
+        table.push_row(vec![
+            start_seconds(spawn).into(),
+            spawn.exit_code.into(),
+            (!spawn.status.is_empty()).then_some(&spawn.status).into(),
+            (&spawn.mnemonic).into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    overview.push_table(table);
+
+    let mut sections = vec![overview];
+    sections.extend(first_failure_section(spawns, top_n));
+    for (index, spawn) in failed.iter().take(top_n).enumerate() {
+        let mut section = spawn_section(&format!("failure_{}", index + 1), spawn);
+        section.title = format!("Failure {}: {}", index + 1, section.title);
+        let siblings: Vec<&SpawnExec> = spawns
             .iter()
-            .filter_map(|file| file.digest.as_ref())
-            .map(|digest| digest.size_bytes)
-            .sum();
-        
-        if total_output_size > 0 {
-            size_data.push((total_output_size, spawn));
+            .filter(|s| !s.target_label.is_empty() && s.target_label == spawn.target_label && !std::ptr::eq(*s, *spawn))
+            .collect();
+        if siblings.is_empty() {
+            section.push_text("No other spawns of this target in the log.");
+        } else {
+            section.push_text("Other spawns of this target:");
+            let mut table = Table::new(vec![
+                Column::text("mnemonic", "Mnemonic"),
+                Column::text("runner", "Runner"),
+                Column::text("status", "Status"),
+                Column::seconds("total_time", "Time", 3),
+                Column::text("output", "First Output"),
+            ]);
+            for sibling in siblings {
+                table.push_row(vec![
+                    (&sibling.mnemonic).into(),
+                    (&sibling.runner).into(),
+                    if is_failed(sibling) { "failed" } else { "ok" }.into(),
+                    total_time(sibling).into(),
+                    sibling.actual_outputs.first().map(|f| f.path.as_str()).into(),
+                ]);
+            }
+            section.push_table(table);
         }
+        sections.push(section);
     }
-    
-    if size_data.is_empty() {
-        println!("No actions with output size data found in the log.");
-        println!();
-        return;
-    }
-    
-    size_data.sort_by_key(|(size, _)| *size);
-    size_data.reverse();
-    
-    // Calculate column widths based on actual data
-    let actions_to_display = size_data.iter().take(top_n);
-    
-    let size_width = actions_to_display.clone()
-        .map(|(size, _)| {
-            let size_mb = *size as f64 / 1_048_576.0;
-            format!("{:.2}MB", size_mb).len()
-        })
-        .max()
-        .unwrap_or(11)
-        .max(11); // "Output Size" header
-    
-    let files_width = actions_to_display.clone()
-        .map(|(_, spawn)| spawn.actual_outputs.len().to_string().len())
-        .max()
-        .unwrap_or(12)
-        .max(12); // "Output Files" header
-    
-    // Print header
-    println!(
-        "{:>width1$} | {:>width2$} | {}",
-        "Output Size", "Output Files", "Target",
-        width1 = size_width,
-        width2 = files_width
+    sections
+}
+
+/// Spawns ending this soon after the first failure are treated as reactions to it.
+const CANCELLATION_WINDOW_SECONDS: f64 = 5.0;
+
+fn end_seconds(spawn: &SpawnExec) -> Option<f64> {
+    start_seconds(spawn).map(|start| start + total_time(spawn).as_secs_f64())
+}
+
+/// Identifies the failure that finished first, which is the one that stopped the build, and the
+/// spawns that were running at that moment and ended shortly after, which were most likely
+/// cancelled or failed as a consequence. `None` when no failed spawn has a start time.
+fn first_failure_section(spawns: &[SpawnExec], top_n: usize) -> Option<Section> {
+    let (first, failed_at) = spawns
+        .iter()
+        .filter(|s| is_failed(s))
+        .filter_map(|s| end_seconds(s).map(|end| (s, end)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    let mut section = Section::new("first_failure", "First Failure and Its Aftermath");
+    section.push_field(Column::text("target", "First Failure"), display_label(first));
+    section.push_field(Column::text("mnemonic", "Mnemonic"), &first.mnemonic);
+    section.push_field(Column::integer("exit_code", "Exit Code"), first.exit_code);
+    section.push_field(Column::float("failed_at", "Finished At (Unix s)", 3), failed_at);
+    section.push_field(
+        Column::text("primary_output", "Primary Output"),
+        first.actual_outputs.first().map(|f| f.path.as_str()),
     );
-    
-    // Print separator line
-    let separator_width = size_width + files_width + 6 + 6; // separators + "Target"
-    println!("{}", "-".repeat(separator_width));
-    
+
+    let mut aftermath: Vec<(f64, &SpawnExec)> = spawns
+        .iter()
+        .filter(|s| !std::ptr::eq(*s, first))
+        .filter_map(|s| Some((start_seconds(s)?, end_seconds(s)?, s)))
+        .filter(|(start, end, _)| {
+            *start <= failed_at && *end >= failed_at && *end <= failed_at + CANCELLATION_WINDOW_SECONDS
+        })
+        .map(|(_, end, s)| (end - failed_at, s))
+        .collect();
+    let started_after = spawns
+        .iter()
+        .filter(|s| start_seconds(s).is_some_and(|start| start > failed_at))
+        .count();
+    let later_failures = spawns
+        .iter()
+        .filter(|s| is_failed(s) && !std::ptr::eq(*s, first))
+        .count();
+    section.push_field(Column::integer("later_failures", "Other Failures"), later_failures);
+    section.push_field(Column::integer("likely_cancelled", "Running at the Failure, Ended Within 5s"), aftermath.len());
+    section.push_field(Column::integer("started_after", "Started After the Failure"), started_after);
+
+    if !aftermath.is_empty() {
+        aftermath.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut table = Table::new(vec![
+            Column::seconds("after_failure", "Ended After", 3),
+            Column::text("status", "Status"),
+            Column::text("mnemonic", "Mnemonic"),
+            Column::text("target", "Target"),
+            spawn_id_column(),
+        ]);
+        for (delay, spawn) in aftermath.iter().take(top_n) {
+            table.push_row(vec![
+                (*delay).into(),
+                if is_failed(spawn) { spawn.status.as_str() } else { "ok" }.into(),
+                (&spawn.mnemonic).into(),
+                display_label(spawn).into(),
+                spawn_id(spawn).into(),
+            ]);
+        }
+        section.push_table(table);
+    }
+    section.push_text("Note: Failures and interruptions among the spawns listed here are most likely collateral of the first failure; fix that one first. Spawns started after it indicate --keep_going.");
+    Some(section)
+}
+
+fn aggregate_phases_section(spawns: &[SpawnExec]) -> Section {
+    let mut section = Section::new("aggregate_phases", "Aggregate Phase Timings (Executed Actions)");
+
+    let executed: Vec<&SpawnExec> = spawns.iter().filter(|s| !s.cache_hit).collect();
+    if executed.is_empty() {
+        section.push_text("No executed actions found (all were cache hits).");
+        return section;
+    }
+
+    let sum = |phase: fn(&SpawnMetrics) -> Option<&prost_types::Duration>| -> Duration {
+        executed.iter().map(|s| phase_duration(s, phase)).sum()
+    };
+    let total_time = sum(|m| m.total_time.as_ref());
+    let total_seconds = total_time.as_secs_f64();
+
+    section.push_field(Column::integer("executed_actions", "Executed Actions"), executed.len());
+    section.push_field(Column::seconds("total_time", "Total Execution Time", 2), total_time);
+
+    let phases = [
+        ("Queue", sum(|m| m.queue_time.as_ref())),
+        ("Setup", sum(|m| m.setup_time.as_ref())),
+        ("Upload", sum(|m| m.upload_time.as_ref())),
+        ("Execution", sum(|m| m.execution_wall_time.as_ref())),
+        ("Fetch", sum(|m| m.fetch_time.as_ref())),
+        ("Retry", sum(|m| m.retry_time.as_ref())),
+    ];
+
+    let mut table = Table::new(vec![
+        Column::text("phase", "Phase"),
+        Column::seconds("time", "Time", 2),
+        Column::percent("share", "% of Total", 1),
+    ]);
+    for (name, duration) in phases {
+        table.push_row(vec![
+            name.into(),
+            duration.into(),
+            percentage(duration.as_secs_f64(), total_seconds).into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
+
+fn output_analysis_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new("output_analysis", format!("Top {} Actions by Output Size", top_n));
+
+    let mut size_data: Vec<(i64, &SpawnExec)> = spawns
+        .iter()
+        .map(|s| (output_bytes(s), s))
+        .filter(|(size, _)| *size > 0)
+        .collect();
+
+    if size_data.is_empty() {
+        section.push_text("No actions with output size data found in the log.");
+        return section;
+    }
+    size_data.sort_by_key(|(size, _)| std::cmp::Reverse(*size));
+
+    let mut table = Table::new(vec![
+        Column::bytes("output_bytes", "Output Size", ByteUnit::Megabytes, 2),
+        Column::integer("output_files", "Output Files"),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
     for (size, spawn) in size_data.iter().take(top_n) {
-        println!(
-            "{:>width1$.2}MB | {:>width2$} | {}",
-            *size as f64 / 1_048_576.0,
-            spawn.actual_outputs.len(),
-            spawn.target_label,
-            width1 = size_width - 2, // -2 for "MB" suffix
-            width2 = files_width
-        );
+        table.push_row(vec![
+            (*size).into(),
+            spawn.actual_outputs.len().into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
+
+fn memory_analysis_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new(
+        "memory_analysis",
+        format!("Top {} Actions by Memory Usage vs. Limit", top_n),
+    );
+
+    let mut memory_data: Vec<(f64, &SpawnExec, &SpawnMetrics)> = spawns
+        .iter()
+        .filter_map(|s| s.metrics.as_ref().map(|m| (s, m)))
+        .filter(|(_, m)| m.memory_bytes_limit > 0)
+        .map(|(s, m)| (m.memory_estimate_bytes as f64 / m.memory_bytes_limit as f64, s, m))
+        .collect();
+
+    if memory_data.is_empty() {
+        section.push_text("No actions with memory limit data found in the log.");
+        return section;
+    }
+    memory_data.sort_by(|(a, _, _), (b, _, _)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut table = Table::new(vec![
+        Column::bytes("memory_estimate_bytes", "Memory Used", ByteUnit::Megabytes, 1),
+        Column::bytes("memory_limit_bytes", "Memory Limit", ByteUnit::Megabytes, 1),
+        Column::percent("usage", "Usage %", 1),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
+    for (ratio, spawn, metrics) in memory_data.iter().take(top_n) {
+        table.push_row(vec![
+            metrics.memory_estimate_bytes.into(),
+            metrics.memory_bytes_limit.into(),
+            (ratio * 100.0).into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
     }
-    println!();
+    section.push_table(table);
+    section
+}
+
+/// Usage at or above this share of the limit counts as at risk of running out of memory, and an
+/// action killed by SIGKILL at it as most likely killed for running out.
+const OOM_RISK_RATIO: f64 = 0.9;
+
+/// Limits are suggested with this much headroom over the highest observed usage...
+const MEMORY_HEADROOM: f64 = 1.25;
+
+/// ...rounded up to a multiple of this many bytes.
+const MEMORY_LIMIT_STEP: i64 = 64 * 1024 * 1024;
+
+/// A limit this many times the highest usage is worth lowering to free scheduling capacity.
+const OVERSIZED_LIMIT_FACTOR: f64 = 4.0;
+
+#[derive(Default)]
+struct MemoryStats {
+    ratios: Vec<f64>,
+    at_risk: u64,
+    max_used: i64,
+    max_limit: i64,
+}
+
+fn suggested_memory_limit(max_used: i64) -> i64 {
+    let target = (max_used as f64 * MEMORY_HEADROOM).ceil() as i64;
+    ((target + MEMORY_LIMIT_STEP - 1) / MEMORY_LIMIT_STEP).max(1) * MEMORY_LIMIT_STEP
 }
 
-fn print_memory_analysis_report(spawns: &[SpawnExec], top_n: usize) {
-    println!("--- Top {} Actions by Memory Usage vs. Limit ---", top_n);
-    
-    let mut memory_data: Vec<(f64, &SpawnExec)> = Vec::new();
-    
+fn memory_headroom_section(spawns: &[SpawnExec]) -> Section {
+    let mut section = Section::new("memory_headroom", "Memory Headroom and OOM Risk");
+
+    const BUCKETS: &[(&str, f64)] = &[
+        ("< 25%", 0.25),
+        ("25-50%", 0.5),
+        ("50-75%", 0.75),
+        ("75-90%", OOM_RISK_RATIO),
+        ("90-100%", 1.0),
+        ("> 100%", f64::INFINITY),
+    ];
+    let mut bucket_counts = vec![0u64; BUCKETS.len()];
+    let mut by_mnemonic: HashMap<&str, MemoryStats> = HashMap::new();
     for spawn in spawns {
-        if let Some(metrics) = spawn.metrics.as_ref() {
-            if metrics.memory_bytes_limit > 0 {
-                let usage_ratio = metrics.memory_estimate_bytes as f64 / metrics.memory_bytes_limit as f64;
-                memory_data.push((usage_ratio, spawn));
-            }
+        let Some(metrics) = spawn.metrics.as_ref().filter(|m| m.memory_bytes_limit > 0) else {
+            continue;
+        };
+        let ratio = metrics.memory_estimate_bytes as f64 / metrics.memory_bytes_limit as f64;
+        let bucket = BUCKETS.iter().position(|(_, upper)| ratio < *upper).unwrap_or(BUCKETS.len() - 1);
+        bucket_counts[bucket] += 1;
+
+        let stats = by_mnemonic.entry(&spawn.mnemonic).or_default();
+        stats.ratios.push(ratio);
+        if ratio >= OOM_RISK_RATIO {
+            stats.at_risk += 1;
         }
+        stats.max_used = stats.max_used.max(metrics.memory_estimate_bytes);
+        stats.max_limit = stats.max_limit.max(metrics.memory_bytes_limit);
     }
-    
-    if memory_data.is_empty() {
-        println!("No actions with memory limit data found in the log.");
-        println!();
-        return;
-    }
-    
-    memory_data.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
-    
-    // Calculate column widths based on actual data
-    let actions_to_display = memory_data.iter().take(top_n);
-    
-    let estimate_width = actions_to_display.clone()
-        .map(|(_, spawn)| {
-            let estimate_mb = spawn.metrics.as_ref().unwrap().memory_estimate_bytes as f64 / 1_048_576.0;
-            format!("{:.1}MB", estimate_mb).len()
-        })
-        .max()
-        .unwrap_or(12)
-        .max(12); // "Memory Used" header
-    
-    let limit_width = actions_to_display.clone()
-        .map(|(_, spawn)| {
-            let limit_mb = spawn.metrics.as_ref().unwrap().memory_bytes_limit as f64 / 1_048_576.0;
-            format!("{:.1}MB", limit_mb).len()
-        })
-        .max()
-        .unwrap_or(13)
-        .max(13); // "Memory Limit" header
-    
-    let usage_width = 7; // "Usage %" header
-    
-    // Print header
-    println!(
-        "{:>width1$} | {:>width2$} | {:>width3$} | {}",
-        "Memory Used", "Memory Limit", "Usage %", "Target",
-        width1 = estimate_width,
-        width2 = limit_width,
-        width3 = usage_width
+    if by_mnemonic.is_empty() {
+        section.push_text("No actions with memory limit data found in the log.");
+        return section;
+    }
+
+    let measured: u64 = bucket_counts.iter().sum();
+    let at_risk: u64 = by_mnemonic.values().map(|stats| stats.at_risk).sum();
+    section.push_field(Column::integer("measured_actions", "Actions with Memory Data"), measured);
+    section.push_field(Column::integer("at_risk_actions", "Actions over 90% of Their Limit"), at_risk);
+    section.push_field(
+        Column::percent("at_risk_share", "At-Risk Share", 1),
+        percentage(at_risk as f64, measured as f64),
     );
-    
-    // Print separator line
-    let separator_width = estimate_width + limit_width + usage_width + 6 + 9; // separators + "Target"
-    println!("{}", "-".repeat(separator_width));
-    
-    for (ratio, spawn) in memory_data.iter().take(top_n) {
-        let metrics = spawn.metrics.as_ref().unwrap();
-        let estimate_mb = metrics.memory_estimate_bytes as f64 / 1_048_576.0;
-        let limit_mb = metrics.memory_bytes_limit as f64 / 1_048_576.0;
-        let usage_pct = ratio * 100.0;
-        
-        println!(
-            "{:>width1$.1}MB | {:>width2$.1}MB | {:>width3$.1}% | {}",
-            estimate_mb,
-            limit_mb,
-            usage_pct,
-            spawn.target_label,
-            width1 = estimate_width - 2, // -2 for "MB" suffix
-            width2 = limit_width - 2,    // -2 for "MB" suffix
-            width3 = usage_width - 1     // -1 for "%" suffix
-        );
+
+    let mut table = Table::new(vec![
+        Column::text("usage", "Usage of Limit"),
+        Column::integer("count", "Actions"),
+        Column::percent("share", "Share", 1),
+    ]);
+    for ((name, _), count) in BUCKETS.iter().zip(&bucket_counts) {
+        table.push_row(vec![(*name).into(), (*count).into(), percentage(*count as f64, measured as f64).into()]);
+    }
+    section.push_table(table);
+
+    let mut sorted: Vec<_> = by_mnemonic.into_iter().collect();
+    for (_, stats) in &mut sorted {
+        stats.ratios.sort_by(f64::total_cmp);
+    }
+    sorted.sort_by(|(a_name, a), (b_name, b)| {
+        let max = |stats: &MemoryStats| stats.ratios.last().copied().unwrap_or(0.0);
+        max(b).total_cmp(&max(a)).then(a_name.cmp(b_name))
+    });
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("count", "Actions"),
+        Column::percent("p50_usage", "p50 Usage", 1),
+        Column::percent("p90_usage", "p90 Usage", 1),
+        Column::percent("max_usage", "Max Usage", 1),
+        Column::integer("at_risk", "> 90%"),
+        Column::bytes("max_used_bytes", "Max Used", ByteUnit::Megabytes, 0),
+        Column::bytes("limit_bytes", "Limit", ByteUnit::Megabytes, 0),
+        Column::bytes("suggested_limit_bytes", "Suggested Limit", ByteUnit::Megabytes, 0),
+        Column::text("suggestion", "Suggestion"),
+    ]);
+    for (mnemonic, stats) in sorted {
+        let suggested = suggested_memory_limit(stats.max_used);
+        let suggestion = if stats.at_risk > 0 {
+            Some("raise")
+        } else if stats.max_used as f64 * OVERSIZED_LIMIT_FACTOR < stats.max_limit as f64 && suggested < stats.max_limit {
+            Some("lower")
+        } else {
+            None
+        };
+        let percent = |pct: f64| percentile(&stats.ratios, pct).map(|ratio| ratio * 100.0);
+        table.push_row(vec![
+            mnemonic.into(),
+            stats.ratios.len().into(),
+            percent(50.0).into(),
+            percent(90.0).into(),
+            percent(100.0).into(),
+            stats.at_risk.into(),
+            stats.max_used.into(),
+            stats.max_limit.into(),
+            suggestion.map(|_| suggested).into(),
+            suggestion.unwrap_or("keep").into(),
+        ]);
     }
-    println!();
+    section.push_table(table);
+    section.push_text(format!(
+        "Note: Suggested limits are {:.0}% of the highest usage seen, rounded up to 64 MB. Apply them to remote executions with --experimental_remote_execution_memory or a memory execution property, and to local actions through the rule's resource_set.",
+        MEMORY_HEADROOM * 100.0
+    ));
+    section
 }
 
-fn print_execution_comparison_report(spawns: &[SpawnExec]) {
-    println!("--- Remote vs. Local Execution Time Comparison ---");
-    
-    let mut mnemonic_stats: HashMap<String, MnemonicExecutionStats> = HashMap::new();
-    
+fn execution_comparison_section(spawns: &[SpawnExec]) -> Section {
+    let mut section = Section::new(
+        "execution_comparison",
+        "Remote vs. Local Execution Time Comparison",
+    );
+
+    let mut mnemonic_stats: HashMap<&str, MnemonicExecutionStats> = HashMap::new();
     for spawn in spawns {
-        if !spawn.cache_hit {
-            if let Some(metrics) = spawn.metrics.as_ref() {
-                if let Some(execution_time) = metrics.execution_wall_time.as_ref() {
-                    let duration = to_std_duration(execution_time);
-                    let stats = mnemonic_stats.entry(spawn.mnemonic.clone()).or_default();
-                    
-                    if spawn.runner.contains("remote") {
-                        stats.remote.count += 1;
-                        stats.remote.total_duration += duration;
-                    } else if spawn.runner.contains("sandbox") || spawn.runner.contains("local") {
-                        stats.local.count += 1;
-                        stats.local.total_duration += duration;
-                    }
-                }
+        if !spawn.cache_hit
+            && let Some(metrics) = spawn.metrics.as_ref()
+            && let Some(execution_time) = metrics.execution_wall_time.as_ref()
+        {
+            let duration = to_std_duration(execution_time);
+            let stats = mnemonic_stats.entry(&spawn.mnemonic).or_default();
+
+            if runner_kind(spawn) == RunnerKind::Remote {
+                stats.remote.count += 1;
+                stats.remote.total_duration += duration;
+            } else if is_local_runner(&spawn.runner) {
+                stats.local.count += 1;
+                stats.local.total_duration += duration;
             }
         }
     }
-    
+
     // Filter for mnemonics that have both remote and local executions
-    let comparable_mnemonics: Vec<_> = mnemonic_stats
-        .iter()
+    let mut comparable_mnemonics: Vec<_> = mnemonic_stats
+        .into_iter()
         .filter(|(_, stats)| stats.remote.count > 0 && stats.local.count > 0)
         .collect();
-    
+
     if comparable_mnemonics.is_empty() {
-        println!("No mnemonics found with both remote and local executions.");
-        println!();
-        return;
+        section.push_text("No mnemonics found with both remote and local executions.");
+        return section;
     }
-    
-    // Calculate column widths
-    let mnemonic_width = comparable_mnemonics
-        .iter()
-        .map(|(name, _)| name.len())
-        .max()
-        .unwrap_or(8)
-        .max(8); // "Mnemonic" header
-    
-    let count_width = 8; // "Remote/Local" headers
-    let time_width = 10; // "Avg Time" headers
-    
-    // Print header
-    println!(
-        "{:<width1$} | {:>width2$} | {:>width3$} | {:>width2$} | {:>width3$} | {:>12}",
-        "Mnemonic", "Remote", "Avg Time", "Local", "Avg Time", "Difference",
-        width1 = mnemonic_width,
-        width2 = count_width,
-        width3 = time_width
-    );
-    
-    // Print separator line
-    let separator_width = mnemonic_width + count_width * 2 + time_width * 2 + 12 + 15; // separators
-    println!("{}", "-".repeat(separator_width));
-    
-    let mut sorted_mnemonics = comparable_mnemonics;
-    sorted_mnemonics.sort_by(|(a, _), (b, _)| a.cmp(b));
-    
-    for (mnemonic, stats) in sorted_mnemonics {
-        let remote_avg = if stats.remote.count > 0 {
-            stats.remote.total_duration.as_secs_f64() / stats.remote.count as f64
-        } else {
-            0.0
-        };
-        
-        let local_avg = if stats.local.count > 0 {
-            stats.local.total_duration.as_secs_f64() / stats.local.count as f64
-        } else {
-            0.0
-        };
-        
+    comparable_mnemonics.sort_by_key(|(name, _)| *name);
+
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("remote_count", "Remote"),
+        Column::seconds("remote_avg_time", "Avg Time", 3),
+        Column::integer("local_count", "Local"),
+        Column::seconds("local_avg_time", "Avg Time", 3),
+        Column::text("difference", "Difference"),
+    ]);
+    for (mnemonic, stats) in comparable_mnemonics {
+        let remote_avg = stats.remote.average_seconds();
+        let local_avg = stats.local.average_seconds();
+
         let difference_ratio = if local_avg > 0.0 {
             remote_avg / local_avg
         } else {
             0.0
         };
-        
+
         let difference_text = if difference_ratio > 1.0 {
             format!("{:.1}x slower", difference_ratio)
         } else if difference_ratio > 0.0 && difference_ratio < 1.0 {
@@ -973,96 +1857,3573 @@ fn print_execution_comparison_report(spawns: &[SpawnExec]) {
         } else {
             "N/A".to_string()
         };
-        
-        println!(
-            "{:<width1$} | {:>width2$} | {:>width3$.3}s | {:>width2$} | {:>width3$.3}s | {:>12}",
-            mnemonic,
-            stats.remote.count,
-            remote_avg,
-            stats.local.count,
-            local_avg,
-            difference_text,
-            width1 = mnemonic_width,
-            width2 = count_width,
-            width3 = time_width - 1 // -1 for 's' suffix
-        );
+
+        table.push_row(vec![
+            mnemonic.into(),
+            stats.remote.count.into(),
+            remote_avg.into(),
+            stats.local.count.into(),
+            local_avg.into(),
+            difference_text.into(),
+        ]);
     }
-    println!();
+    section.push_table(table);
+    section
 }
 
-fn print_queue_analysis_report(spawns: &[SpawnExec], top_n: usize) {
-    println!("--- Top {} Actions by Queue Time ---", top_n);
-    
-    let mut non_cache_hits: Vec<&SpawnExec> = spawns.iter().filter(|s| !s.cache_hit).collect();
-    
+fn queue_analysis_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new("queue_analysis", format!("Top {} Actions by Queue Time", top_n));
+
+    let mut non_cache_hits: Vec<&SpawnExec> = spawns
+        .iter()
+        .filter(|s| !s.cache_hit && s.metrics.is_some())
+        .collect();
+
     if non_cache_hits.is_empty() {
-        println!("No executed actions found (all were cache hits).");
-        println!();
-        return;
-    }
-    
-    non_cache_hits.sort_by_key(|s| {
-        s.metrics
-            .as_ref()
-            .and_then(|m| m.queue_time.as_ref())
-            .map(to_std_duration)
-            .unwrap_or_default()
-    });
-    non_cache_hits.reverse();
-    
-    // Calculate column widths based on actual data
-    let actions_to_display = non_cache_hits.iter().take(top_n);
-    
-    let queue_width = actions_to_display.clone()
-        .map(|s| {
-            let queue_time = s.metrics.as_ref()
-                .and_then(|m| m.queue_time.as_ref())
-                .map(to_std_duration)
-                .unwrap_or_default();
-            format!("{:.2}s", queue_time.as_secs_f64()).len()
+        section.push_text("No executed actions found (all were cache hits).");
+        return section;
+    }
+    non_cache_hits.sort_by_key(|s| std::cmp::Reverse(phase_duration(s, |m| m.queue_time.as_ref())));
+
+    let mut table = Table::new(vec![
+        Column::seconds("queue_time", "Queue Time", 2),
+        Column::seconds("total_time", "Total Time", 2),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
+    for spawn in non_cache_hits.iter().take(top_n) {
+        table.push_row(vec![
+            phase_duration(spawn, |m| m.queue_time.as_ref()).into(),
+            total_time(spawn).into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
+
+/// Number of rows of the queue pressure table.
+const QUEUE_PRESSURE_BANDS: u64 = 10;
+
+/// Remote executions a band of the queue pressure table needs before its p95 queue time counts
+/// towards the pool size estimate.
+const MIN_QUEUE_BAND_SAMPLES: usize = 5;
+
+/// Pearson correlation of the pairs, or `None` when either side does not vary.
+fn correlation(pairs: &[(f64, f64)]) -> Option<f64> {
+    let n = pairs.len() as f64;
+    let (mean_x, mean_y) = pairs.iter().fold((0.0, 0.0), |(x, y), (a, b)| (x + a / n, y + b / n));
+    let (mut covariance, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        covariance += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+    (var_x > 0.0 && var_y > 0.0).then(|| covariance / (var_x * var_y).sqrt())
+}
+
+/// Relates the queue time of remote executions to how many remote actions were outstanding when
+/// each started, to estimate the executor pool size at which queueing stays under `target`.
+fn queue_pressure_section(spawns: &[SpawnExec], target: f64) -> Section {
+    let mut section = Section::new("queue_pressure", "Queue Time vs. Outstanding Remote Actions");
+    let remote: Vec<(f64, f64, f64)> = spawns
+        .iter()
+        .filter(|s| runner_kind(s) == RunnerKind::Remote)
+        .filter_map(|s| {
+            let start = start_seconds(s)?;
+            let queue = phase_duration(s, |m| m.queue_time.as_ref()).as_secs_f64();
+            Some((start, start + total_time(s).as_secs_f64(), queue))
         })
-        .max()
-        .unwrap_or(10)
-        .max(10); // "Queue Time" header
-    
-    let total_width = actions_to_display.clone()
-        .map(|s| {
-            let total_time = s.metrics.as_ref()
-                .and_then(|m| m.total_time.as_ref())
-                .map(to_std_duration)
-                .unwrap_or_default();
-            format!("{:.2}s", total_time.as_secs_f64()).len()
+        .collect();
+    if remote.len() < 2 {
+        section.push_text("Not enough remote executions with a start time to relate queue times to load.");
+        return section;
+    }
+
+    // Outstanding at a spawn's start: remote spawns started by then, itself included, and not yet done.
+    let mut starts: Vec<f64> = remote.iter().map(|(start, _, _)| *start).collect();
+    let mut ends: Vec<f64> = remote.iter().map(|(_, end, _)| *end).collect();
+    starts.sort_by(f64::total_cmp);
+    ends.sort_by(f64::total_cmp);
+    let mut samples: Vec<(i64, f64)> = remote
+        .iter()
+        .map(|(start, _, queue)| {
+            let started = starts.partition_point(|s| s <= start);
+            let done = ends.partition_point(|e| e <= start);
+            ((started - done) as i64, *queue)
         })
-        .max()
-        .unwrap_or(10)
-        .max(10); // "Total Time" header
-    
-    // Print header
-    println!(
-        "{:>width1$} | {:>width2$} | {}",
-        "Queue Time", "Total Time", "Target",
-        width1 = queue_width,
-        width2 = total_width
+        .collect();
+    samples.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+    let queue_p95 = |samples: &[(i64, f64)]| {
+        let mut queues: Vec<f64> = samples.iter().map(|(_, queue)| *queue).collect();
+        queues.sort_by(f64::total_cmp);
+        percentile(&queues, 95.0).unwrap_or(0.0)
+    };
+    let peak = samples.last().map_or(0, |(outstanding, _)| *outstanding);
+    let overall_p95 = queue_p95(&samples);
+    let pairs: Vec<(f64, f64)> = samples.iter().map(|(outstanding, queue)| (*outstanding as f64, *queue)).collect();
+
+    // Bands of outstanding levels, as in the table, with their queue times sorted.
+    let band = (peak.max(1) as u64).div_ceil(QUEUE_PRESSURE_BANDS) as i64;
+    let mut bands: Vec<(i64, i64, Vec<f64>)> = Vec::new();
+    let mut rest = samples.as_slice();
+    while let Some(&(first, _)) = rest.first() {
+        let low = (first - 1) / band * band + 1;
+        let high = low + band - 1;
+        let end = rest.partition_point(|(outstanding, _)| *outstanding <= high);
+        let (rows, tail) = rest.split_at(end);
+        // Samples are sorted by queue time within a level, but not across the levels of a band.
+        let mut queues: Vec<f64> = rows.iter().map(|(_, queue)| *queue).collect();
+        queues.sort_by(f64::total_cmp);
+        bands.push((low, high, queues));
+        rest = tail;
+    }
+    // The pool size for the target: the highest load whose queue times still met it.
+    let measured: Vec<&(i64, i64, Vec<f64>)> =
+        bands.iter().filter(|(_, _, queues)| queues.len() >= MIN_QUEUE_BAND_SAMPLES).collect();
+    let estimated = measured
+        .iter()
+        .filter(|(_, _, queues)| percentile(queues, 95.0).is_some_and(|p95| p95 <= target))
+        .map(|(_, high, _)| (*high).min(peak))
+        .max();
+
+    section.push_field(Column::integer("remote_actions", "Remote executions"), samples.len());
+    section.push_field(Column::integer("peak_outstanding", "Peak outstanding"), peak);
+    section.push_field(Column::seconds("queue_p95", "P95 queue time", 2), overall_p95);
+    section.push_field(Column::float("correlation", "Correlation of queue time and load", 2), correlation(&pairs));
+    section.push_field(Column::seconds("queue_target", "Target p95 queue time", 2), target);
+    if overall_p95 <= target {
+        section.push_text(format!(
+            "The p95 queue time is within the {:.2}s target: the pool kept up with up to {} outstanding actions.",
+            target, peak
+        ));
+    } else if measured.is_empty() {
+        section.push_text(format!(
+            "Not enough data to estimate a pool size for the {:.2}s target: no band of outstanding actions has \
+             the {} remote executions needed for a p95 queue time.",
+            target, MIN_QUEUE_BAND_SAMPLES
+        ));
+    } else if let Some(estimated) = estimated {
+        section.push_field(Column::integer("estimated_pool", "Estimated pool size for the target"), estimated);
+        section.push_text(format!(
+            "Hint: The p95 queue time stayed within the {:.2}s target with up to {} remote actions outstanding and \
+             missed it beyond, so the executors behave like a pool of about {} concurrent slots at that target. \
+             This build had up to {} outstanding; growing the pool towards that keeps its queue times on target.",
+            target, estimated, estimated, peak
+        ));
+    } else {
+        section.push_text(format!(
+            "Hint: Queue times miss the {:.2}s target at every load with enough remote executions to tell, so they \
+             come from the executor's scheduling or other tenants rather than this build's load; a larger pool \
+             would not remove them.",
+            target
+        ));
+    }
+
+    let mut table = Table::new(vec![
+        Column::text("outstanding", "Outstanding"),
+        Column::integer("actions", "Actions"),
+        Column::seconds("median_queue", "Median Queue", 2),
+        Column::seconds("p95_queue", "P95 Queue", 2),
+    ]);
+    for (low, high, queues) in &bands {
+        let label = if low == high { low.to_string() } else { format!("{}-{}", low, high) };
+        table.push_row(vec![
+            label.into(),
+            queues.len().into(),
+            percentile(queues, 50.0).into(),
+            percentile(queues, 95.0).into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
+
+fn remote_fallback_section(spawns: &[SpawnExec]) -> Section {
+    let mut section = Section::new("remote_fallback", "Suspected Remote Execution Fallbacks");
+    section.push_text("Note: Only remotable, executed actions are considered; a mnemonic is flagged when it ran both remotely and locally.");
+
+    let mut mnemonic_stats: HashMap<&str, MnemonicExecutionStats> = HashMap::new();
+    for spawn in spawns.iter().filter(|s| s.remotable && !s.cache_hit) {
+        let duration = total_time(spawn);
+        let stats = mnemonic_stats.entry(&spawn.mnemonic).or_default();
+        if runner_kind(spawn) == RunnerKind::Remote {
+            stats.remote.count += 1;
+            stats.remote.total_duration += duration;
+        } else if is_local_runner(&spawn.runner) {
+            stats.local.count += 1;
+            stats.local.total_duration += duration;
+        }
+    }
+
+    let mut suspicious: Vec<_> = mnemonic_stats
+        .into_iter()
+        .filter(|(_, stats)| stats.remote.count > 0 && stats.local.count > 0)
+        .collect();
+
+    if suspicious.is_empty() {
+        section.push_text("No remotable mnemonics with a mix of remote and local executions found.");
+        return section;
+    }
+    suspicious.sort_by_key(|(name, stats)| (std::cmp::Reverse(stats.local.total_duration), *name));
+
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("remote_count", "Remote"),
+        Column::integer("local_count", "Local"),
+        Column::percent("local_share", "Local %", 1),
+        Column::seconds("local_time", "Local Time", 2),
+        Column::seconds("extra_time", "Extra Time", 2),
+    ]);
+    let mut total_local_time = Duration::ZERO;
+    let mut total_extra_seconds = 0.0;
+    for (mnemonic, stats) in suspicious {
+        let local_seconds = stats.local.total_duration.as_secs_f64();
+        // Time spent locally beyond what the same number of actions took on average remotely.
+        let extra_seconds =
+            (local_seconds - stats.remote.average_seconds() * stats.local.count as f64).max(0.0);
+
+        total_local_time += stats.local.total_duration;
+        total_extra_seconds += extra_seconds;
+
+        table.push_row(vec![
+            mnemonic.into(),
+            stats.remote.count.into(),
+            stats.local.count.into(),
+            percentage(stats.local.count as f64, (stats.local.count + stats.remote.count) as f64).into(),
+            local_seconds.into(),
+            extra_seconds.into(),
+        ]);
+    }
+    section.push_table(table);
+    section.push_field(
+        Column::seconds("total_local_time", "Total local time for remotable actions", 2),
+        total_local_time,
     );
-    
-    // Print separator line
-    let separator_width = queue_width + total_width + 6 + 6; // separators + "Target"
-    println!("{}", "-".repeat(separator_width));
-    
-    for spawn in non_cache_hits.iter().take(top_n) {
-        if let Some(metrics) = spawn.metrics.as_ref() {
-            let queue_time = metrics.queue_time.as_ref().map(to_std_duration).unwrap_or_default();
-            let total_time = metrics.total_time.as_ref().map(to_std_duration).unwrap_or_default();
-            
-            println!(
-                "{:>width1$.2}s | {:>width2$.2}s | {}",
-                queue_time.as_secs_f64(),
-                total_time.as_secs_f64(),
-                spawn.target_label,
-                width1 = queue_width - 1, // -1 for 's' suffix
-                width2 = total_width - 1  // -1 for 's' suffix
-            );
+    section.push_field(
+        Column::seconds("total_extra_time", "Extra time vs. remote average", 2),
+        total_extra_seconds,
+    );
+    section.push_text("Hint: This usually indicates remote executor errors or `--remote_local_fallback` kicking in.");
+    section
+}
+
+/// Looks up a platform property by name (case-insensitive), ignoring empty values.
+pub(crate) fn platform_property<'a>(spawn: &'a SpawnExec, name: &str) -> Option<&'a str> {
+    spawn
+        .platform
+        .as_ref()?
+        .properties
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+        .map(|p| p.value.as_str())
+        .filter(|value| !value.is_empty())
+}
+
+/// Platform properties that identify the machine an action ran on, matched case-insensitively.
+const WORKER_PLATFORM_PROPERTIES: [&str; 6] =
+    ["hostname", "worker", "worker-id", "bot-id", "bot_id", "executor"];
+
+/// Environment variables that identify the machine an action ran on.
+const WORKER_ENV_VARS: [&str; 2] = ["HOSTNAME", "COMPUTERNAME"];
+
+/// Extracts the executor/worker identity of a spawn from its platform properties or environment.
+fn worker_identity(spawn: &SpawnExec) -> Option<&str> {
+    WORKER_PLATFORM_PROPERTIES
+        .iter()
+        .find_map(|name| platform_property(spawn, name))
+        .or_else(|| {
+            spawn
+                .environment_variables
+                .iter()
+                .find(|e| WORKER_ENV_VARS.contains(&e.name.as_str()))
+                .map(|e| e.value.as_str())
+        })
+        .filter(|identity| !identity.is_empty())
+}
+
+fn worker_skew_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new("worker_skew", "Executor/Worker Skew (Executed Actions)");
+
+    let executed: Vec<(&SpawnExec, Duration)> = spawns
+        .iter()
+        .filter(|s| !s.cache_hit)
+        .filter_map(|s| {
+            s.metrics
+                .as_ref()
+                .and_then(|m| m.execution_wall_time.as_ref())
+                .map(|d| (s, to_std_duration(d)))
+        })
+        .collect();
+
+    let mut mnemonic_timings: HashMap<&str, ExecutionTimings> = HashMap::new();
+    for (spawn, duration) in &executed {
+        let timings = mnemonic_timings.entry(&spawn.mnemonic).or_default();
+        timings.count += 1;
+        timings.total_duration += *duration;
+    }
+
+    let mut worker_stats: HashMap<&str, WorkerStats> = HashMap::new();
+    let mut unidentified = 0;
+    for (spawn, duration) in &executed {
+        let Some(identity) = worker_identity(spawn) else {
+            unidentified += 1;
+            continue;
+        };
+        let mnemonic_avg = mnemonic_timings[spawn.mnemonic.as_str()].average_seconds();
+        let stats = worker_stats.entry(identity).or_default();
+        stats.count += 1;
+        stats.total_duration += *duration;
+        stats.relative_duration_sum += if mnemonic_avg > 0.0 {
+            duration.as_secs_f64() / mnemonic_avg
+        } else {
+            1.0
+        };
+    }
+
+    if worker_stats.is_empty() {
+        section.push_text("No worker identity found (looked for platform properties like `hostname`/`bot-id` and the HOSTNAME env var).");
+        return section;
+    }
+
+    // Slowest workers first, judged by their mix-normalized relative speed.
+    let mut sorted_workers: Vec<_> = worker_stats.into_iter().collect();
+    sorted_workers.sort_by(|(a_name, a), (b_name, b)| {
+        let a_rel = a.relative_duration_sum / a.count as f64;
+        let b_rel = b.relative_duration_sum / b.count as f64;
+        b_rel
+            .partial_cmp(&a_rel)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a_name.cmp(b_name))
+    });
+
+    let mut table = Table::new(vec![
+        Column::text("worker", "Worker"),
+        Column::integer("actions", "Actions"),
+        Column::seconds("total_time", "Total Time", 2),
+        Column::seconds("avg_time", "Avg Time", 3),
+        Column::ratio("relative_speed", "Relative Speed", 2),
+    ]);
+    for (worker, stats) in sorted_workers.iter().take(top_n) {
+        table.push_row(vec![
+            (*worker).into(),
+            stats.count.into(),
+            stats.total_duration.into(),
+            (stats.total_duration.as_secs_f64() / stats.count as f64).into(),
+            (stats.relative_duration_sum / stats.count as f64).into(),
+        ]);
+    }
+    section.push_table(table);
+    section.push_text("Relative Speed is the average of each action's execution time divided by its mnemonic's average (1.00x = typical, higher = slower).");
+    if unidentified > 0 {
+        section.push_text(format!(
+            "{} executed actions had no worker identity and were skipped.",
+            unidentified
+        ));
+    }
+    section
+}
+
+fn container_image_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new("container_images", "Container Image Usage");
+
+    let mut image_metrics: HashMap<&str, MnemonicMetrics> = HashMap::new();
+    let mut unpinned_by_mnemonic: HashMap<&str, u64> = HashMap::new();
+    for spawn in spawns {
+        match platform_property(spawn, "container-image") {
+            Some(image) => {
+                let metrics = image_metrics.entry(image).or_default();
+                metrics.count += 1;
+                metrics.total_duration += total_time(spawn);
+                if spawn.cache_hit {
+                    metrics.cache_hits += 1;
+                }
+            }
+            None => *unpinned_by_mnemonic.entry(&spawn.mnemonic).or_default() += 1,
+        }
+    }
+
+    if image_metrics.is_empty() {
+        section.push_text("No spawns with a `container-image` platform property found in the log.");
+        return section;
+    }
+
+    let mut sorted_images: Vec<_> = image_metrics.into_iter().collect();
+    sorted_images.sort_by_key(|(image, metrics)| (std::cmp::Reverse(metrics.count), *image));
+
+    section.push_field(Column::integer("distinct_images", "Distinct Images"), sorted_images.len());
+    let mut table = Table::new(vec![
+        Column::integer("actions", "Actions"),
+        Column::percent("cache_hit_rate", "Cache Hits", 1),
+        Column::seconds("total_time", "Total Time", 2),
+        Column::text("pinned", "Pinned"),
+        Column::text("image", "Image"),
+    ]);
+    for (image, metrics) in &sorted_images {
+        // Images referenced by tag rather than by digest can silently change underneath the cache key.
+        let pinned = if image.contains("@sha256:") { "digest" } else { "tag" };
+        table.push_row(vec![
+            metrics.count.into(),
+            percentage(metrics.cache_hits as f64, metrics.count as f64).into(),
+            metrics.total_duration.into(),
+            pinned.into(),
+            (*image).into(),
+        ]);
+    }
+    section.push_table(table);
+
+    let unpinned_total: u64 = unpinned_by_mnemonic.values().sum();
+    if unpinned_total > 0 {
+        section.push_field(
+            Column::integer("actions_without_image", "Actions with no container image"),
+            unpinned_total,
+        );
+        let mut sorted_unpinned: Vec<_> = unpinned_by_mnemonic.into_iter().collect();
+        sorted_unpinned.sort_by_key(|(mnemonic, count)| (std::cmp::Reverse(*count), *mnemonic));
+        let mut table = Table::new(vec![
+            Column::text("mnemonic", "Mnemonic"),
+            Column::integer("actions", "Actions Without Image"),
+        ]);
+        for (mnemonic, count) in sorted_unpinned.into_iter().take(top_n) {
+            table.push_row(vec![mnemonic.into(), count.into()]);
+        }
+        section.push_table(table);
+    }
+    section
+}
+
+/// Platform properties that select a remote execution pool, in order of preference.
+const POOL_PLATFORM_PROPERTIES: [&str; 3] = ["Pool", "worker-pool", "pool-name"];
+
+fn pool_utilization_section(spawns: &[SpawnExec]) -> Section {
+    let mut section = Section::new("pool_utilization", "Pool Utilization");
+
+    let mut pool_stats: HashMap<&str, PoolStats> = HashMap::new();
+    for spawn in spawns {
+        let pool = POOL_PLATFORM_PROPERTIES
+            .iter()
+            .find_map(|name| platform_property(spawn, name))
+            .unwrap_or("(no pool)");
+        let stats = pool_stats.entry(pool).or_default();
+        stats.count += 1;
+        if spawn.cache_hit {
+            continue;
         }
+        stats.executed += 1;
+        let queue = phase_duration(spawn, |m| m.queue_time.as_ref());
+        stats.total_queue += queue;
+        stats.max_queue = stats.max_queue.max(queue);
+        stats.total_execution += phase_duration(spawn, |m| m.execution_wall_time.as_ref());
+    }
+
+    if pool_stats.len() == 1 && pool_stats.contains_key("(no pool)") {
+        section.push_text("No spawns with a `Pool` platform property found in the log.");
+        return section;
+    }
+
+    let mut sorted_pools: Vec<_> = pool_stats.into_iter().collect();
+    sorted_pools.sort_by_key(|(pool, stats)| (std::cmp::Reverse(stats.total_execution), *pool));
+
+    let mut table = Table::new(vec![
+        Column::text("pool", "Pool"),
+        Column::integer("actions", "Actions"),
+        Column::integer("executed", "Executed"),
+        Column::seconds("execution_time", "Exec Time", 2),
+        Column::seconds("queue_time", "Queue Time", 2),
+        Column::seconds("avg_queue_time", "Avg Queue", 3),
+        Column::seconds("max_queue_time", "Max Queue", 3),
+    ]);
+    for (pool, stats) in sorted_pools {
+        let avg_queue = if stats.executed > 0 {
+            stats.total_queue.as_secs_f64() / stats.executed as f64
+        } else {
+            0.0
+        };
+        table.push_row(vec![
+            pool.into(),
+            stats.count.into(),
+            stats.executed.into(),
+            stats.total_execution.into(),
+            stats.total_queue.into(),
+            avg_queue.into(),
+            stats.max_queue.into(),
+        ]);
     }
-    println!();
-}
\ No newline at end of file
+    section.push_table(table);
+    section.push_text("Hint: Pools with high average queue times are saturated; pools with few executed actions may be over-provisioned.");
+    section
+}
+
+/// Command lines above this size are flagged as candidates for params files. It is past the
+/// Windows limit of 32,767 characters and a sizeable fraction of Linux's per-argument limit.
+const ARGV_WARNING_BYTES: usize = 128 * 1024;
+
+/// Size of the command line as passed to exec: every argument plus its terminating NUL.
+fn command_line_bytes(spawn: &SpawnExec) -> usize {
+    spawn.command_args.iter().map(|arg| arg.len() + 1).sum()
+}
+
+/// Returns true if an argument references a params file (`@file` or `--flagfile=file`).
+fn is_params_file_arg(arg: &str) -> bool {
+    (arg.starts_with('@') && arg.len() > 1) || arg.starts_with("--flagfile=")
+}
+
+fn command_line_sections(spawns: &[SpawnExec], top_n: usize) -> Vec<Section> {
+    let mut longest = Section::new(
+        "longest_command_lines",
+        format!("Top {} Longest Command Lines", top_n),
+    );
+
+    let mut by_size: Vec<(usize, &SpawnExec)> = spawns
+        .iter()
+        .filter(|s| !s.command_args.is_empty())
+        .map(|s| (command_line_bytes(s), s))
+        .collect();
+
+    if by_size.is_empty() {
+        longest.push_text("No command lines found in the log.");
+        return vec![longest];
+    }
+    by_size.sort_by_key(|(bytes, _)| std::cmp::Reverse(*bytes));
+
+    let mut table = Table::new(vec![
+        Column::bytes("bytes", "Size", ByteUnit::Kilobytes, 1),
+        Column::integer("argc", "Argc"),
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
+    for (bytes, spawn) in by_size.iter().take(top_n) {
+        table.push_row(vec![
+            (*bytes).into(),
+            spawn.command_args.len().into(),
+            (&spawn.mnemonic).into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    longest.push_table(table);
+
+    let near_limit: Vec<_> = by_size
+        .iter()
+        .filter(|(bytes, spawn)| {
+            *bytes >= ARGV_WARNING_BYTES && !spawn.command_args.iter().any(|arg| is_params_file_arg(arg))
+        })
+        .collect();
+    if !near_limit.is_empty() {
+        longest.push_text(format!(
+            "Warning: {} actions have command lines over {}KB without a params file:",
+            near_limit.len(),
+            ARGV_WARNING_BYTES / 1024
+        ));
+        let mut table = Table::new(vec![
+            Column::bytes("bytes", "Size", ByteUnit::Kilobytes, 1),
+            Column::text("mnemonic", "Mnemonic"),
+            Column::text("target", "Target"),
+            spawn_id_column(),
+        ]);
+        for (bytes, spawn) in near_limit.iter().take(top_n) {
+            table.push_row(vec![
+                (*bytes).into(),
+                (&spawn.mnemonic).into(),
+                display_label(spawn).into(),
+                spawn_id(spawn).into(),
+            ]);
+        }
+        longest.push_table(table);
+        longest.push_text("Hint: Switch these actions to params files (e.g. `Args.use_param_file`) before they hit OS argv limits.");
+    }
+
+    let mut executables_section = Section::new("common_executables", "Most Common Executables");
+    let mut executables: HashMap<&str, u64> = HashMap::new();
+    for (_, spawn) in &by_size {
+        *executables.entry(&spawn.command_args[0]).or_default() += 1;
+    }
+    let mut sorted_executables: Vec<_> = executables.into_iter().collect();
+    sorted_executables.sort_by_key(|(exe, count)| (std::cmp::Reverse(*count), *exe));
+    let mut table = Table::new(vec![
+        Column::integer("actions", "Actions"),
+        Column::text("executable", "Executable"),
+    ]);
+    for (exe, count) in sorted_executables.into_iter().take(top_n) {
+        table.push_row(vec![count.into(), exe.into()]);
+    }
+    executables_section.push_table(table);
+
+    let mut by_mnemonic = Section::new("command_lines_by_mnemonic", "Command Lines by Mnemonic");
+    let mut mnemonic_stats: HashMap<&str, CommandLineStats> = HashMap::new();
+    for (bytes, spawn) in &by_size {
+        let stats = mnemonic_stats.entry(&spawn.mnemonic).or_default();
+        stats.count += 1;
+        stats.total_argc += spawn.command_args.len();
+        stats.max_argc = stats.max_argc.max(spawn.command_args.len());
+        stats.max_bytes = stats.max_bytes.max(*bytes);
+        if spawn.command_args.iter().any(|arg| is_params_file_arg(arg)) {
+            stats.params_file_count += 1;
+        }
+    }
+    let mut sorted_mnemonics: Vec<_> = mnemonic_stats.into_iter().collect();
+    sorted_mnemonics.sort_by_key(|(mnemonic, stats)| (std::cmp::Reverse(stats.max_bytes), *mnemonic));
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("actions", "Actions"),
+        Column::float("avg_argc", "Avg Argc", 1),
+        Column::integer("max_argc", "Max Argc"),
+        Column::bytes("max_bytes", "Max Size", ByteUnit::Kilobytes, 1),
+        Column::percent("params_file_share", "Params File", 1),
+    ]);
+    for (mnemonic, stats) in sorted_mnemonics {
+        table.push_row(vec![
+            mnemonic.into(),
+            stats.count.into(),
+            (stats.total_argc as f64 / stats.count as f64).into(),
+            stats.max_argc.into(),
+            stats.max_bytes.into(),
+            percentage(stats.params_file_count as f64, stats.count as f64).into(),
+        ]);
+    }
+    by_mnemonic.push_table(table);
+
+    vec![longest, executables_section, by_mnemonic]
+}
+
+/// Size of one environment entry as serialized for exec: `NAME=value` plus a terminating NUL.
+fn env_var_bytes(var: &crate::proto::EnvironmentVariable) -> usize {
+    var.name.len() + var.value.len() + 2
+}
+
+fn environment_bytes(spawn: &SpawnExec) -> usize {
+    spawn.environment_variables.iter().map(env_var_bytes).sum()
+}
+
+fn environment_size_sections(spawns: &[SpawnExec], top_n: usize) -> Vec<Section> {
+    let mut largest = Section::new(
+        "environment_size",
+        format!("Top {} Actions by Environment Size", top_n),
+    );
+
+    let mut by_size: Vec<(usize, &SpawnExec)> = spawns
+        .iter()
+        .map(|s| (environment_bytes(s), s))
+        .filter(|(bytes, _)| *bytes > 0)
+        .collect();
+
+    if by_size.is_empty() {
+        largest.push_text("No environment variables found in the log.");
+        return vec![largest];
+    }
+    by_size.sort_by_key(|(bytes, _)| std::cmp::Reverse(*bytes));
+
+    let mut table = Table::new(vec![
+        Column::bytes("bytes", "Env Size", ByteUnit::Kilobytes, 1),
+        Column::integer("variables", "Vars"),
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
+    for (bytes, spawn) in by_size.iter().take(top_n) {
+        table.push_row(vec![
+            (*bytes).into(),
+            spawn.environment_variables.len().into(),
+            (&spawn.mnemonic).into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    largest.push_table(table);
+
+    let mut by_mnemonic = Section::new("environment_size_by_mnemonic", "Environment Size by Mnemonic");
+    let mut mnemonic_stats: HashMap<&str, EnvironmentStats> = HashMap::new();
+    for (bytes, spawn) in &by_size {
+        let stats = mnemonic_stats.entry(&spawn.mnemonic).or_default();
+        stats.count += 1;
+        stats.total_bytes += bytes;
+        stats.max_bytes = stats.max_bytes.max(*bytes);
+    }
+    let mut sorted_mnemonics: Vec<_> = mnemonic_stats.into_iter().collect();
+    sorted_mnemonics.sort_by_key(|(mnemonic, stats)| (std::cmp::Reverse(stats.total_bytes), *mnemonic));
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("actions", "Actions"),
+        Column::bytes("avg_bytes", "Avg Size", ByteUnit::Kilobytes, 1),
+        Column::bytes("max_bytes", "Max Size", ByteUnit::Kilobytes, 1),
+        Column::bytes("total_bytes", "Total Size", ByteUnit::Megabytes, 2),
+    ]);
+    for (mnemonic, stats) in sorted_mnemonics {
+        table.push_row(vec![
+            mnemonic.into(),
+            stats.count.into(),
+            Value::Float(stats.total_bytes as f64 / stats.count as f64),
+            stats.max_bytes.into(),
+            stats.total_bytes.into(),
+        ]);
+    }
+    by_mnemonic.push_table(table);
+
+    let mut variables_section = Section::new("largest_environment_variables", "Largest Environment Variables");
+    // Keyed by name; tracks the largest value seen and how many spawns carry the variable.
+    let mut variables: HashMap<&str, (usize, u64)> = HashMap::new();
+    for (_, spawn) in &by_size {
+        for var in &spawn.environment_variables {
+            let entry = variables.entry(&var.name).or_default();
+            entry.0 = entry.0.max(env_var_bytes(var));
+            entry.1 += 1;
+        }
+    }
+    let mut sorted_variables: Vec<_> = variables.into_iter().collect();
+    sorted_variables.sort_by_key(|(name, (bytes, _))| (std::cmp::Reverse(*bytes), *name));
+    let mut table = Table::new(vec![
+        Column::bytes("max_bytes", "Max Size", ByteUnit::Kilobytes, 1),
+        Column::integer("actions", "Actions"),
+        Column::text("variable", "Variable"),
+    ]);
+    for (name, (bytes, count)) in sorted_variables.into_iter().take(top_n) {
+        table.push_row(vec![bytes.into(), count.into(), name.into()]);
+    }
+    variables_section.push_table(table);
+    variables_section.push_text("Hint: Large inherited variables (PATH, JAVA_TOOL_OPTIONS) are part of the action key; prefer `--incompatible_strict_action_env`.");
+
+    vec![largest, by_mnemonic, variables_section, environment_secrets_section(spawns, top_n)]
+}
+
+/// Name fragments of variables that usually hold credentials.
+const SECRET_NAME_PARTS: &[&str] = &[
+    "TOKEN", "SECRET", "PASSWORD", "PASSWD", "API_KEY", "APIKEY", "PRIVATE_KEY", "ACCESS_KEY", "CREDENTIAL", "AUTH",
+];
+
+/// Value prefixes of well-known credential formats (GitHub, GitLab, Slack, AWS, OpenAI-style keys).
+const SECRET_VALUE_PREFIXES: &[&str] =
+    &["ghp_", "gho_", "ghs_", "ghu_", "github_pat_", "glpat-", "xoxb-", "xoxp-", "xoxa-", "AKIA", "ASIA", "sk-"];
+
+/// Shannon entropy, in bits per character, above which a long opaque value looks like a random
+/// token. Hex digests top out at 4 bits, so they stay below it.
+const SECRET_MIN_ENTROPY: f64 = 4.2;
+
+/// Shortest value the entropy heuristic considers.
+const SECRET_MIN_LENGTH: usize = 20;
+
+/// A variable named like a credential counts as one only if its value is at least this long or
+/// at least this varied (bits per character), so that flags such as `false` or `1` don't.
+const SECRET_NAME_MIN_LENGTH: usize = 12;
+const SECRET_NAME_MIN_ENTROPY: f64 = 3.0;
+
+fn shannon_entropy(value: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let length = value.chars().count() as f64;
+    counts.values().fold(0.0, |entropy, &count| {
+        let p = count as f64 / length;
+        entropy - p * p.log2()
+    })
+}
+
+/// Whether a value is a single token rather than a path, a list or words: paths and lists are long
+/// and varied but never a credential.
+fn is_opaque(value: &str) -> bool {
+    !value.contains(|c: char| c.is_whitespace() || matches!(c, ':' | ';' | ','))
+        && !value.starts_with(['/', '\\', '~', '.'])
+}
+
+/// Whether the value of a variable named like a credential looks like one: an opaque token, not a
+/// boolean or number, and long or varied enough.
+fn is_credential_like(value: &str) -> bool {
+    let flag = matches!(value.to_ascii_lowercase().as_str(), "true" | "false" | "yes" | "no" | "on" | "off");
+    is_opaque(value)
+        && !flag
+        && value.parse::<f64>().is_err()
+        && (value.len() >= SECRET_NAME_MIN_LENGTH || shannon_entropy(value) >= SECRET_NAME_MIN_ENTROPY)
+}
+
+/// Why an environment variable looks like it carries a secret, or `None` if it doesn't.
+fn secret_reason(name: &str, value: &str) -> Option<&'static str> {
+    if value.is_empty() {
+        return None;
+    }
+    if value.starts_with("-----BEGIN") {
+        return Some("private key");
+    }
+    if SECRET_VALUE_PREFIXES.iter().any(|prefix| value.starts_with(prefix) && value.len() >= prefix.len() + 16) {
+        return Some("token format");
+    }
+    let upper = name.to_ascii_uppercase();
+    if SECRET_NAME_PARTS.iter().any(|part| upper.contains(part))
+        || (upper.starts_with("AWS_") && !upper.ends_with("REGION") && !upper.ends_with("PROFILE"))
+    {
+        return is_credential_like(value).then_some("name");
+    }
+    (is_opaque(value) && value.len() >= SECRET_MIN_LENGTH && shannon_entropy(value) >= SECRET_MIN_ENTROPY).then_some("high entropy")
+}
+
+#[derive(Default)]
+struct SecretStats<'a> {
+    reason: &'static str,
+    max_length: usize,
+    actions: u64,
+    values: HashSet<&'a str>,
+    mnemonics: BTreeSet<&'a str>,
+}
+
+fn environment_secrets_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new("environment_secrets", "Likely Secrets in Action Environments");
+    let mut variables: BTreeMap<&str, SecretStats> = BTreeMap::new();
+    let mut actions: Vec<(Vec<&str>, &SpawnExec)> = Vec::new();
+    for spawn in spawns {
+        let mut names = Vec::new();
+        for var in &spawn.environment_variables {
+            let Some(reason) = secret_reason(&var.name, &var.value) else { continue };
+            let stats = variables.entry(&var.name).or_default();
+            // A value-based reason is stronger evidence than the name alone.
+            if stats.reason.is_empty() || stats.reason == "name" {
+                stats.reason = reason;
+            }
+            stats.max_length = stats.max_length.max(var.value.len());
+            stats.actions += 1;
+            stats.values.insert(&var.value);
+            stats.mnemonics.insert(&spawn.mnemonic);
+            names.push(var.name.as_str());
+        }
+        if !names.is_empty() {
+            actions.push((names, spawn));
+        }
+    }
+    if variables.is_empty() {
+        section.push_text("No environment variables look like secrets.");
+        return section;
+    }
+
+    // Values are never shown: only their length and how many distinct ones occur.
+    let mut sorted_variables: Vec<_> = variables.into_iter().collect();
+    sorted_variables.sort_by_key(|(name, stats)| (std::cmp::Reverse(stats.actions), *name));
+    let mut table = Table::new(vec![
+        Column::text("variable", "Variable"),
+        Column::text("reason", "Reason"),
+        Column::integer("value_length", "Value Length"),
+        Column::integer("distinct_values", "Distinct Values"),
+        Column::integer("actions", "Actions"),
+        Column::text("mnemonics", "Mnemonics"),
+    ]);
+    for (name, stats) in &sorted_variables {
+        table.push_row(vec![
+            (*name).into(),
+            stats.reason.into(),
+            stats.max_length.into(),
+            stats.values.len().into(),
+            stats.actions.into(),
+            stats.mnemonics.iter().copied().collect::<Vec<_>>().join(", ").into(),
+        ]);
+    }
+    section.push_table(table);
+
+    actions.sort_by_key(|(names, _)| std::cmp::Reverse(names.len()));
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+        Column::text("variables", "Variables"),
+        spawn_id_column(),
+    ]);
+    for (names, spawn) in actions.iter().take(top_n) {
+        table.push_row(vec![
+            (&spawn.mnemonic).into(),
+            display_label(spawn).into(),
+            names.join(", ").into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    section.push_table(table);
+
+    section.push_text(format!(
+        "Warning: {} variable(s) in {} action(s) look like credentials. The environment is part of the action key \
+         and of this log, so their values end up in cache keys, exec logs and build event streams, and every \
+         rotation changes the keys of all these actions. Pass credentials through `--credential_helper` or files \
+         read at runtime, and drop them from `--action_env`.",
+        sorted_variables.len(),
+        actions.len()
+    ));
+    let rotating: Vec<&str> =
+        sorted_variables.iter().filter(|(_, stats)| stats.values.len() > 1).map(|(name, _)| *name).collect();
+    if !rotating.is_empty() {
+        section.push_text(format!(
+            "Warning: {} already had more than one value in this build, splitting the cache between them.",
+            rotating.join(", ")
+        ));
+    }
+    section
+}
+
+/// Seconds from the first start to the last end among the spawns with a start time, or `None`
+/// if no spawn has one.
+pub(crate) fn wall_time(spawns: &[SpawnExec]) -> Option<f64> {
+    let (first, last) = spawns
+        .iter()
+        .filter_map(|s| Some((start_seconds(s)?, end_seconds(s)?)))
+        .reduce(|(first, last), (start, end)| (first.min(start), last.max(end)))?;
+    Some(last - first)
+}
+
+/// What a row's time is a share of in the main tables: the summed time of every spawn, and the
+/// build's wall time when start times were recorded.
+pub(crate) struct BuildTime {
+    total: f64,
+    wall: Option<f64>,
+}
+
+impl BuildTime {
+    pub(crate) fn of(spawns: &[SpawnExec]) -> BuildTime {
+        BuildTime {
+            total: spawns.iter().map(|s| total_time(s).as_secs_f64()).sum(),
+            wall: wall_time(spawns),
+        }
+    }
+
+    /// The share columns, to append to a table; `% of Wall Time` only with a wall time.
+    pub(crate) fn columns(&self) -> Vec<Column> {
+        let mut columns = vec![Column::percent("total_time_share", "% of Total Time", 1)];
+        if self.wall.is_some() {
+            columns.push(Column::percent("wall_time_share", "% of Wall Time", 1));
+        }
+        columns
+    }
+
+    /// The values of [`columns`](Self::columns) for a row taking `seconds`.
+    pub(crate) fn shares(&self, seconds: f64) -> Vec<Value> {
+        let mut shares = vec![percentage(seconds, self.total).into()];
+        if let Some(wall) = self.wall {
+            shares.push(percentage(seconds, wall).into());
+        }
+        shares
+    }
+}
+
+/// Start time of a spawn in seconds since the Unix epoch, if the log recorded one.
+pub(crate) fn start_seconds(spawn: &SpawnExec) -> Option<f64> {
+    spawn
+        .metrics
+        .as_ref()
+        .and_then(|m| m.start_time.as_ref())
+        .filter(|t| t.seconds > 0 || t.nanos > 0)
+        .map(|t| t.seconds as f64 + f64::from(t.nanos) / 1e9)
+}
+
+const SPARKLINE_SYMBOLS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Splits the time covered by `spawns` into spans of constant load, where each running spawn adds
+/// `weight(spawn)`, and returns `(load, seconds)` pairs in time order. Spawns without a start time
+/// are skipped.
+pub(crate) fn load_spans(spawns: &[&SpawnExec], weight: impl Fn(&SpawnExec) -> i64) -> Vec<(i64, f64)> {
+    let mut events: Vec<(f64, i64)> = Vec::new();
+    for spawn in spawns {
+        if let Some(start) = start_seconds(spawn) {
+            events.push((start, weight(spawn)));
+            events.push((start + total_time(spawn).as_secs_f64(), -weight(spawn)));
+        }
+    }
+    // Ends sort before starts at the same instant, so back-to-back actions do not overlap.
+    events.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    let mut load = 0;
+    events
+        .windows(2)
+        .map(|pair| {
+            load += pair[0].1;
+            (load, pair[1].0 - pair[0].0)
+        })
+        .collect()
+}
+
+/// Share of the executing time spent at peak concurrency above which `--jobs` looks like the limit.
+pub(crate) const MIN_SATURATED_SHARE: f64 = 25.0;
+
+/// How many spawns ran at once, over the time any of them ran.
+pub(crate) struct Parallelism {
+    pub peak: i64,
+    /// Time-weighted mean.
+    pub average: f64,
+    /// Time-weighted 95th percentile.
+    pub p95: i64,
+    /// Share of the time spent at `peak`.
+    pub at_peak_share: f64,
+    /// Seconds spent at each level, most common first.
+    pub levels: Vec<(i64, f64)>,
+}
+
+impl Parallelism {
+    /// `None` when no spawn has a start time and a duration.
+    pub(crate) fn of(spawns: &[&SpawnExec]) -> Option<Self> {
+        let mut levels: BTreeMap<i64, f64> = BTreeMap::new();
+        for (running, seconds) in load_spans(spawns, |_| 1) {
+            if running > 0 {
+                *levels.entry(running).or_default() += seconds;
+            }
+        }
+        let busy = levels.values().fold(0.0, |sum, seconds| sum + seconds);
+        if busy <= 0.0 {
+            return None;
+        }
+        let (&peak, &at_peak) = levels.last_key_value()?;
+        let mut covered = 0.0;
+        let p95 = levels
+            .iter()
+            .find(|&(_, seconds)| {
+                covered += seconds;
+                covered >= 0.95 * busy
+            })
+            .map_or(peak, |(&running, _)| running);
+        let average = levels.iter().map(|(&running, seconds)| running as f64 * seconds).sum::<f64>() / busy;
+        let mut levels: Vec<(i64, f64)> = levels.into_iter().collect();
+        levels.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.0.cmp(&a.0)));
+        Some(Parallelism {
+            peak,
+            average,
+            p95,
+            at_peak_share: percentage(at_peak, busy),
+            levels,
+        })
+    }
+
+    /// Whether the spawns spent much of their time at a flat ceiling, the shape a `--jobs` limit
+    /// leaves.
+    pub(crate) fn plateaued(&self) -> bool {
+        self.peak >= 2 && self.at_peak_share >= MIN_SATURATED_SHARE
+    }
+
+    /// Whether the ceiling is the build's `--jobs`, so that raising it lets more actions run.
+    pub(crate) fn limited_by_jobs(&self, jobs: Option<i64>) -> bool {
+        self.plateaued() && jobs.is_some_and(|jobs| self.peak >= jobs)
+    }
+}
+
+fn concurrency_section(spawns: &[SpawnExec], top_n: usize, bep: Option<&BuildEvents>) -> Section {
+    let mut section = Section::new("concurrency", "Concurrency and Jobs");
+    let executed: Vec<&SpawnExec> = spawns.iter().filter(|s| !s.cache_hit).collect();
+    let Some(all) = Parallelism::of(&executed) else {
+        section.push_text("No executed actions with a start time found in the log.");
+        return section;
+    };
+    let remote: Vec<&SpawnExec> = executed.iter().copied().filter(|s| runner_kind(s) == RunnerKind::Remote).collect();
+    let local: Vec<&SpawnExec> = executed.iter().copied().filter(|s| is_local_runner(&s.runner)).collect();
+
+    let mut table = Table::new(vec![
+        Column::text("runners", "Executed"),
+        Column::integer("actions", "Actions"),
+        Column::integer("peak", "Peak"),
+        Column::float("average", "Average", 1),
+        Column::integer("p95", "P95"),
+        Column::percent("at_peak_share", "Time at Peak", 1),
+        Column::text("plateau", "Plateau"),
+    ]);
+    let mut rows = vec![("all", executed.len(), all)];
+    for (name, group) in [("remote", &remote), ("local", &local)] {
+        if let Some(parallelism) = Parallelism::of(group) {
+            rows.push((name, group.len(), parallelism));
+        }
+    }
+    for (name, actions, parallelism) in &rows {
+        table.push_row(vec![
+            (*name).into(),
+            (*actions).into(),
+            parallelism.peak.into(),
+            parallelism.average.into(),
+            parallelism.p95.into(),
+            parallelism.at_peak_share.into(),
+            if parallelism.plateaued() { "yes" } else { "no" }.into(),
+        ]);
+    }
+    section.push_text(
+        "Note: Parallelism counts the executed actions running at once, weighted by time, over the time any \
+         action was executing. A plateau is a flat ceiling the actions spent at least a quarter of that time at.",
+    );
+    section.push_table(table);
+
+    let mut levels = Table::new(vec![
+        Column::integer("running", "Running Actions"),
+        Column::seconds("time", "Time", 1),
+        Column::percent("share", "% of Executing Time", 1),
+    ]);
+    let all = &rows[0].2;
+    let busy = all.levels.iter().fold(0.0, |sum, (_, seconds)| sum + seconds);
+    for &(running, seconds) in all.levels.iter().take(top_n) {
+        levels.push_row(vec![running.into(), seconds.into(), percentage(seconds, busy).into()]);
+    }
+    section.push_table(levels);
+
+    let find = |runners: &str| rows.iter().find(|(name, _, _)| *name == runners).map(|(_, _, parallelism)| parallelism);
+    let (remote, local) = (find("remote"), find("local"));
+    let jobs = bep.and_then(BuildEvents::job_limit);
+    if all.limited_by_jobs(jobs) {
+        section.push_text(format!(
+            "Warning: The build was starved by its job limit: {:.0}% of the executing time ran exactly {} actions \
+             at once, its --jobs. Raise --jobs above {}{}",
+            all.at_peak_share,
+            all.peak,
+            all.peak,
+            if remote.is_some() {
+                "; the remote executors never saw more than this ceiling, so their real demand shows only once it \
+                 is raised."
+            } else {
+                "."
+            }
+        ));
+    } else if let Some(jobs) = jobs.filter(|_| all.plateaued()) {
+        section.push_text(format!(
+            "Hint: {:.0}% of the executing time ran exactly {} actions at once, below --jobs={}, so the ceiling is \
+             not the job limit but e.g. {}.",
+            all.at_peak_share,
+            all.peak,
+            jobs,
+            if remote.is_some() { "the remote executor pool or --remote_max_connections" } else { "local resources" }
+        ));
+    } else if let Some(local) = local.filter(|local| local.plateaued()) {
+        section.push_text(format!(
+            "Warning: {:.0}% of the executing time ran exactly {} local actions at once, the shape of a --jobs or \
+             --local_resources=cpu limit. Raise it if the machine has idle cores during the build.",
+            local.at_peak_share, local.peak
+        ));
+    } else if all.plateaued() && remote.is_some() {
+        section.push_text(format!(
+            "Hint: {:.0}% of the executing time ran exactly {} actions at once, the shape of a --jobs limit. Pass \
+             the build's --bep file to check it against --jobs before raising it.",
+            all.at_peak_share, all.peak
+        ));
+    } else if all.plateaued() {
+        section.push_text(format!(
+            "Warning: {:.0}% of the executing time ran exactly {} actions at once without remote executions, and no \
+             single kind of runner sat at that ceiling. The limit is --jobs, a worker or resource limit, or actions \
+             on runners this report does not recognize; pass the build's --bep file to check --jobs.",
+            all.at_peak_share, all.peak
+        ));
+    } else {
+        section.push_text(format!(
+            "Hint: No job limit held the build back: at most {} actions ran at once and {:.1} on average, so the \
+             dependency graph, not --jobs, bounds the parallelism.",
+            all.peak, all.average
+        ));
+    }
+    if let Some(remote) = remote
+        && !all.plateaued()
+    {
+        section.push_text(format!(
+            "Hint: Remote executions needed {} concurrent slots 95% of the time and {} at peak; an executor pool \
+             of about {} keeps them from queueing (see --queue-analysis).",
+            remote.p95, remote.peak, remote.p95
+        ));
+    }
+    section
+}
+
+/// Shortest stretch without any running action that the start-latency report lists.
+const MIN_IDLE_GAP_SECONDS: f64 = 5.0;
+
+/// A stretch of the build without any running action, between the last action to finish and the
+/// next to start.
+struct IdleGap<'a> {
+    start: f64,
+    seconds: f64,
+    before: &'a SpawnExec,
+    after: &'a SpawnExec,
+}
+
+fn start_latency_section(spawns: &[SpawnExec], inputs: &SideInputs, top_n: usize) -> Section {
+    let mut section = Section::new("start_latency", "Start Latency and Idle Gaps");
+    let mut timed: Vec<(f64, f64, &SpawnExec)> = spawns
+        .iter()
+        .filter_map(|s| start_seconds(s).map(|start| (start, start + total_time(s).as_secs_f64(), s)))
+        .collect();
+    timed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let (Some(&(first_start, _, _)), Some(last_end)) =
+        (timed.first(), timed.iter().map(|(_, end, _)| *end).reduce(f64::max))
+    else {
+        section.push_text("No actions with a start time found in the log.");
+        return section;
+    };
+
+    let mut gaps = Vec::new();
+    let (mut running_until, mut last) = (timed[0].1, timed[0].2);
+    for &(start, end, spawn) in &timed[1..] {
+        if start - running_until >= MIN_IDLE_GAP_SECONDS {
+            gaps.push(IdleGap {
+                start: running_until,
+                seconds: start - running_until,
+                before: last,
+                after: spawn,
+            });
+        }
+        if end > running_until {
+            (running_until, last) = (end, spawn);
+        }
+    }
+    let idle = gaps.iter().fold(0.0, |sum, gap| sum + gap.seconds);
+
+    let bep = inputs.bep.as_ref();
+    let build_start = bep.and_then(|bep| bep.start_millis).map(|ms| ms as f64 / 1000.0);
+    let build_finish = bep.and_then(|bep| bep.finish_millis).map(|ms| ms as f64 / 1000.0);
+    let before_first = build_start.map(|start| first_start - start);
+    let after_last = build_finish.map(|finish| finish - last_end);
+    section.push_field(
+        Column::seconds("before_first_action", "Time before first action", 2),
+        before_first.map_or(Value::Null, Value::from),
+    );
+    section.push_field(Column::seconds("action_span", "First action to last action", 2), last_end - first_start);
+    section.push_field(
+        Column::seconds("after_last_action", "Time after last action", 2),
+        after_last.map_or(Value::Null, Value::from),
+    );
+    section.push_field(Column::integer("idle_gaps", "Idle gaps"), gaps.len());
+    section.push_field(Column::seconds("idle_time", "Idle time between actions", 2), idle);
+    match (before_first, build_start.zip(build_finish)) {
+        (Some(before), Some((start, finish))) => section.push_text(format!(
+            "Note: {:.0}% of the command's {:.1}s passed before the first action started, in loading, analysis \
+             and repository fetching.",
+            percentage(before, finish - start),
+            finish - start
+        )),
+        _ => section.push_text(
+            "Note: Pass the Build Event Protocol file of the invocation with --bep to measure the time from the \
+             command's start to the first action.",
+        ),
+    }
+
+    if !gaps.is_empty() {
+        gaps.sort_by(|a, b| b.seconds.total_cmp(&a.seconds));
+        let mut table = Table::new(vec![
+            Column::seconds("offset", "Offset", 1),
+            Column::seconds("gap", "Gap", 2),
+            Column::text("before", "Last Action Before"),
+            Column::text("after", "First Action After"),
+        ]);
+        for gap in gaps.iter().take(top_n) {
+            let describe = |spawn: &SpawnExec| format!("{} {}", spawn.mnemonic, display_label(spawn));
+            table.push_row(vec![
+                (gap.start - first_start).into(),
+                gap.seconds.into(),
+                describe(gap.before).into(),
+                describe(gap.after).into(),
+            ]);
+        }
+        section.push_table(table);
+    }
+    section.push_text(format!(
+        "Hint: Gaps of {:.0}s or more with no action running are time Bazel spent outside execution: analysis of \
+         targets reached late, repository rules fetching dependencies, or a slow step that is not a spawn. A \
+         JSON profile (--profile) shows which.",
+        MIN_IDLE_GAP_SECONDS
+    ));
+    section
+}
+
+/// Draws one character per bucket, scaled 0-100%; buckets without actions are left blank.
+fn sparkline(rates: &[Option<f64>]) -> String {
+    rates
+        .iter()
+        .map(|rate| match rate {
+            Some(rate) => {
+                let index = (rate.clamp(0.0, 100.0) / 100.0 * (SPARKLINE_SYMBOLS.len() - 1) as f64).round();
+                SPARKLINE_SYMBOLS[index as usize]
+            }
+            None => ' ',
+        })
+        .collect()
+}
+
+/// Most buckets the cache timeline shows; longer builds get wider buckets.
+const MAX_TIMELINE_BUCKETS: u64 = 1000;
+
+/// Seconds from the median start beyond which a start time is taken to be bogus and left out of
+/// the cache timeline.
+const MAX_TIMELINE_OUTLIER_SECONDS: f64 = 86_400.0;
+
+fn cache_timeline_section(spawns: &[SpawnExec], bucket_seconds: u64) -> Section {
+    let mut timed: Vec<(f64, &SpawnExec)> = spawns
+        .iter()
+        .filter_map(|s| start_seconds(s).map(|start| (start, s)))
+        .collect();
+    timed.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    // A single bad timestamp, easy to come by in untrusted or merged logs, would otherwise
+    // stretch the timeline over years of empty buckets.
+    let median = timed.get(timed.len() / 2).map_or(0.0, |(start, _)| *start);
+    let timed_count = timed.len();
+    timed.retain(|(start, _)| (start - median).abs() <= MAX_TIMELINE_OUTLIER_SECONDS);
+    let outliers = timed_count - timed.len();
+
+    let requested_seconds = bucket_seconds.max(1);
+    let span = match (timed.first(), timed.last()) {
+        (Some((first, _)), Some((last, _))) => last - first,
+        _ => 0.0,
+    };
+    let bucket_seconds = requested_seconds.max((span as u64 + 1).div_ceil(MAX_TIMELINE_BUCKETS));
+    let mut section = Section::new(
+        "cache_timeline",
+        format!("Cache Hit Rate over Time ({}s buckets)", bucket_seconds),
+    );
+    let Some((build_start, _)) = timed.first().copied() else {
+        section.push_text("No actions with a start time found in the log.");
+        return section;
+    };
+
+    // Buckets are contiguous from the first action's start so that idle windows show up as gaps.
+    let bucket_count = (span / bucket_seconds as f64) as usize + 1;
+    let mut buckets: Vec<CacheHitCounts> = Vec::new();
+    buckets.resize_with(bucket_count, Default::default);
+    for (start, spawn) in &timed {
+        let index = (((start - build_start) / bucket_seconds as f64) as usize).min(bucket_count - 1);
+        buckets[index].count += 1;
+        buckets[index].cache_hits += u64::from(spawn.cache_hit);
+    }
+
+    let rates: Vec<Option<f64>> = buckets
+        .iter()
+        .map(|b| (b.count > 0).then(|| percentage(b.cache_hits as f64, b.count as f64)))
+        .collect();
+    section.push_field(Column::text("sparkline", "Hit Rate Trend"), sparkline(&rates));
+
+    let mut table = Table::new(vec![
+        Column::seconds("bucket_start", "Offset", 0),
+        Column::integer("actions", "Actions"),
+        Column::integer("cache_hits", "Cache Hits"),
+        Column::percent("cache_hit_rate", "Hit Rate", 1),
+        Column::bar("cache_hit_rate_bar", "", 20, &['#']),
+    ]);
+    for (index, (bucket, rate)) in buckets.iter().zip(&rates).enumerate() {
+        table.push_row(vec![
+            (index as u64 * bucket_seconds).into(),
+            bucket.count.into(),
+            bucket.cache_hits.into(),
+            (*rate).into(),
+            Value::Bar(vec![rate.unwrap_or(0.0) / 100.0]),
+        ]);
+    }
+    section.push_table(table);
+
+    let skipped = spawns.len() - timed_count;
+    if skipped > 0 {
+        section.push_text(format!("{} actions had no start time and were skipped.", skipped));
+    }
+    if outliers > 0 {
+        section.push_text(format!(
+            "{} actions started more than {:.0}h from the median start time and were skipped as bad timestamps.",
+            outliers,
+            MAX_TIMELINE_OUTLIER_SECONDS / 3600.0
+        ));
+    }
+    if bucket_seconds > requested_seconds {
+        section.push_text(format!(
+            "Buckets were widened from {}s to {}s to keep the timeline within {} buckets.",
+            requested_seconds, bucket_seconds, MAX_TIMELINE_BUCKETS
+        ));
+    }
+    section.push_text("Hint: A sudden drop usually follows an action whose output invalidates many downstream keys (e.g. code generation or a stamped header).");
+    section
+}
+
+#[derive(Default)]
+pub(crate) struct DownloadSavings {
+    pub count: u64,
+    pub fetched_bytes: i64,
+    /// Bytes of outputs that no locally executed action reads.
+    pub minimal_bytes: i64,
+    /// Bytes of outputs that no action at all reads, which are likely top-level outputs.
+    pub unconsumed_bytes: i64,
+}
+
+/// Returns true if `path`, or any file below it when it is a tree artifact, is in `consumed`.
+fn is_consumed(consumed: &BTreeSet<&str>, path: &str) -> bool {
+    if consumed.contains(path) {
+        return true;
+    }
+    let prefix = format!("{}/", path);
+    consumed
+        .range::<&str, _>(prefix.as_str()..)
+        .next()
+        .is_some_and(|next| next.starts_with(&prefix))
+}
+
+/// Per mnemonic, the bytes remote cache hits downloaded and how many of them the minimal and
+/// top-level download modes would have skipped, judged by which outputs later actions read.
+pub(crate) fn download_savings_by_mnemonic<'a>(
+    spawns: &'a [SpawnExec],
+    cache_hit_runners: &[String],
+) -> HashMap<&'a str, DownloadSavings> {
+    let mut consumed_locally: BTreeSet<&str> = BTreeSet::new();
+    let mut consumed_anywhere: BTreeSet<&str> = BTreeSet::new();
+    for spawn in spawns {
+        let local = !spawn.cache_hit && is_local_runner(&spawn.runner);
+        for input in &spawn.inputs {
+            consumed_anywhere.insert(&input.path);
+            if local {
+                consumed_locally.insert(&input.path);
+            }
+        }
+    }
+
+    let mut by_mnemonic: HashMap<&str, DownloadSavings> = HashMap::new();
+    for spawn in spawns.iter().filter(|s| cache_hit_runners.contains(&s.runner)) {
+        let savings = by_mnemonic.entry(&spawn.mnemonic).or_default();
+        savings.count += 1;
+        for output in &spawn.actual_outputs {
+            let bytes = output.digest.as_ref().map_or(0, |d| d.size_bytes);
+            savings.fetched_bytes += bytes;
+            if !is_consumed(&consumed_locally, &output.path) {
+                savings.minimal_bytes += bytes;
+            }
+            if !is_consumed(&consumed_anywhere, &output.path) {
+                savings.unconsumed_bytes += bytes;
+            }
+        }
+    }
+    by_mnemonic
+}
+
+fn download_savings_section(spawns: &[SpawnExec], cache_hit_runners: &[String], top_n: usize) -> Section {
+    let mut section = Section::new(
+        "download_savings",
+        "Estimated Savings from Minimal Output Downloads",
+    );
+
+    if spawns.iter().all(|s| s.inputs.is_empty()) {
+        section.push_text("No action inputs were recorded in this log, so output consumption cannot be determined.");
+        return section;
+    }
+    let by_mnemonic = download_savings_by_mnemonic(spawns, cache_hit_runners);
+    if by_mnemonic.is_empty() {
+        section.push_text("No remote cache hits found in the log.");
+        return section;
+    }
+
+    let fetched: i64 = by_mnemonic.values().map(|s| s.fetched_bytes).sum();
+    let minimal: i64 = by_mnemonic.values().map(|s| s.minimal_bytes).sum();
+    let unconsumed: i64 = by_mnemonic.values().map(|s| s.unconsumed_bytes).sum();
+    // Top-level mode still downloads the outputs of requested targets; outputs that nothing
+    // in the build reads are the best available stand-in for those.
+    let toplevel = minimal - unconsumed;
+
+    section.push_field(
+        Column::bytes("fetched_bytes", "Outputs Downloaded on Cache Hits", ByteUnit::DecimalMegabytes, 2),
+        fetched,
+    );
+    section.push_field(
+        Column::bytes("minimal_savings_bytes", "Avoidable with --remote_download_minimal", ByteUnit::DecimalMegabytes, 2),
+        minimal,
+    );
+    section.push_field(
+        Column::percent("minimal_savings_share", "Minimal Savings Share", 1),
+        percentage(minimal as f64, fetched as f64),
+    );
+    section.push_field(
+        Column::bytes("toplevel_savings_bytes", "Avoidable with --remote_download_toplevel (est.)", ByteUnit::DecimalMegabytes, 2),
+        toplevel,
+    );
+    section.push_field(
+        Column::percent("toplevel_savings_share", "Top-Level Savings Share", 1),
+        percentage(toplevel as f64, fetched as f64),
+    );
+
+    let mut sorted: Vec<_> = by_mnemonic.into_iter().collect();
+    sorted.sort_by_key(|(mnemonic, s)| (std::cmp::Reverse(s.minimal_bytes), *mnemonic));
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("cache_hits", "Cache Hits"),
+        Column::bytes("fetched_bytes", "Downloaded", ByteUnit::DecimalMegabytes, 2),
+        Column::bytes("minimal_savings_bytes", "Minimal Savings", ByteUnit::DecimalMegabytes, 2),
+        Column::bytes("toplevel_savings_bytes", "Top-Level Savings", ByteUnit::DecimalMegabytes, 2),
+    ]);
+    for (mnemonic, savings) in sorted.into_iter().take(top_n) {
+        table.push_row(vec![
+            mnemonic.into(),
+            savings.count.into(),
+            savings.fetched_bytes.into(),
+            savings.minimal_bytes.into(),
+            (savings.minimal_bytes - savings.unconsumed_bytes).into(),
+        ]);
+    }
+    section.push_table(table);
+    section.push_text("Note: Minimal mode still downloads outputs read by locally executed actions. The top-level estimate also keeps outputs no action reads, assuming they are the requested top-level outputs.");
+    section
+}
+
+fn hermeticity_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new("hermeticity", "Hermeticity Findings");
+    section.push_text("Note: Only executed actions are checked. Use --sarif to annotate the BUILD files in code scanning.");
+
+    let mut findings = hermeticity_findings(spawns);
+    if findings.is_empty() {
+        section.push_text("No hermeticity findings.");
+        return section;
+    }
+    for rule in RULES {
+        let count = findings.iter().filter(|f| f.rule.id == rule.id).count();
+        section.push_field(Column::integer(&rule.id.replace(['/', '-'], "_"), rule.name), count);
+    }
+
+    findings.sort_by(|a, b| {
+        b.rule.level.cmp(&a.rule.level)
+            .then(b.action_count.cmp(&a.action_count))
+            .then_with(|| a.target_label.cmp(&b.target_label))
+    });
+    let mut table = Table::new(vec![
+        Column::text("rule", "Rule"),
+        Column::integer("actions", "Actions"),
+        Column::text("target", "Target"),
+        Column::text("message", "Message"),
+    ]);
+    for finding in findings.iter().take(top_n) {
+        table.push_row(vec![
+            finding.rule.id.into(),
+            finding.action_count.into(),
+            finding.target_label.as_str().into(),
+            finding.message.as_str().into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
+
+#[derive(Default)]
+struct ProfileOverhead {
+    count: u64,
+    action_seconds: f64,
+    spawn_seconds: f64,
+}
+
+fn profile_correlation_section(spawns: &[SpawnExec], inputs: &SideInputs, top_n: usize) -> Section {
+    let mut section = Section::new("profile_correlation", "Profile Correlation");
+    let Some(profile) = inputs.profile.as_ref() else {
+        section.push_text("No JSON profile given; pass one with --profile.");
+        return section;
+    };
+    section.push_text("Note: \"Outside Spawn\" is the time Bazel spent on an action beyond its spawn: input checking, scheduling, output handling and waiting for resources.");
+    if profile.actions.iter().all(|a| a.label.is_empty() && a.primary_output.is_empty()) {
+        section.push_text("The profile's actions carry no target labels or outputs to match on. Record it with --experimental_profile_include_target_label.");
+        return section;
+    }
+
+    let joined = profile::join(profile, spawns);
+    section.push_field(Column::integer("profile_actions", "Profile actions"), profile.actions.len());
+    section.push_field(Column::integer("matched_actions", "Matched actions"), joined.matched.len());
+    section.push_field(Column::integer("unmatched_profile_actions", "Profile actions without a spawn"), joined.unmatched_actions);
+    section.push_field(Column::integer("unmatched_spawns", "Spawns without a profile action"), joined.unmatched_spawns);
+    section.push_field(Column::seconds("profile_wall_time", "Profile wall time", 2), profile.wall_time_micros / 1e6);
+
+    let mut by_mnemonic: HashMap<&str, ProfileOverhead> = HashMap::new();
+    let mut totals = ProfileOverhead::default();
+    for matched in &joined.matched {
+        let action_seconds = matched.action.duration_micros / 1e6;
+        let spawn_seconds = total_time(matched.spawn).as_secs_f64();
+        for stats in [by_mnemonic.entry(&matched.spawn.mnemonic).or_default(), &mut totals] {
+            stats.count += 1;
+            stats.action_seconds += action_seconds;
+            stats.spawn_seconds += spawn_seconds;
+        }
+    }
+    let outside = |stats: &ProfileOverhead| (stats.action_seconds - stats.spawn_seconds).max(0.0);
+    section.push_field(Column::seconds("action_time", "Total action time (profile)", 2), totals.action_seconds);
+    section.push_field(Column::seconds("spawn_time", "Total spawn time (execution log)", 2), totals.spawn_seconds);
+    section.push_field(Column::seconds("outside_spawn_time", "Total time outside spawns", 2), outside(&totals));
+    if joined.matched.is_empty() {
+        return section;
+    }
+
+    let mut mnemonics: Vec<_> = by_mnemonic.into_iter().collect();
+    mnemonics.sort_by(|(a_name, a), (b_name, b)| outside(b).total_cmp(&outside(a)).then(a_name.cmp(b_name)));
+    let mut mnemonic_table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("count", "Actions"),
+        Column::seconds("action_time", "Action Time", 2),
+        Column::seconds("spawn_time", "Spawn Time", 2),
+        Column::seconds("outside_spawn_time", "Outside Spawn", 2),
+        Column::percent("outside_spawn_share", "Outside %", 1),
+    ]);
+    for (mnemonic, stats) in &mnemonics {
+        mnemonic_table.push_row(vec![
+            (*mnemonic).into(),
+            stats.count.into(),
+            stats.action_seconds.into(),
+            stats.spawn_seconds.into(),
+            outside(stats).into(),
+            percentage(outside(stats), stats.action_seconds).into(),
+        ]);
+    }
+    section.push_table(mnemonic_table);
+
+    let mut slowest: Vec<_> = joined
+        .matched
+        .iter()
+        .map(|m| (m, (m.action.duration_micros / 1e6 - total_time(m.spawn).as_secs_f64()).max(0.0)))
+        .collect();
+    slowest.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    let mut action_table = Table::new(vec![
+        Column::seconds("outside_spawn_time", "Outside Spawn", 3),
+        Column::seconds("action_time", "Action Time", 3),
+        Column::seconds("spawn_time", "Spawn Time", 3),
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+        Column::text("description", "Description"),
+        spawn_id_column(),
+    ]);
+    for (matched, outside_seconds) in slowest.into_iter().take(top_n) {
+        action_table.push_row(vec![
+            outside_seconds.into(),
+            (matched.action.duration_micros / 1e6).into(),
+            total_time(matched.spawn).into(),
+            matched.spawn.mnemonic.as_str().into(),
+            display_label(matched.spawn).into(),
+            matched.action.description.as_str().into(),
+            spawn_id(matched.spawn).into(),
+        ]);
+    }
+    section.push_table(action_table);
+    section
+}
+
+#[derive(Default)]
+struct TargetKindStats {
+    targets: u64,
+    spawns: u64,
+    total_duration: Duration,
+}
+
+fn invocation_section(spawns: &[SpawnExec], inputs: &SideInputs, top_n: usize) -> Section {
+    let mut section = Section::new("invocation", "Invocation");
+    let Some(bep) = inputs.bep.as_ref() else {
+        section.push_text("No Build Event Protocol file given; pass one with --bep.");
+        return section;
+    };
+    section.push_field(Column::text("command", "Command"), bep.command.as_str());
+    section.push_field(Column::text("bazel_version", "Bazel version"), bep.bazel_version.as_str());
+    section.push_field(Column::text("exit_code", "Exit code"), bep.exit_code.as_str());
+    section.push_field(
+        Column::seconds("wall_time", "Wall time", 2),
+        bep.wall_time_millis.map_or(Value::Null, |ms| (ms as f64 / 1000.0).into()),
+    );
+    section.push_field(Column::integer("configurations", "Configurations"), bep.configurations.len());
+    section.push_field(Column::integer("requested_targets", "Requested targets"), bep.targets.len());
+    section.push_field(Column::integer("aspects", "Aspect applications"), bep.aspects.len());
+    section.push_field(Column::integer("failed_targets", "Failed targets"), bep.failed_targets().count());
+    if !bep.command_line.is_empty() {
+        section.push_field(Column::text("command_line", "Command line"), bep.command_line.join(" "));
+    }
+
+    let failed: Vec<_> = bep.failed_targets().collect();
+    if !failed.is_empty() {
+        let mut table = Table::new(vec![
+            Column::text("target", "Failed Target"),
+            Column::text("kind", "Kind"),
+            Column::text("configuration", "Configuration"),
+        ]);
+        for (label, target) in failed.into_iter().take(top_n) {
+            let configuration = bep
+                .configurations
+                .get(&target.configuration_id)
+                .map_or(target.configuration_id.as_str(), |c| c.mnemonic.as_str());
+            table.push_row(vec![label.into(), target.kind.as_str().into(), configuration.into()]);
+        }
+        section.push_table(table);
+    }
+
+    // BEP announces only the requested (top-level) targets; spawns of their dependencies are
+    // grouped together.
+    const DEPENDENCIES: &str = "(dependencies)";
+    let mut kinds: HashMap<&str, TargetKindStats> = HashMap::new();
+    for target in bep.targets.values() {
+        kinds.entry(target.kind.as_str()).or_default().targets += 1;
+    }
+    for spawn in spawns {
+        let kind = bep.targets.get(&spawn.target_label).map_or(DEPENDENCIES, |t| t.kind.as_str());
+        let stats = kinds.entry(kind).or_default();
+        stats.spawns += 1;
+        stats.total_duration += total_time(spawn);
+    }
+    let total_seconds: f64 = spawns.iter().map(|s| total_time(s).as_secs_f64()).sum();
+    let mut kinds: Vec<_> = kinds.into_iter().collect();
+    kinds.sort_by_key(|(kind, stats)| (std::cmp::Reverse(stats.total_duration), *kind));
+
+    let mut table = Table::new(vec![
+        Column::text("target_kind", "Target Kind"),
+        Column::integer("targets", "Targets"),
+        Column::integer("spawns", "Spawns"),
+        Column::seconds("total_time", "Total Time", 2),
+        Column::percent("time_share", "% of Time", 1),
+    ]);
+    for (kind, stats) in kinds {
+        table.push_row(vec![
+            kind.into(),
+            if kind == DEPENDENCIES { Value::Null } else { stats.targets.into() },
+            stats.spawns.into(),
+            stats.total_duration.into(),
+            percentage(stats.total_duration.as_secs_f64(), total_seconds).into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
+
+/// The output directory name (`x86_64-fastbuild`, `android-arm64-v8a-opt`, with the host CPU folded
+/// into its architecture unless --raw-paths is given) of a spawn's first output under `bazel-out/`,
+/// which identifies the configuration it was built in. Other CPUs are kept, so a target built for
+/// two platforms counts as built in two configurations.
+pub(crate) fn output_configuration(spawn: &SpawnExec) -> Option<&str> {
+    let paths = spawn.actual_outputs.iter().map(|f| f.path.as_str());
+    paths.chain(spawn.listed_outputs.iter().map(String::as_str)).find_map(|path| {
+        let mut segments = path.split(['/', '\\']);
+        segments.find(|segment| *segment == "bazel-out")?;
+        segments.next().filter(|segment| !segment.is_empty())
+    })
+}
+
+/// The target label of a spawn, or for a spawn without one a synthetic label naming the directory
+/// of its first output, e.g. `(unlabeled: app/gen)`, so that such spawns group together.
+pub(crate) fn display_label(spawn: &SpawnExec) -> String {
+    if spawn.target_label.is_empty() {
+        synthetic_label(spawn)
+    } else {
+        spawn.target_label.clone()
+    }
+}
+
+fn synthetic_label(spawn: &SpawnExec) -> String {
+    let paths = spawn.actual_outputs.iter().map(|f| f.path.as_str());
+    match paths.chain(spawn.listed_outputs.iter().map(String::as_str)).next() {
+        Some(path) => {
+            let path = output_relative_path(path);
+            format!("(unlabeled: {})", path.rsplit_once('/').map_or(path, |(directory, _)| directory))
+        }
+        None => format!("(unlabeled {})", spawn.mnemonic),
+    }
+}
+
+/// An output path without `bazel-out/<configuration>/` and the `bin` or `genfiles` directory.
+fn output_relative_path(path: &str) -> &str {
+    let Some((_, rest)) = path.split_once("bazel-out/") else {
+        return path;
+    };
+    let rest = rest.split_once('/').map_or(rest, |(_, rest)| rest);
+    rest.strip_prefix("bin/").or_else(|| rest.strip_prefix("genfiles/")).unwrap_or(rest)
+}
+
+fn unlabeled_sections(spawns: &[SpawnExec], top_n: usize) -> Vec<Section> {
+    let mut section = Section::new("unlabeled", "Spawns Without a Target Label");
+    let unlabeled: Vec<&SpawnExec> = spawns.iter().filter(|s| s.target_label.is_empty()).collect();
+    let build_time: Duration = spawns.iter().map(total_time).sum();
+    let unattributed: Duration = unlabeled.iter().map(|s| total_time(s)).sum();
+    section.push_field(Column::integer("spawns", "Unlabeled spawns"), unlabeled.len());
+    section.push_field(
+        Column::percent("spawn_share", "Share of spawns", 1),
+        percentage(unlabeled.len() as f64, spawns.len() as f64),
+    );
+    section.push_field(Column::seconds("unattributed_time", "Unattributed time", 2), unattributed);
+    section.push_field(
+        Column::percent("time_share", "Share of time", 1),
+        percentage(unattributed.as_secs_f64(), build_time.as_secs_f64()),
+    );
+    if unlabeled.is_empty() {
+        section.push_text("Every spawn has a target label.");
+        return vec![section];
+    }
+    section.push_text("Note: Reports show these spawns under a synthetic label naming the directory of their first output, e.g. `(unlabeled: app/gen)`. Middlemen, symlink trees and some rules' internal actions are typically unlabeled; Bazel releases before 6.0 label none.");
+
+    let mut by_mnemonic: HashMap<&str, (u64, Duration)> = HashMap::new();
+    let mut by_label: HashMap<String, (BTreeSet<&str>, u64, Duration)> = HashMap::new();
+    for spawn in &unlabeled {
+        let mnemonic = by_mnemonic.entry(&spawn.mnemonic).or_default();
+        mnemonic.0 += 1;
+        mnemonic.1 += total_time(spawn);
+        let label = by_label.entry(synthetic_label(spawn)).or_default();
+        label.0.insert(&spawn.mnemonic);
+        label.1 += 1;
+        label.2 += total_time(spawn);
+    }
+
+    let mut mnemonics = Section::new("unlabeled_mnemonics", "Unlabeled Spawns by Mnemonic");
+    let mut sorted: Vec<_> = by_mnemonic.into_iter().collect();
+    sorted.sort_by_key(|(name, (count, time))| (std::cmp::Reverse(*time), std::cmp::Reverse(*count), *name));
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("count", "Spawns"),
+        Column::seconds("total_time", "Total Time", 2),
+        Column::percent("time_share", "Share of Unattributed", 1),
+    ]);
+    for (mnemonic, (count, time)) in sorted {
+        table.push_row(vec![
+            mnemonic.into(),
+            count.into(),
+            time.into(),
+            percentage(time.as_secs_f64(), unattributed.as_secs_f64()).into(),
+        ]);
+    }
+    mnemonics.push_table(table);
+
+    let mut labels = Section::new("unlabeled_groups", format!("Top {} Synthetic Labels", top_n));
+    let mut sorted: Vec<_> = by_label.into_iter().collect();
+    sorted.sort_by(|(a, (_, _, a_time)), (b, (_, _, b_time))| b_time.cmp(a_time).then_with(|| a.cmp(b)));
+    let mut table = Table::new(vec![
+        Column::text("synthetic_label", "Synthetic Label"),
+        Column::integer("count", "Spawns"),
+        Column::seconds("total_time", "Total Time", 2),
+        Column::text("mnemonics", "Mnemonics"),
+    ]);
+    for (label, (mnemonic_names, count, time)) in sorted.into_iter().take(top_n) {
+        table.push_row(vec![
+            label.into(),
+            count.into(),
+            time.into(),
+            mnemonic_names.into_iter().collect::<Vec<_>>().join(", ").into(),
+        ]);
+    }
+    labels.push_table(table);
+    vec![section, mnemonics, labels]
+}
+
+#[derive(Default)]
+struct SpawnGroupStats {
+    count: u64,
+    executed: u64,
+    total_duration: Duration,
+}
+
+#[derive(Default)]
+struct DuplicateStats<'a> {
+    count: u64,
+    total_duration: Duration,
+    per_configuration: BTreeMap<&'a str, Duration>,
+}
+
+fn configuration_sections(spawns: &[SpawnExec], inputs: &SideInputs, top_n: usize) -> Vec<Section> {
+    const UNKNOWN: &str = "(no bazel-out output)";
+    let mut section = Section::new("configurations", "Build Configurations");
+    section.push_text("Note: Configurations are read from the spawns' output paths (bazel-out/<configuration>/...); platform and tool details come from --bep when given.");
+
+    let mut by_configuration: HashMap<&str, SpawnGroupStats> = HashMap::new();
+    let mut by_action: HashMap<(&str, &str), DuplicateStats> = HashMap::new();
+    for spawn in spawns {
+        let configuration = output_configuration(spawn);
+        let duration = total_time(spawn);
+        let stats = by_configuration.entry(configuration.unwrap_or(UNKNOWN)).or_default();
+        stats.count += 1;
+        stats.total_duration += duration;
+        if !spawn.cache_hit {
+            stats.executed += 1;
+        }
+        if let Some(configuration) = configuration
+            && !spawn.target_label.is_empty()
+        {
+            let duplicate = by_action.entry((&spawn.target_label, &spawn.mnemonic)).or_default();
+            duplicate.count += 1;
+            duplicate.total_duration += duration;
+            *duplicate.per_configuration.entry(configuration).or_default() += duration;
+        }
+    }
+
+    let bep_configurations: HashMap<Cow<str>, &Configuration> = inputs
+        .bep
+        .iter()
+        .flat_map(|bep| bep.configurations.values())
+        // Output paths are normalized unless --raw-paths is given, so look up both names.
+        .flat_map(|c| [(Cow::Borrowed(c.mnemonic.as_str()), c), (filters::fold_host_cpu(&c.mnemonic), c)])
+        .collect();
+    let total_seconds: f64 = spawns.iter().map(|s| total_time(s).as_secs_f64()).sum();
+    let mut configurations: Vec<_> = by_configuration.into_iter().collect();
+    configurations.sort_by_key(|(name, stats)| (std::cmp::Reverse(stats.total_duration), *name));
+
+    let mut table = Table::new(vec![
+        Column::text("configuration", "Configuration"),
+        Column::text("transition", "Transition"),
+        Column::text("platform", "Platform"),
+        Column::text("tool", "Tool"),
+        Column::integer("count", "Spawns"),
+        Column::integer("executed", "Executed"),
+        Column::seconds("total_time", "Total Time", 2),
+        Column::percent("time_share", "% of Time", 1),
+    ]);
+    for (name, stats) in &configurations {
+        // Starlark transitions append `-ST-<hash of the changed options>` to the directory name.
+        let transition = name.split_once("-ST-").map_or(Value::Null, |(_, hash)| hash.into());
+        let bep = bep_configurations.get(*name);
+        table.push_row(vec![
+            (*name).into(),
+            transition,
+            bep.map_or(Value::Null, |c| c.platform.as_str().into()),
+            bep.map_or(Value::Null, |c| if c.is_tool { "yes" } else { "no" }.into()),
+            stats.count.into(),
+            stats.executed.into(),
+            stats.total_duration.into(),
+            percentage(stats.total_duration.as_secs_f64(), total_seconds).into(),
+        ]);
+    }
+    section.push_table(table);
+
+    let mut duplicates_section = Section::new(
+        "cross_configuration_duplicates",
+        "Actions Built in More Than One Configuration",
+    );
+    duplicates_section.push_text("Note: Duplicate Time is the time spent in all but the most expensive configuration of each action.");
+    let duplicate_time = |stats: &DuplicateStats| {
+        let max = stats.per_configuration.values().max().copied().unwrap_or_default();
+        stats.total_duration.saturating_sub(max)
+    };
+    let mut duplicates: Vec<_> = by_action
+        .into_iter()
+        .filter(|(_, stats)| stats.per_configuration.len() > 1)
+        .collect();
+    if duplicates.is_empty() {
+        duplicates_section.push_text("No action was built under more than one configuration.");
+        return vec![section, duplicates_section];
+    }
+    duplicates.sort_by(|(a_key, a), (b_key, b)| duplicate_time(b).cmp(&duplicate_time(a)).then(a_key.cmp(b_key)));
+    let total_duplicate: Duration = duplicates.iter().map(|(_, stats)| duplicate_time(stats)).sum();
+    duplicates_section.push_field(Column::integer("duplicated_actions", "Actions built more than once"), duplicates.len());
+    duplicates_section.push_field(Column::seconds("duplicate_time", "Total duplicate time", 2), total_duplicate);
+
+    let mut table = Table::new(vec![
+        Column::seconds("duplicate_time", "Duplicate Time", 2),
+        Column::seconds("total_time", "Total Time", 2),
+        Column::integer("count", "Spawns"),
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+        Column::text("configurations", "Configurations"),
+    ]);
+    for ((label, mnemonic), stats) in duplicates.iter().take(top_n) {
+        let names: Vec<&str> = stats.per_configuration.keys().copied().collect();
+        table.push_row(vec![
+            duplicate_time(stats).into(),
+            stats.total_duration.into(),
+            stats.count.into(),
+            (*mnemonic).into(),
+            (*label).into(),
+            names.join(", ").into(),
+        ]);
+    }
+    duplicates_section.push_table(table);
+    vec![section, duplicates_section]
+}
+
+/// Why a spawn ran, as far as its mnemonic and output paths tell.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum SpawnOrigin {
+    Primary,
+    Tool,
+    IdeAspect,
+    LintAspect,
+    FormatCheck,
+}
+
+impl SpawnOrigin {
+    fn title(self) -> &'static str {
+        match self {
+            SpawnOrigin::Primary => "Primary build",
+            SpawnOrigin::Tool => "Tools (exec configuration)",
+            SpawnOrigin::IdeAspect => "IDE indexing aspects",
+            SpawnOrigin::LintAspect => "Lint and static analysis",
+            SpawnOrigin::FormatCheck => "Formatting checks",
+        }
+    }
+}
+
+/// Classifies a spawn by well-known aspect mnemonics and output names, then by whether it was
+/// built in an exec configuration (`k8-opt-exec-...`), i.e. for a tool or toolchain.
+fn spawn_origin(spawn: &SpawnExec) -> SpawnOrigin {
+    const IDE_MARKERS: &[&str] = &["intellij", "ide-info", "compile_commands", "clion", "vscode"];
+    const LINT_MARKERS: &[&str] = &[
+        "lint", "tidy", "clippy", "mypy", "ruff", "flake8", "checkstyle", "shellcheck", "detekt", "pmd",
+        "spotbugs",
+    ];
+    const FORMAT_MARKERS: &[&str] = &["fmt", "format", "buildifier"];
+
+    let mnemonic = spawn.mnemonic.to_ascii_lowercase();
+    let outputs: Vec<String> = spawn
+        .actual_outputs
+        .iter()
+        .map(|f| f.path.to_ascii_lowercase())
+        .collect();
+    let file_names: Vec<&str> = outputs.iter().map(|path| file_name(path)).collect();
+    let matches = |markers: &[&str]| {
+        markers
+            .iter()
+            .any(|m| mnemonic.contains(m) || file_names.iter().any(|name| name.contains(m)))
+    };
+    if matches(IDE_MARKERS) {
+        SpawnOrigin::IdeAspect
+    } else if matches(LINT_MARKERS) {
+        SpawnOrigin::LintAspect
+    } else if matches(FORMAT_MARKERS) {
+        SpawnOrigin::FormatCheck
+    } else if output_configuration(spawn).is_some_and(|c| c.contains("-exec")) {
+        SpawnOrigin::Tool
+    } else {
+        SpawnOrigin::Primary
+    }
+}
+
+fn attribution_section(spawns: &[SpawnExec], inputs: &SideInputs, top_n: usize) -> Section {
+    let mut section = Section::new("attribution", "Aspect and Tool Attribution");
+    section.push_text("Note: Spawns are classified by well-known aspect mnemonics and output names, then by exec configuration; anything else counts as primary build.");
+    if let Some(bep) = inputs.bep.as_ref() {
+        let aspects: BTreeSet<&str> = bep.aspects.keys().map(|(aspect, _)| aspect.as_str()).collect();
+        if !aspects.is_empty() {
+            section.push_field(
+                Column::text("requested_aspects", "Aspects requested (BEP)"),
+                aspects.into_iter().collect::<Vec<_>>().join(", "),
+            );
+        }
+    }
+
+    let mut by_origin: BTreeMap<SpawnOrigin, SpawnGroupStats> = BTreeMap::new();
+    let mut by_mnemonic: HashMap<(SpawnOrigin, &str), SpawnGroupStats> = HashMap::new();
+    for spawn in spawns {
+        let origin = spawn_origin(spawn);
+        let duration = total_time(spawn);
+        for stats in [
+            by_origin.entry(origin).or_default(),
+            by_mnemonic.entry((origin, spawn.mnemonic.as_str())).or_default(),
+        ] {
+            stats.count += 1;
+            stats.total_duration += duration;
+            if !spawn.cache_hit {
+                stats.executed += 1;
+            }
+        }
+    }
+    let total_seconds: f64 = spawns.iter().map(|s| total_time(s).as_secs_f64()).sum();
+
+    let mut table = Table::new(vec![
+        Column::text("origin", "Origin"),
+        Column::integer("count", "Spawns"),
+        Column::integer("executed", "Executed"),
+        Column::seconds("total_time", "Total Time", 2),
+        Column::percent("time_share", "% of Time", 1),
+    ]);
+    for (origin, stats) in &by_origin {
+        table.push_row(vec![
+            origin.title().into(),
+            stats.count.into(),
+            stats.executed.into(),
+            stats.total_duration.into(),
+            percentage(stats.total_duration.as_secs_f64(), total_seconds).into(),
+        ]);
+    }
+    section.push_table(table);
+
+    let mut secondary: Vec<_> = by_mnemonic
+        .into_iter()
+        .filter(|((origin, _), _)| *origin != SpawnOrigin::Primary)
+        .collect();
+    if secondary.is_empty() {
+        section.push_text("All spawns belong to the primary build.");
+        return section;
+    }
+    secondary.sort_by_key(|(key, stats)| (std::cmp::Reverse(stats.total_duration), *key));
+    let mut table = Table::new(vec![
+        Column::text("origin", "Origin"),
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("count", "Spawns"),
+        Column::seconds("total_time", "Total Time", 2),
+    ]);
+    for ((origin, mnemonic), stats) in secondary.into_iter().take(top_n) {
+        table.push_row(vec![
+            origin.title().into(),
+            mnemonic.into(),
+            stats.count.into(),
+            stats.total_duration.into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
+
+/// How an executed spawn ran, from its runner.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Strategy {
+    Remote,
+    Worker,
+    Sandbox,
+    Local,
+}
+
+impl Strategy {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Strategy::Remote => "remote",
+            Strategy::Worker => "worker",
+            Strategy::Sandbox => "local-sandbox",
+            Strategy::Local => "local",
+        }
+    }
+}
+
+/// The strategy an executed spawn ran with; `None` for cache hits and unrecognized runners.
+fn execution_strategy(spawn: &SpawnExec) -> Option<Strategy> {
+    match runner_kind(spawn) {
+        RunnerKind::Remote => Some(Strategy::Remote),
+        RunnerKind::Worker => Some(Strategy::Worker),
+        RunnerKind::Sandbox => Some(Strategy::Sandbox),
+        RunnerKind::Local => Some(Strategy::Local),
+        RunnerKind::RemoteCache | RunnerKind::DiskCache | RunnerKind::Other => None,
+    }
+}
+
+/// Executions a strategy needs before its average time is trusted for a recommendation.
+pub(crate) const MIN_STRATEGY_SAMPLES: u64 = 3;
+
+/// Average local time below which remote execution round trips are unlikely to pay off.
+const SHORT_ACTION_SECONDS: f64 = 1.0;
+
+#[derive(Default)]
+pub(crate) struct StrategyStats {
+    count: u64,
+    cache_hits: u64,
+    remotable: u64,
+    total_duration: Duration,
+    by_strategy: BTreeMap<Strategy, ExecutionTimings>,
+}
+
+impl StrategyStats {
+    /// Number of executed (not cached) spawns.
+    pub(crate) fn executed(&self) -> u64 {
+        self.by_strategy.values().map(|t| t.count).sum()
+    }
+
+    /// The strategy most executions used.
+    pub(crate) fn most_used(&self) -> Option<Strategy> {
+        self.by_strategy
+            .iter()
+            .max_by_key(|(strategy, timings)| (timings.count, std::cmp::Reverse(**strategy)))
+            .map(|(strategy, _)| *strategy)
+    }
+
+    pub(crate) fn average(&self, strategy: Strategy) -> Option<f64> {
+        self.by_strategy
+            .get(&strategy)
+            .filter(|timings| timings.count > 0)
+            .map(ExecutionTimings::average_seconds)
+    }
+
+    /// Recommends a strategy with the reason for it. Measured averages win when at least two
+    /// strategies have enough executions; otherwise remotability and action length decide.
+    pub(crate) fn recommend(&self) -> (Strategy, String) {
+        let can_remote = self.remotable > 0;
+        // Unsandboxed local runs stand in for sandboxed ones, which add only a little overhead.
+        let mut local = ExecutionTimings::default();
+        for strategy in [Strategy::Sandbox, Strategy::Local] {
+            if let Some(timings) = self.by_strategy.get(&strategy) {
+                local.count += timings.count;
+                local.total_duration += timings.total_duration;
+            }
+        }
+        let local_average = (local.count > 0).then(|| local.average_seconds());
+        let none = ExecutionTimings::default();
+        let measured: Vec<(Strategy, f64)> = [
+            (Strategy::Remote, self.by_strategy.get(&Strategy::Remote).unwrap_or(&none)),
+            (Strategy::Worker, self.by_strategy.get(&Strategy::Worker).unwrap_or(&none)),
+            (Strategy::Sandbox, &local),
+        ]
+        .into_iter()
+        .filter(|&(strategy, timings)| {
+            timings.count >= MIN_STRATEGY_SAMPLES && (can_remote || strategy != Strategy::Remote)
+        })
+        .map(|(strategy, timings)| (strategy, timings.average_seconds()))
+        .collect();
+        if measured.len() >= 2
+            && let Some(&(best, best_average)) = measured.iter().min_by(|a, b| a.1.total_cmp(&b.1))
+        {
+            let others: Vec<String> = measured
+                .iter()
+                .filter(|(strategy, _)| *strategy != best)
+                .map(|(strategy, average)| format!("{} {:.2}s", strategy.name(), average))
+                .collect();
+            return (
+                best,
+                format!("fastest measured: {:.2}s avg vs. {}", best_average, others.join(", ")),
+            );
+        }
+
+        if !can_remote {
+            return if self.average(Strategy::Worker).is_some() {
+                (Strategy::Worker, "not remotable; runs in persistent workers".to_string())
+            } else {
+                (Strategy::Sandbox, "not remotable (no-remote tags or execution requirements)".to_string())
+            };
+        }
+        if let Some(remote_average) = self.average(Strategy::Remote)
+            && local_average.is_none()
+            && self.average(Strategy::Worker).is_none()
+        {
+            return (Strategy::Remote, format!("only executed remotely, {:.2}s avg", remote_average));
+        }
+        match (self.average(Strategy::Worker), local_average) {
+            (Some(worker_average), _) if worker_average < SHORT_ACTION_SECONDS => (
+                Strategy::Worker,
+                format!("short actions in persistent workers ({:.2}s avg); remote round trips would dominate", worker_average),
+            ),
+            (_, Some(local_average)) if local_average < SHORT_ACTION_SECONDS => (
+                Strategy::Sandbox,
+                format!("short actions ({:.2}s avg); remote round trips would dominate", local_average),
+            ),
+            (worker_average, local_average) => (
+                Strategy::Remote,
+                format!(
+                    "remotable and {:.2}s avg locally; remote execution offloads it",
+                    worker_average.or(local_average).unwrap_or(0.0)
+                ),
+            ),
+        }
+    }
+}
+
+/// Cache and execution statistics per mnemonic, split by the strategy each spawn ran with.
+pub(crate) fn strategy_stats_by_mnemonic(spawns: &[SpawnExec]) -> HashMap<&str, StrategyStats> {
+    let mut by_mnemonic: HashMap<&str, StrategyStats> = HashMap::new();
+    for spawn in spawns {
+        let stats = by_mnemonic.entry(&spawn.mnemonic).or_default();
+        stats.count += 1;
+        if spawn.cache_hit {
+            stats.cache_hits += 1;
+        }
+        if spawn.remotable {
+            stats.remotable += 1;
+        }
+        if let Some(strategy) = execution_strategy(spawn) {
+            let duration = total_time(spawn);
+            let timings = stats.by_strategy.entry(strategy).or_default();
+            timings.count += 1;
+            timings.total_duration += duration;
+            stats.total_duration += duration;
+        }
+    }
+    by_mnemonic
+}
+
+fn strategy_section(spawns: &[SpawnExec]) -> Section {
+    let mut section = Section::new("strategies", "Execution Strategy Recommendations");
+    section.push_text(format!(
+        "Note: Strategies are compared by the average total time of executed spawns when at least two have {} or more executions; otherwise remotability and action length decide.",
+        MIN_STRATEGY_SAMPLES
+    ));
+
+    let by_mnemonic = strategy_stats_by_mnemonic(spawns);
+    let fully_cached = by_mnemonic.values().filter(|stats| stats.by_strategy.is_empty()).count();
+    let mut executed: Vec<_> = by_mnemonic
+        .into_iter()
+        .filter(|(_, stats)| !stats.by_strategy.is_empty())
+        .collect();
+    if executed.is_empty() {
+        section.push_text("No executed actions found (all were cache hits).");
+        return section;
+    }
+    executed.sort_by_key(|(name, stats)| (std::cmp::Reverse(stats.total_duration), *name));
+
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("executed", "Executed"),
+        Column::percent("cache_hit_rate", "Cache Hits", 1),
+        Column::percent("remotable_share", "Remotable", 0),
+        Column::seconds("remote_avg_time", "Remote Avg", 2),
+        Column::seconds("worker_avg_time", "Worker Avg", 2),
+        Column::seconds("sandbox_avg_time", "Sandbox Avg", 2),
+        Column::seconds("local_avg_time", "Local Avg", 2),
+        Column::text("strategy", "Recommended"),
+        Column::text("reason", "Reason"),
+    ]);
+    for (mnemonic, stats) in executed {
+        let (strategy, reason) = stats.recommend();
+        table.push_row(vec![
+            mnemonic.into(),
+            stats.executed().into(),
+            percentage(stats.cache_hits as f64, stats.count as f64).into(),
+            percentage(stats.remotable as f64, stats.count as f64).into(),
+            stats.average(Strategy::Remote).into(),
+            stats.average(Strategy::Worker).into(),
+            stats.average(Strategy::Sandbox).into(),
+            stats.average(Strategy::Local).into(),
+            strategy.name().into(),
+            reason.into(),
+        ]);
+    }
+    section.push_table(table);
+    if fully_cached > 0 {
+        section.push_text(format!(
+            "{} mnemonics were only seen as cache hits and are left out.",
+            fully_cached
+        ));
+    }
+    section
+}
+
+/// Longest executions below this share of their timeout suggest the timeout can be tightened.
+const GENEROUS_TIMEOUT_RATIO: f64 = 0.1;
+
+/// Executions above this share of their timeout are at risk of timing out.
+const TIGHT_TIMEOUT_RATIO: f64 = 0.8;
+
+#[derive(Default)]
+struct TimeoutStats {
+    executed: u64,
+    without_timeout: u64,
+    /// Executions per configured timeout, in milliseconds.
+    timeouts: BTreeMap<i64, u64>,
+    durations: Vec<f64>,
+    /// Highest execution time as a share of the spawn's own timeout.
+    max_ratio: Option<f64>,
+}
+
+/// Time the timeout applies to: the subprocess wall time, or the total time when it is missing.
+fn execution_seconds(spawn: &SpawnExec) -> f64 {
+    let execution = phase_duration(spawn, |m| m.execution_wall_time.as_ref());
+    if execution.is_zero() { total_time(spawn) } else { execution }.as_secs_f64()
+}
+
+fn format_timeout(millis: i64) -> String {
+    if millis % 1000 == 0 {
+        format!("{}s", millis / 1000)
+    } else {
+        format!("{:.1}s", millis as f64 / 1000.0)
+    }
+}
+
+fn timeout_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new("timeouts", "Spawn Timeout Distribution");
+    let executed: Vec<&SpawnExec> = spawns.iter().filter(|s| !s.cache_hit).collect();
+    if executed.is_empty() {
+        section.push_text("No executed actions found (all were cache hits).");
+        return section;
+    }
+
+    let mut by_mnemonic: HashMap<&str, TimeoutStats> = HashMap::new();
+    for spawn in &executed {
+        let seconds = execution_seconds(spawn);
+        let stats = by_mnemonic.entry(&spawn.mnemonic).or_default();
+        stats.executed += 1;
+        stats.durations.push(seconds);
+        if spawn.timeout_millis > 0 {
+            *stats.timeouts.entry(spawn.timeout_millis).or_default() += 1;
+            let ratio = seconds * 1000.0 / spawn.timeout_millis as f64;
+            stats.max_ratio = Some(stats.max_ratio.map_or(ratio, |max| max.max(ratio)));
+        } else {
+            stats.without_timeout += 1;
+        }
+    }
+    let without_timeout: u64 = by_mnemonic.values().map(|stats| stats.without_timeout).sum();
+    section.push_field(Column::integer("executed_actions", "Executed Actions"), executed.len());
+    section.push_field(Column::integer("without_timeout", "Without a Timeout"), without_timeout);
+    section.push_field(
+        Column::percent("without_timeout_share", "Share Without a Timeout", 1),
+        percentage(without_timeout as f64, executed.len() as f64),
+    );
+
+    let mut sorted: Vec<_> = by_mnemonic.into_iter().collect();
+    sorted.sort_by_key(|(name, stats)| (std::cmp::Reverse(stats.executed), *name));
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("executed", "Executed"),
+        Column::integer("without_timeout", "No Timeout"),
+        Column::text("timeouts", "Timeouts (count)"),
+        Column::seconds("p90_time", "p90 Time", 2),
+        Column::seconds("max_time", "Max Time", 2),
+        Column::percent("max_timeout_usage", "Max % of Timeout", 1),
+        Column::text("assessment", "Assessment"),
+    ]);
+    for (mnemonic, mut stats) in sorted {
+        stats.durations.sort_by(f64::total_cmp);
+        let timeouts: Vec<String> = stats
+            .timeouts
+            .iter()
+            .map(|(millis, count)| format!("{} ({})", format_timeout(*millis), count))
+            .collect();
+        let assessment = match stats.max_ratio {
+            _ if stats.without_timeout > 0 => "missing timeouts",
+            Some(ratio) if ratio >= TIGHT_TIMEOUT_RATIO => "close to timing out",
+            Some(ratio) if ratio < GENEROUS_TIMEOUT_RATIO => "generous",
+            _ => "ok",
+        };
+        table.push_row(vec![
+            mnemonic.into(),
+            stats.executed.into(),
+            stats.without_timeout.into(),
+            timeouts.join(", ").into(),
+            percentile(&stats.durations, 90.0).into(),
+            percentile(&stats.durations, 100.0).into(),
+            stats.max_ratio.map(|ratio| ratio * 100.0).into(),
+            assessment.into(),
+        ]);
+    }
+    section.push_table(table);
+
+    let mut closest: Vec<(f64, &SpawnExec)> = executed
+        .iter()
+        .filter(|s| s.timeout_millis > 0)
+        .map(|s| (execution_seconds(s) * 1000.0 / s.timeout_millis as f64, *s))
+        .collect();
+    if !closest.is_empty() {
+        closest.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let mut table = Table::new(vec![
+            Column::percent("timeout_usage", "% of Timeout", 1),
+            Column::seconds("execution_time", "Execution Time", 2),
+            Column::text("timeout", "Timeout"),
+            Column::text("mnemonic", "Mnemonic"),
+            Column::text("target", "Target"),
+            spawn_id_column(),
+        ]);
+        for (ratio, spawn) in closest.into_iter().take(top_n) {
+            table.push_row(vec![
+                (ratio * 100.0).into(),
+                execution_seconds(spawn).into(),
+                format_timeout(spawn.timeout_millis).into(),
+                (&spawn.mnemonic).into(),
+                display_label(spawn).into(),
+                spawn_id(spawn).into(),
+            ]);
+        }
+        section.push_table(table);
+    }
+    section.push_text(format!(
+        "Note: Times are subprocess wall times. \"generous\" means even the slowest execution used less than {:.0}% of its timeout; \"close to timing out\" means one used {:.0}% or more.",
+        GENEROUS_TIMEOUT_RATIO * 100.0,
+        TIGHT_TIMEOUT_RATIO * 100.0
+    ));
+    section
+}
+
+/// Environment variables that carry workspace status values into actions.
+const STAMP_VARIABLES: &[&str] = &["BUILD_TIMESTAMP", "BUILD_TIME", "BUILD_USER", "BUILD_HOST", "BUILD_EMBED_LABEL"];
+
+/// Why a spawn looks stamped: the status files it reads and stamp-like variables it gets, or
+/// `None` when it shows neither.
+fn stamping_evidence(spawn: &SpawnExec) -> Option<String> {
+    let mut evidence: BTreeSet<&str> = BTreeSet::new();
+    for path in spawn.inputs.iter().map(|f| f.path.as_str()).chain(spawn.command_args.iter().map(String::as_str)) {
+        if is_workspace_status_file(path) {
+            evidence.insert(file_name(path));
+        }
+    }
+    for var in &spawn.environment_variables {
+        if STAMP_VARIABLES.contains(&var.name.as_str()) || var.name.starts_with("BUILD_SCM_") || var.name.starts_with("STABLE_") {
+            evidence.insert(&var.name);
+        }
+    }
+    (!evidence.is_empty()).then(|| evidence.into_iter().collect::<Vec<_>>().join(", "))
+}
+
+/// For each spawn, the indices of the spawns that read one of its outputs.
+pub(crate) fn consumers(spawns: &[SpawnExec]) -> Vec<BTreeSet<usize>> {
+    let mut producers: HashMap<&str, usize> = HashMap::new();
+    for (i, spawn) in spawns.iter().enumerate() {
+        for output in &spawn.actual_outputs {
+            producers.insert(&output.path, i);
+        }
+    }
+    let mut consumers: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); spawns.len()];
+    for (i, spawn) in spawns.iter().enumerate() {
+        for input in &spawn.inputs {
+            if let Some(&producer) = producers.get(input.path.as_str())
+                && producer != i
+            {
+                consumers[producer].insert(i);
+            }
+        }
+    }
+    consumers
+}
+
+/// For each spawn, the time of the longest chain of dependent spawns through it as a share of
+/// the longest chain in the log, the critical path: 1.0 on the critical path and less the more
+/// slack the spawn has. `None` when the log records no inputs to link spawns by.
+pub(crate) fn critical_path_shares(spawns: &[SpawnExec]) -> Option<Vec<f64>> {
+    if spawns.iter().all(|s| s.inputs.is_empty()) {
+        return None;
+    }
+    let consumers = consumers(spawns);
+    let mut producer_count = vec![0usize; spawns.len()];
+    for &j in consumers.iter().flatten() {
+        producer_count[j] += 1;
+    }
+    // Topological order; spawns on a cycle, which a valid log cannot have, are left out.
+    let mut ready: Vec<usize> = (0..spawns.len()).filter(|&i| producer_count[i] == 0).collect();
+    let mut order = Vec::with_capacity(spawns.len());
+    while let Some(i) = ready.pop() {
+        order.push(i);
+        for &j in &consumers[i] {
+            producer_count[j] -= 1;
+            if producer_count[j] == 0 {
+                ready.push(j);
+            }
+        }
+    }
+
+    let time: Vec<f64> = spawns.iter().map(|s| total_time(s).as_secs_f64()).collect();
+    // The longest chains ending and starting at each spawn, both including it.
+    let mut ending = time.clone();
+    for &i in &order {
+        for &j in &consumers[i] {
+            ending[j] = ending[j].max(ending[i] + time[j]);
+        }
+    }
+    let mut starting = time.clone();
+    for &i in order.iter().rev() {
+        for &j in &consumers[i] {
+            starting[i] = starting[i].max(time[i] + starting[j]);
+        }
+    }
+    let critical = ending.iter().copied().fold(0.0, f64::max);
+    Some(
+        (0..spawns.len())
+            .map(|i| if critical > 0.0 { (ending[i] + starting[i] - time[i]) / critical } else { 0.0 })
+            .collect(),
+    )
+}
+
+fn stamping_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new("stamping", "Stamping and Volatile Status");
+    let stamped: Vec<(usize, String)> = spawns
+        .iter()
+        .enumerate()
+        .filter_map(|(i, spawn)| stamping_evidence(spawn).map(|evidence| (i, evidence)))
+        .collect();
+    if stamped.is_empty() {
+        section.push_text("No actions read workspace status files or stamp variables.");
+        return section;
+    }
+
+    // Spawns consuming each spawn's outputs, to follow the rebuild cascade.
+    let consumers = consumers(spawns);
+    let downstream = |start: usize| {
+        let mut seen = BTreeSet::new();
+        let mut queue = vec![start];
+        while let Some(i) = queue.pop() {
+            for &next in &consumers[i] {
+                if seen.insert(next) {
+                    queue.push(next);
+                }
+            }
+        }
+        seen
+    };
+
+    let mut cascade: BTreeSet<usize> = BTreeSet::new();
+    let mut rows: Vec<(Duration, usize, &SpawnExec, String)> = Vec::new();
+    for (i, evidence) in stamped.iter().cloned() {
+        let reached = downstream(i);
+        let time = reached.iter().map(|&j| total_time(&spawns[j])).sum();
+        rows.push((time, reached.len(), &spawns[i], evidence));
+        cascade.extend(reached);
+    }
+    let stamped_indices: BTreeSet<usize> = stamped.iter().map(|(i, _)| *i).collect();
+    let cascade: Vec<&SpawnExec> = cascade.difference(&stamped_indices).map(|&i| &spawns[i]).collect();
+
+    section.push_field(Column::integer("stamped_actions", "Stamped Actions"), stamped.len());
+    section.push_field(
+        Column::integer("stamped_executed", "Stamped Actions Executed"),
+        stamped.iter().filter(|(i, _)| !spawns[*i].cache_hit).count(),
+    );
+    section.push_field(Column::integer("downstream_actions", "Actions Downstream of Stamped Outputs"), cascade.len());
+    section.push_field(
+        Column::integer("downstream_executed", "Downstream Actions Executed"),
+        cascade.iter().filter(|s| !s.cache_hit).count(),
+    );
+    section.push_field(
+        Column::seconds("downstream_time", "Downstream Time", 2),
+        cascade.iter().map(|s| total_time(s)).sum::<Duration>(),
+    );
+
+    rows.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+        Column::text("evidence", "Reads"),
+        Column::text("cache_hit", "Cache Hit"),
+        Column::integer("downstream_actions", "Downstream"),
+        Column::seconds("downstream_time", "Downstream Time", 2),
+        spawn_id_column(),
+    ]);
+    for (time, count, spawn, evidence) in rows.into_iter().take(top_n) {
+        table.push_row(vec![
+            (&spawn.mnemonic).into(),
+            display_label(spawn).into(),
+            evidence.into(),
+            if spawn.cache_hit { "yes" } else { "no" }.into(),
+            count.into(),
+            time.into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    section.push_table(table);
+    if spawns.iter().all(|s| s.inputs.is_empty()) {
+        section.push_text("Note: No action inputs were recorded, so stamping is detected from command lines and environments only and the downstream cascade is unknown.");
+    } else {
+        section.push_text("Note: volatile-status.txt changes on every build. Bazel's local action cache ignores that, but remote cache keys include it, so stamped actions and everything consuming their outputs miss the remote cache. Keep --stamp to release builds.");
+    }
+    section
+}
+
+/// Targets need at least this many spawns to be reported as fan-out anomalies...
+const MIN_ANOMALOUS_SPAWNS: usize = 10;
+
+/// ...and this many times the median number of spawns per target.
+const ANOMALOUS_SPAWN_FACTOR: f64 = 5.0;
+
+/// A guess at why a target has many spawns, from its mnemonics and output paths.
+fn fan_out_cause(spawns: &[&SpawnExec]) -> &'static str {
+    let share = |matches: &dyn Fn(&SpawnExec) -> bool| {
+        spawns.iter().filter(|s| matches(s)).count() as f64 / spawns.len() as f64
+    };
+    let outputs = |s: &SpawnExec, marker: &str| s.actual_outputs.iter().any(|f| f.path.contains(marker));
+    if share(&|s| !phase_duration(s, |m| m.retry_time.as_ref()).is_zero()) >= 0.25 {
+        "retries"
+    } else if share(&|s| s.mnemonic.contains("Coverage") || outputs(s, "coverage.dat")) >= 0.25 {
+        "coverage"
+    } else if share(&|s| outputs(s, "/shard_") || outputs(s, "/run_")) >= 0.5 {
+        "test shards or --runs_per_test"
+    } else if share(&|s| s.mnemonic == "TestRunner") >= 0.5 {
+        "repeated test runs"
+    } else {
+        "many actions"
+    }
+}
+
+fn fan_out_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new("fan_out", "Spawn Fan-Out per Target");
+    let mut by_target: HashMap<&str, Vec<&SpawnExec>> = HashMap::new();
+    for spawn in spawns.iter().filter(|s| !s.target_label.is_empty()) {
+        by_target.entry(&spawn.target_label).or_default().push(spawn);
+    }
+    if by_target.is_empty() {
+        section.push_text("No spawns with target labels found.");
+        return section;
+    }
+
+    let mut counts: Vec<f64> = by_target.values().map(|s| s.len() as f64).collect();
+    counts.sort_by(f64::total_cmp);
+    let median = percentile(&counts, 50.0).unwrap_or(0.0);
+    let threshold = (median * ANOMALOUS_SPAWN_FACTOR).max(MIN_ANOMALOUS_SPAWNS as f64);
+    section.push_field(Column::integer("targets", "Targets"), by_target.len());
+    section.push_field(Column::float("median_spawns", "Median Spawns per Target", 1), median);
+    section.push_field(Column::float("threshold", "Anomaly Threshold", 1), threshold);
+
+    let mut anomalies: Vec<(&str, Vec<&SpawnExec>)> = by_target
+        .into_iter()
+        .filter(|(_, spawns)| spawns.len() as f64 >= threshold)
+        .collect();
+    if anomalies.is_empty() {
+        section.push_text("No target has an unusually high number of spawns.");
+        return section;
+    }
+    anomalies.sort_by_key(|(label, spawns)| (std::cmp::Reverse(spawns.len()), *label));
+
+    let mut table = Table::new(vec![
+        Column::text("target", "Target"),
+        Column::integer("spawns", "Spawns"),
+        Column::integer("executed", "Executed"),
+        Column::integer("failed", "Failed"),
+        Column::seconds("total_time", "Total Time", 2),
+        Column::text("mnemonics", "Top Mnemonics"),
+        Column::text("cause", "Likely Cause"),
+    ]);
+    for (label, target_spawns) in anomalies.iter().take(top_n) {
+        let mut mnemonics: HashMap<&str, usize> = HashMap::new();
+        for spawn in target_spawns {
+            *mnemonics.entry(&spawn.mnemonic).or_default() += 1;
+        }
+        let mut mnemonics: Vec<_> = mnemonics.into_iter().collect();
+        mnemonics.sort_by_key(|(name, count)| (std::cmp::Reverse(*count), *name));
+        let top: Vec<String> = mnemonics
+            .iter()
+            .take(3)
+            .map(|(name, count)| format!("{} x{}", name, count))
+            .collect();
+        table.push_row(vec![
+            (*label).into(),
+            target_spawns.len().into(),
+            target_spawns.iter().filter(|s| !s.cache_hit).count().into(),
+            target_spawns.iter().filter(|s| is_failed(s)).count().into(),
+            target_spawns.iter().map(|s| total_time(s)).sum::<Duration>().into(),
+            top.join(", ").into(),
+            fan_out_cause(target_spawns).into(),
+        ]);
+    }
+    section.push_table(table);
+    section.push_text(format!(
+        "Note: Targets are listed when they have at least {} spawns and {:.0}x the median per target.",
+        MIN_ANOMALOUS_SPAWNS, ANOMALOUS_SPAWN_FACTOR
+    ));
+    section
+}
+
+fn coverage_section(spawns: &[SpawnExec]) -> Section {
+    let mut section = Section::new("coverage", "Coverage Post-Processing");
+    let coverage: Vec<&SpawnExec> = spawns.iter().filter(|s| is_coverage_spawn(s)).collect();
+    if coverage.is_empty() {
+        section.push_text("No coverage post-processing spawns found; this does not look like a coverage run.");
+        return section;
+    }
+
+    let coverage_time: Duration = coverage.iter().map(|s| total_time(s)).sum();
+    let total: Duration = spawns.iter().map(total_time).sum();
+    section.push_field(Column::integer("spawns", "Coverage Spawns"), coverage.len());
+    section.push_field(
+        Column::integer("executed", "Executed"),
+        coverage.iter().filter(|s| !s.cache_hit).count(),
+    );
+    section.push_field(Column::seconds("total_time", "Total Time", 2), coverage_time);
+    section.push_field(
+        Column::percent("time_share", "% of Build Time", 1),
+        percentage(coverage_time.as_secs_f64(), total.as_secs_f64()),
+    );
+
+    let mut by_mnemonic: BTreeMap<&str, SpawnGroupStats> = BTreeMap::new();
+    for spawn in &coverage {
+        let stats = by_mnemonic.entry(&spawn.mnemonic).or_default();
+        stats.count += 1;
+        stats.total_duration += total_time(spawn);
+        if !spawn.cache_hit {
+            stats.executed += 1;
+        }
+    }
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("count", "Spawns"),
+        Column::integer("executed", "Executed"),
+        Column::seconds("total_time", "Total Time", 2),
+    ]);
+    for (mnemonic, stats) in by_mnemonic {
+        table.push_row(vec![
+            mnemonic.into(),
+            stats.count.into(),
+            stats.executed.into(),
+            stats.total_duration.into(),
+        ]);
+    }
+    section.push_table(table);
+    section.push_text("Note: Coverage runs instrument compilation and add these post-processing steps, so they skew normal build baselines. Use --exclude-coverage to leave these spawns out of every report.");
+    section
+}
+
+/// Number of time buckets in each heatmap row.
+const HEATMAP_CELLS: usize = 60;
+
+/// Cell shades from idle to the busiest cell of the heatmap.
+const HEATMAP_SYMBOLS: [char; 10] = [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+/// Draws one character per cell, shaded by its share of `max`; only empty cells are blank.
+fn heat_row(cells: &[f64], max: f64) -> String {
+    cells
+        .iter()
+        .map(|&seconds| {
+            if seconds <= 0.0 || max <= 0.0 {
+                return HEATMAP_SYMBOLS[0];
+            }
+            let index = (seconds / max * (HEATMAP_SYMBOLS.len() - 1) as f64).ceil() as usize;
+            HEATMAP_SYMBOLS[index.clamp(1, HEATMAP_SYMBOLS.len() - 1)]
+        })
+        .collect()
+}
+
+fn heatmap_section(spawns: &[SpawnExec], top_n: usize) -> Section {
+    let mut section = Section::new("heatmap", "Execution Time Heatmap by Mnemonic");
+    let executed: Vec<(f64, f64, &SpawnExec)> = spawns
+        .iter()
+        .filter(|s| !s.cache_hit)
+        .filter_map(|s| Some((start_seconds(s)?, end_seconds(s)?, s)))
+        .collect();
+    let Some(build_start) = executed.iter().map(|(start, _, _)| *start).reduce(f64::min) else {
+        section.push_text("No executed actions with a start time found in the log.");
+        return section;
+    };
+    let build_end = executed.iter().map(|(_, end, _)| *end).fold(build_start, f64::max);
+    let cell_seconds = ((build_end - build_start) / HEATMAP_CELLS as f64).max(0.001);
+
+    // Each action's execution time is spread over the cells it overlaps.
+    let mut rows: HashMap<&str, Vec<f64>> = HashMap::new();
+    for (start, end, spawn) in &executed {
+        let cells = rows.entry(&spawn.mnemonic).or_insert_with(|| vec![0.0; HEATMAP_CELLS]);
+        let first = ((start - build_start) / cell_seconds) as usize;
+        let last = (((end - build_start) / cell_seconds) as usize).min(HEATMAP_CELLS - 1);
+        for (index, cell) in cells.iter_mut().enumerate().take(last + 1).skip(first) {
+            let cell_start = build_start + index as f64 * cell_seconds;
+            *cell += (end.min(cell_start + cell_seconds) - start.max(cell_start)).max(0.0);
+        }
+    }
+    let mut rows: Vec<(&str, f64, Vec<f64>)> = rows
+        .into_iter()
+        .map(|(mnemonic, cells)| (mnemonic, cells.iter().sum(), cells))
+        .collect();
+    rows.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+    rows.truncate(top_n);
+    let max = rows.iter().flat_map(|(_, _, cells)| cells.iter().copied()).fold(0.0, f64::max);
+
+    section.push_field(Column::seconds("cell_seconds", "Seconds per Cell", 1), cell_seconds);
+    section.push_field(Column::seconds("busiest_cell", "Busiest Cell", 1), max);
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::seconds("execution_time", "Execution Time", 1),
+        Column::text("heatmap", "Build Timeline"),
+    ]);
+    for (mnemonic, total, cells) in &rows {
+        table.push_row(vec![(*mnemonic).into(), (*total).into(), heat_row(cells, max).into()]);
+    }
+    section.push_table(table);
+    section.push_text(format!(
+        "Note: Shades run from '{}' to '{}' (the busiest cell); cache hits and actions without a start time are left out.",
+        HEATMAP_SYMBOLS[1],
+        HEATMAP_SYMBOLS[HEATMAP_SYMBOLS.len() - 1]
+    ));
+    section
+}
+
+/// Shares of execution time for which the number of slowest actions covering them is reported.
+const TIME_SHARES: [f64; 5] = [50.0, 80.0, 90.0, 95.0, 99.0];
+
+/// Shares of the slowest actions for which the execution time they cover is reported.
+const ACTION_SHARES: [f64; 5] = [1.0, 2.0, 5.0, 10.0, 20.0];
+
+fn time_concentration_section(spawns: &[SpawnExec]) -> Section {
+    let mut section = Section::new("time_concentration", "Concentration of Execution Time");
+    let mut durations: Vec<f64> = spawns
+        .iter()
+        .filter(|s| !s.cache_hit)
+        .map(|s| total_time(s).as_secs_f64())
+        .collect();
+    let total: f64 = durations.iter().sum();
+    if total <= 0.0 {
+        section.push_text("No executed actions with a duration found in the log.");
+        return section;
+    }
+    durations.sort_by(|a, b| b.total_cmp(a));
+    // cumulative[i] is the time taken by the i + 1 slowest actions.
+    let cumulative: Vec<f64> = durations
+        .iter()
+        .scan(0.0, |sum, duration| {
+            *sum += duration;
+            Some(*sum)
+        })
+        .collect();
+    let count = durations.len();
+    // Fewest slowest actions whose time reaches `share` percent of the total.
+    let actions_for = |share: f64| (cumulative.partition_point(|&sum| sum < total * share / 100.0) + 1).min(count);
+
+    let needed = actions_for(80.0);
+    section.push_text(format!(
+        "The slowest {} of {} executed actions ({:.1}%) account for 80% of execution time; each took at least {:.3}s.",
+        needed,
+        count,
+        percentage(needed as f64, count as f64),
+        durations[needed - 1]
+    ));
+
+    let mut by_time = Table::new(vec![
+        Column::percent("time_share", "Execution Time", 0),
+        Column::integer("actions", "Slowest Actions"),
+        Column::percent("action_share", "% of Actions", 2),
+        Column::seconds("cutoff", "Cut-off Duration", 3),
+    ]);
+    for share in TIME_SHARES {
+        let needed = actions_for(share);
+        by_time.push_row(vec![
+            share.into(),
+            needed.into(),
+            percentage(needed as f64, count as f64).into(),
+            durations[needed - 1].into(),
+        ]);
+    }
+    section.push_table(by_time);
+
+    let mut by_actions = Table::new(vec![
+        Column::percent("action_share", "Slowest Actions", 0),
+        Column::integer("actions", "Actions"),
+        Column::percent("time_share", "% of Execution Time", 1),
+        Column::bar("time_share_bar", "", 20, &['#']),
+    ]);
+    for share in ACTION_SHARES {
+        let actions = ((count as f64 * share / 100.0).ceil() as usize).clamp(1, count);
+        let time_share = percentage(cumulative[actions - 1], total);
+        by_actions.push_row(vec![
+            share.into(),
+            actions.into(),
+            time_share.into(),
+            Value::Bar(vec![time_share / 100.0]),
+        ]);
+    }
+    section.push_table(by_actions);
+    section.push_text("Hint: When a small share of actions dominates, speeding those up (or caching them better) pays off far more than broad tuning.");
+    section
+}
+
+fn fetch_analysis_sections(spawns: &[SpawnExec], top_n: usize) -> Vec<Section> {
+    let fetch_time = |s: &SpawnExec| phase_duration(s, |m| m.fetch_time.as_ref());
+    let mut by_time = Section::new("fetch_time", format!("Top {} Actions by Fetch Time", top_n));
+    let mut by_bytes = Section::new("fetched_bytes", format!("Top {} Actions by Bytes Fetched", top_n));
+    let fetching: Vec<&SpawnExec> = spawns
+        .iter()
+        .filter(|s| !fetch_time(s).is_zero() || network_bytes(s).0 > 0)
+        .collect();
+    if fetching.is_empty() {
+        by_time.push_text("No actions fetched outputs in this log (no remote cache hits or remote executions).");
+        return vec![by_time];
+    }
+
+    let columns = || {
+        vec![
+            Column::seconds("fetch_time", "Fetch Time", 3),
+            Column::bytes("bytes", "Fetched", ByteUnit::DecimalMegabytes, 2),
+            Column::integer("outputs", "Outputs"),
+            Column::text("runner", "Runner"),
+            Column::text("target", "Target"),
+            spawn_id_column(),
+        ]
+    };
+    let row = |spawn: &SpawnExec| -> Vec<Value> {
+        vec![
+            fetch_time(spawn).into(),
+            network_bytes(spawn).0.into(),
+            spawn.actual_outputs.len().into(),
+            (&spawn.runner).into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]
+    };
+
+    let mut sorted = fetching.clone();
+    sorted.sort_by_key(|s| std::cmp::Reverse(fetch_time(s)));
+    let mut table = Table::new(columns());
+    for spawn in sorted.iter().take(top_n) {
+        table.push_row(row(spawn));
+    }
+    by_time.push_field(
+        Column::seconds("total_fetch_time", "Total Fetch Time", 2),
+        fetching.iter().map(|s| fetch_time(s)).sum::<Duration>(),
+    );
+    by_time.push_table(table);
+
+    sorted.sort_by_key(|s| std::cmp::Reverse(network_bytes(s).0));
+    let mut table = Table::new(columns());
+    for spawn in sorted.iter().take(top_n) {
+        table.push_row(row(spawn));
+    }
+    by_bytes.push_field(
+        Column::bytes("total_bytes", "Total Fetched", ByteUnit::DecimalMegabytes, 2),
+        fetching.iter().map(|s| network_bytes(s).0).sum::<i64>(),
+    );
+    by_bytes.push_table(table);
+    by_bytes.push_text("Hint: Slow fetches of huge outputs (often tree artifacts) call for --remote_download_minimal, output pruning or a faster link; they do not improve with more executors, unlike queueing.");
+    vec![by_time, by_bytes]
+}
+
+/// Upper bounds of the input file count groups in the setup hotspot report.
+const INPUT_FILE_GROUPS: [(i64, &str); 4] = [(100, "< 100"), (1_000, "100 - 999"), (10_000, "1,000 - 9,999"), (i64::MAX, ">= 10,000")];
+
+#[derive(Default)]
+struct SetupStats {
+    count: u64,
+    setup: Duration,
+    execution: Duration,
+    input_files: i64,
+}
+
+impl SetupStats {
+    fn add(&mut self, setup: Duration, execution: Duration, input_files: i64) {
+        self.count += 1;
+        self.setup += setup;
+        self.execution += execution;
+        self.input_files += input_files;
+    }
+
+    fn row(&self, name: &str) -> Vec<Value> {
+        vec![
+            name.into(),
+            self.count.into(),
+            self.setup.into(),
+            self.execution.into(),
+            percentage(self.setup.as_secs_f64(), (self.setup + self.execution).as_secs_f64()).into(),
+            (self.input_files as f64 / self.count as f64).into(),
+        ]
+    }
+}
+
+fn setup_hotspot_sections(spawns: &[SpawnExec], top_n: usize) -> Vec<Section> {
+    let mut section = Section::new("setup_hotspots", format!("Top {} Actions by Setup Time", top_n));
+    let setup_time = |s: &SpawnExec| phase_duration(s, |m| m.setup_time.as_ref());
+    let execution_time = |s: &SpawnExec| phase_duration(s, |m| m.execution_wall_time.as_ref());
+    let input_files = |s: &SpawnExec| s.metrics.as_ref().map_or(0, |m| m.input_files);
+    let mut executed: Vec<&SpawnExec> = spawns
+        .iter()
+        .filter(|s| !s.cache_hit && !setup_time(s).is_zero())
+        .collect();
+    if executed.is_empty() {
+        section.push_text("No executed actions with a setup time found in the log.");
+        return vec![section];
+    }
+    executed.sort_by_key(|s| std::cmp::Reverse(setup_time(s)));
+
+    let mut table = Table::new(vec![
+        Column::seconds("setup_time", "Setup", 3),
+        Column::seconds("execution_time", "Execution", 3),
+        Column::ratio("setup_ratio", "Setup/Exec", 2),
+        Column::integer("input_files", "Input Files"),
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
+    for spawn in executed.iter().take(top_n) {
+        let execution = execution_time(spawn).as_secs_f64();
+        table.push_row(vec![
+            setup_time(spawn).into(),
+            execution_time(spawn).into(),
+            (execution > 0.0).then(|| setup_time(spawn).as_secs_f64() / execution).into(),
+            input_files(spawn).into(),
+            (&spawn.mnemonic).into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    section.push_table(table);
+
+    let mut by_mnemonic: BTreeMap<&str, SetupStats> = BTreeMap::new();
+    let mut by_inputs: Vec<SetupStats> = INPUT_FILE_GROUPS.iter().map(|_| SetupStats::default()).collect();
+    for spawn in &executed {
+        let (setup, execution, files) = (setup_time(spawn), execution_time(spawn), input_files(spawn));
+        by_mnemonic.entry(&spawn.mnemonic).or_default().add(setup, execution, files);
+        let group = INPUT_FILE_GROUPS.iter().position(|(limit, _)| files < *limit).unwrap_or(0);
+        by_inputs[group].add(setup, execution, files);
+    }
+    let columns = |name: Column| {
+        vec![
+            name,
+            Column::integer("count", "Actions"),
+            Column::seconds("setup_time", "Total Setup", 2),
+            Column::seconds("execution_time", "Total Execution", 2),
+            Column::percent("setup_share", "Setup Share", 1),
+            Column::float("avg_input_files", "Avg Input Files", 0),
+        ]
+    };
+
+    let mut mnemonics = Section::new("setup_by_mnemonic", "Setup Time by Mnemonic");
+    let mut sorted: Vec<_> = by_mnemonic.into_iter().collect();
+    sorted.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.setup));
+    let mut table = Table::new(columns(Column::text("mnemonic", "Mnemonic")));
+    for (mnemonic, stats) in sorted.iter().take(top_n) {
+        table.push_row(stats.row(mnemonic));
+    }
+    mnemonics.push_table(table);
+
+    let mut inputs = Section::new("setup_by_input_files", "Setup Time by Input File Count");
+    let mut table = Table::new(columns(Column::text("input_files", "Input Files")));
+    for ((_, name), stats) in INPUT_FILE_GROUPS.iter().zip(&by_inputs) {
+        if stats.count > 0 {
+            table.push_row(stats.row(name));
+        }
+    }
+    inputs.push_table(table);
+    inputs.push_text("Hint: Setup covers input staging and sandbox creation, so enormous input trees show up here rather than as execution time; narrowing the inputs or using a persistent worker helps more than faster executors.");
+    vec![section, mnemonics, inputs]
+}
+
+/// The package of a label with its repository (`@repo//foo/bar:baz` -> `@repo//foo/bar`), cut to
+/// `depth` directory levels when given.
+pub(crate) fn package_of(label: &str, depth: Option<usize>) -> String {
+    let Some((repository, rest)) = label.split_once("//") else {
+        return "(no target label)".to_string();
+    };
+    // `@//` and `@@//` name the main repository, like a plain `//`.
+    let repository = repository.trim_start_matches('@');
+    let repository = if repository.is_empty() { String::new() } else { format!("@{}", repository) };
+    let package = rest.split_once(':').map_or(rest, |(package, _)| package);
+    let package = match depth {
+        Some(depth) => package.split('/').take(depth).collect::<Vec<_>>().join("/"),
+        None => package.to_string(),
+    };
+    format!("{}//{}", repository, package)
+}
+
+#[derive(Default)]
+struct PackageStats {
+    count: u64,
+    cache_hits: u64,
+    time: Duration,
+}
+
+impl PackageStats {
+    fn add(&mut self, spawn: &SpawnExec) {
+        self.count += 1;
+        self.time += total_time(spawn);
+        if spawn.cache_hit {
+            self.cache_hits += 1;
+        }
+    }
+
+    fn values(&self, build_time: Duration) -> Vec<Value> {
+        vec![
+            self.count.into(),
+            percentage(self.cache_hits as f64, self.count as f64).into(),
+            self.time.into(),
+            (self.time.as_secs_f64() / self.count as f64).into(),
+            percentage(self.time.as_secs_f64(), build_time.as_secs_f64()).into(),
+        ]
+    }
+}
+
+fn package_mnemonic_sections(spawns: &[SpawnExec], depth: Option<usize>, top_n: usize) -> Vec<Section> {
+    let mut packages: HashMap<String, (PackageStats, HashMap<&str, PackageStats>)> = HashMap::new();
+    for spawn in spawns {
+        let (totals, mnemonics) = packages.entry(package_of(&spawn.target_label, depth)).or_default();
+        totals.add(spawn);
+        mnemonics.entry(&spawn.mnemonic).or_default().add(spawn);
+    }
+    let build_time: Duration = spawns.iter().map(total_time).sum();
+    let mut packages: Vec<_> = packages.into_iter().collect();
+    packages.sort_by(|(a_name, (a, _)), (b_name, (b, _))| b.time.cmp(&a.time).then_with(|| a_name.cmp(b_name)));
+    let stats_columns = || {
+        vec![
+            Column::integer("count", "Actions"),
+            Column::percent("cache_hit_rate", "Cache Hit Rate", 1),
+            Column::seconds("total_time", "Total Time", 2),
+            Column::seconds("avg_time", "Avg Time", 3),
+            Column::percent("time_share", "Share of Time", 1),
+        ]
+    };
+
+    let mut by_package = Section::new("packages", format!("Top {} Packages by Time", top_n));
+    let mut table = Table::new([vec![Column::text("package", "Package")], stats_columns()].concat());
+    for (package, (totals, _)) in packages.iter().take(top_n) {
+        table.push_row([vec![package.into()], totals.values(build_time)].concat());
+    }
+    by_package.push_table(table);
+
+    let mut nested = Section::new("package_mnemonics", format!("Mnemonics Within the Top {} Packages", top_n));
+    let mut table = Table::new(
+        [vec![Column::text("package", "Package"), Column::text("mnemonic", "Mnemonic")], stats_columns()].concat(),
+    )
+    .group_by("package");
+    for (package, (_, mnemonics)) in packages.iter().take(top_n) {
+        let mut mnemonics: Vec<_> = mnemonics.iter().collect();
+        mnemonics.sort_by(|(a_name, a), (b_name, b)| b.time.cmp(&a.time).then_with(|| a_name.cmp(b_name)));
+        for (mnemonic, stats) in mnemonics.into_iter().take(top_n) {
+            table.push_row([vec![package.into(), (*mnemonic).into()], stats.values(build_time)].concat());
+        }
+    }
+    nested.push_table(table);
+    nested.push_text("Note: Time is the total time of all actions, cache hits included; shares are of the whole log.");
+    vec![by_package, nested]
+}
+
+/// Rule classes inferred from a mnemonic and, when given, an output file suffix. The first match
+/// wins, so suffix-specific entries come before the general one for the same mnemonic.
+const RULE_CLASSES: &[(&[&str], &str, &str)] = &[
+    (&["CppLink"], ".a", "cc_library (static archive)"),
+    (&["CppArchive"], "", "cc_library (static archive)"),
+    (&["CppLink"], ".so", "cc_library / cc_shared_library (shared)"),
+    (&["CppLink"], ".dylib", "cc_library / cc_shared_library (shared)"),
+    (&["CppLink"], ".dll", "cc_library / cc_shared_library (shared)"),
+    // MSVC static libraries; DLL links also write an import `.lib` but match `.dll` first.
+    (&["CppLink"], ".lib", "cc_library (static archive)"),
+    (&["CppLink", "ObjcLink"], "", "cc_binary / cc_test (link)"),
+    (&["CppCompile", "CppModuleMap", "CcStrip"], "", "cc_* (compile)"),
+    (&["ObjcCompile"], "", "objc_library"),
+    (&["SwiftCompile", "SwiftDeriveFiles"], "", "swift_library"),
+    (&["JavaDeployJar"], "", "java_binary (deploy jar)"),
+    (&[], "_deploy.jar", "java_binary (deploy jar)"),
+    (&["Javac", "JavaResourceJar"], "", "java_* (compile)"),
+    (&["JavaIjar", "JavaHeaderCompile", "Turbine"], "", "java_* (header jars)"),
+    (&["JavaSourceJar"], "", "java_* (source jars)"),
+    (&["KotlinCompile", "KotlinKapt"], "", "kt_jvm_*"),
+    (&["GoCompilePkg", "GoCompile", "GoCompilePkgExternal"], "", "go_* (compile)"),
+    (&["GoLink"], "", "go_binary / go_test (link)"),
+    (&["Rustc"], ".rlib", "rust_library"),
+    (&["Rustc"], "", "rust_binary / rust_test"),
+    (&["GenProto", "GenProtoDescriptorSet", "GenProtoSources", "ProtoCompile"], "", "proto_library / *_proto_library"),
+    (&["TypeScriptCompile", "TsProject", "Tsc"], "", "ts_project"),
+    (&["PythonZipper", "PyZip"], "", "py_binary (zip)"),
+    (&["Genrule"], "", "genrule"),
+    (&["TestRunner"], "", "*_test (run)"),
+    (DEFAULT_BOOKKEEPING_MNEMONICS, "", "(bookkeeping)"),
+];
+
+/// The rule class a spawn most likely belongs to, or `None` for mnemonics the table does not know.
+fn infer_rule_class(spawn: &SpawnExec) -> Option<&'static str> {
+    let has_output = |suffix: &str| {
+        spawn.actual_outputs.iter().any(|f| f.path.ends_with(suffix))
+            || spawn.listed_outputs.iter().any(|path| path.ends_with(suffix))
+    };
+    RULE_CLASSES
+        .iter()
+        .find(|(mnemonics, suffix, _)| {
+            (mnemonics.is_empty() || mnemonics.contains(&spawn.mnemonic.as_str())) && (suffix.is_empty() || has_output(suffix))
+        })
+        .map(|(_, _, class)| *class)
+}
+
+#[derive(Default)]
+struct RuleClassStats<'a> {
+    count: u64,
+    executed: u64,
+    time: Duration,
+    execution_time: Duration,
+    mnemonics: BTreeSet<&'a str>,
+}
+
+fn rule_class_section(spawns: &[SpawnExec]) -> Section {
+    let mut section = Section::new("rule_classes", "Build Cost by Inferred Rule Class");
+    let mut classes: HashMap<String, RuleClassStats> = HashMap::new();
+    for spawn in spawns {
+        let class = infer_rule_class(spawn).map_or_else(|| format!("(unknown: {})", spawn.mnemonic), str::to_string);
+        let stats = classes.entry(class).or_default();
+        stats.count += 1;
+        stats.time += total_time(spawn);
+        if !spawn.cache_hit {
+            stats.executed += 1;
+            stats.execution_time += total_time(spawn);
+        }
+        stats.mnemonics.insert(&spawn.mnemonic);
+    }
+    if classes.is_empty() {
+        section.push_text("No actions found in the log.");
+        return section;
+    }
+    let execution_time: Duration = classes.values().map(|c| c.execution_time).sum();
+    let mut classes: Vec<_> = classes.into_iter().collect();
+    classes.sort_by(|(a_name, a), (b_name, b)| {
+        b.execution_time.cmp(&a.execution_time).then_with(|| a_name.cmp(b_name))
+    });
+
+    let mut table = Table::new(vec![
+        Column::text("rule_class", "Inferred Rule Class"),
+        Column::integer("count", "Actions"),
+        Column::integer("executed", "Executed"),
+        Column::seconds("execution_time", "Execution Time", 2),
+        Column::percent("execution_share", "Share", 1),
+        Column::seconds("total_time", "Total Time", 2),
+        Column::text("mnemonics", "Mnemonics"),
+    ]);
+    for (class, stats) in &classes {
+        table.push_row(vec![
+            class.into(),
+            stats.count.into(),
+            stats.executed.into(),
+            stats.execution_time.into(),
+            percentage(stats.execution_time.as_secs_f64(), execution_time.as_secs_f64()).into(),
+            stats.time.into(),
+            stats.mnemonics.iter().copied().collect::<Vec<_>>().join(", ").into(),
+        ]);
+    }
+    section.push_table(table);
+    section.push_text("Note: Rule classes are inferred from mnemonics and output file names, so they approximate the rules that created the actions; --bep data is not needed. Execution time covers actions that were not cache hits.");
+    section
+}
+
+/// Command patterns that make a shell action depend on more than its declared inputs. Commands
+/// match whole words of the script; other patterns match anywhere in it.
+const SHELL_PATTERNS: &[(&str, bool, &str)] = &[
+    ("date", true, "timestamp"),
+    ("curl", true, "network access"),
+    ("wget", true, "network access"),
+    ("git", true, "version control state"),
+    ("pip", true, "package manager"),
+    ("npm", true, "package manager"),
+    ("apt-get", true, "package manager"),
+    ("hostname", true, "user or host name"),
+    ("whoami", true, "user or host name"),
+    ("$USER", false, "user or host name"),
+    ("$HOME", false, "home directory"),
+    ("~/", false, "home directory"),
+    ("$RANDOM", false, "randomness"),
+    ("/tmp/", false, "shared /tmp"),
+];
+
+/// Whether a spawn is a genrule or another action running a shell script.
+fn is_shell_action(spawn: &SpawnExec) -> bool {
+    matches!(spawn.mnemonic.as_str(), "Genrule" | "ShellCommand")
+        || (spawn.command_args.len() > 2
+            && ["bash", "sh", "zsh"].contains(&program_name(&spawn.command_args[0]))
+            && spawn.command_args[1] == "-c")
+}
+
+/// The non-hermetic patterns found in a shell action's command, in [`SHELL_PATTERNS`] order,
+/// followed by absolute paths outside the execution root.
+fn shell_issues(spawn: &SpawnExec) -> Vec<&'static str> {
+    let script = spawn.command_args.join(" ");
+    let words: Vec<&str> = script
+        .split(|c: char| c.is_whitespace() || ";|&()`\"'<>$".contains(c))
+        .filter(|word| !word.is_empty())
+        .collect();
+    let mut issues: Vec<&'static str> = Vec::new();
+    for &(pattern, is_command, issue) in SHELL_PATTERNS {
+        let found = if is_command {
+            words.iter().any(|word| program_name(word) == pattern)
+        } else {
+            script.contains(pattern)
+        };
+        if found && !issues.contains(&issue) {
+            issues.push(issue);
+        }
+    }
+    // The shell itself is expected; its script is the remaining arguments. Labels and URLs
+    // (`https://host/...` splits into `https:` and `//host/...`) start with two slashes.
+    let absolute = words.iter().skip(1).any(|word| {
+        is_absolute_path(word)
+            && !word.starts_with("//")
+            && !word.starts_with("/tmp/")
+            && !is_expected_absolute_path(word)
+    });
+    if absolute {
+        issues.push("absolute path");
+    }
+    issues
+}
+
+fn cacheability(spawn: &SpawnExec) -> &'static str {
+    match (spawn.cacheable, spawn.remote_cacheable) {
+        (true, true) => "yes",
+        (true, false) => "local only",
+        (false, _) => "no",
+    }
+}
+
+fn genrule_audit_sections(spawns: &[SpawnExec], top_n: usize) -> Vec<Section> {
+    let mut section = Section::new("genrule_audit", "Genrule and Shell Action Audit");
+    let mut shell: Vec<&SpawnExec> = spawns.iter().filter(|s| is_shell_action(s)).collect();
+    if shell.is_empty() {
+        section.push_text("No genrule or shell actions found in the log.");
+        return vec![section];
+    }
+    let executed_time = |spawns: &mut dyn Iterator<Item = &SpawnExec>| {
+        spawns.filter(|s| !s.cache_hit).map(total_time).sum::<Duration>()
+    };
+    let shell_time = executed_time(&mut shell.iter().copied());
+    let build_time = executed_time(&mut spawns.iter());
+    section.push_field(Column::integer("actions", "Genrule and Shell Actions"), shell.len());
+    section.push_field(Column::integer("executed", "Executed"), shell.iter().filter(|s| !s.cache_hit).count());
+    section.push_field(Column::integer("not_cacheable", "Not Cacheable"), shell.iter().filter(|s| !s.cacheable).count());
+    section.push_field(
+        Column::integer("not_remote_cacheable", "Cacheable Only Locally"),
+        shell.iter().filter(|s| s.cacheable && !s.remote_cacheable).count(),
+    );
+    section.push_field(Column::seconds("execution_time", "Execution Time", 2), shell_time);
+    section.push_field(
+        Column::percent("execution_share", "Share of Execution Time", 1),
+        percentage(shell_time.as_secs_f64(), build_time.as_secs_f64()),
+    );
+
+    let mut by_issue: Vec<(&str, u64, BTreeSet<&str>)> = Vec::new();
+    for spawn in &shell {
+        for issue in shell_issues(spawn) {
+            let index = match by_issue.iter().position(|(name, _, _)| *name == issue) {
+                Some(index) => index,
+                None => {
+                    by_issue.push((issue, 0, BTreeSet::new()));
+                    by_issue.len() - 1
+                }
+            };
+            by_issue[index].1 += 1;
+            by_issue[index].2.insert(&spawn.target_label);
+        }
+    }
+    section.push_field(
+        Column::integer("with_issues", "With Non-Hermetic Patterns"),
+        shell.iter().filter(|s| !shell_issues(s).is_empty()).count(),
+    );
+    if !by_issue.is_empty() {
+        by_issue.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let mut table = Table::new(vec![
+            Column::text("pattern", "Pattern"),
+            Column::integer("actions", "Actions"),
+            Column::text("examples", "Example Targets"),
+        ]);
+        for (issue, count, targets) in &by_issue {
+            let examples: Vec<&str> = targets.iter().copied().take(3).collect();
+            table.push_row(vec![(*issue).into(), (*count).into(), examples.join(", ").into()]);
+        }
+        section.push_table(table);
+    }
+    section.push_text("Hint: Genrules rerun whenever any input changes and are rarely tuned; move heavy or frequently changing ones to proper rules or tools, and replace timestamps, downloads and host paths with declared inputs.");
+
+    let mut largest = Section::new("largest_genrules", format!("Top {} Largest Genrule and Shell Actions", top_n));
+    shell.sort_by_key(|s| std::cmp::Reverse(total_time(s)));
+    let mut table = Table::new(vec![
+        Column::seconds("total_time", "Total Time", 3),
+        Column::text("runner", "Runner"),
+        Column::text("cacheable", "Cacheable"),
+        Column::text("issues", "Non-Hermetic Patterns"),
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
+    for spawn in shell.iter().take(top_n) {
+        table.push_row(vec![
+            total_time(spawn).into(),
+            (&spawn.runner).into(),
+            cacheability(spawn).into(),
+            shell_issues(spawn).join(", ").into(),
+            (&spawn.mnemonic).into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    largest.push_table(table);
+    vec![section, largest]
+}
+
+/// A mnemonic needs at least this many actions to count as an action explosion.
+const EXPLOSION_MIN_ACTIONS: usize = 1000;
+/// ... and executed actions shorter than this on average.
+const EXPLOSION_MAX_AVERAGE: Duration = Duration::from_millis(100);
+
+/// A mnemonic with very many short actions.
+struct ActionExplosion<'a> {
+    mnemonic: &'a str,
+    count: usize,
+    executed: usize,
+    total_time: Duration,
+    /// Time outside execution, summed over the executed actions.
+    overhead: Duration,
+    on_workers: usize,
+}
+
+fn action_explosion_section(spawns: &[SpawnExec]) -> Section {
+    let mut section = Section::new("action_explosion", "Action Count Explosions");
+    section.push_text(format!(
+        "Note: Mnemonics with at least {} actions whose executed actions average under {}ms. Overhead is the time outside execution (queueing, setup, fetching and uploading) that every action pays regardless of its work.",
+        EXPLOSION_MIN_ACTIONS,
+        EXPLOSION_MAX_AVERAGE.as_millis()
+    ));
+    let mut by_mnemonic: HashMap<&str, Vec<&SpawnExec>> = HashMap::new();
+    for spawn in spawns {
+        by_mnemonic.entry(&spawn.mnemonic).or_default().push(spawn);
+    }
+
+    let execution = |s: &SpawnExec| phase_duration(s, |m| m.execution_wall_time.as_ref());
+    let mut explosions: Vec<ActionExplosion> = by_mnemonic
+        .into_iter()
+        .filter(|(_, spawns)| spawns.len() >= EXPLOSION_MIN_ACTIONS)
+        .filter_map(|(mnemonic, spawns)| {
+            let executed: Vec<&SpawnExec> = spawns.iter().copied().filter(|s| !s.cache_hit).collect();
+            let time: Duration = executed.iter().map(|s| total_time(s)).sum();
+            if executed.is_empty() || time / executed.len() as u32 >= EXPLOSION_MAX_AVERAGE {
+                return None;
+            }
+            Some(ActionExplosion {
+                mnemonic,
+                count: spawns.len(),
+                executed: executed.len(),
+                total_time: time,
+                overhead: executed.iter().map(|s| total_time(s).saturating_sub(execution(s))).sum(),
+                on_workers: executed.iter().filter(|s| runner_kind(s) == RunnerKind::Worker).count(),
+            })
+        })
+        .collect();
+    if explosions.is_empty() {
+        section.push_text("No mnemonic has that many short actions.");
+        return section;
+    }
+    explosions.sort_by(|a, b| b.overhead.cmp(&a.overhead).then_with(|| a.mnemonic.cmp(b.mnemonic)));
+
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("count", "Actions"),
+        Column::percent("action_share", "Share of Actions", 1),
+        Column::integer("executed", "Executed"),
+        Column::seconds("avg_time", "Avg Time", 3),
+        Column::seconds("avg_overhead", "Avg Overhead", 3),
+        Column::seconds("total_overhead", "Total Overhead", 2),
+        Column::percent("overhead_share", "Overhead Share", 1),
+        Column::text("recommendation", "Recommendation"),
+    ]);
+    for explosion in &explosions {
+        let recommendation = if explosion.on_workers * 2 >= explosion.executed {
+            "Already on workers: batch several inputs per action (e.g. one codegen call per library)".to_string()
+        } else {
+            format!(
+                "Run on a persistent worker (--strategy={}=worker) or batch inputs into fewer actions",
+                explosion.mnemonic
+            )
+        };
+        table.push_row(vec![
+            explosion.mnemonic.into(),
+            explosion.count.into(),
+            percentage(explosion.count as f64, spawns.len() as f64).into(),
+            explosion.executed.into(),
+            (explosion.total_time / explosion.executed as u32).into(),
+            (explosion.overhead / explosion.executed as u32).into(),
+            explosion.overhead.into(),
+            percentage(explosion.overhead.as_secs_f64(), explosion.total_time.as_secs_f64()).into(),
+            recommendation.into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
+
+fn server_timings_section(spawns: &[SpawnExec], inputs: &SideInputs, top_n: usize) -> Section {
+    let mut section = Section::new("server_timings", "Client vs. Server Execution Times");
+    let Some(timings) = inputs.server_timings.as_ref() else {
+        section.push_text("No server timings given; pass a CSV with --server-timings.");
+        return section;
+    };
+    section.push_text("Note: Overhead is the client-observed time minus the server-reported time: network transfers, scheduling outside the server's queue, and client-side bookkeeping.");
+
+    let remote: Vec<&SpawnExec> = spawns
+        .iter()
+        .filter(|s| runner_kind(s) == RunnerKind::Remote)
+        .collect();
+    let matched: Vec<_> = remote
+        .iter()
+        .filter_map(|spawn| timings.get(spawn).map(|timing| (*spawn, timing)))
+        .collect();
+    section.push_field(Column::integer("remote_executions", "Remote executions"), remote.len());
+    section.push_field(Column::integer("matched", "With server timings"), matched.len());
+    if matched.is_empty() {
+        section.push_text("No remote execution in the log has a matching digest in the CSV.");
+        return section;
+    }
+
+    let overhead = |spawn: &SpawnExec, server_seconds: f64| total_time(spawn).as_secs_f64() - server_seconds;
+    let client_total: f64 = matched.iter().map(|(s, _)| total_time(s).as_secs_f64()).sum();
+    let server_total: f64 = matched.iter().map(|(_, t)| t.total_seconds).sum();
+    let mut overheads: Vec<f64> = matched.iter().map(|(s, t)| overhead(s, t.total_seconds)).collect();
+    overheads.sort_by(f64::total_cmp);
+    section.push_field(Column::seconds("client_time", "Client-observed time", 2), client_total);
+    section.push_field(Column::seconds("server_time", "Server-reported time", 2), server_total);
+    section.push_field(Column::seconds("overhead", "Total overhead", 2), client_total - server_total);
+    section.push_field(Column::percent("overhead_share", "Overhead share", 1), percentage(client_total - server_total, client_total));
+    section.push_field(Column::seconds("p50_overhead", "Median overhead per action", 3), percentile(&overheads, 50.0));
+    section.push_field(Column::seconds("p90_overhead", "p90 overhead per action", 3), percentile(&overheads, 90.0));
+    section.push_field(
+        Column::seconds("server_queue_time", "Server queue time", 2),
+        matched.iter().map(|(_, t)| t.queue_seconds).sum::<f64>(),
+    );
+    section.push_field(
+        Column::seconds("server_input_fetch_time", "Server input fetch time", 2),
+        matched.iter().map(|(_, t)| t.input_fetch_seconds).sum::<f64>(),
+    );
+    section.push_field(
+        Column::seconds("server_execution_time", "Server execution time", 2),
+        matched.iter().map(|(_, t)| t.execution_seconds).sum::<f64>(),
+    );
+    section.push_field(
+        Column::seconds("server_output_upload_time", "Server output upload time", 2),
+        matched.iter().map(|(_, t)| t.output_upload_seconds).sum::<f64>(),
+    );
+
+    let mut slowest = matched;
+    slowest.sort_by(|(a, at), (b, bt)| overhead(b, bt.total_seconds).total_cmp(&overhead(a, at.total_seconds)));
+    let mut table = Table::new(vec![
+        Column::seconds("overhead", "Overhead", 3),
+        Column::seconds("client_time", "Client", 3),
+        Column::seconds("server_time", "Server", 3),
+        Column::seconds("server_queue_time", "Server Queue", 3),
+        Column::text("worker", "Worker"),
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+        spawn_id_column(),
+    ]);
+    for (spawn, timing) in slowest.into_iter().take(top_n) {
+        table.push_row(vec![
+            overhead(spawn, timing.total_seconds).into(),
+            total_time(spawn).into(),
+            timing.total_seconds.into(),
+            timing.queue_seconds.into(),
+            timing.worker.as_str().into(),
+            spawn.mnemonic.as_str().into(),
+            display_label(spawn).into(),
+            spawn_id(spawn).into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
@@ -0,0 +1,43 @@
+//! `convert`: rewrites a log in the other format with the [`writer`](crate::writer) API. A compact
+//! log converted to the compact format is rewritten entry by entry as a minimal log, with only the
+//! entries its actions reference and contiguous IDs.
+
+use crate::cli::{ConvertArgs, LogFormatArg};
+use crate::parsers::{parse_log_file, read_compact_entries, LogFormat, ParseLimits};
+use crate::writer::{write_spawns, EntryCompactor};
+use crate::AppResult;
+use std::fs::{self, File};
+use std::io::{BufWriter, Read};
+
+pub fn run_convert(args: ConvertArgs) -> AppResult<()> {
+    let mut magic = [0; 4];
+    let read = File::open(&args.file)?.read(&mut magic)?;
+    let from = LogFormat::detect(&magic[..read]);
+    let to = match args.to {
+        Some(LogFormatArg::Verbose) => LogFormat::Verbose,
+        Some(LogFormatArg::Compact) => LogFormat::Compact,
+        None => match from {
+            LogFormat::Verbose => LogFormat::Compact,
+            LogFormat::Compact => LogFormat::Verbose,
+        },
+    };
+    let spawns = if from == LogFormat::Compact && to == LogFormat::Compact {
+        let mut compactor = EntryCompactor::new();
+        compactor.add_log(&read_compact_entries(&fs::read(&args.file)?, &ParseLimits::default())?, |_| true);
+        let spawns = compactor.spawns();
+        compactor.finish(BufWriter::new(File::create(&args.out)?))?;
+        spawns
+    } else {
+        let spawns = parse_log_file(&args.file)?;
+        write_spawns(&spawns, to, BufWriter::new(File::create(&args.out)?))?;
+        spawns.len()
+    };
+    eprintln!(
+        "Wrote {} spawns to {} ({} format, {} bytes).",
+        spawns,
+        args.out.display(),
+        to,
+        fs::metadata(&args.out)?.len()
+    );
+    Ok(())
+}
@@ -0,0 +1,518 @@
+use crate::cli::{CorpusAnalysis, CorpusArgs, LogsDirArgs};
+use crate::commands::ab::{mean, variance};
+use crate::commands::analyze::{display_label, output_bytes, percentage, percentile, total_time, wall_time};
+use crate::parsers::parse_logs_dir;
+use crate::proto::SpawnExec;
+use crate::render::{renderer_for, ByteUnit, Column, Document, Section, Table, Value};
+use crate::AppResult;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io;
+use std::path::PathBuf;
+
+pub fn run_corpus(args: CorpusArgs) -> AppResult<()> {
+    match args.analysis {
+        CorpusAnalysis::Reuse(args) => run_reuse(args),
+        CorpusAnalysis::Churn(args) => run_churn(args),
+        CorpusAnalysis::Noise(args) => run_noise(args),
+    }
+}
+
+fn corpus_document(title: &str, args: &LogsDirArgs, logs: &[(PathBuf, Vec<SpawnExec>)]) -> Document {
+    let mut document = Document::new(title);
+    document.format_version = args.format_version;
+    document.push_metadata(Column::text("logs_dir", "Logs Directory"), args.logs_dir.display().to_string());
+    document.push_metadata(Column::integer("builds", "Builds"), logs.len());
+    document
+}
+
+/// Everything the corpus saw of one action cache entry.
+struct CacheEntry<'a> {
+    digest: &'a str,
+    /// The first spawn seen with the digest, for its label and outputs.
+    spawn: &'a SpawnExec,
+    /// Builds (in log order) that executed the action and could upload the result.
+    written: Vec<usize>,
+    /// Builds (in log order) that got the result from a cache.
+    hit: Vec<usize>,
+}
+
+impl CacheEntry<'_> {
+    fn first_write(&self) -> Option<usize> {
+        self.written.first().copied()
+    }
+
+    /// Builds after the first write that read the entry.
+    fn reads_after_write(&self) -> Option<usize> {
+        let first = self.first_write()?;
+        Some(self.hit.iter().filter(|&&build| build > first).count())
+    }
+}
+
+/// Distinct builds hitting an entry, grouped into the ranges of the distribution table.
+const REUSE_BUCKETS: &[(usize, usize, &str)] = &[
+    (0, 0, "0"),
+    (1, 1, "1"),
+    (2, 4, "2-4"),
+    (5, 9, "5-9"),
+    (10, usize::MAX, "10+"),
+];
+
+fn cache_entries(logs: &[(PathBuf, Vec<SpawnExec>)]) -> Vec<CacheEntry<'_>> {
+    let mut entries: HashMap<&str, CacheEntry> = HashMap::new();
+    for (build, (_, spawns)) in logs.iter().enumerate() {
+        for spawn in spawns {
+            let Some(digest) = spawn.digest.as_ref().filter(|d| !d.hash.is_empty()) else {
+                continue;
+            };
+            let entry = entries.entry(&digest.hash).or_insert_with(|| CacheEntry {
+                digest: &digest.hash,
+                spawn,
+                written: Vec::new(),
+                hit: Vec::new(),
+            });
+            // A build counts once per entry, however often it ran the action.
+            let builds = if spawn.cache_hit {
+                &mut entry.hit
+            } else if spawn.remote_cacheable {
+                &mut entry.written
+            } else {
+                continue;
+            };
+            if builds.last() != Some(&build) {
+                builds.push(build);
+            }
+        }
+    }
+    let mut entries: Vec<CacheEntry> = entries.into_values().collect();
+    entries.sort_by(|a, b| b.hit.len().cmp(&a.hit.len()).then_with(|| a.digest.cmp(b.digest)));
+    entries
+}
+
+/// Per action digest, counts the distinct builds that hit the cache entry, and how many entries
+/// written by a build in the corpus were never read by a later one.
+fn run_reuse(args: LogsDirArgs) -> AppResult<()> {
+    let logs = parse_logs_dir(&args.logs_dir)?;
+    let entries = cache_entries(&logs);
+
+    let mut document = corpus_document("Cache Entry Reuse", &args, &logs);
+    document.sections.push(reuse_distribution_section(&entries));
+    document.sections.push(unread_writes_section(&entries, logs.len()));
+    document.sections.push(most_reused_section(&entries, &args));
+    renderer_for(args.output).render(&document, &mut io::stdout().lock())?;
+    Ok(())
+}
+
+fn reuse_distribution_section(entries: &[CacheEntry]) -> Section {
+    let mut section = Section::new("reuse_distribution", "Cache Entry Reuse Distribution");
+    section.push_field(Column::integer("cache_entries", "Action Cache Entries"), entries.len());
+    let mut table = Table::new(vec![
+        Column::text("builds_hitting", "Builds Hitting"),
+        Column::integer("entries", "Entries"),
+        Column::percent("share", "Share", 1),
+        Column::bytes("output_bytes", "Output Size", ByteUnit::DecimalMegabytes, 2),
+    ]);
+    for &(low, high, name) in REUSE_BUCKETS {
+        let bucket: Vec<&CacheEntry> = entries
+            .iter()
+            .filter(|e| (low..=high).contains(&e.hit.len()))
+            .collect();
+        table.push_row(vec![
+            name.into(),
+            bucket.len().into(),
+            percentage(bucket.len() as f64, entries.len() as f64).into(),
+            bucket.iter().map(|e| output_bytes(e.spawn)).sum::<i64>().into(),
+        ]);
+    }
+    section.push_table(table);
+    section.push_text("Note: Entries are keyed by action digest; each build counts once per entry.");
+    section
+}
+
+fn unread_writes_section(entries: &[CacheEntry], builds: usize) -> Section {
+    let mut section = Section::new("unread_writes", "Cache Writes Never Read");
+    let written: Vec<&CacheEntry> = entries.iter().filter(|e| e.first_write().is_some()).collect();
+    // Writes by the last build have had no chance to be read within the corpus.
+    let observable: Vec<&CacheEntry> = written
+        .iter()
+        .copied()
+        .filter(|e| e.first_write() != Some(builds - 1))
+        .collect();
+    let unread: Vec<&CacheEntry> = observable
+        .iter()
+        .copied()
+        .filter(|e| e.reads_after_write() == Some(0))
+        .collect();
+    let bytes = |entries: &[&CacheEntry]| entries.iter().map(|e| output_bytes(e.spawn)).sum::<i64>();
+
+    section.push_field(Column::integer("written_entries", "Entries Written"), written.len());
+    section.push_field(
+        Column::integer("observable_entries", "Entries Written Before the Last Build"),
+        observable.len(),
+    );
+    section.push_field(Column::integer("unread_entries", "Never Read by a Later Build"), unread.len());
+    section.push_field(
+        Column::percent("unread_share", "Share of Writes Never Read", 1),
+        percentage(unread.len() as f64, observable.len() as f64),
+    );
+    section.push_field(
+        Column::bytes("unread_bytes", "Output Size Never Read", ByteUnit::DecimalMegabytes, 2),
+        bytes(&unread),
+    );
+    section.push_field(
+        Column::percent("unread_bytes_share", "Share of Written Bytes Never Read", 1),
+        percentage(bytes(&unread) as f64, bytes(&observable) as f64),
+    );
+
+    // How many builds after the write the last read came, for entries that were read at all.
+    let mut spans: Vec<f64> = observable
+        .iter()
+        .filter_map(|e| {
+            let first = e.first_write()?;
+            let last = e.hit.iter().copied().filter(|&build| build > first).max()?;
+            Some((last - first) as f64)
+        })
+        .collect();
+    spans.sort_by(f64::total_cmp);
+    for (key, title, pct) in [
+        ("read_span_p50", "Builds From Write to Last Read (p50)", 50.0),
+        ("read_span_p90", "Builds From Write to Last Read (p90)", 90.0),
+        ("read_span_max", "Builds From Write to Last Read (max)", 100.0),
+    ] {
+        if let Some(span) = percentile(&spans, pct) {
+            section.push_field(Column::float(key, title, 0), span);
+        }
+    }
+    section.push_text(
+        "Note: A write is an executed, remote-cacheable spawn. Entries first written by the last build \
+         are left out because no later build could read them.",
+    );
+    section.push_text(
+        "Hint: A large share of writes never read suggests the TTL can be short; a long write-to-read \
+         span means entries must survive that many builds to pay off.",
+    );
+    section
+}
+
+fn most_reused_section(entries: &[CacheEntry], args: &LogsDirArgs) -> Section {
+    let mut section = Section::new("most_reused", format!("Top {} Most Reused Cache Entries", args.top_n));
+    let mut table = Table::new(vec![
+        Column::text("action_digest", "Action Digest"),
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+        Column::integer("builds_hitting", "Builds Hitting"),
+        Column::integer("builds_writing", "Builds Writing"),
+        Column::bytes("output_bytes", "Output Size", ByteUnit::DecimalMegabytes, 2),
+    ]);
+    for entry in entries.iter().filter(|e| !e.hit.is_empty()).take(args.top_n) {
+        table.push_row(vec![
+            entry.digest.into(),
+            (&entry.spawn.mnemonic).into(),
+            display_label(entry.spawn).into(),
+            entry.hit.len().into(),
+            entry.written.len().into(),
+            output_bytes(entry.spawn).into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
+
+/// The history of one output path across the builds that produced it.
+struct OutputHistory<'a> {
+    path: &'a str,
+    /// The spawn that produced the path in the latest build.
+    spawn: &'a SpawnExec,
+    /// Builds that produced the path.
+    builds_seen: usize,
+    /// Consecutive appearances with a different digest.
+    changes: usize,
+    last_build: usize,
+    last_digest: &'a str,
+}
+
+impl OutputHistory<'_> {
+    /// Share of consecutive appearances in which the digest changed.
+    fn change_rate(&self) -> f64 {
+        percentage(self.changes as f64, (self.builds_seen - 1) as f64)
+    }
+}
+
+fn output_histories(logs: &[(PathBuf, Vec<SpawnExec>)]) -> Vec<OutputHistory<'_>> {
+    let mut histories: HashMap<&str, OutputHistory> = HashMap::new();
+    for (build, (_, spawns)) in logs.iter().enumerate() {
+        for spawn in spawns {
+            for output in &spawn.actual_outputs {
+                let Some(digest) = output.digest.as_ref().filter(|d| !d.hash.is_empty()) else {
+                    continue;
+                };
+                let history = histories.entry(&output.path).or_insert_with(|| OutputHistory {
+                    path: &output.path,
+                    spawn,
+                    builds_seen: 0,
+                    changes: 0,
+                    last_build: build,
+                    last_digest: &digest.hash,
+                });
+                if history.builds_seen == 0 || history.last_build != build {
+                    history.builds_seen += 1;
+                    if history.last_digest != digest.hash {
+                        history.changes += 1;
+                    }
+                }
+                history.spawn = spawn;
+                history.last_build = build;
+                history.last_digest = &digest.hash;
+            }
+        }
+    }
+    // A single appearance says nothing about churn.
+    histories.into_values().filter(|h| h.builds_seen > 1).collect()
+}
+
+/// Number of spawns per input path in one log, i.e. the actions an output change invalidates.
+fn consumer_counts(spawns: &[SpawnExec]) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    for spawn in spawns {
+        let paths: HashSet<&str> = spawn.inputs.iter().map(|f| f.path.as_str()).collect();
+        for path in paths {
+            *counts.entry(path).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Per output path, counts how often its digest changed between the builds that produced it,
+/// separating outputs that change every build from those that stay stable.
+fn run_churn(args: LogsDirArgs) -> AppResult<()> {
+    let logs = parse_logs_dir(&args.logs_dir)?;
+    let mut histories = output_histories(&logs);
+    let consumers: Vec<HashMap<&str, usize>> = logs.iter().map(|(_, spawns)| consumer_counts(spawns)).collect();
+    let consumers_of = |h: &OutputHistory| consumers[h.last_build].get(h.path).copied().unwrap_or(0);
+
+    let mut document = corpus_document("Artifact Churn", &args, &logs);
+    let always = histories.iter().filter(|h| h.changes == h.builds_seen - 1).count();
+    let never = histories.iter().filter(|h| h.changes == 0).count();
+    let mut summary = Section::new("churn_summary", "Artifact Churn Summary");
+    summary.push_field(Column::integer("outputs", "Outputs Seen in Several Builds"), histories.len());
+    summary.push_field(Column::integer("always_changing", "Changed in Every Build"), always);
+    summary.push_field(
+        Column::percent("always_changing_share", "Share Changed in Every Build", 1),
+        percentage(always as f64, histories.len() as f64),
+    );
+    summary.push_field(Column::integer("never_changing", "Never Changed"), never);
+    summary.push_field(
+        Column::percent("never_changing_share", "Share Never Changed", 1),
+        percentage(never as f64, histories.len() as f64),
+    );
+    document.sections.push(summary);
+
+    let columns = || {
+        vec![
+            Column::text("path", "Output Path"),
+            Column::text("mnemonic", "Mnemonic"),
+            Column::text("target", "Target"),
+            Column::integer("builds_seen", "Builds Seen"),
+            Column::integer("changes", "Digest Changes"),
+            Column::percent("change_rate", "Change Rate", 1),
+            Column::integer("consumers", "Consuming Actions"),
+        ]
+    };
+    let row = |h: &OutputHistory| {
+        vec![
+            h.path.into(),
+            (&h.spawn.mnemonic).into(),
+            display_label(h.spawn).into(),
+            h.builds_seen.into(),
+            h.changes.into(),
+            h.change_rate().into(),
+            consumers_of(h).into(),
+        ]
+    };
+
+    // Churning outputs with many consumers invalidate the most downstream work.
+    histories.sort_by(|a, b| {
+        b.change_rate()
+            .total_cmp(&a.change_rate())
+            .then_with(|| consumers_of(b).cmp(&consumers_of(a)))
+            .then_with(|| a.path.cmp(b.path))
+    });
+    let mut high = Section::new("high_churn", format!("Top {} High-Churn Outputs", args.top_n));
+    let mut table = Table::new(columns());
+    for history in histories.iter().filter(|h| h.changes > 0).take(args.top_n) {
+        table.push_row(row(history));
+    }
+    high.push_table(table);
+    high.push_text(
+        "Hint: An output that changes in every build usually embeds a timestamp, a build number or \
+         an unsorted listing; making it deterministic stops it from invalidating its consumers.",
+    );
+    document.sections.push(high);
+
+    histories.sort_by(|a, b| {
+        b.builds_seen
+            .cmp(&a.builds_seen)
+            .then_with(|| consumers_of(b).cmp(&consumers_of(a)))
+            .then_with(|| a.path.cmp(b.path))
+    });
+    let mut stable = Section::new("stable_outputs", format!("Top {} Stable Outputs", args.top_n));
+    let mut table = Table::new(columns());
+    for history in histories.iter().filter(|h| h.changes == 0).take(args.top_n) {
+        table.push_row(row(history));
+    }
+    stable.push_table(table);
+    document.sections.push(stable);
+
+    renderer_for(args.output).render(&document, &mut io::stdout().lock())?;
+    Ok(())
+}
+
+/// How one quantity varied over the builds it was measured in.
+struct Spread {
+    builds: usize,
+    mean: f64,
+    std_dev: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Spread {
+    /// `None` for fewer than two measurements, which have no spread.
+    fn of(values: &[f64]) -> Option<Spread> {
+        (values.len() > 1).then(|| Spread {
+            builds: values.len(),
+            mean: mean(values),
+            std_dev: variance(values).sqrt(),
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+
+    /// Coefficient of variation: the standard deviation as a percentage of the mean.
+    fn cv(&self) -> f64 {
+        percentage(self.std_dev, self.mean)
+    }
+}
+
+/// Per build, and per mnemonic and per action across builds, how much the time of executed
+/// spawns varies when the same build runs again.
+fn run_noise(args: LogsDirArgs) -> AppResult<()> {
+    let logs = parse_logs_dir(&args.logs_dir)?;
+    let mut document = corpus_document("Build Noise", &args, &logs);
+
+    // Per build, the summed time per mnemonic and per (label, mnemonic), of executed spawns only:
+    // a cache hit in one build and not another is not timing noise.
+    let mut by_mnemonic: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+    let mut by_action: BTreeMap<(String, &str), Vec<f64>> = BTreeMap::new();
+    for (_, spawns) in &logs {
+        let mut mnemonics: BTreeMap<&str, f64> = BTreeMap::new();
+        let mut actions: BTreeMap<(String, &str), f64> = BTreeMap::new();
+        for spawn in spawns.iter().filter(|s| !s.cache_hit) {
+            let seconds = total_time(spawn).as_secs_f64();
+            *mnemonics.entry(&spawn.mnemonic).or_default() += seconds;
+            *actions.entry((display_label(spawn), &spawn.mnemonic)).or_default() += seconds;
+        }
+        for (mnemonic, seconds) in mnemonics {
+            by_mnemonic.entry(mnemonic).or_default().push(seconds);
+        }
+        for (action, seconds) in actions {
+            by_action.entry(action).or_default().push(seconds);
+        }
+    }
+
+    let mut summary = Section::new("build_noise", "Build-to-Build Variation");
+    let execution: Vec<f64> = logs
+        .iter()
+        .map(|(_, spawns)| spawns.iter().filter(|s| !s.cache_hit).map(|s| total_time(s).as_secs_f64()).sum())
+        .collect();
+    let wall: Option<Vec<f64>> = logs.iter().map(|(_, spawns)| wall_time(spawns)).collect();
+    let mut push_spread = |key: &str, title: &str, values: &[f64]| {
+        if let Some(spread) = Spread::of(values) {
+            summary.push_field(Column::seconds(&format!("{}_mean", key), &format!("{} (mean)", title), 2), spread.mean);
+            summary.push_field(
+                Column::seconds(&format!("{}_std_dev", key), &format!("{} (std dev)", title), 2),
+                spread.std_dev,
+            );
+            summary.push_field(Column::percent(&format!("{}_cv", key), &format!("{} (CV)", title), 1), spread.cv());
+        }
+    };
+    push_spread("execution_time", "Execution Time", &execution);
+    if let Some(wall) = &wall {
+        push_spread("wall_time", "Wall Time", wall);
+    }
+    if logs.len() < 2 {
+        summary.push_text("Variation needs at least two builds.");
+    } else {
+        summary.push_text(
+            "Hint: Differences between two builds smaller than about twice the coefficient of variation are \
+             within the noise; compare groups of builds with `ab` instead.",
+        );
+    }
+    document.sections.push(summary);
+
+    let columns = |first: Vec<Column>| {
+        first
+            .into_iter()
+            .chain([
+                Column::integer("builds", "Builds"),
+                Column::seconds("mean", "Mean", 2),
+                Column::seconds("std_dev", "Std Dev", 2),
+                Column::percent("cv", "CV", 1),
+                Column::seconds("min", "Min", 2),
+                Column::seconds("max", "Max", 2),
+            ])
+            .collect()
+    };
+    let spread_cells = |spread: &Spread| -> Vec<Value> {
+        vec![
+            spread.builds.into(),
+            spread.mean.into(),
+            spread.std_dev.into(),
+            spread.cv().into(),
+            spread.min.into(),
+            spread.max.into(),
+        ]
+    };
+    // Ranked by standard deviation: the seconds of noise an action adds to a comparison, whereas
+    // the coefficient of variation of a tiny action can be large without mattering.
+    let ranked = |spreads: &mut Vec<(Spread, Vec<Value>)>| {
+        spreads.sort_by(|(a, _), (b, _)| b.std_dev.total_cmp(&a.std_dev));
+        spreads.truncate(args.top_n);
+    };
+
+    let mut mnemonics: Vec<(Spread, Vec<Value>)> = by_mnemonic
+        .iter()
+        .filter_map(|(mnemonic, times)| Some((Spread::of(times)?, vec![(*mnemonic).into()])))
+        .collect();
+    ranked(&mut mnemonics);
+    let mut section = Section::new("noisiest_mnemonics", format!("Top {} Noisiest Mnemonics", args.top_n));
+    let mut table = Table::new(columns(vec![Column::text("mnemonic", "Mnemonic")]));
+    for (spread, mut row) in mnemonics {
+        row.extend(spread_cells(&spread));
+        table.push_row(row);
+    }
+    section.push_table(table);
+    document.sections.push(section);
+
+    let mut actions: Vec<(Spread, Vec<Value>)> = by_action
+        .iter()
+        .filter_map(|((label, mnemonic), times)| {
+            Some((Spread::of(times)?, vec![label.as_str().into(), (*mnemonic).into()]))
+        })
+        .collect();
+    ranked(&mut actions);
+    let mut section = Section::new("noisiest_actions", format!("Top {} Noisiest Actions", args.top_n));
+    let mut table = Table::new(columns(vec![Column::text("target", "Target"), Column::text("mnemonic", "Mnemonic")]));
+    for (spread, mut row) in actions {
+        row.extend(spread_cells(&spread));
+        table.push_row(row);
+    }
+    section.push_table(table);
+    section.push_text(
+        "Note: Times are summed per build over the executed spawns of a target and mnemonic; builds in which \
+         the action was a cache hit or did not run are left out.",
+    );
+    document.sections.push(section);
+
+    renderer_for(args.output).render(&document, &mut io::stdout().lock())?;
+    Ok(())
+}
@@ -0,0 +1,606 @@
+use crate::cli::{DiffArgs, DiffThreshold};
+use crate::commands::analyze::{critical_path_shares, display_label, is_local_runner, is_workspace_status_file, total_time};
+use crate::filters;
+use crate::matching::{match_spawns, only_new, MatchKind, MatchedPair};
+use crate::parsers::parse_log_file;
+use crate::proto::SpawnExec;
+use crate::render::{renderer_for, Column, Document, Section, Table, Value};
+use crate::AppResult;
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::time::Duration;
+
+#[derive(Default)]
+struct LogSummary {
+    total_actions: usize,
+    cache_hits: usize,
+    total_time: Duration,
+}
+
+#[derive(Default)]
+struct CacheHitCounts {
+    count: u64,
+    cache_hits: u64,
+}
+
+#[derive(Default)]
+struct MnemonicCacheDelta {
+    old: CacheHitCounts,
+    new: CacheHitCounts,
+}
+
+struct Regression<'a> {
+    spawn: &'a SpawnExec,
+    old_time: Duration,
+    new_time: Duration,
+}
+
+/// The slowdowns of one action (target label and mnemonic) over all its runs, weighted by how
+/// critical it is to the new build.
+struct ImpactfulRegression<'a> {
+    spawn: &'a SpawnExec,
+    runs: usize,
+    /// Summed baseline time in seconds.
+    old: f64,
+    /// Summed change in seconds; speedups of some runs offset slowdowns of others.
+    delta: f64,
+    /// `delta` with each run weighted by its share of the critical path.
+    impact: f64,
+}
+
+struct NewlyExecuted<'a> {
+    spawn: &'a SpawnExec,
+    reason: &'static str,
+}
+
+/// Executed actions that moved from one runner to another, aggregated per runner pair.
+struct RunnerTransition<'a> {
+    from: &'a str,
+    to: &'a str,
+    count: u64,
+    old_time: Duration,
+    new_time: Duration,
+}
+
+impl RunnerTransition<'_> {
+    /// A remote execution in the baseline that ran on a local strategy in the new log,
+    /// e.g. because `--remote_local_fallback` kicked in after executor errors.
+    fn is_remote_fallback(&self) -> bool {
+        self.from.contains("remote") && is_local_runner(self.to)
+    }
+}
+
+struct OutputChange<'a> {
+    target_label: &'a str,
+    path: &'a str,
+    old_hash: &'a str,
+    new_hash: &'a str,
+}
+
+/// An action that executed in the new log although its inputs and command line match the baseline.
+struct UnchangedReexecution<'a> {
+    spawn: &'a SpawnExec,
+    hint: &'static str,
+}
+
+/// Everything the diff renderers need, computed once from the two logs.
+struct LogDiff<'a> {
+    old_summary: LogSummary,
+    new_summary: LogSummary,
+    /// Matched actions whose configuration directory or target label changed.
+    loose_matches: usize,
+    /// Slower actions left out because their change stayed within `--diff-threshold`.
+    below_threshold: usize,
+    regressions: Vec<Regression<'a>>,
+    /// Actions with a positive impact, the most impactful first.
+    impactful_regressions: Vec<ImpactfulRegression<'a>>,
+    /// Whether impacts are weighted by criticality, which needs the inputs of the new log.
+    weighted_by_criticality: bool,
+    newly_executed: Vec<NewlyExecuted<'a>>,
+    cache_deltas: BTreeMap<&'a str, MnemonicCacheDelta>,
+    runner_transitions: Vec<RunnerTransition<'a>>,
+    output_changes: Vec<OutputChange<'a>>,
+    /// `None` when either log has no inputs recorded, so inputs cannot be compared.
+    unchanged_reexecutions: Option<Vec<UnchangedReexecution<'a>>>,
+}
+
+pub fn run_diff(args: DiffArgs) -> AppResult<()> {
+    let mut old_spawns = parse_log_file(&args.old)?;
+    let mut new_spawns = parse_log_file(&args.new)?;
+    if !args.raw_paths {
+        filters::normalize_paths(&mut old_spawns);
+        filters::normalize_paths(&mut new_spawns);
+    }
+
+    let diff = compute_diff(&old_spawns, &new_spawns, &args.diff_threshold);
+
+    let document = diff_document(&diff, &args);
+    renderer_for(args.output).render(&document, &mut io::stdout().lock())?;
+    Ok(())
+}
+
+/// Input paths with their content hashes, sorted by path.
+fn input_digests(spawn: &SpawnExec) -> Vec<(&str, &str)> {
+    let mut inputs: Vec<(&str, &str)> = spawn
+        .inputs
+        .iter()
+        .map(|f| (f.path.as_str(), f.digest.as_ref().map_or("", |d| d.hash.as_str())))
+        .collect();
+    inputs.sort_unstable();
+    inputs
+}
+
+/// Matched actions that executed in the new log with the same inputs and command line as in the
+/// baseline, which an incremental build should not need to do.
+fn unchanged_reexecutions<'a>(matched: &[MatchedPair<'a>]) -> Vec<UnchangedReexecution<'a>> {
+    let mut found: Vec<UnchangedReexecution> = matched
+        .iter()
+        .filter(|pair| {
+            !pair.new.cache_hit
+                && !pair.new.inputs.is_empty()
+                && pair.old.command_args == pair.new.command_args
+                && input_digests(pair.old) == input_digests(pair.new)
+        })
+        .map(|pair| {
+            let hint = if pair.new.inputs.iter().any(|f| is_workspace_status_file(&f.path)) {
+                "stamped: reads workspace status"
+            } else if pair.old.environment_variables != pair.new.environment_variables {
+                "environment changed"
+            } else if pair.old.platform != pair.new.platform {
+                "platform changed"
+            } else {
+                "volatile or untracked inputs"
+            };
+            UnchangedReexecution { spawn: pair.new, hint }
+        })
+        .collect();
+    found.sort_by_key(|r| std::cmp::Reverse(total_time(r.spawn)));
+    found
+}
+
+fn summarize(spawns: &[SpawnExec]) -> LogSummary {
+    LogSummary {
+        total_actions: spawns.len(),
+        cache_hits: spawns.iter().filter(|s| s.cache_hit).count(),
+        total_time: spawns.iter().map(total_time).sum(),
+    }
+}
+
+fn compute_diff<'a>(old: &'a [SpawnExec], new: &'a [SpawnExec], thresholds: &[DiffThreshold]) -> LogDiff<'a> {
+    let matched = match_spawns(old, new);
+    let only_new = only_new(new, &matched);
+
+    let exceeds_thresholds =
+        |old: f64, new: f64| thresholds.iter().all(|threshold| threshold.exceeded_by(old, new));
+    let mut regressions: Vec<Regression> = matched
+        .iter()
+        .filter_map(|pair| {
+            let old_time = total_time(pair.old);
+            let new_time = total_time(pair.new);
+            (new_time > old_time).then_some(Regression {
+                spawn: pair.new,
+                old_time,
+                new_time,
+            })
+        })
+        .collect();
+    let slower = regressions.len();
+    regressions.retain(|r| exceeds_thresholds(r.old_time.as_secs_f64(), r.new_time.as_secs_f64()));
+    let below_threshold = slower - regressions.len();
+    regressions.sort_by_key(|r| std::cmp::Reverse(r.new_time - r.old_time));
+
+    let shares = critical_path_shares(new);
+    let criticality: HashMap<*const SpawnExec, f64> = shares
+        .as_ref()
+        .map(|shares| new.iter().map(|s| s as *const SpawnExec).zip(shares.iter().copied()).collect())
+        .unwrap_or_default();
+    // Unlabeled spawns are told apart by their synthetic labels.
+    let mut by_action: HashMap<(String, &str), ImpactfulRegression> = HashMap::new();
+    for pair in &matched {
+        let old_secs = total_time(pair.old).as_secs_f64();
+        let delta = total_time(pair.new).as_secs_f64() - old_secs;
+        let weight = criticality.get(&(pair.new as *const SpawnExec)).copied().unwrap_or(1.0);
+        let action = by_action
+            .entry((display_label(pair.new), &pair.new.mnemonic))
+            .or_insert_with(|| ImpactfulRegression {
+                spawn: pair.new,
+                runs: 0,
+                old: 0.0,
+                delta: 0.0,
+                impact: 0.0,
+            });
+        action.runs += 1;
+        action.old += old_secs;
+        action.delta += delta;
+        action.impact += delta * weight;
+    }
+    let mut impactful_regressions: Vec<ImpactfulRegression> =
+        by_action
+            .into_values()
+            .filter(|action| action.impact > 0.0 && exceeds_thresholds(action.old, action.old + action.delta))
+            .collect();
+    impactful_regressions.sort_by(|a, b| b.impact.total_cmp(&a.impact));
+
+    let mut newly_executed: Vec<NewlyExecuted> = matched
+        .iter()
+        .filter(|pair| pair.old.cache_hit && !pair.new.cache_hit)
+        .map(|pair| NewlyExecuted {
+            spawn: pair.new,
+            reason: "was a cache hit",
+        })
+        .chain(
+            only_new
+                .iter()
+                .filter(|s| !s.cache_hit)
+                .map(|s| NewlyExecuted {
+                    spawn: s,
+                    reason: "not in baseline",
+                }),
+        )
+        .collect();
+    newly_executed.sort_by_key(|n| std::cmp::Reverse(total_time(n.spawn)));
+
+    let mut cache_deltas: BTreeMap<&str, MnemonicCacheDelta> = BTreeMap::new();
+    for spawn in old {
+        let counts = &mut cache_deltas.entry(&spawn.mnemonic).or_default().old;
+        counts.count += 1;
+        counts.cache_hits += u64::from(spawn.cache_hit);
+    }
+    for spawn in new {
+        let counts = &mut cache_deltas.entry(&spawn.mnemonic).or_default().new;
+        counts.count += 1;
+        counts.cache_hits += u64::from(spawn.cache_hit);
+    }
+
+    // Only executed actions are considered; cache hit <-> executed moves are reported as newly executed.
+    let mut transitions: HashMap<(&str, &str), RunnerTransition> = HashMap::new();
+    for pair in matched
+        .iter()
+        .filter(|p| !p.old.cache_hit && !p.new.cache_hit && p.old.runner != p.new.runner)
+    {
+        let transition = transitions
+            .entry((&pair.old.runner, &pair.new.runner))
+            .or_insert_with(|| RunnerTransition {
+                from: &pair.old.runner,
+                to: &pair.new.runner,
+                count: 0,
+                old_time: Duration::ZERO,
+                new_time: Duration::ZERO,
+            });
+        transition.count += 1;
+        transition.old_time += total_time(pair.old);
+        transition.new_time += total_time(pair.new);
+    }
+    let mut runner_transitions: Vec<RunnerTransition> = transitions.into_values().collect();
+    runner_transitions.sort_by_key(|t| (std::cmp::Reverse(t.is_remote_fallback()), std::cmp::Reverse(t.count)));
+
+    let mut output_changes = Vec::new();
+    for pair in &matched {
+        let old_hashes: HashMap<&str, &str> = pair
+            .old
+            .actual_outputs
+            .iter()
+            .filter_map(|f| f.digest.as_ref().map(|d| (f.path.as_str(), d.hash.as_str())))
+            .collect();
+        for file in &pair.new.actual_outputs {
+            if let Some(digest) = file.digest.as_ref()
+                && let Some(old_hash) = old_hashes.get(file.path.as_str())
+                && *old_hash != digest.hash
+            {
+                output_changes.push(OutputChange {
+                    target_label: &pair.new.target_label,
+                    path: &file.path,
+                    old_hash,
+                    new_hash: &digest.hash,
+                });
+            }
+        }
+    }
+
+    let inputs_recorded = |spawns: &[SpawnExec]| spawns.iter().any(|s| !s.inputs.is_empty());
+    let unchanged_reexecutions =
+        (inputs_recorded(old) && inputs_recorded(new)).then(|| unchanged_reexecutions(&matched));
+
+    LogDiff {
+        old_summary: summarize(old),
+        new_summary: summarize(new),
+        loose_matches: matched.iter().filter(|pair| pair.kind != MatchKind::Exact).count(),
+        below_threshold,
+        regressions,
+        impactful_regressions,
+        weighted_by_criticality: shares.is_some(),
+        newly_executed,
+        cache_deltas,
+        runner_transitions,
+        output_changes,
+        unchanged_reexecutions,
+    }
+}
+
+fn hit_rate(hits: u64, count: u64) -> f64 {
+    if count > 0 {
+        hits as f64 / count as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Cache-hit-rate deltas sorted by the largest drop first; mnemonics present in only one log are skipped.
+fn sorted_cache_deltas<'a>(diff: &'a LogDiff) -> Vec<(&'a str, f64, f64)> {
+    let mut deltas: Vec<_> = diff
+        .cache_deltas
+        .iter()
+        .filter(|(_, d)| d.old.count > 0 && d.new.count > 0)
+        .map(|(mnemonic, d)| {
+            (
+                *mnemonic,
+                hit_rate(d.old.cache_hits, d.old.count),
+                hit_rate(d.new.cache_hits, d.new.count),
+            )
+        })
+        .filter(|(_, old_rate, new_rate)| (old_rate - new_rate).abs() >= 0.05)
+        .collect();
+    deltas.sort_by(|(_, a_old, a_new), (_, b_old, b_new)| {
+        (a_new - a_old)
+            .partial_cmp(&(b_new - b_old))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    deltas
+}
+
+fn short_hash(hash: &str) -> &str {
+    hash.get(..12).unwrap_or(hash)
+}
+
+// --- REPORT ---
+
+/// Symbols of the regression waterfall in plain-text output: baseline time, then the added time.
+const WATERFALL_SYMBOLS: &[char] = &['=', '+'];
+
+fn diff_document(diff: &LogDiff, args: &DiffArgs) -> Document {
+    let mut document = Document::new("Bazel Execution Log Diff");
+    document.format_version = args.format_version;
+    document.push_metadata(Column::text("baseline", "Baseline"), args.old.display().to_string());
+    document.push_metadata(Column::text("new", "New"), args.new.display().to_string());
+    if !args.diff_threshold.is_empty() {
+        let thresholds: Vec<String> = args.diff_threshold.iter().map(ToString::to_string).collect();
+        document.push_metadata(Column::text("diff_threshold", "Diff threshold"), thresholds.join(", "));
+    }
+
+    let mut summary = Section::new("overall_summary", "Overall Summary");
+    let mut table = Table::new(vec![
+        Column::text("log", "Log"),
+        Column::integer("total_actions", "Total Actions"),
+        Column::percent("cache_hit_rate", "Cache Hit Rate", 2),
+        Column::seconds("total_time", "Total Time", 2),
+    ]);
+    for (name, log) in [("Baseline", &diff.old_summary), ("New", &diff.new_summary)] {
+        table.push_row(vec![
+            name.into(),
+            log.total_actions.into(),
+            hit_rate(log.cache_hits as u64, log.total_actions as u64).into(),
+            log.total_time.into(),
+        ]);
+    }
+    summary.push_table(table);
+    summary.push_field(
+        Column::seconds("regression_score", "Regression Score", 2),
+        diff.impactful_regressions.iter().fold(0.0, |score, r| score + r.impact),
+    );
+    if diff.loose_matches > 0 {
+        summary.push_text(format!(
+            "Note: {} actions were matched across a changed configuration directory or target label.",
+            diff.loose_matches
+        ));
+    }
+    if diff.below_threshold > 0 {
+        summary.push_text(format!(
+            "Note: {} slower actions changed by no more than --diff-threshold and are left out as noise.",
+            diff.below_threshold
+        ));
+    }
+    document.sections.push(summary);
+
+    // Waterfall: one bar per regression, the baseline time followed by the added time on a shared scale.
+    let mut regressions = Section::new("regressions", format!("Top {} Regressions", args.top_n));
+    if diff.regressions.is_empty() {
+        regressions.push_text("No matched actions got slower.");
+    } else {
+        let scale = diff
+            .regressions
+            .iter()
+            .take(args.top_n)
+            .map(|r| r.new_time.as_secs_f64())
+            .fold(0.0, f64::max)
+            .max(f64::EPSILON);
+        let mut table = Table::new(vec![
+            Column::seconds("baseline_time", "Baseline", 3),
+            Column::seconds("new_time", "New", 3),
+            Column::seconds("delta", "Delta", 3).signed(),
+            Column::text("mnemonic", "Mnemonic"),
+            Column::text("target", "Target"),
+            Column::bar("waterfall", "Waterfall", 30, WATERFALL_SYMBOLS),
+        ]);
+        for r in diff.regressions.iter().take(args.top_n) {
+            let old_secs = r.old_time.as_secs_f64();
+            let new_secs = r.new_time.as_secs_f64();
+            table.push_row(vec![
+                old_secs.into(),
+                new_secs.into(),
+                (new_secs - old_secs).into(),
+                (&r.spawn.mnemonic).into(),
+                display_label(r.spawn).into(),
+                Value::Bar(vec![old_secs / scale, (new_secs - old_secs) / scale]),
+            ]);
+        }
+        regressions.push_table(table);
+    }
+    document.sections.push(regressions);
+    document.sections.push(impactful_regressions_section(diff, args.top_n));
+
+    let mut newly_executed = Section::new(
+        "newly_executed",
+        format!("Newly Executed Actions ({} total)", diff.newly_executed.len()),
+    );
+    if diff.newly_executed.is_empty() {
+        newly_executed.push_text("No actions were newly executed.");
+    } else {
+        let mut table = Table::new(vec![
+            Column::seconds("time", "Time", 3),
+            Column::text("reason", "Reason"),
+            Column::text("mnemonic", "Mnemonic"),
+            Column::text("target", "Target"),
+        ]);
+        for n in diff.newly_executed.iter().take(args.top_n) {
+            table.push_row(vec![
+                total_time(n.spawn).into(),
+                n.reason.into(),
+                (&n.spawn.mnemonic).into(),
+                display_label(n.spawn).into(),
+            ]);
+        }
+        newly_executed.push_table(table);
+    }
+    document.sections.push(newly_executed);
+
+    let mut cache = Section::new("cache_hit_rate_changes", "Cache Hit Rate Changes by Mnemonic");
+    let deltas = sorted_cache_deltas(diff);
+    if deltas.is_empty() {
+        cache.push_text("Cache hit rates are unchanged for all mnemonics present in both logs.");
+    } else {
+        let mut table = Table::new(vec![
+            Column::text("mnemonic", "Mnemonic"),
+            Column::percent("baseline", "Baseline", 1),
+            Column::percent("new", "New", 1),
+            Column::percent("delta", "Delta", 1).signed(),
+        ]);
+        for (mnemonic, old_rate, new_rate) in deltas {
+            table.push_row(vec![
+                mnemonic.into(),
+                old_rate.into(),
+                new_rate.into(),
+                (new_rate - old_rate).into(),
+            ]);
+        }
+        cache.push_table(table);
+    }
+    document.sections.push(cache);
+
+    let mut runners = Section::new("runner_transitions", "Runner Transitions (Executed Actions)");
+    if diff.runner_transitions.is_empty() {
+        runners.push_text("No executed actions changed runners.");
+    } else {
+        let mut table = Table::new(vec![
+            Column::text("baseline_runner", "Baseline"),
+            Column::text("new_runner", "New"),
+            Column::integer("actions", "Actions"),
+            Column::seconds("old_time", "Old Time", 2),
+            Column::seconds("new_time", "New Time", 2),
+            Column::text("note", "Note"),
+        ]);
+        for t in &diff.runner_transitions {
+            table.push_row(vec![
+                t.from.into(),
+                t.to.into(),
+                t.count.into(),
+                t.old_time.into(),
+                t.new_time.into(),
+                t.is_remote_fallback().then_some("remote to local fallback").into(),
+            ]);
+        }
+        runners.push_table(table);
+    }
+    document.sections.push(runners);
+
+    let mut outputs = Section::new(
+        "output_changes",
+        format!("Changed Output Digests ({} total)", diff.output_changes.len()),
+    );
+    if diff.output_changes.is_empty() {
+        outputs.push_text("No output digests changed between the two logs.");
+    } else {
+        let mut table = Table::new(vec![
+            Column::text("baseline_digest", "Baseline Digest"),
+            Column::text("new_digest", "New Digest"),
+            Column::text("target", "Target"),
+            Column::text("output", "Output"),
+        ]);
+        for change in diff.output_changes.iter().take(args.top_n) {
+            table.push_row(vec![
+                short_hash(change.old_hash).into(),
+                short_hash(change.new_hash).into(),
+                change.target_label.into(),
+                change.path.into(),
+            ]);
+        }
+        outputs.push_table(table);
+    }
+    document.sections.push(outputs);
+
+    let mut unchanged = Section::new("unchanged_reexecutions", "Re-executed Despite Unchanged Inputs");
+    match diff.unchanged_reexecutions.as_deref() {
+        None => unchanged.push_text("Inputs were not recorded in both logs, so they cannot be compared."),
+        Some([]) => unchanged.push_text("No action re-executed with the same inputs and command line."),
+        Some(reexecutions) => {
+            unchanged.push_field(Column::integer("actions", "Actions"), reexecutions.len());
+            unchanged.push_field(
+                Column::seconds("total_time", "Total Time", 2),
+                reexecutions.iter().map(|r| total_time(r.spawn)).sum::<Duration>(),
+            );
+            let mut table = Table::new(vec![
+                Column::seconds("time", "Time", 3),
+                Column::text("hint", "Likely Cause"),
+                Column::text("mnemonic", "Mnemonic"),
+                Column::text("target", "Target"),
+            ]);
+            for r in reexecutions.iter().take(args.top_n) {
+                table.push_row(vec![
+                    total_time(r.spawn).into(),
+                    r.hint.into(),
+                    (&r.spawn.mnemonic).into(),
+                    display_label(r.spawn).into(),
+                ]);
+            }
+            unchanged.push_table(table);
+            unchanged.push_text("Note: For an incremental build compared with the build before it, these actions should have been skipped. Stamping, volatile inputs missing from the log or a changed action environment are the usual causes.");
+        }
+    }
+    document.sections.push(unchanged);
+
+    document
+}
+
+/// Regressions ranked by their weighted impact, so a 2x slowdown of a 5ms action does not outrank
+/// a 10% slowdown of a long action on the critical path.
+fn impactful_regressions_section(diff: &LogDiff, top_n: usize) -> Section {
+    let mut section = Section::new("impactful_regressions", format!("Top {} Most Impactful Regressions", top_n));
+    if diff.weighted_by_criticality {
+        section.push_text("Note: Impact is an action's change in time summed over its runs, each weighted by its share of the new build's critical path (100% on it). The regression score in the summary adds up the impacts.");
+    } else {
+        section.push_text("Note: Impact is an action's change in time summed over its runs. The new log records no inputs to find the critical path with, so runs are not weighted by criticality.");
+    }
+    if diff.impactful_regressions.is_empty() {
+        section.push_text("No action got slower overall.");
+        return section;
+    }
+    let mut table = Table::new(vec![
+        Column::seconds("impact", "Impact", 3).signed(),
+        Column::seconds("delta", "Delta", 3).signed(),
+        Column::integer("runs", "Runs"),
+        Column::percent("criticality", "Criticality", 0),
+        Column::text("mnemonic", "Mnemonic"),
+        Column::text("target", "Target"),
+    ]);
+    for r in diff.impactful_regressions.iter().take(top_n) {
+        table.push_row(vec![
+            r.impact.into(),
+            r.delta.into(),
+            r.runs.into(),
+            (r.delta > 0.0).then(|| r.impact / r.delta * 100.0).into(),
+            (&r.spawn.mnemonic).into(),
+            display_label(r.spawn).into(),
+        ]);
+    }
+    section.push_table(table);
+    section
+}
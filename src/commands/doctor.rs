@@ -0,0 +1,339 @@
+//! `doctor`: checks that a log is readable and holds what the reports need, and explains how to
+//! fix the build flags when it does not.
+
+use crate::cli::DoctorArgs;
+use crate::commands::analyze::percentage;
+use crate::parsers::{
+    compact_contents, parse_compact_bytes, parse_verbose_bytes, CompactContents, LogFormat, OtherOutput, ParseLimits,
+    ParsedLog,
+};
+use crate::proto::SpawnExec;
+use crate::render::{renderer_for, ByteUnit, Column, Document, Section, Table};
+use crate::AppResult;
+use std::fs;
+use std::io;
+
+/// Share of spawns below which a field counts as missing from the log.
+const MOSTLY_MISSING_PERCENT: f64 = 10.0;
+
+/// A field of `SpawnExec`, how to tell whether a spawn has it and what needs it.
+struct FieldCheck {
+    name: &'static str,
+    present: fn(&SpawnExec) -> bool,
+    used_by: &'static str,
+}
+
+const FIELDS: &[FieldCheck] = &[
+    FieldCheck {
+        name: "target_label",
+        present: |s| !s.target_label.is_empty(),
+        used_by: "per-target and per-package reports",
+    },
+    FieldCheck {
+        name: "mnemonic",
+        present: |s| !s.mnemonic.is_empty(),
+        used_by: "per-mnemonic reports",
+    },
+    FieldCheck {
+        name: "runner",
+        present: |s| !s.runner.is_empty(),
+        used_by: "strategy and cache reports",
+    },
+    FieldCheck {
+        name: "digest",
+        present: |s| s.digest.is_some(),
+        used_by: "cache analysis, digest map, diff",
+    },
+    FieldCheck {
+        name: "metrics",
+        present: |s| s.metrics.is_some(),
+        used_by: "all timing reports",
+    },
+    FieldCheck {
+        name: "metrics.total_time",
+        present: |s| {
+            let total_time = s.metrics.as_ref().and_then(|m| m.total_time.as_ref());
+            total_time.is_some_and(|d| d.seconds > 0 || d.nanos > 0)
+        },
+        used_by: "all timing reports",
+    },
+    FieldCheck {
+        name: "metrics.start_time",
+        present: |s| s.metrics.as_ref().is_some_and(|m| m.start_time.is_some()),
+        used_by: "critical path, concurrency, timelines",
+    },
+    FieldCheck {
+        name: "metrics.input_bytes",
+        present: |s| s.metrics.as_ref().is_some_and(|m| m.input_bytes > 0),
+        used_by: "input size reports",
+    },
+    FieldCheck {
+        name: "metrics.memory_estimate_bytes",
+        present: |s| s.metrics.as_ref().is_some_and(|m| m.memory_estimate_bytes > 0),
+        used_by: "memory analysis",
+    },
+    FieldCheck {
+        name: "command_args",
+        present: |s| !s.command_args.is_empty(),
+        used_by: "show, hermeticity checks",
+    },
+    FieldCheck {
+        name: "environment_variables",
+        present: |s| !s.environment_variables.is_empty(),
+        used_by: "hermeticity checks, diff",
+    },
+    FieldCheck {
+        name: "platform",
+        present: |s| s.platform.as_ref().is_some_and(|p| !p.properties.is_empty()),
+        used_by: "platform reports",
+    },
+    FieldCheck {
+        name: "inputs",
+        present: |s| !s.inputs.is_empty(),
+        used_by: "input analysis, diff",
+    },
+    FieldCheck {
+        name: "actual_outputs",
+        present: |s| !s.actual_outputs.is_empty(),
+        used_by: "output size reports, spawn IDs",
+    },
+    FieldCheck {
+        name: "timeout_millis",
+        present: |s| s.timeout_millis > 0,
+        used_by: "timeout reports",
+    },
+];
+
+pub fn run_doctor(args: DoctorArgs) -> AppResult<()> {
+    let bytes = fs::read(&args.file)?;
+    let format = LogFormat::detect(&bytes);
+
+    let mut document = Document::new("Log Doctor");
+    document.push_metadata(Column::text("log_file", "Log file"), args.file.display().to_string());
+
+    let mut log = Section::new("log", "Log");
+    log.push_field(Column::text("format", "Format"), format.to_string());
+    log.push_field(Column::bytes("file_size", "File size", ByteUnit::Megabytes, 2), bytes.len());
+    let parsed = match format {
+        LogFormat::Compact => parse_compact_bytes(&bytes, &ParseLimits::default())?,
+        LogFormat::Verbose => parse_verbose_bytes(&bytes, &ParseLimits::default())?,
+    };
+    if format == LogFormat::Compact {
+        let contents = compact_contents(&bytes);
+        log.push_field(
+            Column::bytes("decompressed_size", "Decompressed size", ByteUnit::Megabytes, 2),
+            contents.decompressed_bytes,
+        );
+        log.push_field(
+            Column::integer("entries", "Entries"),
+            contents.entry_counts.iter().map(|(_, count)| count).sum::<u64>(),
+        );
+        log.push_field(Column::integer("spawns", "Spawns"), parsed.spawns.len());
+        let mut table = Table::new(vec![Column::text("entry_type", "Entry type"), Column::integer("count", "Count")]);
+        for (name, count) in &contents.entry_counts {
+            table.push_row(vec![(*name).into(), (*count).into()]);
+        }
+        log.push_table(table);
+        document.sections.push(log);
+        document.sections.push(version_section(format, &parsed, Some(&contents)));
+    } else {
+        log.push_field(Column::integer("spawns", "Spawns"), parsed.spawns.len());
+        document.sections.push(log);
+        document.sections.push(version_section(format, &parsed, None));
+    }
+
+    // A file that is not an execution log at all makes the rest of the checks meaningless.
+    if parsed.error.is_some()
+        && let Some(output) = OtherOutput::detect(&bytes)
+    {
+        let mut checks = Section::new("checks", "Checks");
+        checks.push_text(format!("Warning: this file is {}, not an execution log.", output));
+        checks.push_text(format!("Hint: {}", output.hint()));
+        document.sections.truncate(1);
+        document.sections.push(checks);
+    } else {
+        document.sections.push(completeness_section(&parsed.spawns));
+        document.sections.push(checks_section(format, &parsed));
+        document.sections.push(parsed.warnings.section());
+    }
+    renderer_for(args.output).render(&document, &mut io::stdout().lock())?;
+    Ok(())
+}
+
+/// Share of `spawns` that have the field.
+fn coverage(spawns: &[SpawnExec], field: &FieldCheck) -> (usize, f64) {
+    let count = spawns.iter().filter(|s| (field.present)(s)).count();
+    (count, percentage(count as f64, spawns.len() as f64))
+}
+
+fn field(name: &str) -> &'static FieldCheck {
+    FIELDS.iter().find(|f| f.name == name).expect("a known field")
+}
+
+fn completeness_section(spawns: &[SpawnExec]) -> Section {
+    let mut section = Section::new("field_completeness", "Field Completeness");
+    if spawns.is_empty() {
+        section.push_text("No spawns to check.");
+        return section;
+    }
+    let mut table = Table::new(vec![
+        Column::text("field", "Field"),
+        Column::integer("spawns", "Spawns"),
+        Column::percent("share", "Share", 1),
+        Column::text("used_by", "Used by"),
+    ]);
+    for field in FIELDS {
+        let (count, share) = coverage(spawns, field);
+        table.push_row(vec![field.name.into(), count.into(), share.into(), field.used_by.into()]);
+    }
+    section.push_table(table);
+    section
+}
+
+/// What the log reveals about the Bazel release and flags that wrote it. Neither format records
+/// the Bazel version, so these are inferences.
+fn version_section(format: LogFormat, parsed: &ParsedLog, contents: Option<&CompactContents>) -> Section {
+    let mut section = Section::new("bazel_version", "Bazel Version Hints");
+    match format {
+        LogFormat::Compact => {
+            section.push_text("The compact format was added in Bazel 7.1, so the log comes from 7.1 or newer.");
+        }
+        LogFormat::Verbose => section.push_text(
+            "The verbose format is written by every Bazel release; --execution_log_compact_file (Bazel 7.1 and newer) \
+             writes smaller logs faster.",
+        ),
+    }
+    if let Some(contents) = contents {
+        if contents.entry_counts.iter().any(|(name, _)| *name == "RunfilesTree") {
+            section.push_text("Runfiles trees are recorded as entries of their own, as Bazel 8 and newer do.");
+        }
+        if let Some(invocation) = &contents.invocation {
+            if invocation.workspace_runfiles_directory == "_main" {
+                section.push_text("The main repository is named `_main`, so Bzlmod is enabled.");
+            }
+            if invocation.sibling_repository_layout {
+                section.push_text("--experimental_sibling_repository_layout is enabled.");
+            }
+            if !invocation.hash_function_name.is_empty() {
+                section.push_text(format!("Digests use {} (--digest_function).", invocation.hash_function_name));
+            }
+        }
+    }
+    if let Some(hash_function) = parsed
+        .spawns
+        .iter()
+        .find_map(|s| s.digest.as_ref().map(|d| d.hash_function_name.as_str()).filter(|name| !name.is_empty()))
+        && contents.is_none_or(|c| c.invocation.as_ref().is_none_or(|i| i.hash_function_name.is_empty()))
+    {
+        section.push_text(format!("Digests use {} (--digest_function).", hash_function));
+    }
+    if !parsed.spawns.is_empty() && parsed.spawns.iter().all(|s| s.target_label.is_empty()) {
+        section.push_text("No spawn has a target label; Bazel releases before 6.0 did not record it.");
+    }
+    section
+}
+
+fn checks_section(format: LogFormat, parsed: &ParsedLog) -> Section {
+    let mut section = Section::new("checks", "Checks");
+    let mut table = Table::new(vec![
+        Column::text("status", "Status"),
+        Column::text("check", "Check"),
+        Column::text("detail", "Detail"),
+    ]);
+    let mut push = |ok: bool, check: &str, detail: String| {
+        table.push_row(vec![if ok { "ok" } else { "warning" }.into(), check.into(), detail.into()]);
+    };
+    let spawns = &parsed.spawns;
+
+    match &parsed.error {
+        None => push(true, "readable", format!("The {} log was read to the end.", format)),
+        Some(error) => push(
+            false,
+            "readable",
+            format!("{}; the checks cover the spawns before it.", error),
+        ),
+    }
+
+    push(
+        !spawns.is_empty(),
+        "spawns",
+        if spawns.is_empty() {
+            "The log holds no spawns. Actions that hit Bazel's action cache are not run and not logged; build after \
+             `bazel clean` or in a new output base to record every action."
+                .to_string()
+        } else {
+            format!("{} spawns.", spawns.len())
+        },
+    );
+    if !spawns.is_empty() {
+        field_checks(parsed, &mut push);
+    }
+
+    section.push_table(table);
+    if let Some(error) = &parsed.error {
+        section.push_text(format!("Hint: {}", error.hint(format)));
+    }
+    section
+}
+
+/// Checks of the fields the reports rely on most.
+fn field_checks(parsed: &ParsedLog, push: &mut impl FnMut(bool, &str, String)) {
+    let spawns = &parsed.spawns;
+    let (_, timed) = coverage(spawns, field("metrics.total_time"));
+    push(
+        timed >= MOSTLY_MISSING_PERCENT,
+        "timings",
+        if timed < MOSTLY_MISSING_PERCENT {
+            format!(
+                "Only {:.1}% of spawns have a total time, so timing reports show zeros. Build with \
+                 --execution_log_spawn_metrics to record them.",
+                timed
+            )
+        } else {
+            format!("{:.1}% of spawns have a total time.", timed)
+        },
+    );
+
+    let (_, started) = coverage(spawns, field("metrics.start_time"));
+    push(
+        started >= MOSTLY_MISSING_PERCENT,
+        "start_times",
+        if started < MOSTLY_MISSING_PERCENT {
+            "Spawns have no start times, so the critical path, concurrency and timeline reports are empty. \
+             Bazel records them with --execution_log_spawn_metrics."
+                .to_string()
+        } else {
+            format!("{:.1}% of spawns have a start time.", started)
+        },
+    );
+
+    let (_, digested) = coverage(spawns, field("digest"));
+    push(
+        digested > 0.0,
+        "action_digests",
+        if digested == 0.0 {
+            "No spawn has an action digest; Bazel records them only with a remote or disk cache (--remote_cache, \
+             --disk_cache), which the cache reports and the digest map need."
+                .to_string()
+        } else {
+            format!("{:.1}% of spawns have an action digest.", digested)
+        },
+    );
+
+    let (labeled_count, labeled) = coverage(spawns, field("target_label"));
+    push(
+        labeled >= MOSTLY_MISSING_PERCENT,
+        "target_labels",
+        format!("{} of {} spawns have a target label.", labeled_count, spawns.len()),
+    );
+
+    push(
+        parsed.warnings.is_empty(),
+        "data_quality",
+        if parsed.warnings.is_empty() {
+            "No data quality issues found.".to_string()
+        } else {
+            "The log has data quality issues, listed under Data Quality Warnings.".to_string()
+        },
+    );
+}
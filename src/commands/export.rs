@@ -0,0 +1,265 @@
+use crate::cli::{DigestMapArgs, DigestMapFormat, ExportArgs, ExportFormat, MsgpackArgs, NdjsonArgs, ResourcesArgs};
+use crate::commands::analyze::{is_local_runner, load_spans, percentile, total_time};
+use crate::parsers::parse_log_file;
+use crate::proto::SpawnExec;
+use crate::rows::{spawn_id, SpawnRow};
+use crate::writer::write_spawns_msgpack;
+use crate::AppResult;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+pub fn run_export(args: ExportArgs) -> AppResult<()> {
+    match args.format {
+        ExportFormat::Ndjson(args) => export_ndjson(args),
+        ExportFormat::Resources(args) => export_resources(args),
+        ExportFormat::DigestMap(args) => export_digest_map(args),
+        ExportFormat::Msgpack(args) => export_msgpack(args),
+        #[cfg(feature = "xlsx")]
+        ExportFormat::Xlsx(args) => export_xlsx(args),
+    }
+}
+
+/// Opens the `--out` file, or stdout when none is given.
+pub(crate) fn open_output(path: Option<&std::path::Path>) -> AppResult<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    })
+}
+
+fn export_ndjson(args: NdjsonArgs) -> AppResult<()> {
+    let spawns = parse_log_file(&args.file)?;
+
+    if let Some(schema_path) = args.schema.as_deref() {
+        let mut schema = BufWriter::new(File::create(schema_path)?);
+        serde_json::to_writer_pretty(&mut schema, &SpawnRow::bigquery_schema()).map_err(io::Error::from)?;
+        writeln!(schema)?;
+        schema.flush()?;
+        eprintln!("Wrote BigQuery schema to {}.", schema_path.display());
+    }
+
+    let mut out = open_output(args.out.as_deref())?;
+    for spawn in &spawns {
+        serde_json::to_writer(&mut out, &SpawnRow::from_spawn(spawn)).map_err(io::Error::from)?;
+        writeln!(out)?;
+    }
+    out.flush()?;
+    eprintln!("Exported {} spawn rows.", spawns.len());
+    Ok(())
+}
+
+/// Headroom applied to observed memory before it is suggested as an estimate.
+const RESOURCE_HEADROOM: f64 = 1.25;
+
+const MEBIBYTE: f64 = 1024.0 * 1024.0;
+
+/// Observed resources of one mnemonic's executed spawns.
+#[derive(Serialize)]
+struct MnemonicResources {
+    mnemonic: String,
+    executed: usize,
+    executed_locally: usize,
+    /// Percentiles of Bazel's memory estimate, over spawns that have one.
+    memory_p50_mb: Option<f64>,
+    memory_p90_mb: Option<f64>,
+    memory_max_mb: Option<f64>,
+    duration_p50_seconds: Option<f64>,
+    duration_p90_seconds: Option<f64>,
+    duration_max_seconds: Option<f64>,
+    /// The p90 memory estimate with headroom, in the megabytes a rule's `resource_set` returns.
+    suggested_resource_set_memory_mb: Option<u64>,
+}
+
+/// Peak load of locally executed spawns running at the same time.
+#[derive(Serialize)]
+struct LocalPeak {
+    concurrent_actions: i64,
+    concurrent_memory_mb: f64,
+    /// A `--local_resources` value that fits the observed peak memory with headroom.
+    suggested_local_resources: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ResourceEstimates {
+    log_file: String,
+    local_peak: LocalPeak,
+    mnemonics: Vec<MnemonicResources>,
+}
+
+fn megabytes(bytes: f64) -> f64 {
+    (bytes / MEBIBYTE * 10.0).round() / 10.0
+}
+
+fn milliseconds_precision(seconds: f64) -> f64 {
+    (seconds * 1000.0).round() / 1000.0
+}
+
+fn mnemonic_resources(mnemonic: &str, spawns: &[&SpawnExec]) -> MnemonicResources {
+    let mut memory: Vec<f64> = spawns
+        .iter()
+        .filter_map(|s| s.metrics.as_ref())
+        .map(|m| m.memory_estimate_bytes as f64)
+        .filter(|&bytes| bytes > 0.0)
+        .collect();
+    memory.sort_by(f64::total_cmp);
+    let mut durations: Vec<f64> = spawns
+        .iter()
+        .map(|s| milliseconds_precision(total_time(s).as_secs_f64()))
+        .collect();
+    durations.sort_by(f64::total_cmp);
+
+    let memory_p90 = percentile(&memory, 90.0);
+    MnemonicResources {
+        mnemonic: mnemonic.to_string(),
+        executed: spawns.len(),
+        executed_locally: spawns.iter().filter(|s| is_local_runner(&s.runner)).count(),
+        memory_p50_mb: percentile(&memory, 50.0).map(megabytes),
+        memory_p90_mb: memory_p90.map(megabytes),
+        memory_max_mb: percentile(&memory, 100.0).map(megabytes),
+        duration_p50_seconds: percentile(&durations, 50.0),
+        duration_p90_seconds: percentile(&durations, 90.0),
+        duration_max_seconds: percentile(&durations, 100.0),
+        suggested_resource_set_memory_mb: memory_p90.map(|bytes| (bytes * RESOURCE_HEADROOM / MEBIBYTE).ceil() as u64),
+    }
+}
+
+/// Writes per-mnemonic memory and duration estimates of executed spawns as JSON, for tuning
+/// `resource_set` in rules and `--local_resources`.
+fn export_resources(args: ResourcesArgs) -> AppResult<()> {
+    let spawns = parse_log_file(&args.file)?;
+
+    let mut by_mnemonic: BTreeMap<&str, Vec<&SpawnExec>> = BTreeMap::new();
+    for spawn in spawns.iter().filter(|s| !s.cache_hit) {
+        by_mnemonic.entry(&spawn.mnemonic).or_default().push(spawn);
+    }
+    let mnemonics: Vec<MnemonicResources> = by_mnemonic
+        .iter()
+        .map(|(mnemonic, spawns)| mnemonic_resources(mnemonic, spawns))
+        .collect();
+
+    let local: Vec<&SpawnExec> = spawns
+        .iter()
+        .filter(|s| !s.cache_hit && is_local_runner(&s.runner))
+        .collect();
+    let peak = |weight: fn(&SpawnExec) -> i64| load_spans(&local, weight).into_iter().map(|(load, _)| load).max();
+    let peak_memory = peak(|s| s.metrics.as_ref().map_or(0, |m| m.memory_estimate_bytes)).unwrap_or(0) as f64;
+    let estimates = ResourceEstimates {
+        log_file: args.file.display().to_string(),
+        local_peak: LocalPeak {
+            concurrent_actions: peak(|_| 1).unwrap_or(0),
+            concurrent_memory_mb: megabytes(peak_memory),
+            suggested_local_resources: (peak_memory > 0.0)
+                .then(|| format!("memory={}", (peak_memory * RESOURCE_HEADROOM / MEBIBYTE).ceil())),
+        },
+        mnemonics,
+    };
+
+    let mut out = open_output(args.out.as_deref())?;
+    serde_json::to_writer_pretty(&mut out, &estimates).map_err(io::Error::from)?;
+    writeln!(out)?;
+    out.flush()?;
+    eprintln!("Exported resource estimates for {} mnemonics.", estimates.mnemonics.len());
+    Ok(())
+}
+
+/// One output of an action, keyed by the action's cache digest.
+#[derive(Serialize)]
+struct DigestMapRow<'a> {
+    spawn_id: String,
+    action_digest: &'a str,
+    mnemonic: &'a str,
+    target_label: &'a str,
+    cache_hit: bool,
+    output_path: &'a str,
+    output_digest: &'a str,
+    output_size_bytes: i64,
+}
+
+/// Writes one row per output file of every spawn that has an action digest, so remote cache
+/// administrators can join the action cache entries against CAS access logs.
+fn export_digest_map(args: DigestMapArgs) -> AppResult<()> {
+    let spawns = parse_log_file(&args.file)?;
+    let mut rows = Vec::new();
+    let mut without_digest = 0;
+    for spawn in &spawns {
+        let Some(action_digest) = spawn.digest.as_ref().filter(|d| !d.hash.is_empty()) else {
+            without_digest += 1;
+            continue;
+        };
+        let id = spawn_id(spawn);
+        // Directories and outputs that were not produced have no digest to join on.
+        for (output, digest) in spawn
+            .actual_outputs
+            .iter()
+            .filter_map(|f| f.digest.as_ref().filter(|d| !d.hash.is_empty()).map(|d| (f, d)))
+        {
+            rows.push(DigestMapRow {
+                spawn_id: id.clone(),
+                action_digest: &action_digest.hash,
+                mnemonic: &spawn.mnemonic,
+                target_label: &spawn.target_label,
+                cache_hit: spawn.cache_hit,
+                output_path: &output.path,
+                output_digest: &digest.hash,
+                output_size_bytes: digest.size_bytes,
+            });
+        }
+    }
+
+    let mut out = open_output(args.out.as_deref())?;
+    match args.format {
+        DigestMapFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut out);
+            for row in &rows {
+                writer.serialize(row).map_err(io::Error::from)?;
+            }
+            writer.flush()?;
+        }
+        DigestMapFormat::Ndjson => {
+            for row in &rows {
+                serde_json::to_writer(&mut out, row).map_err(io::Error::from)?;
+                writeln!(out)?;
+            }
+        }
+    }
+    out.flush()?;
+    if without_digest > 0 {
+        eprintln!(
+            "Skipped {} spawns without an action digest (Bazel records one only for cacheable spawns).",
+            without_digest
+        );
+    }
+    eprintln!("Exported {} output digests.", rows.len());
+    Ok(())
+}
+
+fn export_msgpack(args: MsgpackArgs) -> AppResult<()> {
+    let spawns = parse_log_file(&args.file)?;
+    write_spawns_msgpack(&spawns, open_output(args.out.as_deref())?)?;
+    eprintln!("Exported {} spawns.", spawns.len());
+    Ok(())
+}
+
+#[cfg(feature = "xlsx")]
+fn export_xlsx(args: crate::cli::XlsxArgs) -> AppResult<()> {
+    use crate::commands::analyze::{selected_reports, skip_untimed_reports};
+    use crate::correlate::SideInputs;
+    use crate::render::{write_workbook, Sheet};
+    use crate::warnings::Warnings;
+
+    let selected = selected_reports(&args.reports)?;
+    let inputs = SideInputs::load(&args.reports)?;
+    let spawns = parse_log_file(&args.file)?;
+    let sheets: Vec<Sheet> = skip_untimed_reports(selected, &spawns, &mut Warnings::new())
+        .into_iter()
+        .map(|report| Sheet {
+            name: report.name,
+            sections: report.sections(&spawns, &args.reports, &inputs),
+        })
+        .collect();
+    write_workbook(&sheets, &args.out)?;
+    eprintln!("Wrote {} report sheets to {}.", sheets.len(), args.out.display());
+    Ok(())
+}
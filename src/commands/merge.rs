@@ -0,0 +1,199 @@
+//! `merge`: joins several logs into one, typically the attempts of a CI job that was retried.
+//! Retries run actions again: spawns with the same action digest in several logs are attempts of
+//! one action, and `--dedup` keeps one of them. Either way, the summary reports the work the
+//! repeated executions took.
+//!
+//! Compact logs merged into a compact log are joined entry by entry with [`EntryCompactor`], so
+//! symlink actions, runfiles trees and directory outputs, which spawns cannot carry, are kept.
+
+use crate::cli::{DedupPolicy, LogFormatArg, MergeArgs};
+use crate::commands::analyze::{start_seconds, total_time};
+use crate::parsers::{parse_log_file, read_compact_entries, LogFormat, ParseLimits};
+use crate::proto::exec_log_entry::Type as CompactEntryType;
+use crate::proto::SpawnExec;
+use crate::render::{renderer_for, Column, Document, Section, Table};
+use crate::writer::{write_spawns, EntryCompactor};
+use crate::AppResult;
+use prost::Message;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read};
+use std::time::Duration;
+
+/// One spawn of one of the merged logs.
+struct Attempt {
+    log: usize,
+    spawn: SpawnExec,
+}
+
+/// Orders attempts by start time when both have one, and by the order of the logs and of the
+/// spawns in them otherwise (`a` and `b` are indices into `attempts`, which keep that order).
+fn start_order(attempts: &[Attempt], a: usize, b: usize) -> Ordering {
+    match (start_seconds(&attempts[a].spawn), start_seconds(&attempts[b].spawn)) {
+        (Some(x), Some(y)) if x != y => x.total_cmp(&y),
+        _ => (attempts[a].log, a).cmp(&(attempts[b].log, b)),
+    }
+}
+
+/// The attempt `policy` keeps out of `group`.
+fn chosen(attempts: &[Attempt], group: &[usize], policy: DedupPolicy) -> usize {
+    let by_start = |&&a: &&usize, &&b: &&usize| start_order(attempts, a, b);
+    let chosen = match policy {
+        DedupPolicy::First => group.iter().min_by(by_start),
+        DedupPolicy::Last => group.iter().max_by(by_start),
+        DedupPolicy::Fastest => group.iter().min_by(|a, b| {
+            let time = |&&index: &&usize| total_time(&attempts[index].spawn);
+            time(a).cmp(&time(b)).then(by_start(a, b))
+        }),
+    };
+    *chosen.expect("groups are not empty")
+}
+
+/// Executions of actions beyond their first, per mnemonic.
+#[derive(Default)]
+struct Repeats {
+    actions: usize,
+    executions: usize,
+    time: Duration,
+}
+
+/// The format of the log at `path`, from its first bytes.
+fn format_of(path: &std::path::Path) -> AppResult<LogFormat> {
+    let mut magic = [0; 4];
+    let read = File::open(path)?.read(&mut magic)?;
+    Ok(LogFormat::detect(&magic[..read]))
+}
+
+pub fn run_merge(args: MergeArgs) -> AppResult<()> {
+    let formats = args.logs.iter().map(|path| format_of(path)).collect::<AppResult<Vec<_>>>()?;
+    let to = match args.to {
+        Some(LogFormatArg::Verbose) => LogFormat::Verbose,
+        Some(LogFormatArg::Compact) => LogFormat::Compact,
+        None => formats[0],
+    };
+    let entry_by_entry = to == LogFormat::Compact && formats.iter().all(|&format| format == to);
+    let mut attempts = Vec::new();
+    // Where each log's attempts start in `attempts`.
+    let mut first_attempts = Vec::new();
+    for (log, path) in args.logs.iter().enumerate() {
+        first_attempts.push(attempts.len());
+        attempts.extend(parse_log_file(path)?.into_iter().map(|spawn| Attempt { log, spawn }));
+    }
+
+    // Spawns without a digest (Bazel records one only for cacheable spawns) cannot be matched.
+    let mut groups: HashMap<(&str, i64), Vec<usize>> = HashMap::new();
+    let mut without_digest = 0;
+    for (index, attempt) in attempts.iter().enumerate() {
+        match &attempt.spawn.digest {
+            Some(digest) if !digest.hash.is_empty() => {
+                groups.entry((&digest.hash, digest.size_bytes)).or_default().push(index)
+            }
+            _ => without_digest += 1,
+        }
+    }
+
+    let mut dropped = vec![false; attempts.len()];
+    let mut repeats: HashMap<&str, Repeats> = HashMap::new();
+    for group in groups.values().filter(|group| group.len() > 1) {
+        if let Some(policy) = args.dedup {
+            let kept = chosen(&attempts, group, policy);
+            for &index in group.iter().filter(|&&index| index != kept) {
+                dropped[index] = true;
+            }
+        }
+        let mut executed: Vec<usize> = group.iter().copied().filter(|&index| !attempts[index].spawn.cache_hit).collect();
+        executed.sort_by(|&a, &b| start_order(&attempts, a, b));
+        if executed.len() > 1 {
+            let spawn = &attempts[executed[0]].spawn;
+            let entry = repeats.entry(spawn.mnemonic.as_str()).or_default();
+            entry.actions += 1;
+            entry.executions += executed.len() - 1;
+            entry.time += executed[1..].iter().map(|&index| total_time(&attempts[index].spawn)).sum::<Duration>();
+        }
+    }
+    let dropped_count = dropped.iter().filter(|&&dropped| dropped).count();
+
+    let written = if entry_by_entry {
+        let mut compactor = EntryCompactor::new();
+        // With --dedup, a symlink action repeated by several logs is kept once.
+        let mut symlinks = HashSet::new();
+        for (log, path) in args.logs.iter().enumerate() {
+            let entries = read_compact_entries(&fs::read(path)?, &ParseLimits::default())?;
+            // Spawn entries reconstruct into the log's attempts one by one, in order.
+            let mut next_attempt = first_attempts[log];
+            compactor.add_log(&entries, |entry| match &entry.r#type {
+                Some(CompactEntryType::Spawn(_)) => {
+                    next_attempt += 1;
+                    !dropped[next_attempt - 1]
+                }
+                Some(CompactEntryType::SymlinkAction(action)) => args.dedup.is_none() || symlinks.insert(action.encode_to_vec()),
+                _ => true,
+            });
+        }
+        let written = compactor.spawns();
+        compactor.finish(BufWriter::new(File::create(&args.out)?))?;
+        written
+    } else {
+        let merged: Vec<SpawnExec> = attempts
+            .iter()
+            .zip(&dropped)
+            .filter(|(_, dropped)| !**dropped)
+            .map(|(attempt, _)| attempt.spawn.clone())
+            .collect();
+        write_spawns(&merged, to, BufWriter::new(File::create(&args.out)?))?;
+        merged.len()
+    };
+    eprintln!(
+        "Wrote {} spawns to {} ({} format, {} bytes).",
+        written,
+        args.out.display(),
+        to,
+        fs::metadata(&args.out)?.len()
+    );
+
+    let mut document = Document::new("Merged Logs");
+    document.push_metadata(Column::integer("logs", "Logs merged"), args.logs.len());
+    document.push_metadata(Column::text("out", "Merged log"), args.out.display().to_string());
+    let mut section = Section::new("retry_duplicates", "Repeated Work");
+    section.push_field(Column::integer("spawns_read", "Spawns read"), attempts.len());
+    section.push_field(
+        Column::integer("repeated_actions", "Actions executed more than once"),
+        repeats.values().map(|r| r.actions).sum::<usize>(),
+    );
+    section.push_field(
+        Column::integer("repeated_executions", "Repeated executions"),
+        repeats.values().map(|r| r.executions).sum::<usize>(),
+    );
+    section.push_field(
+        Column::seconds("repeated_time", "Repeated execution time", 2),
+        repeats.values().map(|r| r.time).sum::<Duration>(),
+    );
+    section.push_field(Column::integer("dropped", "Duplicate spawns dropped"), dropped_count);
+    section.push_field(Column::integer("spawns_written", "Spawns written"), written);
+    if without_digest > 0 {
+        section.push_text(format!(
+            "Note: {} spawns have no action digest and were kept without matching them against the other logs.",
+            without_digest
+        ));
+    }
+    if args.dedup.is_none() && groups.values().any(|group| group.len() > 1) {
+        section.push_text("Hint: Pass --dedup first, last or fastest to keep one attempt of each action.");
+    }
+    let mut table = Table::new(vec![
+        Column::text("mnemonic", "Mnemonic"),
+        Column::integer("actions", "Actions"),
+        Column::integer("repeated_executions", "Repeated Executions"),
+        Column::seconds("repeated_time", "Repeated Time", 2),
+    ]);
+    let mut repeats: Vec<(&str, Repeats)> = repeats.into_iter().collect();
+    repeats.sort_by(|(a, x), (b, y)| y.time.cmp(&x.time).then(a.cmp(b)));
+    for (mnemonic, repeats) in repeats.iter().take(args.top_n) {
+        table.push_row(vec![(*mnemonic).into(), repeats.actions.into(), repeats.executions.into(), repeats.time.into()]);
+    }
+    section.push_table(table);
+    document.sections.push(section);
+
+    renderer_for(args.output).render(&document, &mut io::stdout().lock())?;
+    Ok(())
+}
@@ -1 +1,16 @@
-pub mod analyze;
\ No newline at end of file
+pub mod ab;
+pub mod analyze;
+pub mod convert;
+pub mod corpus;
+pub mod diff;
+pub mod doctor;
+pub mod export;
+pub mod merge;
+pub mod report;
+pub mod show;
+pub mod split;
+#[cfg(feature = "sql")]
+pub mod sql;
+pub mod stats;
+pub mod suggest;
+pub mod watch;
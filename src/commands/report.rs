@@ -0,0 +1,82 @@
+//! `report`: writes the analysis report in the formats a CI pipeline publishes, from one parse of
+//! the log instead of one invocation per format:
+//!
+//! - `report.txt`, `report.json` and `report.html`: the whole report, as `--output` writes it
+//! - `csv/<section>.csv`: each table of the report as a CSV file of its own
+//! - `summary.md`: the overall summary and data-quality warnings in Markdown, for job summaries
+
+use crate::cli::{BundleArgs, OutputFormat};
+use crate::commands::analyze::analysis_document;
+use crate::render::{renderer_for, write_table, Block, Document};
+use crate::AppResult;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Sections kept in `summary.md`.
+const SUMMARY_SECTIONS: &[&str] = &["overall_summary", "warnings"];
+
+fn write_file(path: PathBuf, write: impl FnOnce(&mut dyn Write) -> std::io::Result<()>) -> AppResult<PathBuf> {
+    let mut out = BufWriter::new(File::create(&path)?);
+    write(&mut out)?;
+    out.flush()?;
+    Ok(path)
+}
+
+fn render(document: &Document, format: OutputFormat, path: PathBuf) -> AppResult<PathBuf> {
+    write_file(path, |out| renderer_for(format).render(document, out))
+}
+
+/// Writes each table as `<section>.csv`, numbering the further tables of a section.
+fn write_csvs(document: &Document, dir: &Path) -> AppResult<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let mut written = Vec::new();
+    let mut taken = HashSet::new();
+    for section in &document.sections {
+        let tables = section.blocks.iter().filter_map(|block| match block {
+            Block::Table(table) => Some(table),
+            _ => None,
+        });
+        for (index, table) in tables.enumerate() {
+            let mut name = if index == 0 { section.id.clone() } else { format!("{}_{}", section.id, index + 1) };
+            // A report can appear twice, e.g. the same section for two mnemonics.
+            while !taken.insert(name.clone()) {
+                name.push('_');
+            }
+            written.push(write_file(dir.join(format!("{}.csv", name)), |out| write_table(table, out))?);
+        }
+    }
+    Ok(written)
+}
+
+pub fn run_report(args: BundleArgs) -> AppResult<()> {
+    fs::create_dir_all(&args.out_dir)?;
+    let Some(mut document) = analysis_document(
+        &args.file,
+        &args.reports,
+        &args.filters,
+        args.failures_only,
+        None,
+        args.format_version,
+    )?
+    else {
+        return Ok(());
+    };
+
+    let dir = &args.out_dir;
+    let mut written = vec![
+        render(&document, OutputFormat::Text, dir.join("report.txt"))?,
+        render(&document, OutputFormat::Json, dir.join("report.json"))?,
+        render(&document, OutputFormat::Html, dir.join("report.html"))?,
+    ];
+    written.extend(write_csvs(&document, &dir.join("csv"))?);
+    document.sections.retain(|section| SUMMARY_SECTIONS.contains(&section.id.as_str()));
+    written.push(render(&document, OutputFormat::Markdown, dir.join("summary.md"))?);
+
+    eprintln!("Wrote {} report files to {}:", written.len(), dir.display());
+    for path in &written {
+        eprintln!("  {}", path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use crate::cli::{RunnerKind, ShowArgs};
+use crate::commands::analyze::{is_local_runner, output_bytes, phase_duration, runner_kind, start_seconds};
+use crate::filters::normalize_path;
+use crate::parsers::parse_log_file;
+use crate::proto::{SpawnExec, SpawnMetrics};
+use crate::rows::spawn_id;
+use crate::render::{renderer_for, ByteUnit, Column, Document, Section, Table};
+use crate::{AppError, AppResult};
+use std::io;
+
+pub fn run_show(args: ShowArgs) -> AppResult<()> {
+    let spawns = parse_log_file(&args.file)?;
+
+    let matches: Vec<&SpawnExec> = spawns.iter().filter(|s| matches_pattern(s, &args.pattern)).collect();
+    if matches.is_empty() {
+        return Err(AppError::Analysis(format!(
+            "No action matches '{}' (searched target labels, output paths, action digests and spawn IDs).",
+            args.pattern
+        )));
+    }
+    eprintln!("Found {} matching actions.", matches.len());
+
+    let mut document = Document::new("Bazel Execution Log Action Details");
+    document.format_version = args.format_version;
+    document.push_metadata(Column::text("log_file", "Log file"), args.file.display().to_string());
+    document.push_metadata(Column::text("pattern", "Pattern"), args.pattern.as_str());
+    document.push_metadata(Column::integer("matches", "Matching Actions"), matches.len());
+    for (index, spawn) in matches.iter().take(args.limit).enumerate() {
+        document.sections.push(spawn_section(&format!("action_{}", index + 1), spawn));
+    }
+
+    renderer_for(args.output).render(&document, &mut io::stdout().lock())?;
+    Ok(())
+}
+
+/// Output paths are compared normalized, so a path copied from a Windows or Linux build finds the
+/// action in a log of the other.
+fn matches_pattern(spawn: &SpawnExec, pattern: &str) -> bool {
+    let path_pattern = normalize_path(pattern);
+    spawn.target_label.contains(pattern)
+        || spawn.digest.as_ref().is_some_and(|d| d.hash.starts_with(pattern))
+        || spawn.actual_outputs.iter().any(|f| normalize_path(&f.path).contains(&path_pattern))
+        || spawn.listed_outputs.iter().any(|path| normalize_path(path).contains(&path_pattern))
+        || spawn_id(spawn) == pattern
+}
+
+/// Output bytes moved over the network by a spawn: `(downloaded, uploaded)`.
+///
+/// Remote cache hits and remote executions download their outputs; actions executed locally
+/// upload their outputs when the result is remotely cacheable. Disk cache hits stay off the
+/// network, and inputs uploaded for remote execution are not recorded per file.
+pub(crate) fn network_bytes(spawn: &SpawnExec) -> (i64, i64) {
+    let bytes = output_bytes(spawn);
+    if matches!(runner_kind(spawn), RunnerKind::Remote | RunnerKind::RemoteCache) {
+        (bytes, 0)
+    } else if !spawn.cache_hit && spawn.remote_cacheable && is_local_runner(&spawn.runner) {
+        (0, bytes)
+    } else {
+        (0, 0)
+    }
+}
+
+type PhaseGetter = fn(&SpawnMetrics) -> Option<&prost_types::Duration>;
+
+/// Every recorded detail of one spawn: identity, phases, outputs and the full command.
+pub(crate) fn spawn_section(id: &str, spawn: &SpawnExec) -> Section {
+    let label = if spawn.target_label.is_empty() {
+        "(no target label)"
+    } else {
+        spawn.target_label.as_str()
+    };
+    let mut section = Section::new(id, format!("{} ({})", label, spawn.mnemonic));
+
+    section.push_field(Column::text("target", "Target"), &spawn.target_label);
+    section.push_field(Column::text("mnemonic", "Mnemonic"), &spawn.mnemonic);
+    section.push_field(Column::text("runner", "Runner"), &spawn.runner);
+    section.push_field(Column::text("cache_hit", "Cache Hit"), if spawn.cache_hit { "yes" } else { "no" });
+    section.push_field(
+        Column::text("status", "Status"),
+        (!spawn.status.is_empty()).then_some(&spawn.status),
+    );
+    section.push_field(Column::integer("exit_code", "Exit Code"), spawn.exit_code);
+    section.push_field(
+        Column::text("action_digest", "Action Digest"),
+        spawn.digest.as_ref().map(|d| d.hash.as_str()),
+    );
+    section.push_field(Column::text("spawn_id", "Spawn ID"), spawn_id(spawn));
+    section.push_field(Column::float("start_time", "Start Time (Unix s)", 3), start_seconds(spawn));
+
+    let (downloaded, uploaded) = network_bytes(spawn);
+    let mut phases = Table::new(vec![
+        Column::text("phase", "Phase"),
+        Column::seconds("time", "Time", 3),
+        Column::bytes("bytes", "Bytes", ByteUnit::DecimalMegabytes, 3),
+    ]);
+    let rows: [(&str, PhaseGetter, Option<i64>); 7] = [
+        ("Queue", |m| m.queue_time.as_ref(), None),
+        ("Setup", |m| m.setup_time.as_ref(), None),
+        ("Upload", |m| m.upload_time.as_ref(), Some(uploaded)),
+        ("Execution", |m| m.execution_wall_time.as_ref(), None),
+        ("Fetch", |m| m.fetch_time.as_ref(), Some(downloaded)),
+        ("Retry", |m| m.retry_time.as_ref(), None),
+        ("Total", |m| m.total_time.as_ref(), None),
+    ];
+    for (name, phase, bytes) in rows {
+        phases.push_row(vec![name.into(), phase_duration(spawn, phase).into(), bytes.into()]);
+    }
+    section.push_table(phases);
+    if spawn.metrics.is_none() {
+        section.push_text("No metrics were recorded for this action.");
+    }
+
+    let mut outputs = Table::new(vec![
+        Column::bytes("bytes", "Size", ByteUnit::DecimalMegabytes, 3),
+        Column::text("digest", "Digest"),
+        Column::text("path", "Output"),
+    ]);
+    for file in &spawn.actual_outputs {
+        outputs.push_row(vec![
+            file.digest.as_ref().map(|d| d.size_bytes).into(),
+            file.digest.as_ref().map(|d| d.hash.as_str()).into(),
+            (&file.path).into(),
+        ]);
+    }
+    if !outputs.is_empty() {
+        section.push_table(outputs);
+    }
+
+    section.push_field(Column::integer("argc", "Arguments"), spawn.command_args.len());
+    section.push_field(Column::text("command", "Command"), spawn.command_args.join(" "));
+    section
+}
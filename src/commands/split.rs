@@ -0,0 +1,138 @@
+//! `split`: cuts a log into one smaller log per package or mnemonic, so a team can download and
+//! analyze its own slice of a monorepo's log. The logs keep the input's format; compact logs are
+//! cut entry by entry with [`EntryCompactor`], so each keeps only the entries its actions need.
+
+use crate::cli::{SplitArgs, SplitBy};
+use crate::commands::analyze::package_of;
+use crate::parsers::{parse_log_file, read_compact_entries, LogFormat, ParseLimits};
+use crate::proto::exec_log_entry::Type as CompactEntryType;
+use crate::proto::{ExecLogEntry, SpawnExec};
+use crate::render::{renderer_for, ByteUnit, Column, Document, Section, Table};
+use crate::writer::{write_spawns, EntryCompactor};
+use crate::AppResult;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+/// The group of an action with this target label and mnemonic.
+fn group_of(args: &SplitArgs, target_label: &str, mnemonic: &str) -> String {
+    match args.by {
+        SplitBy::Package => package_of(target_label, args.package_depth),
+        SplitBy::Mnemonic if mnemonic.is_empty() => "(no mnemonic)".to_string(),
+        SplitBy::Mnemonic => mnemonic.to_string(),
+    }
+}
+
+fn entry_group(args: &SplitArgs, entry: &ExecLogEntry) -> String {
+    match &entry.r#type {
+        Some(CompactEntryType::Spawn(spawn)) => group_of(args, &spawn.target_label, &spawn.mnemonic),
+        Some(CompactEntryType::SymlinkAction(action)) => group_of(args, &action.target_label, &action.mnemonic),
+        _ => String::new(),
+    }
+}
+
+/// A file name for a group's log, unique among `taken`: `@repo//foo/bar` becomes `repo_foo_bar`.
+fn file_name(group: &str, extension: &str, taken: &mut HashSet<String>) -> String {
+    let name: String = group
+        .trim_start_matches('@')
+        .replace("//", "/")
+        .trim_matches(|c| c == '/' || c == '(' || c == ')')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect();
+    let name = if name.is_empty() { "root".to_string() } else { name };
+    let mut candidate = format!("{}.{}", name, extension);
+    for suffix in 2.. {
+        if taken.insert(candidate.clone()) {
+            break;
+        }
+        candidate = format!("{}-{}.{}", name, suffix, extension);
+    }
+    candidate
+}
+
+/// One log written: its group, path and number of spawns.
+struct Shard {
+    group: String,
+    path: PathBuf,
+    spawns: usize,
+}
+
+fn split_compact(args: &SplitArgs, bytes: &[u8], taken: &mut HashSet<String>) -> AppResult<Vec<Shard>> {
+    let entries = read_compact_entries(bytes, &ParseLimits::default())?;
+    let mut shards = Vec::new();
+    for (group, compactor) in EntryCompactor::split(&entries, |entry| entry_group(args, entry)) {
+        let path = args.out_dir.join(file_name(&group, "log.zst", taken));
+        let spawns = compactor.spawns();
+        compactor.finish(BufWriter::new(File::create(&path)?))?;
+        shards.push(Shard { group, path, spawns });
+    }
+    Ok(shards)
+}
+
+fn split_verbose(args: &SplitArgs, taken: &mut HashSet<String>) -> AppResult<Vec<Shard>> {
+    let mut groups: BTreeMap<String, Vec<SpawnExec>> = BTreeMap::new();
+    for spawn in parse_log_file(&args.file)? {
+        groups.entry(group_of(args, &spawn.target_label, &spawn.mnemonic)).or_default().push(spawn);
+    }
+    let mut shards = Vec::new();
+    for (group, spawns) in groups {
+        let path = args.out_dir.join(file_name(&group, "log", taken));
+        write_spawns(&spawns, LogFormat::Verbose, BufWriter::new(File::create(&path)?))?;
+        shards.push(Shard {
+            group,
+            path,
+            spawns: spawns.len(),
+        });
+    }
+    Ok(shards)
+}
+
+fn size(path: &Path) -> AppResult<u64> {
+    Ok(fs::metadata(path)?.len())
+}
+
+pub fn run_split(args: SplitArgs) -> AppResult<()> {
+    let bytes = fs::read(&args.file)?;
+    fs::create_dir_all(&args.out_dir)?;
+    let mut taken = HashSet::new();
+    let shards = match LogFormat::detect(&bytes) {
+        LogFormat::Compact => split_compact(&args, &bytes, &mut taken)?,
+        LogFormat::Verbose => {
+            drop(bytes);
+            split_verbose(&args, &mut taken)?
+        }
+    };
+
+    let mut document = Document::new("Split Log");
+    document.push_metadata(Column::text("log_file", "Log file"), args.file.display().to_string());
+    document.push_metadata(Column::text("out_dir", "Output directory"), args.out_dir.display().to_string());
+    let mut section = Section::new("logs", "Logs Written");
+    section.push_field(Column::integer("logs", "Logs"), shards.len());
+    let mut table = Table::new(vec![
+        Column::text(
+            "group",
+            match args.by {
+                SplitBy::Package => "Package",
+                SplitBy::Mnemonic => "Mnemonic",
+            },
+        ),
+        Column::text("file", "File"),
+        Column::integer("spawns", "Spawns"),
+        Column::bytes("bytes", "Size", ByteUnit::Megabytes, 2),
+    ]);
+    for shard in &shards {
+        table.push_row(vec![
+            shard.group.as_str().into(),
+            shard.path.display().to_string().into(),
+            shard.spawns.into(),
+            size(&shard.path)?.into(),
+        ]);
+    }
+    section.push_table(table);
+    document.sections.push(section);
+
+    renderer_for(args.output).render(&document, &mut io::stdout().lock())?;
+    Ok(())
+}
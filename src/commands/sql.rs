@@ -0,0 +1,134 @@
+//! Ad-hoc SQL over a log: the spawns are loaded into an in-memory SQLite database.
+//!
+//! - `spawns`: one row per spawn with the columns of `export ndjson`, plus `id`, the spawn's
+//!   position in the log.
+//! - `outputs`: `spawn_id`, `path`, `hash` and `size_bytes` of every output file.
+//! - `env`: `spawn_id`, `name` and `value` of every environment variable.
+
+use crate::cli::SqlArgs;
+use crate::parsers::parse_log_file;
+use crate::proto::SpawnExec;
+use crate::render::{renderer_for, Column, Document, Section, Table, Value};
+use crate::rows::SpawnRow;
+use crate::AppResult;
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params, params_from_iter, Connection};
+use std::io;
+
+pub fn run_sql(args: SqlArgs) -> AppResult<()> {
+    let spawns = parse_log_file(&args.file)?;
+    let mut connection = Connection::open_in_memory()?;
+    load_tables(&mut connection, &spawns)?;
+
+    let mut statement = connection.prepare(&args.query)?;
+    let names: Vec<String> = statement.column_names().iter().map(|name| name.to_string()).collect();
+    let mut rows: Vec<Vec<Value>> = Vec::new();
+    let mut result = statement.query([])?;
+    while let Some(row) = result.next()? {
+        let values = (0..names.len())
+            .map(|i| row.get_ref(i).map(cell_value))
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.push(values);
+    }
+
+    let mut document = Document::new("SQL Query");
+    document.format_version = args.format_version;
+    document.push_metadata(Column::text("log_file", "Log file"), args.file.display().to_string());
+    document.push_metadata(Column::text("query", "Query"), args.query.as_str());
+    let mut section = Section::new("query_result", "Query Result");
+    // SQLite columns have no fixed type; the first non-null value decides how a column is shown.
+    let columns = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| match rows.iter().map(|row| &row[i]).find(|v| !matches!(v, Value::Null)) {
+            Some(Value::Int(_)) => Column::integer(name, name),
+            Some(Value::Float(_)) => Column::float(name, name, 3),
+            _ => Column::text(name, name),
+        })
+        .collect();
+    section.push_field(Column::integer("rows", "Rows"), rows.len());
+    let mut table = Table::new(columns);
+    for row in rows {
+        table.push_row(row);
+    }
+    section.push_table(table);
+    document.sections.push(section);
+    renderer_for(args.output).render(&document, &mut io::stdout().lock())?;
+    Ok(())
+}
+
+fn cell_value(value: ValueRef) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::Int(i),
+        ValueRef::Real(f) => Value::Float(f),
+        ValueRef::Text(text) => Value::Text(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(blob) => Value::Text(format!("<{} bytes>", blob.len())),
+    }
+}
+
+/// The SQLite column type for a BigQuery column type of [`SpawnRow`].
+fn sql_type(bigquery_type: &str) -> &'static str {
+    match bigquery_type {
+        "INTEGER" | "BOOLEAN" => "INTEGER",
+        "FLOAT" => "REAL",
+        _ => "TEXT",
+    }
+}
+
+fn sql_value(value: &serde_json::Value) -> SqlValue {
+    match value {
+        serde_json::Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_json::Value::String(s) => SqlValue::Text(s.clone()),
+        _ => SqlValue::Null,
+    }
+}
+
+/// Creates and fills the tables. The `spawns` columns come from the same field list as the
+/// NDJSON rows and their BigQuery schema.
+fn load_tables(connection: &mut Connection, spawns: &[SpawnExec]) -> AppResult<()> {
+    let schema = SpawnRow::bigquery_schema();
+    let columns: Vec<String> = schema
+        .iter()
+        .map(|field| format!("{} {}", field.name, sql_type(field.field_type)))
+        .collect();
+    connection.execute_batch(&format!(
+        "CREATE TABLE spawns (id INTEGER PRIMARY KEY, {});
+         CREATE TABLE outputs (spawn_id INTEGER, path TEXT, hash TEXT, size_bytes INTEGER);
+         CREATE TABLE env (spawn_id INTEGER, name TEXT, value TEXT);",
+        columns.join(", ")
+    ))?;
+
+    let transaction = connection.transaction()?;
+    {
+        let placeholders = vec!["?"; schema.len() + 1].join(", ");
+        let mut insert_spawn = transaction.prepare(&format!("INSERT INTO spawns VALUES ({})", placeholders))?;
+        let mut insert_output = transaction.prepare("INSERT INTO outputs VALUES (?, ?, ?, ?)")?;
+        let mut insert_env = transaction.prepare("INSERT INTO env VALUES (?, ?, ?)")?;
+        for (id, spawn) in spawns.iter().enumerate() {
+            let id = id as i64;
+            let row = serde_json::to_value(SpawnRow::from_spawn(spawn)).map_err(io::Error::from)?;
+            let values = std::iter::once(SqlValue::Integer(id))
+                .chain(schema.iter().map(|field| sql_value(&row[field.name])));
+            insert_spawn.execute(params_from_iter(values))?;
+            for output in &spawn.actual_outputs {
+                let digest = output.digest.as_ref();
+                insert_output.execute(params![
+                    id,
+                    output.path,
+                    digest.map(|d| d.hash.as_str()),
+                    digest.map(|d| d.size_bytes)
+                ])?;
+            }
+            for variable in &spawn.environment_variables {
+                insert_env.execute(params![id, variable.name, variable.value])?;
+            }
+        }
+    }
+    transaction.commit()?;
+    Ok(())
+}
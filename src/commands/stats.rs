@@ -0,0 +1,89 @@
+use crate::cli::{RunnerKind, StatsArgs};
+use crate::commands::analyze::{is_failed, percentage, percentile, runner_kind, total_time};
+use crate::commands::show::network_bytes;
+use crate::filters;
+use crate::parsers::parse_log_file;
+use crate::render::Document;
+use crate::AppResult;
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// The key numbers of a log, without any tables.
+#[derive(Serialize)]
+struct Stats {
+    actions: usize,
+    executed: usize,
+    cache_hits: usize,
+    remote_cache_hits: usize,
+    disk_cache_hits: usize,
+    failed: usize,
+    cache_hit_rate: f64,
+    remote_cache_hit_rate: f64,
+    /// Percentiles of the total time of executed actions, in seconds.
+    duration_p50_seconds: Option<f64>,
+    duration_p90_seconds: Option<f64>,
+    duration_p99_seconds: Option<f64>,
+    execution_seconds: f64,
+    bytes_downloaded: i64,
+    bytes_uploaded: i64,
+}
+
+pub fn run_stats(args: StatsArgs) -> AppResult<()> {
+    let spawns = parse_log_file(&args.file)?;
+    // Filters note what they removed on stderr; the document only collects their metadata.
+    let spawns = filters::apply(spawns, &args.filters, &mut Document::new("Statistics"));
+
+    let count_kind = |kind: RunnerKind| spawns.iter().filter(|s| runner_kind(s) == kind).count();
+    let cache_hits = spawns.iter().filter(|s| s.cache_hit).count();
+    let remote_cache_hits = count_kind(RunnerKind::RemoteCache);
+    let mut durations: Vec<f64> = spawns
+        .iter()
+        .filter(|s| !s.cache_hit)
+        .map(|s| total_time(s).as_secs_f64())
+        .collect();
+    durations.sort_by(f64::total_cmp);
+    let (bytes_downloaded, bytes_uploaded) = spawns
+        .iter()
+        .map(network_bytes)
+        .fold((0, 0), |(down, up), (d, u)| (down + d, up + u));
+
+    let stats = Stats {
+        actions: spawns.len(),
+        executed: spawns.len() - cache_hits,
+        cache_hits,
+        remote_cache_hits,
+        disk_cache_hits: count_kind(RunnerKind::DiskCache),
+        failed: spawns.iter().filter(|s| is_failed(s)).count(),
+        cache_hit_rate: percentage(cache_hits as f64, spawns.len() as f64),
+        remote_cache_hit_rate: percentage(remote_cache_hits as f64, spawns.len() as f64),
+        duration_p50_seconds: percentile(&durations, 50.0),
+        duration_p90_seconds: percentile(&durations, 90.0),
+        duration_p99_seconds: percentile(&durations, 99.0),
+        execution_seconds: durations.iter().sum(),
+        bytes_downloaded,
+        bytes_uploaded,
+    };
+
+    let mut out = io::stdout().lock();
+    if args.json {
+        serde_json::to_writer_pretty(&mut out, &stats).map_err(io::Error::from)?;
+        writeln!(out)?;
+    } else {
+        let optional = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.3}", v));
+        writeln!(out, "actions: {}", stats.actions)?;
+        writeln!(out, "executed: {}", stats.executed)?;
+        writeln!(out, "cache_hits: {}", stats.cache_hits)?;
+        writeln!(out, "remote_cache_hits: {}", stats.remote_cache_hits)?;
+        writeln!(out, "disk_cache_hits: {}", stats.disk_cache_hits)?;
+        writeln!(out, "failed: {}", stats.failed)?;
+        writeln!(out, "cache_hit_rate: {:.2}%", stats.cache_hit_rate)?;
+        writeln!(out, "remote_cache_hit_rate: {:.2}%", stats.remote_cache_hit_rate)?;
+        writeln!(out, "duration_p50: {}s", optional(stats.duration_p50_seconds))?;
+        writeln!(out, "duration_p90: {}s", optional(stats.duration_p90_seconds))?;
+        writeln!(out, "duration_p99: {}s", optional(stats.duration_p99_seconds))?;
+        writeln!(out, "execution_time: {:.3}s", stats.execution_seconds)?;
+        writeln!(out, "bytes_downloaded: {}", stats.bytes_downloaded)?;
+        writeln!(out, "bytes_uploaded: {}", stats.bytes_uploaded)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use crate::cli::{RunnerKind, SuggestRcArgs};
+use crate::commands::analyze::{
+    download_savings_by_mnemonic, percentage, runner_kind, strategy_stats_by_mnemonic, Parallelism, Strategy,
+    MIN_STRATEGY_SAMPLES,
+};
+use crate::commands::export::open_output;
+use crate::commands::show::network_bytes;
+use crate::correlate::bep::BuildEvents;
+use crate::parsers::parse_log_file;
+use crate::proto::SpawnExec;
+use crate::AppResult;
+use std::io::Write;
+
+/// Share of download volume a download mode must avoid before it is suggested.
+const MIN_DOWNLOAD_SAVINGS_SHARE: f64 = 50.0;
+
+/// Bytes a download mode must avoid before it is suggested.
+const MIN_DOWNLOAD_SAVINGS_BYTES: i64 = 100_000_000;
+
+/// Bytes moved to and from the remote cache before compression is suggested.
+const MIN_COMPRESSION_BYTES: i64 = 1_000_000_000;
+
+/// A candidate flag and the evidence for it, written as comment lines above it.
+struct Suggestion {
+    flag: String,
+    evidence: Vec<String>,
+    /// Whether the flag's value has to be chosen by hand, in which case the flag is written
+    /// commented out.
+    needs_value: bool,
+}
+
+pub fn run_suggest_rc(args: SuggestRcArgs) -> AppResult<()> {
+    let spawns = parse_log_file(&args.file)?;
+    let bep = args.bep.as_deref().map(BuildEvents::load).transpose()?;
+
+    let mut suggestions = Vec::new();
+    suggestions.extend(jobs_suggestion(&spawns, bep.as_ref()));
+    suggestions.extend(download_suggestion(&spawns, &args.cache_hit_runners));
+    suggestions.extend(compression_suggestion(&spawns));
+    suggestions.extend(strategy_suggestions(&spawns));
+    eprintln!("{} candidate flags.", suggestions.len());
+
+    let command = match args.config.as_deref() {
+        Some(config) => format!("build:{}", config),
+        None => "build".to_string(),
+    };
+    let mut out = open_output(args.out.as_deref())?;
+    writeln!(
+        out,
+        "# Candidate flags suggested by {} {} from {} ({} spawns).",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        args.file.display(),
+        spawns.len()
+    )?;
+    writeln!(out, "# The evidence comes from a single build; review each flag before adding it to .bazelrc.")?;
+    if suggestions.is_empty() {
+        writeln!(out, "# Nothing to suggest: the log shows no clear opportunity.")?;
+    }
+    for suggestion in suggestions {
+        writeln!(out)?;
+        for line in &suggestion.evidence {
+            writeln!(out, "# {}", line)?;
+        }
+        let comment = if suggestion.needs_value { "# " } else { "" };
+        writeln!(out, "{}{} {}", comment, command, suggestion.flag)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Suggests more jobs when a build with remote executions spent a large part of its executing
+/// time at its `--jobs`, read from the build's event stream.
+fn jobs_suggestion(spawns: &[SpawnExec], bep: Option<&BuildEvents>) -> Option<Suggestion> {
+    let executed: Vec<&SpawnExec> = spawns.iter().filter(|s| !s.cache_hit).collect();
+    let remote = executed.iter().filter(|s| runner_kind(s) == RunnerKind::Remote).count();
+    if remote == 0 {
+        return None;
+    }
+
+    let jobs = bep.and_then(BuildEvents::job_limit);
+    let parallelism = Parallelism::of(&executed).filter(|parallelism| parallelism.limited_by_jobs(jobs))?;
+    let (peak, saturated_share) = (parallelism.peak, parallelism.at_peak_share);
+
+    Some(Suggestion {
+        flag: format!("--jobs=<more than {}>", peak),
+        evidence: vec![
+            format!(
+                "At most {} actions executed at once, the build's --jobs, and that ceiling held for {:.0}% of the time \
+                 actions were executing.",
+                peak, saturated_share
+            ),
+            format!(
+                "With {} remote executions the executors can likely take more; raise --jobs above {} by what they \
+                 can run (see the concurrency and queue analysis reports).",
+                remote, peak
+            ),
+        ],
+        needs_value: true,
+    })
+}
+
+fn download_suggestion(spawns: &[SpawnExec], cache_hit_runners: &[String]) -> Option<Suggestion> {
+    if spawns.iter().all(|s| s.inputs.is_empty()) {
+        return None;
+    }
+    let savings = download_savings_by_mnemonic(spawns, cache_hit_runners);
+    let fetched: i64 = savings.values().map(|s| s.fetched_bytes).sum();
+    let minimal: i64 = savings.values().map(|s| s.minimal_bytes).sum();
+    let toplevel = minimal - savings.values().map(|s| s.unconsumed_bytes).sum::<i64>();
+    let share = percentage(minimal as f64, fetched as f64);
+    if minimal < MIN_DOWNLOAD_SAVINGS_BYTES || share < MIN_DOWNLOAD_SAVINGS_SHARE {
+        return None;
+    }
+
+    Some(Suggestion {
+        flag: "--remote_download_minimal".to_string(),
+        evidence: vec![
+            format!(
+                "Remote cache hits downloaded {:.1} MB; {:.1} MB ({:.0}%) of it was never read by a locally executed action.",
+                fetched as f64 / 1_000_000.0,
+                minimal as f64 / 1_000_000.0,
+                share
+            ),
+            format!(
+                "--remote_download_toplevel would still avoid about {:.1} MB and keeps the outputs of requested targets.",
+                toplevel as f64 / 1_000_000.0
+            ),
+        ],
+        needs_value: false,
+    })
+}
+
+fn compression_suggestion(spawns: &[SpawnExec]) -> Option<Suggestion> {
+    let (downloaded, uploaded) = spawns
+        .iter()
+        .map(network_bytes)
+        .fold((0, 0), |(down, up), (d, u)| (down + d, up + u));
+    if downloaded + uploaded < MIN_COMPRESSION_BYTES {
+        return None;
+    }
+
+    Some(Suggestion {
+        flag: "--experimental_remote_cache_compression".to_string(),
+        evidence: vec![
+            format!(
+                "{:.1} MB of outputs were downloaded from and {:.1} MB uploaded to the remote cache.",
+                downloaded as f64 / 1_000_000.0,
+                uploaded as f64 / 1_000_000.0
+            ),
+            "zstd compression usually shrinks build outputs well; the cache server must support compressed blobs."
+                .to_string(),
+        ],
+        needs_value: false,
+    })
+}
+
+/// The `--strategy` value for a recommended strategy.
+fn strategy_flag_value(strategy: Strategy) -> &'static str {
+    match strategy {
+        Strategy::Remote => "remote",
+        Strategy::Worker => "worker",
+        Strategy::Sandbox => "sandboxed",
+        Strategy::Local => "local",
+    }
+}
+
+/// One `--strategy` line per mnemonic whose recommended strategy differs from the one most of its
+/// executions used.
+fn strategy_suggestions(spawns: &[SpawnExec]) -> Vec<Suggestion> {
+    let mut by_mnemonic: Vec<_> = strategy_stats_by_mnemonic(spawns).into_iter().collect();
+    by_mnemonic.sort_by_key(|(mnemonic, _)| *mnemonic);
+
+    let mut suggestions = Vec::new();
+    for (mnemonic, stats) in by_mnemonic {
+        let Some(current) = stats.most_used() else { continue };
+        let (recommended, reason) = stats.recommend();
+        if stats.executed() < MIN_STRATEGY_SAMPLES || recommended == current {
+            continue;
+        }
+        suggestions.push(Suggestion {
+            flag: format!("--strategy={}={}", mnemonic, strategy_flag_value(recommended)),
+            evidence: vec![format!(
+                "{}: {} executions, mostly {}; {}.",
+                mnemonic,
+                stats.executed(),
+                current.name(),
+                reason
+            )],
+            needs_value: false,
+        });
+    }
+    suggestions
+}
@@ -0,0 +1,295 @@
+use crate::cli::{RunnerKind, WatchArgs};
+use crate::commands::analyze::{output_bytes, percentage, runner_kind, total_time};
+use crate::parsers::parse_partial_log;
+use crate::proto::SpawnExec;
+use crate::{AppError, AppResult};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, execute, queue};
+use std::collections::HashMap;
+use std::io::{self, IsTerminal, Stdout, Write};
+use std::time::{Duration, Instant};
+
+/// Number of most recent spawns the rolling cache hit rate is computed over.
+const ROLLING_WINDOW: usize = 100;
+
+pub fn run_watch(args: WatchArgs) -> AppResult<()> {
+    let interval = Duration::from_secs_f64(args.interval.max(0.1));
+    if args.delta {
+        return run_delta(&args, interval);
+    }
+    if !io::stdout().is_terminal() {
+        return Err(AppError::Analysis(
+            "watch draws a live screen and needs a terminal on stdout; use --delta for plain output."
+                .to_string(),
+        ));
+    }
+    let mut screen = LiveScreen::enter()?;
+    let mut tracker = RefreshTracker::new();
+    loop {
+        let spawns = read_log(&args)?;
+        let refresh = tracker.update(&spawns);
+        screen.draw(&top_lines(&args, &spawns, &refresh))?;
+        if wait_for_quit(interval)? {
+            return Ok(());
+        }
+    }
+}
+
+/// Appends what changed since the previous refresh to stdout, so the output reads well in a CI
+/// log. Refreshes without new spawns print nothing.
+fn run_delta(args: &WatchArgs, interval: Duration) -> AppResult<()> {
+    let mut tracker = RefreshTracker::new();
+    let mut stdout = io::stdout();
+    loop {
+        let spawns = read_log(args)?;
+        let refresh = tracker.update(&spawns);
+        for line in delta_lines(args, &spawns, &refresh) {
+            writeln!(stdout, "{}", line)?;
+        }
+        stdout.flush()?;
+        std::thread::sleep(interval);
+    }
+}
+
+/// Reads the complete entries written so far; a log that does not exist yet is empty.
+fn read_log(args: &WatchArgs) -> AppResult<Vec<SpawnExec>> {
+    match parse_partial_log(&args.file) {
+        Err(AppError::Io(e)) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        result => result,
+    }
+}
+
+/// What changed between two refreshes.
+struct Refresh {
+    /// Index of the first spawn that was not in the previous refresh.
+    first_new: usize,
+    /// Whether the log shrank since the previous refresh, i.e. Bazel started a new one.
+    restarted: bool,
+    since_previous: Duration,
+    since_start: Duration,
+}
+
+struct RefreshTracker {
+    started: Instant,
+    previous: Instant,
+    previous_count: usize,
+}
+
+impl RefreshTracker {
+    fn new() -> Self {
+        let now = Instant::now();
+        RefreshTracker {
+            started: now,
+            previous: now,
+            previous_count: 0,
+        }
+    }
+
+    fn update(&mut self, spawns: &[SpawnExec]) -> Refresh {
+        let now = Instant::now();
+        // A shorter log means Bazel started writing a new one.
+        let restarted = spawns.len() < self.previous_count;
+        if restarted {
+            self.started = now;
+            self.previous_count = 0;
+        }
+        let refresh = Refresh {
+            first_new: self.previous_count,
+            restarted,
+            since_previous: now - self.previous,
+            since_start: now - self.started,
+        };
+        self.previous = now;
+        self.previous_count = spawns.len();
+        refresh
+    }
+}
+
+fn per_second(amount: f64, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        0.0
+    } else {
+        amount / elapsed.as_secs_f64()
+    }
+}
+
+fn hit_rate(spawns: &[SpawnExec]) -> f64 {
+    percentage(spawns.iter().filter(|s| s.cache_hit).count() as f64, spawns.len() as f64)
+}
+
+/// Bytes downloaded for remote cache hits.
+fn fetched_bytes(spawns: &[SpawnExec]) -> i64 {
+    spawns
+        .iter()
+        .filter(|s| runner_kind(s) == RunnerKind::RemoteCache)
+        .map(output_bytes)
+        .sum()
+}
+
+#[derive(Default)]
+struct MnemonicTotals {
+    count: u64,
+    cache_hits: u64,
+    total_duration: Duration,
+}
+
+fn top_lines(args: &WatchArgs, spawns: &[SpawnExec], refresh: &Refresh) -> Vec<String> {
+    let new = &spawns[refresh.first_new..];
+    let recent = &spawns[spawns.len().saturating_sub(ROLLING_WINDOW)..];
+    let mut lines = vec![
+        format!("{} - watching for {:.0}s - press q to quit", args.file.display(), refresh.since_start.as_secs_f64()),
+        String::new(),
+        format!(
+            "Spawns: {:>8}   Actions/s: {:>7.1} now {:>7.1} avg",
+            spawns.len(),
+            per_second(new.len() as f64, refresh.since_previous),
+            per_second(spawns.len() as f64, refresh.since_start)
+        ),
+        format!(
+            "Cache hit rate: {:>5.1}% last {} {:>5.1}% overall",
+            hit_rate(recent),
+            recent.len(),
+            hit_rate(spawns)
+        ),
+        format!(
+            "Fetched: {:>8.2} MB/s now {:>10.2} MB total",
+            per_second(fetched_bytes(new) as f64, refresh.since_previous) / 1_000_000.0,
+            fetched_bytes(spawns) as f64 / 1_000_000.0
+        ),
+        String::new(),
+    ];
+
+    let mut mnemonics: HashMap<&str, MnemonicTotals> = HashMap::new();
+    for spawn in spawns {
+        let totals = mnemonics.entry(&spawn.mnemonic).or_default();
+        totals.count += 1;
+        totals.total_duration += total_time(spawn);
+        if spawn.cache_hit {
+            totals.cache_hits += 1;
+        }
+    }
+    let mut mnemonics: Vec<_> = mnemonics.into_iter().collect();
+    mnemonics.sort_by_key(|(name, totals)| (std::cmp::Reverse(totals.total_duration), *name));
+    lines.push(format!("{:<30} {:>8} {:>12} {:>10} {:>8}", "MNEMONIC", "SPAWNS", "TOTAL TIME", "AVG", "HIT %"));
+    for (name, totals) in mnemonics.into_iter().take(args.top_n) {
+        lines.push(format!(
+            "{:<30} {:>8} {:>11.1}s {:>9.2}s {:>7.1}%",
+            name,
+            totals.count,
+            totals.total_duration.as_secs_f64(),
+            totals.total_duration.as_secs_f64() / totals.count as f64,
+            percentage(totals.cache_hits as f64, totals.count as f64)
+        ));
+    }
+    if spawns.is_empty() {
+        lines.push("Waiting for spawns...".to_string());
+    }
+    lines
+}
+
+fn delta_lines(args: &WatchArgs, spawns: &[SpawnExec], refresh: &Refresh) -> Vec<String> {
+    let elapsed = format!("[+{:.0}s]", refresh.since_start.as_secs_f64());
+    let mut lines = Vec::new();
+    if refresh.restarted {
+        lines.push(format!("{} {} was restarted", elapsed, args.file.display()));
+    }
+    let new = &spawns[refresh.first_new..];
+    if new.is_empty() {
+        return lines;
+    }
+
+    let previous = &spawns[..refresh.first_new];
+    if previous.is_empty() {
+        // The spawns already in the log did not complete during a known interval.
+        lines.push(format!(
+            "{} {} spawns so far, cache hit rate {:.1}%",
+            elapsed,
+            spawns.len(),
+            hit_rate(spawns)
+        ));
+    } else {
+        let (before, after) = (hit_rate(previous), hit_rate(spawns));
+        lines.push(format!(
+            "{} +{} spawns ({} total, {:.1}/s), cache hit rate {:.1}% in this refresh, overall \
+             {:.1}% -> {:.1}% ({:+.1} pts)",
+            elapsed,
+            new.len(),
+            spawns.len(),
+            per_second(new.len() as f64, refresh.since_previous),
+            hit_rate(new),
+            before,
+            after,
+            after - before
+        ));
+    }
+
+    let mut slowest: Vec<&SpawnExec> = new.iter().filter(|s| !s.cache_hit).collect();
+    slowest.sort_by_key(|s| std::cmp::Reverse(total_time(s)));
+    for spawn in slowest.into_iter().take(args.top_n) {
+        let line = format!(
+            "    {:>9.3}s {:<20} {}",
+            total_time(spawn).as_secs_f64(),
+            spawn.mnemonic,
+            spawn.target_label
+        );
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+/// The alternate screen in raw mode, restored when dropped.
+struct LiveScreen {
+    stdout: Stdout,
+}
+
+impl LiveScreen {
+    fn enter() -> io::Result<Self> {
+        let mut stdout = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(LiveScreen { stdout })
+    }
+
+    fn draw(&mut self, lines: &[String]) -> io::Result<()> {
+        // Some pseudo-terminals report a zero size; draw everything then.
+        let limit = |size: u16| if size == 0 { usize::MAX } else { usize::from(size) };
+        let (width, height) = terminal::size()?;
+        queue!(self.stdout, terminal::Clear(ClearType::All))?;
+        for (row, line) in lines.iter().take(limit(height)).enumerate() {
+            let clipped: String = line.chars().take(limit(width)).collect();
+            queue!(self.stdout, cursor::MoveTo(0, row as u16), Print(clipped))?;
+        }
+        self.stdout.flush()
+    }
+}
+
+impl Drop for LiveScreen {
+    fn drop(&mut self) {
+        let _ = execute!(self.stdout, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Waits for the next refresh, returning early (and `true`) when the user asks to quit. A resize
+/// also ends the wait so the screen is redrawn right away.
+fn wait_for_quit(interval: Duration) -> io::Result<bool> {
+    let deadline = Instant::now() + interval;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() || !event::poll(remaining)? {
+            return Ok(false);
+        }
+        match event::read()? {
+            Event::Key(KeyEvent { code: KeyCode::Char('q') | KeyCode::Esc, .. }) => return Ok(true),
+            Event::Key(KeyEvent { code: KeyCode::Char('c'), modifiers, .. })
+                if modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                return Ok(true);
+            }
+            Event::Resize(..) => return Ok(false),
+            _ => {}
+        }
+    }
+}
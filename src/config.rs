@@ -0,0 +1,103 @@
+//! The optional configuration file, which holds named queries a team can share:
+//!
+//! ```toml
+//! [queries.slow-java]
+//! mnemonic = "Javac"
+//! only = ["summary", "phase-timings"]
+//! top-n = 50
+//! ```
+//!
+//! Each key is a long command-line flag without the leading `--`. `--run slow-java` applies the
+//! flags before those given on the command line, so the command line can override them.
+
+use crate::cli::Cli;
+use crate::{AppError, AppResult};
+use clap::Parser;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Looked up in the working directory when `--config-file` is not given.
+pub const DEFAULT_CONFIG_FILE: &str = ".bzl-exec-log-analyzer.toml";
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Saved queries by name, as flag names and values.
+    #[serde(default)]
+    pub queries: BTreeMap<String, toml::Table>,
+}
+
+impl Config {
+    /// Reads `path`, or the default file if it exists. Without either there are no queries.
+    pub fn load(path: Option<&Path>) -> AppResult<(PathBuf, Config)> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None if Path::new(DEFAULT_CONFIG_FILE).is_file() => PathBuf::from(DEFAULT_CONFIG_FILE),
+            None => return Ok((PathBuf::from(DEFAULT_CONFIG_FILE), Config::default())),
+        };
+        let content = fs::read_to_string(&path)
+            .map_err(|e| AppError::Config(format!("cannot read {}: {}", path.display(), e)))?;
+        let config = toml::from_str(&content).map_err(|e| AppError::Config(format!("{}: {}", path.display(), e)))?;
+        Ok((path, config))
+    }
+
+    /// The command-line arguments of the saved query `name`.
+    pub fn query_args(&self, name: &str) -> AppResult<Vec<String>> {
+        let Some(query) = self.queries.get(name) else {
+            let known: Vec<&str> = self.queries.keys().map(String::as_str).collect();
+            return Err(AppError::Config(if known.is_empty() {
+                format!("no saved query '{}'; the configuration file defines none", name)
+            } else {
+                format!("no saved query '{}'; saved queries: {}", name, known.join(", "))
+            }));
+        };
+        let mut args = Vec::new();
+        for (flag, value) in query {
+            if flag == "run" {
+                return Err(AppError::Config(format!("saved query '{}' cannot run another query", name)));
+            }
+            push_flag(&mut args, name, flag, value)?;
+        }
+        Ok(args)
+    }
+}
+
+fn push_flag(args: &mut Vec<String>, query: &str, flag: &str, value: &toml::Value) -> AppResult<()> {
+    match value {
+        toml::Value::Boolean(true) => args.push(format!("--{}", flag)),
+        toml::Value::Boolean(false) => {}
+        toml::Value::String(text) => args.push(format!("--{}={}", flag, text)),
+        toml::Value::Integer(number) => args.push(format!("--{}={}", flag, number)),
+        toml::Value::Float(number) => args.push(format!("--{}={}", flag, number)),
+        toml::Value::Array(values) => {
+            for value in values {
+                push_flag(args, query, flag, value)?;
+            }
+        }
+        _ => {
+            return Err(AppError::Config(format!(
+                "saved query '{}': '{}' must be a string, number, boolean or a list of them",
+                query, flag
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Parses the command line again with the flags of the saved query `name` in front.
+pub fn expand_saved_query(name: &str, config_file: Option<&Path>) -> AppResult<Cli> {
+    let (path, config) = Config::load(config_file)?;
+    let saved = config.query_args(name)?;
+    let mut given = env::args_os();
+    let program = given.next().unwrap_or_else(|| OsString::from("bzl-exec-log-analyzer"));
+    let args = std::iter::once(program)
+        .chain(saved.into_iter().map(OsString::from))
+        .chain(given);
+    Cli::try_parse_from(args).map_err(|e| {
+        AppError::Config(format!("saved query '{}' in {}: {}", name, path.display(), e.to_string().trim_end()))
+    })
+}
@@ -0,0 +1,182 @@
+//! The Build Event Protocol stream of an invocation, in its JSON form (`--build_event_json_file`).
+//!
+//! The binary form (`--build_event_binary_file`) needs the `build_event_stream.proto` definitions,
+//! which this tool does not bundle.
+
+use crate::{AppError, AppResult};
+use serde_json::Value as Json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// A build configuration announced in the stream.
+pub struct Configuration {
+    /// Output directory name, e.g. `k8-fastbuild`.
+    pub mnemonic: String,
+    pub platform: String,
+    pub is_tool: bool,
+}
+
+/// A top-level target (or aspect applied to one) as configured and completed.
+pub struct ConfiguredTarget {
+    /// Rule kind, e.g. `cc_library rule`.
+    pub kind: String,
+    pub configuration_id: String,
+    /// `None` until the target's completion event is seen.
+    pub success: Option<bool>,
+}
+
+#[derive(Default)]
+pub struct BuildEvents {
+    pub command: String,
+    pub bazel_version: String,
+    pub command_line: Vec<String>,
+    /// Options in effect, including those from rc files (`optionsParsed.cmdLine`).
+    pub options: Vec<String>,
+    pub exit_code: String,
+    pub wall_time_millis: Option<i64>,
+    /// Milliseconds since the Unix epoch at which the command started and finished.
+    pub start_millis: Option<i64>,
+    pub finish_millis: Option<i64>,
+    /// Keyed by configuration ID.
+    pub configurations: BTreeMap<String, Configuration>,
+    /// Keyed by label.
+    pub targets: BTreeMap<String, ConfiguredTarget>,
+    /// Keyed by `(aspect, label)`.
+    pub aspects: BTreeMap<(String, String), ConfiguredTarget>,
+}
+
+/// int64 fields are strings in the proto3 JSON mapping, but accept numbers too.
+fn as_i64(value: &Json) -> Option<i64> {
+    value.as_i64().or_else(|| value.as_str()?.parse().ok())
+}
+
+fn string(value: &Json) -> String {
+    value.as_str().unwrap_or_default().to_string()
+}
+
+impl BuildEvents {
+    pub fn load(path: &Path) -> AppResult<BuildEvents> {
+        let content = fs::read(path)?;
+        if content.iter().find(|b| !b.is_ascii_whitespace()).is_some_and(|&b| b != b'{') {
+            return Err(AppError::LogParsing(format!(
+                "{} looks like a binary Build Event Protocol file; only the JSON form written by \
+                 --build_event_json_file is supported",
+                path.display()
+            )));
+        }
+
+        let mut events = BuildEvents::default();
+        for (number, line) in String::from_utf8_lossy(&content).lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event: Json = serde_json::from_str(line).map_err(|e| {
+                AppError::LogParsing(format!("{} line {}: {}", path.display(), number + 1, e))
+            })?;
+            let id = &event["id"];
+
+            if let Some(started) = event.get("started") {
+                events.command = string(&started["command"]);
+                events.bazel_version = string(&started["buildToolVersion"]);
+                events.start_millis = as_i64(&started["startTimeMillis"]);
+            } else if let Some(command_line) = event.get("unstructuredCommandLine") {
+                events.command_line = command_line["args"]
+                    .as_array()
+                    .map(|args| args.iter().map(string).collect())
+                    .unwrap_or_default();
+            } else if let Some(options) = event.get("optionsParsed") {
+                events.options = options["cmdLine"]
+                    .as_array()
+                    .map(|options| options.iter().map(string).collect())
+                    .unwrap_or_default();
+            } else if let Some(configuration) = event.get("configuration") {
+                events.configurations.insert(
+                    string(&id["configuration"]["id"]),
+                    Configuration {
+                        mnemonic: string(&configuration["mnemonic"]),
+                        platform: string(&configuration["platformName"]),
+                        is_tool: configuration["isTool"].as_bool().unwrap_or(false),
+                    },
+                );
+            } else if let Some(configured) = event.get("configured") {
+                let target_id = &id["targetConfigured"];
+                let target = ConfiguredTarget {
+                    kind: string(&configured["targetKind"]),
+                    configuration_id: String::new(),
+                    success: None,
+                };
+                let label = string(&target_id["label"]);
+                match target_id["aspect"].as_str() {
+                    Some(aspect) => {
+                        events.aspects.insert((aspect.to_string(), label), target);
+                    }
+                    None => {
+                        events.targets.insert(label, target);
+                    }
+                }
+            } else if let Some(completed) = event.get("completed").or(event.get("aborted")) {
+                let target_id = &id["targetCompleted"];
+                let label = string(&target_id["label"]);
+                let target = match target_id["aspect"].as_str() {
+                    Some(aspect) => events.aspects.get_mut(&(aspect.to_string(), label)),
+                    None => events.targets.get_mut(&label),
+                };
+                if let Some(target) = target {
+                    target.configuration_id = string(&target_id["configuration"]["id"]);
+                    target.success = Some(completed["success"].as_bool().unwrap_or(false));
+                }
+            } else if let Some(finished) = event.get("finished") {
+                events.exit_code = string(&finished["exitCode"]["name"]);
+                events.finish_millis = as_i64(&finished["finishTimeMillis"]);
+            } else if let Some(metrics) = event.get("buildMetrics") {
+                events.wall_time_millis = as_i64(&metrics["timingMetrics"]["wallTimeInMs"]);
+            }
+        }
+        if events.wall_time_millis.is_none()
+            && let (Some(start), Some(finish)) = (events.start_millis, events.finish_millis)
+        {
+            events.wall_time_millis = Some(finish - start);
+        }
+        Ok(events)
+    }
+
+    /// Top-level targets whose completion event reported a failure, with aspects shown as
+    /// `label [aspect]`.
+    pub fn failed_targets(&self) -> impl Iterator<Item = (String, &ConfiguredTarget)> {
+        let targets = self.targets.iter().map(|(label, target)| (label.clone(), target));
+        let aspects = self
+            .aspects
+            .iter()
+            .map(|((aspect, label), target)| (format!("{} [{}]", label, aspect), target));
+        targets.chain(aspects).filter(|(_, target)| target.success == Some(false))
+    }
+
+    /// The last value given to the option `--<name>` (or `short`, e.g. `-j`), from the parsed
+    /// options when the stream has them and from the command line otherwise.
+    pub fn option(&self, name: &str, short: Option<&str>) -> Option<&str> {
+        let args = if self.options.is_empty() { &self.command_line } else { &self.options };
+        let long = format!("--{}", name);
+        let mut value = None;
+        for (i, arg) in args.iter().enumerate() {
+            let prefixes = std::iter::once(long.as_str()).chain(short);
+            for prefix in prefixes {
+                let Some(rest) = arg.strip_prefix(prefix) else { continue };
+                if rest.is_empty() {
+                    value = args.get(i + 1).map(String::as_str);
+                } else if let Some(rest) = rest.strip_prefix('=') {
+                    value = Some(rest);
+                } else if Some(prefix) == short {
+                    value = Some(rest);
+                }
+            }
+        }
+        value
+    }
+
+    /// The `--jobs` the build ran with, when it was given as a number rather than `auto` or a
+    /// `HOST_CPUS` expression.
+    pub fn job_limit(&self) -> Option<i64> {
+        self.option("jobs", Some("-j"))?.parse().ok()
+    }
+}
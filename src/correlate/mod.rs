@@ -0,0 +1,56 @@
+//! Other Bazel outputs that reports can join against the execution log.
+
+pub mod bep;
+pub mod profile;
+pub mod server;
+
+use crate::cli::ReportArgs;
+use crate::AppResult;
+use bep::BuildEvents;
+use profile::Profile;
+use server::ServerTimings;
+
+/// The optional inputs given next to the execution log, loaded once before the reports run.
+#[derive(Default)]
+pub struct SideInputs {
+    pub profile: Option<Profile>,
+    pub bep: Option<BuildEvents>,
+    pub server_timings: Option<ServerTimings>,
+}
+
+impl SideInputs {
+    pub fn load(args: &ReportArgs) -> AppResult<SideInputs> {
+        let profile = match args.profile.as_deref() {
+            Some(path) => {
+                let profile = Profile::load(path)?;
+                eprintln!("Read {} actions from the JSON profile.", profile.actions.len());
+                Some(profile)
+            }
+            None => None,
+        };
+        let bep = match args.bep.as_deref() {
+            Some(path) => {
+                let events = BuildEvents::load(path)?;
+                eprintln!(
+                    "Read {} configured targets from the Build Event Protocol file.",
+                    events.targets.len()
+                );
+                Some(events)
+            }
+            None => None,
+        };
+        let server_timings = match args.server_timings.as_deref() {
+            Some(path) => {
+                let timings = ServerTimings::load(path)?;
+                eprintln!("Read server timings for {} actions.", timings.len());
+                Some(timings)
+            }
+            None => None,
+        };
+        Ok(SideInputs {
+            profile,
+            bep,
+            server_timings,
+        })
+    }
+}
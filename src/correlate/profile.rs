@@ -0,0 +1,192 @@
+//! Bazel's JSON trace profile (`--profile`, usually `command.profile.gz`).
+//!
+//! Only the "action processing" events are read: one per executed or cache-checked action,
+//! spanning everything Bazel does for it, from input checking to output handling. They carry the
+//! target label when Bazel runs with `--experimental_profile_include_target_label`, the mnemonic
+//! in recent versions, and the primary output with `--experimental_profile_include_primary_output`.
+
+use crate::commands::analyze::start_seconds;
+use crate::proto::SpawnExec;
+use crate::{AppError, AppResult};
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// One action from the profile. Times are in microseconds since the start of the profile.
+pub struct ProfileAction {
+    pub description: String,
+    pub label: String,
+    pub mnemonic: String,
+    pub primary_output: String,
+    pub start_micros: f64,
+    pub duration_micros: f64,
+}
+
+pub struct Profile {
+    pub actions: Vec<ProfileAction>,
+    /// Time between the first and the last event of any kind.
+    pub wall_time_micros: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TraceFile {
+    Object {
+        #[serde(rename = "traceEvents")]
+        trace_events: Vec<TraceEvent>,
+    },
+    Array(Vec<TraceEvent>),
+}
+
+#[derive(Deserialize)]
+struct TraceEvent {
+    #[serde(default)]
+    cat: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    ph: String,
+    ts: Option<f64>,
+    dur: Option<f64>,
+    #[serde(default)]
+    args: TraceArgs,
+}
+
+#[derive(Deserialize, Default)]
+struct TraceArgs {
+    #[serde(default)]
+    target: String,
+    #[serde(default)]
+    mnemonic: String,
+    #[serde(default)]
+    out: String,
+}
+
+impl Profile {
+    /// Reads a profile, decompressing it first if it is gzipped.
+    pub fn load(path: &Path) -> AppResult<Profile> {
+        let raw = fs::read(path)?;
+        let content = if raw.starts_with(&[0x1f, 0x8b]) {
+            let mut decoded = Vec::new();
+            GzDecoder::new(raw.as_slice()).read_to_end(&mut decoded)?;
+            decoded
+        } else {
+            raw
+        };
+        let events = match serde_json::from_slice(&content) {
+            Ok(TraceFile::Object { trace_events }) | Ok(TraceFile::Array(trace_events)) => trace_events,
+            Err(e) => {
+                return Err(AppError::LogParsing(format!(
+                    "{} is not a JSON trace profile: {}",
+                    path.display(),
+                    e
+                )));
+            }
+        };
+
+        let mut first = f64::INFINITY;
+        let mut last = f64::NEG_INFINITY;
+        let mut actions = Vec::new();
+        for event in events {
+            let Some(start) = event.ts else { continue };
+            let duration = event.dur.unwrap_or(0.0);
+            first = first.min(start);
+            last = last.max(start + duration);
+            if event.ph == "X" && event.cat == "action processing" {
+                actions.push(ProfileAction {
+                    description: event.name,
+                    label: event.args.target,
+                    mnemonic: event.args.mnemonic,
+                    primary_output: event.args.out,
+                    start_micros: start,
+                    duration_micros: duration,
+                });
+            }
+        }
+        actions.sort_by(|a, b| a.start_micros.total_cmp(&b.start_micros));
+        Ok(Profile {
+            actions,
+            wall_time_micros: if last > first { last - first } else { 0.0 },
+        })
+    }
+}
+
+/// A profile action together with the spawn it ran.
+pub struct MatchedAction<'a> {
+    pub action: &'a ProfileAction,
+    pub spawn: &'a SpawnExec,
+}
+
+pub struct ProfileJoin<'a> {
+    pub matched: Vec<MatchedAction<'a>>,
+    pub unmatched_actions: usize,
+    pub unmatched_spawns: usize,
+}
+
+/// Pairs profile actions with spawns. An action is matched by its primary output when the
+/// profile has it, and otherwise with the earliest unmatched spawn of the same target (and
+/// mnemonic, when the profile has it). Each spawn is used at most once.
+pub fn join<'a>(profile: &'a Profile, spawns: &'a [SpawnExec]) -> ProfileJoin<'a> {
+    let mut order: Vec<usize> = (0..spawns.len()).collect();
+    order.sort_by(|&a, &b| {
+        let start = |i: usize| start_seconds(&spawns[i]).unwrap_or(0.0);
+        start(a).total_cmp(&start(b))
+    });
+
+    let mut by_output: HashMap<&str, usize> = HashMap::new();
+    let mut by_target: HashMap<(&str, &str), VecDeque<usize>> = HashMap::new();
+    let mut by_label: HashMap<&str, VecDeque<usize>> = HashMap::new();
+    for &i in &order {
+        let spawn = &spawns[i];
+        if let Some(output) = spawn.actual_outputs.first() {
+            by_output.entry(output.path.as_str()).or_insert(i);
+        }
+        by_target.entry((&spawn.target_label, &spawn.mnemonic)).or_default().push_back(i);
+        by_label.entry(&spawn.target_label).or_default().push_back(i);
+    }
+
+    let mut used = vec![false; spawns.len()];
+    let mut matched = Vec::new();
+    let mut unmatched_actions = 0;
+    for action in &profile.actions {
+        let by_path = by_output
+            .get(action.primary_output.as_str())
+            .copied()
+            .filter(|&i| !action.primary_output.is_empty() && !used[i]);
+        let candidate = by_path.or_else(|| {
+            if action.label.is_empty() {
+                return None;
+            }
+            let queue = if action.mnemonic.is_empty() {
+                by_label.get_mut(action.label.as_str())
+            } else {
+                by_target.get_mut(&(action.label.as_str(), action.mnemonic.as_str()))
+            }?;
+            while let Some(i) = queue.pop_front() {
+                if !used[i] {
+                    return Some(i);
+                }
+            }
+            None
+        });
+        match candidate {
+            Some(i) => {
+                used[i] = true;
+                matched.push(MatchedAction {
+                    action,
+                    spawn: &spawns[i],
+                });
+            }
+            None => unmatched_actions += 1,
+        }
+    }
+
+    ProfileJoin {
+        unmatched_spawns: used.iter().filter(|&&u| !u).count(),
+        matched,
+        unmatched_actions,
+    }
+}
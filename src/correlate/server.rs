@@ -0,0 +1,83 @@
+//! Server-side timings exported from a Remote Execution API backend, as CSV.
+//!
+//! The file needs a header row and a `digest` column holding the action digest hash (optionally
+//! as `hash/size`). The timing columns, in seconds, are all optional: `queue_seconds`,
+//! `input_fetch_seconds`, `execution_seconds`, `output_upload_seconds` and `total_seconds` (from
+//! queueing to completion; the sum of the phases when absent). A `worker` column is kept as is.
+
+use crate::proto::SpawnExec;
+use crate::{AppError, AppResult};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Deserialize)]
+struct Row {
+    digest: String,
+    #[serde(default)]
+    worker: String,
+    queue_seconds: Option<f64>,
+    input_fetch_seconds: Option<f64>,
+    execution_seconds: Option<f64>,
+    output_upload_seconds: Option<f64>,
+    total_seconds: Option<f64>,
+}
+
+/// What the server reported for one action. Missing phases are zero.
+pub struct ServerTiming {
+    pub worker: String,
+    pub queue_seconds: f64,
+    pub input_fetch_seconds: f64,
+    pub execution_seconds: f64,
+    pub output_upload_seconds: f64,
+    pub total_seconds: f64,
+}
+
+pub struct ServerTimings {
+    by_digest: HashMap<String, ServerTiming>,
+}
+
+impl ServerTimings {
+    pub fn load(path: &Path) -> AppResult<ServerTimings> {
+        let invalid = |e: csv::Error| AppError::LogParsing(format!("{}: {}", path.display(), e));
+        let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_path(path).map_err(invalid)?;
+        let mut by_digest = HashMap::new();
+        for row in reader.deserialize() {
+            let row: Row = row.map_err(invalid)?;
+            let phases = [
+                row.queue_seconds,
+                row.input_fetch_seconds,
+                row.execution_seconds,
+                row.output_upload_seconds,
+            ];
+            let timing = ServerTiming {
+                worker: row.worker,
+                queue_seconds: row.queue_seconds.unwrap_or(0.0),
+                input_fetch_seconds: row.input_fetch_seconds.unwrap_or(0.0),
+                execution_seconds: row.execution_seconds.unwrap_or(0.0),
+                output_upload_seconds: row.output_upload_seconds.unwrap_or(0.0),
+                total_seconds: row.total_seconds.unwrap_or_else(|| phases.iter().flatten().sum()),
+            };
+            by_digest.insert(normalize_digest(&row.digest), timing);
+        }
+        Ok(ServerTimings { by_digest })
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_digest.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_digest.is_empty()
+    }
+
+    /// The server's timing for a spawn, looked up by its action digest.
+    pub fn get(&self, spawn: &SpawnExec) -> Option<&ServerTiming> {
+        let digest = spawn.digest.as_ref()?;
+        self.by_digest.get(&digest.hash.to_ascii_lowercase())
+    }
+}
+
+fn normalize_digest(digest: &str) -> String {
+    digest.split('/').next().unwrap_or_default().to_ascii_lowercase()
+}
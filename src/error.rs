@@ -1,3 +1,5 @@
+use crate::parsers::{LimitExceeded, LogFormat, OtherOutput, ParseError};
+use std::path::PathBuf;
 use thiserror::Error;
 
 /// Define a convenient Result type
@@ -14,8 +16,39 @@ pub enum AppError {
     #[error("Log parsing error: {0}")]
     LogParsing(String),
 
+    #[error("Invalid {format} execution log: {error}")]
+    InvalidLog { format: LogFormat, error: ParseError },
+
+    #[error("{} is not an execution log but {output}", path.display())]
+    NotAnExecutionLog { path: PathBuf, output: OtherOutput },
+
     #[error("Analysis error: {0}")]
     Analysis(String),
+
+    #[error("Limit exceeded: {0}")]
+    LimitExceeded(#[from] LimitExceeded),
+
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    #[cfg(feature = "xlsx")]
+    #[error("Spreadsheet error: {0}")]
+    Xlsx(#[from] rust_xlsxwriter::XlsxError),
+
+    #[cfg(feature = "sql")]
+    #[error("SQL error: {0}")]
+    Sql(#[from] rusqlite::Error),
+}
+
+impl AppError {
+    /// A suggestion printed below the error, for errors whose cause is not obvious from the message.
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            AppError::InvalidLog { format, error } => Some(error.hint(*format)),
+            AppError::NotAnExecutionLog { output, .. } => Some(output.hint().to_string()),
+            _ => None,
+        }
+    }
 }
 
 impl From<anyhow::Error> for AppError {
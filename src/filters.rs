@@ -0,0 +1,306 @@
+//! Transformations and filters applied to the spawns of a log before any report sees them.
+
+use crate::cli::FilterArgs;
+use crate::commands::analyze::{is_coverage_spawn, output_bytes, runner_kind, total_time};
+use crate::proto::SpawnExec;
+use crate::render::{group_thousands, output_style, Column, Document};
+use std::borrow::Cow;
+use std::time::Duration;
+
+/// Applies the filters in `args`, noting what they removed in the document metadata and on stderr.
+pub fn apply(mut spawns: Vec<SpawnExec>, args: &FilterArgs, document: &mut Document) -> Vec<SpawnExec> {
+    if !args.raw_paths {
+        normalize_paths(&mut spawns);
+    }
+
+    if args.canonicalize_labels || !args.map_repo.is_empty() {
+        for spawn in &mut spawns {
+            if args.canonicalize_labels {
+                spawn.target_label = canonicalize_label(&spawn.target_label);
+            }
+            if !args.map_repo.is_empty() {
+                spawn.target_label = map_repo(&spawn.target_label, &args.map_repo);
+            }
+        }
+    }
+
+    if !args.include_bookkeeping && !args.bookkeeping_mnemonics.is_empty() {
+        let before = spawns.len();
+        spawns.retain(|s| !is_bookkeeping(s, args));
+        eprintln!(
+            "Excluded {} bookkeeping spawns ({}); pass --include-bookkeeping to keep them.",
+            before - spawns.len(),
+            args.bookkeeping_mnemonics.join(", ")
+        );
+        document.push_metadata(Column::integer("excluded_bookkeeping", "Excluded Bookkeeping Spawns"), before - spawns.len());
+    }
+
+    if args.exclude_coverage {
+        let before = spawns.len();
+        spawns.retain(|s| !is_coverage_spawn(s));
+        eprintln!("Excluded {} coverage post-processing spawns.", before - spawns.len());
+        document.push_metadata(Column::integer("excluded_coverage", "Excluded Coverage Spawns"), before - spawns.len());
+    }
+
+    if let Some(min_duration) = args.min_duration {
+        let before = spawns.len();
+        spawns.retain(|s| total_time(s) >= min_duration);
+        eprintln!("Excluded {} spawns shorter than {:?}.", before - spawns.len(), min_duration);
+        document.push_metadata(Column::integer("excluded_short", "Excluded Short Spawns"), before - spawns.len());
+    }
+
+    if let Some(min_bytes) = args.min_bytes {
+        let before = spawns.len();
+        spawns.retain(|s| spawn_bytes(s) >= min_bytes);
+        eprintln!("Excluded {} spawns with less than {} bytes of inputs and outputs.", before - spawns.len(), min_bytes);
+        document.push_metadata(Column::integer("excluded_small", "Excluded Small Spawns"), before - spawns.len());
+    }
+
+    if !args.runner.is_empty() || !args.exclude_runner.is_empty() {
+        let before = spawns.len();
+        spawns.retain(|s| {
+            let kind = runner_kind(s);
+            (args.runner.is_empty() || args.runner.contains(&kind)) && !args.exclude_runner.contains(&kind)
+        });
+        eprintln!("Excluded {} spawns by runner.", before - spawns.len());
+        document.push_metadata(Column::integer("excluded_runner", "Excluded Spawns by Runner"), before - spawns.len());
+    }
+
+    if !args.mnemonic.is_empty() {
+        let before = spawns.len();
+        spawns.retain(|s| args.mnemonic.contains(&s.mnemonic));
+        eprintln!("Excluded {} spawns with other mnemonics.", before - spawns.len());
+        document.push_metadata(Column::integer("excluded_mnemonic", "Excluded Spawns by Mnemonic"), before - spawns.len());
+    }
+
+    if let Some(pattern) = &args.status {
+        let before = spawns.len();
+        spawns.retain(|s| {
+            let status = if s.status.is_empty() { "SUCCESS" } else { s.status.as_str() };
+            glob_matches(&pattern.to_ascii_uppercase(), &status.to_ascii_uppercase())
+        });
+        eprintln!("Excluded {} spawns whose status does not match '{}'.", before - spawns.len(), pattern);
+        document.push_metadata(Column::integer("excluded_status", "Excluded Spawns by Status"), before - spawns.len());
+    }
+
+    if !args.exit_code.is_empty() {
+        let before = spawns.len();
+        spawns.retain(|s| args.exit_code.contains(&s.exit_code));
+        eprintln!("Excluded {} spawns with other exit codes.", before - spawns.len());
+        document.push_metadata(Column::integer("excluded_exit_code", "Excluded Spawns by Exit Code"), before - spawns.len());
+    }
+
+    if args.hits_only || args.misses_only {
+        let before = spawns.len();
+        spawns.retain(|s| s.cache_hit == args.hits_only);
+        let excluded = if args.hits_only { "cache misses" } else { "cache hits" };
+        eprintln!("Excluded {} {}.", before - spawns.len(), excluded);
+        document.push_metadata(Column::integer("excluded_cache_status", "Excluded Spawns by Cache Status"), before - spawns.len());
+    }
+    spawns
+}
+
+/// Whether a spawn is one of the trivial filesystem actions named by `--bookkeeping-mnemonics`.
+pub fn is_bookkeeping(spawn: &SpawnExec, args: &FilterArgs) -> bool {
+    args.bookkeeping_mnemonics.contains(&spawn.mnemonic)
+}
+
+/// The bookkeeping spawns of a log, summed up so the summary can mention them in one line
+/// instead of a row per mnemonic.
+#[derive(Default)]
+pub struct BookkeepingRollup {
+    pub count: usize,
+    pub total_time: Duration,
+}
+
+pub fn bookkeeping_rollup(spawns: &[SpawnExec], args: &FilterArgs) -> BookkeepingRollup {
+    let mut rollup = BookkeepingRollup::default();
+    for spawn in spawns.iter().filter(|s| is_bookkeeping(s, args)) {
+        rollup.count += 1;
+        rollup.total_time += total_time(spawn);
+    }
+    rollup
+}
+
+impl BookkeepingRollup {
+    /// `12,403 bookkeeping actions totaling 3.2s`, or `None` if there were none.
+    pub fn summary_line(&self, included: bool) -> Option<String> {
+        if self.count == 0 {
+            return None;
+        }
+        let line = format!(
+            "{} bookkeeping actions totaling {}s",
+            group_thousands(self.count as u64),
+            output_style().numbers.apply(&format!("{:.1}", self.total_time.as_secs_f64()))
+        );
+        Some(if included {
+            line
+        } else {
+            format!("{}, left out of the other tables (--include-bookkeeping keeps them).", line)
+        })
+    }
+}
+
+/// Compilation modes, the first part of an output directory name after the CPU.
+const COMPILATION_MODES: &[&str] = &["fastbuild", "dbg", "opt"];
+
+/// The output directory name without the CPU it starts with: `k8-fastbuild` and
+/// `x64_windows-fastbuild` become `fastbuild`, `darwin_arm64-opt-exec-ST-1a2b3c` becomes
+/// `opt-exec-ST-1a2b3c`. Transition hashes (`ST-...`) are kept as they tell configurations apart.
+/// Names without a compilation mode are returned unchanged.
+pub fn normalize_output_directory(name: &str) -> &str {
+    let mut offset = 0;
+    for part in name.split('-') {
+        if COMPILATION_MODES.contains(&part) {
+            return &name[offset..];
+        }
+        offset += part.len() + 1;
+    }
+    name
+}
+
+/// Host CPU names Bazel uses on each operating system, with the architecture they build for.
+const HOST_CPUS: &[(&str, &str)] = &[
+    ("k8", "x86_64"),
+    ("x64_windows", "x86_64"),
+    ("darwin_x86_64", "x86_64"),
+    ("darwin", "x86_64"),
+    ("aarch64", "arm64"),
+    ("arm64_windows", "arm64"),
+    ("darwin_arm64", "arm64"),
+];
+
+/// The output directory name with a host CPU replaced by its architecture: `k8-fastbuild` and
+/// `x64_windows-fastbuild` become `x86_64-fastbuild`. Other CPUs, such as
+/// `android-arm64-v8a-fastbuild`, are kept, so outputs built for two CPUs stay apart.
+pub fn fold_host_cpu(name: &str) -> Cow<'_, str> {
+    let mode = normalize_output_directory(name);
+    let Some(cpu) = name[..name.len() - mode.len()].strip_suffix('-') else {
+        return Cow::Borrowed(name);
+    };
+    match HOST_CPUS.iter().find(|(host, _)| *host == cpu) {
+        Some((_, architecture)) => Cow::Owned(format!("{}-{}", architecture, mode)),
+        None => Cow::Borrowed(name),
+    }
+}
+
+/// `path` with `/` as the only separator and its `bazel-out` directory name passed through
+/// `directory`.
+fn map_output_directory<'a>(path: &'a str, directory: impl Fn(&'a str) -> Cow<'a, str>) -> String {
+    let Some(start) = path.find("bazel-out/").map(|i| i + "bazel-out/".len()) else {
+        return path.to_string();
+    };
+    let tail = &path[start..];
+    let name = tail.split('/').next().unwrap_or(tail);
+    format!("{}{}{}", &path[..start], directory(name), &tail[name.len()..])
+}
+
+/// A path with `/` as the only separator and a normalized `bazel-out` directory (see
+/// [`normalize_output_directory`]), so logs from different operating systems and CPUs name the
+/// same outputs alike.
+pub fn normalize_path(path: &str) -> String {
+    map_output_directory(&path.replace('\\', "/"), |name| Cow::Borrowed(normalize_output_directory(name)))
+}
+
+/// A path with `/` as the only separator and a host CPU in its `bazel-out` directory folded (see
+/// [`fold_host_cpu`]). Unlike [`normalize_path`] it keeps the CPU of cross-compiled outputs, so it
+/// tells apart the actions of one log while naming them alike on every build machine.
+pub fn normalize_path_keeping_cpu(path: &str) -> String {
+    map_output_directory(&path.replace('\\', "/"), fold_host_cpu)
+}
+
+/// Normalizes the input and output paths of every spawn with [`normalize_path_keeping_cpu`], the
+/// form [`spawn_id`](crate::rows::spawn_id) hashes, so reports give the same IDs as exports and
+/// keep the actions of a target built for two CPUs apart.
+pub fn normalize_paths(spawns: &mut [SpawnExec]) {
+    for spawn in spawns {
+        for file in spawn.inputs.iter_mut().chain(&mut spawn.actual_outputs) {
+            file.path = normalize_path_keeping_cpu(&file.path);
+        }
+        for path in &mut spawn.listed_outputs {
+            *path = normalize_path_keeping_cpu(path);
+        }
+    }
+}
+
+/// Input bytes as reported in the spawn metrics, plus the digest sizes of the outputs.
+fn spawn_bytes(spawn: &SpawnExec) -> i64 {
+    spawn.metrics.as_ref().map_or(0, |m| m.input_bytes) + output_bytes(spawn)
+}
+
+/// Matches `text` against a glob where `*` stands for any run of characters and `?` for one.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it is currently matched up to.
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// The apparent name of a bzlmod canonical repository name: `rules_foo~1.2`, `rules_foo~` and
+/// `rules_foo+` become `rules_foo`, and repositories created by module extensions or repository
+/// rules, such as `rules_python~~pip~pypi`, `rules_python++pip+pypi` or `+_repo_rules+foo`, become
+/// the name they were created with (`pypi`, `foo`).
+fn apparent_repo_name(canonical: &str) -> &str {
+    let parts: Vec<&str> = canonical.split(['~', '+']).filter(|part| !part.is_empty()).collect();
+    // The root module's canonical name is empty, so its repositories start with a separator.
+    let root_module = canonical.starts_with(['~', '+']);
+    match parts.as_slice() {
+        [] => canonical,
+        [.., repo] if root_module => repo,
+        [module] | [module, _] => module,
+        [.., repo] => repo,
+    }
+}
+
+/// Rewrites `@@canonical//pkg:name` labels to `@apparent//pkg:name`, and `@@//pkg:name` to
+/// `//pkg:name`. Other labels are returned unchanged.
+fn canonicalize_label(label: &str) -> String {
+    let Some(rest) = label.strip_prefix("@@") else {
+        return label.to_string();
+    };
+    let (repo, target) = rest.split_once("//").unwrap_or((rest, ""));
+    if repo.is_empty() {
+        format!("//{}", target)
+    } else if label.contains("//") {
+        format!("@{}//{}", apparent_repo_name(repo), target)
+    } else {
+        format!("@{}", apparent_repo_name(repo))
+    }
+}
+
+/// Renames the repository of a label by the first matching `(from, to)` pair.
+fn map_repo(label: &str, mapping: &[(String, String)]) -> String {
+    let trimmed = label.trim_start_matches('@');
+    let (repo, target) = match trimmed.split_once("//") {
+        Some((repo, target)) => (repo, Some(target)),
+        None => (trimmed, None),
+    };
+    match (mapping.iter().find(|(from, _)| from == repo), target) {
+        (Some((_, to)), Some(target)) if to.is_empty() => format!("//{}", target),
+        (Some((_, to)), Some(target)) => format!("@{}//{}", to, target),
+        (Some((_, to)), None) => format!("@{}", to),
+        (None, _) => label.to_string(),
+    }
+}
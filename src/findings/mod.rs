@@ -0,0 +1,190 @@
+//! Findings: problems attributed to a target that code-scanning tools can annotate.
+//!
+//! Each check produces at most one finding per rule and target, counting the affected actions.
+
+mod sarif;
+
+pub use self::sarif::write_sarif;
+
+use crate::proto::SpawnExec;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Note,
+    Warning,
+    Error,
+}
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Note => "note",
+            Level::Warning => "warning",
+            Level::Error => "error",
+        }
+    }
+}
+
+/// A check that findings are reported against.
+pub struct Rule {
+    /// Stable identifier, e.g. `hermeticity/absolute-path`.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub level: Level,
+}
+
+pub const ABSOLUTE_PATH: Rule = Rule {
+    id: "hermeticity/absolute-path",
+    name: "AbsolutePathInCommandLine",
+    description: "The command line references an absolute path outside the execution root, so the action depends on the state of the machine it runs on.",
+    level: Level::Warning,
+};
+
+pub const UNSANDBOXED: Rule = Rule {
+    id: "hermeticity/unsandboxed",
+    name: "UnsandboxedExecution",
+    description: "The action ran locally without a sandbox, so undeclared inputs go unnoticed.",
+    level: Level::Note,
+};
+
+/// Every rule, in reporting order.
+pub const RULES: &[&Rule] = &[&ABSOLUTE_PATH, &UNSANDBOXED];
+
+pub struct Finding {
+    pub rule: &'static Rule,
+    pub target_label: String,
+    pub message: String,
+    /// Number of actions of the target that triggered the rule.
+    pub action_count: usize,
+}
+
+/// Whether a path starts with a Windows drive (`C:\Users`, `C:/Users`) or is a UNC path
+/// (`\\server\share`).
+fn is_windows_absolute_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    let drive = bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && matches!(bytes[2], b'/' | b'\\');
+    drive || path.starts_with("\\\\")
+}
+
+/// Whether a path is absolute on Unix (`/usr/include`) or Windows.
+pub(crate) fn is_absolute_path(path: &str) -> bool {
+    (path.starts_with('/') && path.len() > 1) || is_windows_absolute_path(path)
+}
+
+/// Whether a program is a Windows executable or script, by its extension or drive letter.
+fn is_windows_program(program: &str) -> bool {
+    let lowercase = program.to_ascii_lowercase();
+    [".exe", ".bat", ".cmd"].iter().any(|extension| lowercase.ends_with(extension)) || is_windows_absolute_path(program)
+}
+
+/// The absolute path in an argument of a Windows tool. Arguments starting with `/` are options
+/// there, such as MSVC's `/nologo`; options carry paths right after their name (`/IC:\sdk`,
+/// `/LIBPATH:C:\lib`, `--out=C:\dist`).
+fn windows_absolute_path(arg: &str) -> Option<&str> {
+    let is_option_name = |prefix: &str| {
+        let name = prefix.trim_end_matches([':', '=']);
+        name.starts_with(['/', '-']) && name[1..].chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    arg.char_indices()
+        .map(|(i, _)| i)
+        .find(|&i| is_windows_absolute_path(&arg[i..]) && (i == 0 || is_option_name(&arg[..i])))
+        .map(|i| &arg[i..])
+}
+
+/// Paths that are absolute but not machine-specific: the execroot and sandbox, device files and
+/// the shells Bazel itself runs genrules with, including MSYS2 bash and `cmd.exe` on Windows.
+pub(crate) fn is_expected_absolute_path(path: &str) -> bool {
+    const EXPECTED_PREFIXES: &[&str] = &["/dev/", "/proc/self/", "/bin/bash", "/bin/sh", "/usr/bin/env"];
+    const EXPECTED_SEGMENTS: &[&str] = &["/execroot/", "/sandbox/", "/bazel-out/", "/external/"];
+    const EXPECTED_WINDOWS_SEGMENTS: &[&str] = &["/msys64/usr/bin/", "/windows/system32/"];
+    let path = path.replace('\\', "/");
+    let lowercase = path.to_ascii_lowercase();
+    EXPECTED_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+        || EXPECTED_SEGMENTS.iter().any(|segment| path.contains(segment))
+        || EXPECTED_WINDOWS_SEGMENTS.iter().any(|segment| lowercase.contains(segment))
+}
+
+/// Absolute paths in a command line: arguments that are absolute paths, and `--flag=/path`
+/// values. Windows tools are handled by [`windows_absolute_path`].
+fn absolute_paths(spawn: &SpawnExec) -> Box<dyn Iterator<Item = &str> + '_> {
+    let args = spawn.command_args.iter().map(String::as_str);
+    if spawn.command_args.first().is_some_and(|program| is_windows_program(program)) {
+        return Box::new(
+            args.skip(1)
+                .filter_map(windows_absolute_path)
+                .filter(|path| !is_expected_absolute_path(path)),
+        );
+    }
+    Box::new(
+        args.map(|arg| arg.split_once('=').map_or(arg, |(_, value)| value))
+            .filter(|path| is_absolute_path(path) && !is_expected_absolute_path(path)),
+    )
+}
+
+#[derive(Default)]
+struct TargetFindings<'a> {
+    actions: usize,
+    examples: Vec<&'a str>,
+}
+
+/// Runs the hermeticity checks over the executed spawns of a log.
+pub fn hermeticity_findings(spawns: &[SpawnExec]) -> Vec<Finding> {
+    let mut absolute: BTreeMap<&str, TargetFindings> = BTreeMap::new();
+    let mut unsandboxed: BTreeMap<&str, TargetFindings> = BTreeMap::new();
+
+    for spawn in spawns.iter().filter(|s| !s.cache_hit) {
+        let paths: Vec<&str> = absolute_paths(spawn).collect();
+        if !paths.is_empty() {
+            let entry = absolute.entry(&spawn.target_label).or_default();
+            entry.actions += 1;
+            for path in paths {
+                if entry.examples.len() < 3 && !entry.examples.contains(&path) {
+                    entry.examples.push(path);
+                }
+            }
+        }
+        if spawn.runner == "local" {
+            let entry = unsandboxed.entry(&spawn.target_label).or_default();
+            entry.actions += 1;
+            if entry.examples.is_empty() {
+                entry.examples.push(&spawn.mnemonic);
+            }
+        }
+    }
+
+    let mut findings = Vec::new();
+    for (label, found) in absolute {
+        findings.push(Finding {
+            rule: &ABSOLUTE_PATH,
+            target_label: label.to_string(),
+            message: format!(
+                "{} action(s) reference absolute paths outside the execution root, e.g. {}.",
+                found.actions,
+                found.examples.join(", ")
+            ),
+            action_count: found.actions,
+        });
+    }
+    for (label, found) in unsandboxed {
+        findings.push(Finding {
+            rule: &UNSANDBOXED,
+            target_label: label.to_string(),
+            message: format!(
+                "{} action(s) ran locally without a sandbox (first mnemonic: {}).",
+                found.actions,
+                found.examples.join(", ")
+            ),
+            action_count: found.actions,
+        });
+    }
+    findings
+}
+
+/// The package directory of a main-repository label (`//foo/bar:baz` -> `foo/bar`), or `None` for
+/// labels in external repositories and spawns without a label.
+pub fn package_path(label: &str) -> Option<&str> {
+    let package = label.trim_start_matches('@').strip_prefix("//")?;
+    Some(package.split_once(':').map_or(package, |(package, _)| package))
+}
@@ -0,0 +1,76 @@
+use super::{package_path, Finding, RULES};
+use serde_json::json;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Writes findings as a SARIF 2.1.0 log. Findings in main-repository packages point at the
+/// package's BUILD file, relative to the current directory (`BUILD.bazel` if it exists there,
+/// `BUILD` otherwise) so code-scanning UIs can annotate it; the label is always given as a logical
+/// location.
+pub fn write_sarif(findings: &[Finding], out: &mut dyn Write) -> io::Result<()> {
+    let rules: Vec<_> = RULES
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id,
+                "name": rule.name,
+                "shortDescription": { "text": rule.description },
+                "defaultConfiguration": { "level": rule.level.as_str() },
+            })
+        })
+        .collect();
+
+    let results: Vec<_> = findings
+        .iter()
+        .map(|finding| {
+            // Spawns without a target label (e.g. workspace status actions) have no location.
+            let mut locations = Vec::new();
+            if !finding.target_label.is_empty() {
+                let mut location = json!({
+                    "logicalLocations": [{ "fullyQualifiedName": finding.target_label, "kind": "module" }],
+                });
+                if let Some(package) = package_path(&finding.target_label) {
+                    location["physicalLocation"] = json!({
+                        "artifactLocation": { "uri": build_file(package), "uriBaseId": "%SRCROOT%" },
+                    });
+                }
+                locations.push(location);
+            }
+            json!({
+                "ruleId": finding.rule.id,
+                "ruleIndex": RULES.iter().position(|rule| rule.id == finding.rule.id),
+                "level": finding.rule.level.as_str(),
+                "message": { "text": finding.message },
+                "locations": locations,
+                "partialFingerprints": { "targetLabel": finding.target_label },
+                "properties": { "actionCount": finding.action_count },
+            })
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+    serde_json::to_writer_pretty(&mut *out, &log).map_err(io::Error::from)?;
+    writeln!(out)
+}
+
+fn build_file(package: &str) -> String {
+    let directory = if package.is_empty() { String::new() } else { format!("{}/", package) };
+    if Path::new(package).join("BUILD.bazel").is_file() {
+        format!("{}BUILD.bazel", directory)
+    } else {
+        format!("{}BUILD", directory)
+    }
+}
@@ -1,15 +1,51 @@
 pub mod proto;
 pub mod cli;
 pub mod commands;
+pub mod config;
+pub mod correlate;
 pub mod error;
+pub mod filters;
+pub mod findings;
+pub mod matching;
+pub mod pager;
+pub mod parsers;
+pub mod render;
+pub mod rows;
+pub mod warnings;
+pub mod writer;
 
 pub use error::{AppError, AppResult};
 pub use cli::Cli;
 
 use clap::Parser;
+use cli::Command;
 
 /// Main library entry point
 pub fn run() -> AppResult<()> {
-    let cli = Cli::parse();
-    commands::analyze::run_analyze(cli)
-}
\ No newline at end of file
+    let mut cli = Cli::parse();
+    if let Some(name) = cli.run.take() {
+        cli = config::expand_saved_query(&name, cli.config_file.as_deref())?;
+    }
+    render::set_output_style(render::OutputStyle {
+        numbers: cli.number_locale,
+        ascii: cli.ascii,
+    });
+    match cli.command.take() {
+        Some(Command::Diff(args)) => commands::diff::run_diff(args),
+        Some(Command::Ab(args)) => commands::ab::run_ab(args),
+        Some(Command::Show(args)) => commands::show::run_show(args),
+        Some(Command::Export(args)) => commands::export::run_export(args),
+        Some(Command::Watch(args)) => commands::watch::run_watch(args),
+        Some(Command::SuggestRc(args)) => commands::suggest::run_suggest_rc(args),
+        Some(Command::Stats(args)) => commands::stats::run_stats(args),
+        Some(Command::Corpus(args)) => commands::corpus::run_corpus(args),
+        Some(Command::Doctor(args)) => commands::doctor::run_doctor(args),
+        Some(Command::Convert(args)) => commands::convert::run_convert(args),
+        Some(Command::Merge(args)) => commands::merge::run_merge(args),
+        Some(Command::Split(args)) => commands::split::run_split(args),
+        Some(Command::Report(args)) => commands::report::run_report(*args),
+        #[cfg(feature = "sql")]
+        Some(Command::Sql(args)) => commands::sql::run_sql(args),
+        None => commands::analyze::run_analyze(cli),
+    }
+}
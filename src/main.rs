@@ -1,5 +1,15 @@
-use bzl_exec_log_parser::{run, AppResult};
+use bzl_exec_log_parser::run;
+use std::process::ExitCode;
 
-fn main() -> AppResult<()> {
-    run()
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            if let Some(hint) = e.hint() {
+                eprintln!("Hint: {}", hint);
+            }
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,104 @@
+//! Pairs the spawns of two logs of the same build, for the diff and any other comparison of logs.
+//!
+//! A spawn is identified by its target label, mnemonic and primary output path. When that finds
+//! no counterpart, two looser keys are tried in turn: the output path without its configuration
+//! directory (a changed transition hash or compilation mode), then mnemonic and output path alone
+//! (a renamed target or a canonicalized repository name). Paths are compared as given, so
+//! normalize both logs the same way first (see [`filters::normalize_paths`](crate::filters::normalize_paths)).
+
+use crate::proto::SpawnExec;
+use crate::rows::primary_output;
+use std::collections::{HashMap, HashSet};
+
+/// Which key paired two spawns, from the strictest to the loosest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    /// Same target label, mnemonic and primary output path.
+    Exact,
+    /// Same target label and mnemonic, and the same primary output path apart from the
+    /// configuration directory under `bazel-out/`.
+    Configuration,
+    /// Same mnemonic and primary output path, different target label.
+    Output,
+}
+
+impl MatchKind {
+    const ALL: [MatchKind; 3] = [MatchKind::Exact, MatchKind::Configuration, MatchKind::Output];
+
+    /// The key of `spawn` at this level, or `None` if it has nothing to match on.
+    fn key(self, spawn: &SpawnExec) -> Option<(&str, &str, &str)> {
+        let output = primary_output(spawn).unwrap_or("");
+        match self {
+            MatchKind::Exact => Some((&spawn.target_label, &spawn.mnemonic, output)),
+            MatchKind::Configuration => {
+                without_configuration(output).map(|path| (spawn.target_label.as_str(), spawn.mnemonic.as_str(), path))
+            }
+            MatchKind::Output => (!output.is_empty()).then_some(("", spawn.mnemonic.as_str(), output)),
+        }
+    }
+}
+
+/// A spawn of the old log and its counterpart in the new one.
+#[derive(Debug, Clone, Copy)]
+pub struct MatchedPair<'a> {
+    pub old: &'a SpawnExec,
+    pub new: &'a SpawnExec,
+    pub kind: MatchKind,
+}
+
+/// Pairs the spawns of `old` and `new`, in the order of `new`. Every spawn is in at most one
+/// pair; spawns sharing a key (retries, test shards) are paired in log order.
+pub fn match_spawns<'a>(old: &'a [SpawnExec], new: &'a [SpawnExec]) -> Vec<MatchedPair<'a>> {
+    let mut old_left: Vec<usize> = (0..old.len()).collect();
+    let mut new_left: Vec<usize> = (0..new.len()).collect();
+    let mut pairs: Vec<(usize, usize, MatchKind)> = Vec::new();
+    for kind in MatchKind::ALL {
+        let mut old_by_key: HashMap<(&str, &str, &str), Vec<usize>> = HashMap::new();
+        for &i in old_left.iter().rev() {
+            if let Some(key) = kind.key(&old[i]) {
+                old_by_key.entry(key).or_default().push(i);
+            }
+        }
+        let mut paired = vec![false; old.len()];
+        new_left.retain(|&j| {
+            let Some(i) = kind.key(&new[j]).and_then(|key| old_by_key.get_mut(&key)).and_then(Vec::pop) else {
+                return true;
+            };
+            paired[i] = true;
+            pairs.push((i, j, kind));
+            false
+        });
+        old_left.retain(|&i| !paired[i]);
+    }
+    pairs.sort_unstable_by_key(|&(_, j, _)| j);
+    pairs
+        .into_iter()
+        .map(|(i, j, kind)| MatchedPair {
+            old: &old[i],
+            new: &new[j],
+            kind,
+        })
+        .collect()
+}
+
+/// The spawns of `new` that are in none of `pairs`, in log order.
+pub fn only_new<'a>(new: &'a [SpawnExec], pairs: &[MatchedPair<'a>]) -> Vec<&'a SpawnExec> {
+    unpaired(new, pairs.iter().map(|pair| pair.new))
+}
+
+/// The spawns of `old` that are in none of `pairs`, in log order.
+pub fn only_old<'a>(old: &'a [SpawnExec], pairs: &[MatchedPair<'a>]) -> Vec<&'a SpawnExec> {
+    unpaired(old, pairs.iter().map(|pair| pair.old))
+}
+
+fn unpaired<'a>(spawns: &'a [SpawnExec], paired: impl Iterator<Item = &'a SpawnExec>) -> Vec<&'a SpawnExec> {
+    let paired: HashSet<*const SpawnExec> = paired.map(|spawn| spawn as *const SpawnExec).collect();
+    spawns.iter().filter(|spawn| !paired.contains(&(*spawn as *const SpawnExec))).collect()
+}
+
+/// The part of an output path after `bazel-out/<configuration>/`, or `None` if it is not under
+/// `bazel-out/`.
+fn without_configuration(path: &str) -> Option<&str> {
+    let (_, rest) = path.split_once("bazel-out/")?;
+    rest.split_once('/').map(|(_, rest)| rest)
+}
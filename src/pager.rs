@@ -0,0 +1,45 @@
+//! Shows long reports through a pager when they are written to a terminal.
+
+use crate::AppResult;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Pager used when `$PAGER` is not set; `-R` keeps any colors intact.
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Writes `output` to stdout, through `$PAGER` (or `less -R`) when stdout is a terminal and the
+/// output is taller than it. Falls back to writing directly if the pager cannot be started.
+pub fn write_paged(output: &[u8], no_pager: bool) -> AppResult<()> {
+    if !no_pager && io::stdout().is_terminal() && exceeds_terminal(output) {
+        let pager = env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+        let mut words = pager.split_whitespace();
+        if let Some(program) = words.next().filter(|&program| program != "cat") {
+            match Command::new(program).args(words).stdin(Stdio::piped()).spawn() {
+                Ok(mut child) => {
+                    if let Some(mut stdin) = child.stdin.take() {
+                        // The user may quit the pager before reading everything.
+                        match stdin.write_all(output) {
+                            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+                            result => result?,
+                        }
+                    }
+                    child.wait()?;
+                    return Ok(());
+                }
+                Err(e) => eprintln!("Could not start the pager '{}': {}", pager, e),
+            }
+        }
+    }
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(output)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn exceeds_terminal(output: &[u8]) -> bool {
+    match crossterm::terminal::size() {
+        Ok((_, height)) if height > 0 => output.iter().filter(|&&b| b == b'\n').count() >= usize::from(height),
+        _ => false,
+    }
+}
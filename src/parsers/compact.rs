@@ -0,0 +1,301 @@
+use super::framing::{Entries, ParseError};
+use super::limits::{Budget, LimitExceeded};
+use crate::proto::exec_log_entry::{self as compact, Type as CompactEntryType};
+use crate::proto::{ExecLogEntry, SpawnExec};
+use crate::warnings::Warnings;
+use prost::Message;
+use std::collections::{HashMap, HashSet};
+
+/// An enum to hold different types of compact log entries for reconstruction.
+enum StoredEntry {
+    File(compact::File),
+    Directory(compact::Directory),
+    InputSet(compact::InputSet),
+}
+
+/// Parses the compact execution log format and reconstructs SpawnExec messages, up to the first
+/// entry that cannot be read, and the error for that entry.
+pub(super) fn parse_compact_entries(
+    content: &[u8],
+    budget: &mut Budget,
+    warnings: &mut Warnings,
+) -> Result<(Vec<SpawnExec>, Option<ParseError>), LimitExceeded> {
+    let mut stored_entries: HashMap<u32, StoredEntry> = HashMap::new();
+    let mut reconstructed_spawns = Vec::new();
+
+    for framed in Entries::new(content) {
+        let framed = match framed {
+            Ok(framed) => framed,
+            Err(e) => return Ok((reconstructed_spawns, Some(e))),
+        };
+        budget.entry(&framed)?;
+        let entry = match ExecLogEntry::decode(framed.message) {
+            Ok(entry) => entry,
+            Err(e) => return Ok((reconstructed_spawns, Some(framed.error(e)))),
+        };
+        let id = entry.id;
+
+        match entry.r#type {
+            Some(CompactEntryType::Spawn(s)) => {
+                let spawn_exec = reconstruct_spawn_exec(s, &stored_entries, warnings);
+                // Input sets are flattened into every spawn, so a spawn can be far larger than its entry.
+                budget.charge(spawn_exec.encoded_len(), framed.offset)?;
+                reconstructed_spawns.push(spawn_exec);
+            }
+            Some(CompactEntryType::File(f)) if id != 0 => {
+                budget.charge(framed.message.len(), framed.offset)?;
+                stored_entries.insert(id, StoredEntry::File(f));
+            }
+            Some(CompactEntryType::Directory(d)) if id != 0 => {
+                budget.charge(framed.message.len(), framed.offset)?;
+                stored_entries.insert(id, StoredEntry::Directory(d));
+            }
+            Some(CompactEntryType::InputSet(set)) if id != 0 => {
+                budget.charge(framed.message.len(), framed.offset)?;
+                stored_entries.insert(id, StoredEntry::InputSet(set));
+            }
+            // The invocation entry carries nothing the reports use, but is expected.
+            Some(CompactEntryType::Invocation(_)) => {}
+            Some(other) => warnings.push(
+                "ignored_entry",
+                "Compact log entries that are not reconstructed (symlinks, runfiles trees, entries without an ID)",
+                format!("{} entry {}", entry_type_name(&other), id),
+            ),
+            None => warnings.push("empty_entry", "Compact log entries without a type", format!("entry {}", id)),
+        }
+    }
+    Ok((reconstructed_spawns, None))
+}
+
+/// Decodes the entries of a decompressed compact log as they are, up to the first entry that
+/// cannot be read, and the error for that entry.
+pub(super) fn decode_entries(
+    content: &[u8],
+    budget: &mut Budget,
+) -> Result<(Vec<ExecLogEntry>, Option<ParseError>), LimitExceeded> {
+    let mut entries = Vec::new();
+    for framed in Entries::new(content) {
+        let framed = match framed {
+            Ok(framed) => framed,
+            Err(e) => return Ok((entries, Some(e))),
+        };
+        budget.entry(&framed)?;
+        match ExecLogEntry::decode(framed.message) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => return Ok((entries, Some(framed.error(e)))),
+        }
+        budget.charge(framed.message.len(), framed.offset)?;
+    }
+    Ok((entries, None))
+}
+
+/// Counts the entries of each type, in the order the types first appear, up to the first entry
+/// that cannot be read, and returns the invocation entry if there is one.
+pub(super) fn count_entries(content: &[u8]) -> (Vec<(&'static str, u64)>, Option<compact::Invocation>) {
+    let mut counts: Vec<(&'static str, u64)> = Vec::new();
+    let mut invocation = None;
+    for framed in Entries::new(content) {
+        let Ok(framed) = framed else { break };
+        let Ok(entry) = ExecLogEntry::decode(framed.message) else { break };
+        let name = entry.r#type.as_ref().map_or("(empty)", entry_type_name);
+        match counts.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((name, 1)),
+        }
+        if let Some(CompactEntryType::Invocation(i)) = entry.r#type {
+            invocation = Some(i);
+        }
+    }
+    (counts, invocation)
+}
+
+fn entry_type_name(entry: &CompactEntryType) -> &'static str {
+    match entry {
+        CompactEntryType::Invocation(_) => "Invocation",
+        CompactEntryType::File(_) => "File",
+        CompactEntryType::Directory(_) => "Directory",
+        CompactEntryType::UnresolvedSymlink(_) => "UnresolvedSymlink",
+        CompactEntryType::InputSet(_) => "InputSet",
+        CompactEntryType::Spawn(_) => "Spawn",
+        CompactEntryType::SymlinkAction(_) => "SymlinkAction",
+        CompactEntryType::SymlinkEntrySet(_) => "SymlinkEntrySet",
+        CompactEntryType::RunfilesTree(_) => "RunfilesTree",
+    }
+}
+
+/// Converts a compact `Spawn` entry into a verbose `SpawnExec` using stored file/dir info.
+fn reconstruct_spawn_exec(
+    spawn: compact::Spawn,
+    stored_entries: &HashMap<u32, StoredEntry>,
+    warnings: &mut Warnings,
+) -> SpawnExec {
+    let mut actual_outputs = Vec::new();
+    for output in spawn.outputs {
+        let entry = match output.r#type {
+            Some(compact::output::Type::OutputId(id)) => match stored_entries.get(&id) {
+                Some(entry) => Some(entry),
+                None => {
+                    warnings.push(
+                        "dangling_output",
+                        "Spawn outputs referring to an entry that is not in the log",
+                        format!("{} (entry {})", spawn.target_label, id),
+                    );
+                    None
+                }
+            },
+            Some(compact::output::Type::InvalidOutputPath(path)) => {
+                warnings.push("invalid_output", "Declared outputs that were missing or of the wrong type", path);
+                None
+            }
+            None => None,
+        };
+        if let Some(entry) = entry {
+            match entry {
+                StoredEntry::File(f) => {
+                    actual_outputs.push(crate::proto::File {
+                        path: f.path.clone(),
+                        digest: f.digest.clone(),
+                        symlink_target_path: String::new(),
+                        is_tool: false,
+                    });
+                }
+                StoredEntry::Directory(d) => {
+                    // The verbose format represents directories as a single File entry with a path.
+                    // We will omit the digest as it's not directly available/needed for metrics.
+                    actual_outputs.push(crate::proto::File {
+                        path: d.path.clone(),
+                        digest: None,
+                        symlink_target_path: String::new(),
+                        is_tool: false,
+                    });
+                }
+                // An input set is never referenced as an output.
+                StoredEntry::InputSet(_) => {}
+            }
+        }
+    }
+
+    SpawnExec {
+        command_args: spawn.args,
+        environment_variables: spawn.env_vars,
+        platform: spawn.platform,
+        inputs: reconstruct_inputs(spawn.input_set_id, spawn.tool_set_id, stored_entries, warnings),
+        listed_outputs: vec![], // Not reconstructed as it's not used in analysis
+        remotable: spawn.remotable,
+        cacheable: spawn.cacheable,
+        timeout_millis: spawn.timeout_millis,
+        mnemonic: spawn.mnemonic,
+        actual_outputs,
+        runner: spawn.runner,
+        cache_hit: spawn.cache_hit,
+        status: spawn.status,
+        exit_code: spawn.exit_code,
+        remote_cacheable: spawn.remote_cacheable,
+        target_label: spawn.target_label,
+        digest: spawn.digest,
+        metrics: spawn.metrics,
+    }
+}
+
+/// Flattens an input set into the verbose `inputs` list. Directories are expanded into their files,
+/// as in the verbose format; unresolved symlinks and runfiles trees are not reconstructed.
+fn reconstruct_inputs(
+    input_set_id: u32,
+    tool_set_id: u32,
+    stored_entries: &HashMap<u32, StoredEntry>,
+    warnings: &mut Warnings,
+) -> Vec<crate::proto::File> {
+    let tool_paths: HashSet<String> = collect_input_set(tool_set_id, stored_entries, warnings)
+        .into_iter()
+        .map(|file| file.path)
+        .collect();
+    let mut inputs = collect_input_set(input_set_id, stored_entries, warnings);
+    for input in &mut inputs {
+        input.is_tool = tool_paths.contains(&input.path);
+    }
+    inputs
+}
+
+/// Walks an input set and its transitive sets in postorder, returning each file once.
+fn collect_input_set(
+    set_id: u32,
+    stored_entries: &HashMap<u32, StoredEntry>,
+    warnings: &mut Warnings,
+) -> Vec<crate::proto::File> {
+    let mut walk = InputSetWalk {
+        stored_entries,
+        warnings,
+        visited_sets: HashSet::new(),
+        seen_paths: HashSet::new(),
+        files: Vec::new(),
+    };
+    walk.collect(set_id);
+    walk.files
+}
+
+/// State of one walk over an input set and its transitive sets.
+struct InputSetWalk<'a> {
+    stored_entries: &'a HashMap<u32, StoredEntry>,
+    warnings: &'a mut Warnings,
+    visited_sets: HashSet<u32>,
+    seen_paths: HashSet<String>,
+    files: Vec<crate::proto::File>,
+}
+
+impl InputSetWalk<'_> {
+    /// Walks with an explicit stack rather than recursion: a log can nest sets arbitrarily deep.
+    fn collect(&mut self, set_id: u32) {
+        // A set is pushed unexpanded to walk its transitive sets, then expanded to take its inputs.
+        let mut stack = vec![(set_id, false)];
+        while let Some((set_id, expanded)) = stack.pop() {
+            // Sets are shared between spawns and across levels of the same set, so each is walked once.
+            if !expanded && (set_id == 0 || !self.visited_sets.insert(set_id)) {
+                continue;
+            }
+            let Some(StoredEntry::InputSet(set)) = self.stored_entries.get(&set_id) else {
+                self.warnings.push(
+                    "dangling_input_set",
+                    "References to an input set that is not in the log",
+                    format!("entry {}", set_id),
+                );
+                continue;
+            };
+            if expanded {
+                self.push_inputs(set_id, set);
+            } else {
+                stack.push((set_id, true));
+                stack.extend(set.transitive_set_ids.iter().rev().map(|id| (*id, false)));
+            }
+        }
+    }
+
+    fn push_inputs(&mut self, set_id: u32, set: &compact::InputSet) {
+        for input_id in &set.input_ids {
+            match self.stored_entries.get(input_id) {
+                Some(StoredEntry::File(f)) => self.push(f.path.clone(), f.digest.clone()),
+                Some(StoredEntry::Directory(d)) => {
+                    for f in &d.files {
+                        self.push(format!("{}/{}", d.path, f.path), f.digest.clone());
+                    }
+                }
+                Some(StoredEntry::InputSet(_)) => {}
+                None => self.warnings.push(
+                    "dangling_input",
+                    "Input set members referring to an entry that is not in the log (unresolved symlinks are not stored)",
+                    format!("entry {} in set {}", input_id, set_id),
+                ),
+            }
+        }
+    }
+
+    fn push(&mut self, path: String, digest: Option<crate::proto::Digest>) {
+        if self.seen_paths.insert(path.clone()) {
+            self.files.push(crate::proto::File {
+                path,
+                digest,
+                symlink_target_path: String::new(),
+                is_tool: false,
+            });
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Splits a log into its length-delimited entries without trusting the length prefixes, and the
+//! structured error reported for the first entry that cannot be read.
+
+use super::LogFormat;
+use crate::render::group_thousands;
+use thiserror::Error;
+
+/// Why an entry of a log could not be read.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ParseErrorKind {
+    #[error("invalid length prefix")]
+    InvalidLengthPrefix,
+
+    #[error("entry of {length} bytes truncated after {available} bytes")]
+    Truncated { length: u64, available: usize },
+
+    #[error("invalid entry: {0}")]
+    InvalidEntry(#[from] prost::DecodeError),
+
+    #[error("zstd stream truncated after {compressed_bytes} bytes")]
+    TruncatedStream { compressed_bytes: usize },
+
+    #[error("invalid zstd stream: {0}")]
+    Decompression(String),
+}
+
+/// The first entry of a log that could not be read. Offsets of compact logs count decompressed
+/// bytes.
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("{kind} at byte {offset} (entry {entry_index})")]
+pub struct ParseError {
+    pub offset: usize,
+    /// The number of entries read before this one.
+    pub entry_index: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    /// What the error likely means for a log of `format`, and what to do about it.
+    pub fn hint(&self, format: LogFormat) -> String {
+        let offset = group_thousands(self.offset as u64);
+        let intact = match self.entry_index {
+            0 => "no entry before it could be read".to_string(),
+            1 => "the entry before it is intact".to_string(),
+            n => format!("the {} entries before it are intact", group_thousands(n as u64)),
+        };
+        match &self.kind {
+            ParseErrorKind::TruncatedStream { compressed_bytes } => format!(
+                "looks like a compact log, but the zstd frame is truncated at byte {} ({} decompressed bytes, {}). \
+                 The log was probably copied before Bazel finished writing it; `watch` reads logs in progress.",
+                group_thousands(*compressed_bytes as u64),
+                offset,
+                intact
+            ),
+            ParseErrorKind::Decompression(_) => format!(
+                "looks like a compact log, but the zstd stream is damaged after {} decompressed bytes ({}).",
+                offset, intact
+            ),
+            ParseErrorKind::Truncated { length, available } => format!(
+                "looks like a {} log, but it is truncated at byte {}: the entry there needs {} bytes and only {} \
+                 follow ({}). The log was probably copied before Bazel finished writing it.",
+                format,
+                offset,
+                group_thousands(*length),
+                group_thousands(*available as u64),
+                intact
+            ),
+            ParseErrorKind::InvalidLengthPrefix | ParseErrorKind::InvalidEntry(_) if self.entry_index == 0 => format!(
+                "the file does not start with a {} execution log entry. Execution logs are written by Bazel's \
+                 --execution_log_compact_file or --execution_log_binary_file flags.",
+                format
+            ),
+            ParseErrorKind::InvalidLengthPrefix | ParseErrorKind::InvalidEntry(_) => format!(
+                "looks like a {} log, but the entry at byte {} is corrupt ({}).",
+                format, offset, intact
+            ),
+        }
+    }
+}
+
+/// One length-delimited entry of a log.
+pub(super) struct Entry<'a> {
+    pub(super) offset: usize,
+    pub(super) index: usize,
+    pub(super) message: &'a [u8],
+}
+
+impl Entry<'_> {
+    /// The error for this entry, e.g. when its message fails to decode.
+    pub(super) fn error(&self, kind: impl Into<ParseErrorKind>) -> ParseError {
+        ParseError {
+            offset: self.offset,
+            entry_index: self.index,
+            kind: kind.into(),
+        }
+    }
+}
+
+/// Iterates over the entries of a log. A length prefix is checked against the bytes that are
+/// left before anything is sliced or decoded, so a forged length neither reads out of bounds nor
+/// makes the decoder reserve memory for data that is not there.
+pub(super) struct Entries<'a> {
+    content: &'a [u8],
+    offset: usize,
+    index: usize,
+    failed: bool,
+}
+
+impl<'a> Entries<'a> {
+    pub(super) fn new(content: &'a [u8]) -> Self {
+        Self {
+            content,
+            offset: 0,
+            index: 0,
+            failed: false,
+        }
+    }
+
+    fn fail(&mut self, kind: ParseErrorKind) -> Option<Result<Entry<'a>, ParseError>> {
+        self.failed = true;
+        Some(Err(ParseError {
+            offset: self.offset,
+            entry_index: self.index,
+            kind,
+        }))
+    }
+}
+
+impl<'a> Iterator for Entries<'a> {
+    /// Ends after the first entry that cannot be framed.
+    type Item = Result<Entry<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.content.is_empty() {
+            return None;
+        }
+        let mut cursor = self.content;
+        let Ok(length) = prost::encoding::decode_varint(&mut cursor) else {
+            return self.fail(ParseErrorKind::InvalidLengthPrefix);
+        };
+        let message = match usize::try_from(length) {
+            Ok(length) if length <= cursor.len() => &cursor[..length],
+            _ => {
+                let available = cursor.len();
+                return self.fail(ParseErrorKind::Truncated { length, available });
+            }
+        };
+        let entry = Entry {
+            offset: self.offset,
+            index: self.index,
+            message,
+        };
+        let consumed = self.content.len() - cursor.len() + message.len();
+        self.content = &self.content[consumed..];
+        self.offset += consumed;
+        self.index += 1;
+        Some(Ok(entry))
+    }
+}
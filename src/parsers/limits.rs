@@ -0,0 +1,76 @@
+//! Bounds on the work and memory parsing one log may take, for logs from untrusted sources.
+
+use super::framing::Entry;
+use thiserror::Error;
+
+/// Limits for [`parse_verbose_bytes`](super::parse_verbose_bytes) and
+/// [`parse_compact_bytes`](super::parse_compact_bytes). `None` leaves a dimension unbounded,
+/// which is the default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Entries in the log, counting the compact format's file, directory and input set entries.
+    pub max_entries: Option<u64>,
+    /// Bytes of a single entry.
+    pub max_entry_bytes: Option<u64>,
+    /// Bytes a compact log decompresses to.
+    pub max_decompressed_bytes: Option<u64>,
+    /// Memory held while parsing: the decompressed log, the compact entries kept for
+    /// reconstruction and the spawns, each estimated by its encoded size.
+    pub max_memory_bytes: Option<u64>,
+}
+
+/// A limit of [`ParseLimits`] that a log went over.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{limit} of {max} exceeded at byte {offset}")]
+pub struct LimitExceeded {
+    /// The name of the [`ParseLimits`] field.
+    pub limit: &'static str,
+    pub max: u64,
+    /// Where in the (decompressed) log parsing stopped.
+    pub offset: usize,
+}
+
+/// Tracks one parse against its limits.
+pub(super) struct Budget<'a> {
+    limits: &'a ParseLimits,
+    memory: u64,
+}
+
+impl<'a> Budget<'a> {
+    pub(super) fn new(limits: &'a ParseLimits) -> Self {
+        Self { limits, memory: 0 }
+    }
+
+    /// Checks an entry before it is decoded.
+    pub(super) fn entry(&self, entry: &Entry) -> Result<(), LimitExceeded> {
+        let exceeded = |limit, max| LimitExceeded {
+            limit,
+            max,
+            offset: entry.offset,
+        };
+        if let Some(max) = self.limits.max_entries
+            && entry.index as u64 >= max
+        {
+            return Err(exceeded("max_entries", max));
+        }
+        if let Some(max) = self.limits.max_entry_bytes
+            && entry.message.len() as u64 > max
+        {
+            return Err(exceeded("max_entry_bytes", max));
+        }
+        Ok(())
+    }
+
+    /// Accounts for `bytes` more of memory held at `offset`.
+    pub(super) fn charge(&mut self, bytes: usize, offset: usize) -> Result<(), LimitExceeded> {
+        self.memory += bytes as u64;
+        match self.limits.max_memory_bytes {
+            Some(max) if self.memory > max => Err(LimitExceeded {
+                limit: "max_memory_bytes",
+                max,
+                offset,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
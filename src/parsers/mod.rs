@@ -0,0 +1,300 @@
+//! Readers for the execution log formats Bazel can write. Every parser produces verbose
+//! `SpawnExec` messages so that the reports only have to deal with a single representation.
+
+mod compact;
+mod framing;
+mod limits;
+mod sniff;
+mod verbose;
+
+pub use framing::{ParseError, ParseErrorKind};
+pub use limits::{LimitExceeded, ParseLimits};
+pub use sniff::OtherOutput;
+
+use crate::proto::{ExecLogEntry, SpawnExec};
+use crate::warnings::Warnings;
+use crate::{AppError, AppResult};
+use limits::Budget;
+use std::fmt;
+use std::fs;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The execution log formats, as chosen by the zstd magic number at the start of a log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Length-delimited `SpawnExec` messages (`--execution_log_binary_file`).
+    Verbose,
+    /// zstd-compressed `ExecLogEntry` messages (`--execution_log_compact_file`).
+    Compact,
+}
+
+impl LogFormat {
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&ZSTD_MAGIC) {
+            LogFormat::Compact
+        } else {
+            LogFormat::Verbose
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LogFormat::Verbose => "verbose",
+            LogFormat::Compact => "compact",
+        })
+    }
+}
+
+/// The spawns of a log held in memory, read up to the first entry that could not be read.
+#[derive(Debug, Default)]
+pub struct ParsedLog {
+    pub spawns: Vec<SpawnExec>,
+    /// Why reading stopped before the end of the log, if it did.
+    pub error: Option<ParseError>,
+    pub warnings: Warnings,
+}
+
+/// Parses the log file, auto-detecting the format (compact or verbose).
+///
+/// Format detection notes go to stderr so that machine-readable output on stdout stays clean.
+pub fn parse_log_file(path: &Path) -> AppResult<Vec<SpawnExec>> {
+    parse_log_file_with_warnings(path, &mut Warnings::new())
+}
+
+/// Like [`parse_log_file`], also collecting data-quality issues found in the log.
+pub fn parse_log_file_with_warnings(path: &Path, warnings: &mut Warnings) -> AppResult<Vec<SpawnExec>> {
+    let raw_bytes = fs::read(path)?;
+    let format = LogFormat::detect(&raw_bytes);
+    let parsed = match format {
+        LogFormat::Compact => {
+            eprintln!("Detected zstd-compressed compact log format.");
+            parse_compact_bytes(&raw_bytes, &ParseLimits::default())?
+        }
+        LogFormat::Verbose => {
+            eprintln!("No zstd frame found. Reading the log in the verbose format.");
+            parse_verbose_bytes(&raw_bytes, &ParseLimits::default())?
+        }
+    };
+    if let Some(error) = parsed.error {
+        return Err(match OtherOutput::detect(&raw_bytes) {
+            Some(output) => AppError::NotAnExecutionLog {
+                path: path.to_path_buf(),
+                output,
+            },
+            None => AppError::InvalidLog { format, error },
+        });
+    }
+    warnings.extend(parsed.warnings);
+    Ok(parsed.spawns)
+}
+
+/// Parses a verbose log held in memory. Meant for logs from untrusted sources: it never panics,
+/// and a malformed entry ends parsing with the spawns decoded so far and a [`ParseError`]. Going
+/// over one of the `limits` aborts parsing with [`AppError::LimitExceeded`].
+pub fn parse_verbose_bytes(bytes: &[u8], limits: &ParseLimits) -> AppResult<ParsedLog> {
+    let (spawns, error) = verbose::parse_verbose_entries(bytes, &mut Budget::new(limits))?;
+    let mut warnings = Warnings::new();
+    check_fields(&spawns, &mut warnings);
+    Ok(ParsedLog { spawns, error, warnings })
+}
+
+/// Parses a zstd-compressed compact log held in memory, with the guarantees of
+/// [`parse_verbose_bytes`]. When the zstd stream turns out to be corrupt, the entries
+/// decompressed until then are still parsed.
+pub fn parse_compact_bytes(bytes: &[u8], limits: &ParseLimits) -> AppResult<ParsedLog> {
+    let mut budget = Budget::new(limits);
+    let (decompressed, decompression_error) = decompress_bounded(bytes, limits)?;
+    budget.charge(decompressed.len(), decompressed.len())?;
+    let mut warnings = Warnings::new();
+    let (spawns, error) = match decompression_error {
+        None => compact::parse_compact_entries(&decompressed, &mut budget, &mut warnings)?,
+        // The entry cut off by the end of the data is not at fault.
+        Some(kind) => {
+            let complete = complete_messages(&decompressed);
+            let (spawns, error) = compact::parse_compact_entries(complete, &mut budget, &mut warnings)?;
+            let error = error.unwrap_or(ParseError {
+                offset: complete.len(),
+                entry_index: framing::Entries::new(complete).count(),
+                kind,
+            });
+            (spawns, Some(error))
+        }
+    };
+    check_fields(&spawns, &mut warnings);
+    Ok(ParsedLog { spawns, error, warnings })
+}
+
+/// Reads the entries of a zstd-compressed compact log as they are, without reconstructing spawns,
+/// for tools that rewrite the log (see [`writer::EntryCompactor`](crate::writer::EntryCompactor)).
+/// Unlike the parsers, a log that cannot be read to the end is an error: a rewritten log must not
+/// silently lose its tail.
+pub fn read_compact_entries(bytes: &[u8], limits: &ParseLimits) -> AppResult<Vec<ExecLogEntry>> {
+    let mut budget = Budget::new(limits);
+    let (decompressed, decompression_error) = decompress_bounded(bytes, limits)?;
+    budget.charge(decompressed.len(), decompressed.len())?;
+    let (entries, error) = compact::decode_entries(&decompressed, &mut budget)?;
+    let error = error.or_else(|| {
+        decompression_error.map(|kind| ParseError {
+            offset: decompressed.len(),
+            entry_index: entries.len(),
+            kind,
+        })
+    });
+    match error {
+        None => Ok(entries),
+        Some(error) => Err(AppError::InvalidLog {
+            format: LogFormat::Compact,
+            error,
+        }),
+    }
+}
+
+/// Reads spawns written by [`write_spawns_msgpack`](crate::writer::write_spawns_msgpack), e.g. by
+/// `export msgpack`.
+pub fn read_spawns_msgpack(bytes: &[u8]) -> AppResult<Vec<SpawnExec>> {
+    rmp_serde::from_slice(bytes).map_err(|e| AppError::LogParsing(format!("not a MessagePack spawn export: {}", e)))
+}
+
+/// What a compact log holds besides its spawns.
+#[derive(Debug, Default)]
+pub struct CompactContents {
+    pub decompressed_bytes: usize,
+    /// Entries per type, in the order the types first appear.
+    pub entry_counts: Vec<(&'static str, u64)>,
+    pub invocation: Option<crate::proto::exec_log_entry::Invocation>,
+}
+
+/// Describes the entries of a zstd-compressed compact log, up to the first that cannot be read.
+pub fn compact_contents(bytes: &[u8]) -> CompactContents {
+    let Ok((decompressed, _)) = decompress_bounded(bytes, &ParseLimits::default()) else {
+        return CompactContents::default();
+    };
+    let (entry_counts, invocation) = compact::count_entries(&decompressed);
+    CompactContents {
+        decompressed_bytes: decompressed.len(),
+        entry_counts,
+        invocation,
+    }
+}
+
+/// Decompresses a zstd stream, returning what was decompressed and why decompression stopped
+/// early, if it did. Decompression stops at the decompressed size or memory limit, whichever is
+/// lower, so that a small zstd bomb cannot exhaust memory.
+fn decompress_bounded(bytes: &[u8], limits: &ParseLimits) -> Result<(Vec<u8>, Option<ParseErrorKind>), LimitExceeded> {
+    let decoder = match zstd::stream::read::Decoder::new(bytes) {
+        Ok(decoder) => decoder,
+        Err(e) => return Ok((Vec::new(), Some(ParseErrorKind::Decompression(e.to_string())))),
+    };
+    let bound = [
+        ("max_decompressed_bytes", limits.max_decompressed_bytes),
+        ("max_memory_bytes", limits.max_memory_bytes),
+    ]
+    .into_iter()
+    .filter_map(|(limit, max)| Some((limit, max?)))
+    .min_by_key(|(_, max)| *max);
+    let mut decompressed = Vec::new();
+    // One byte over the limit tells a stream of exactly the limit from a larger one.
+    let read = decoder
+        .take(bound.map_or(u64::MAX, |(_, max)| max.saturating_add(1)))
+        .read_to_end(&mut decompressed);
+    if let Some((limit, max)) = bound
+        && decompressed.len() as u64 > max
+    {
+        return Err(LimitExceeded {
+            limit,
+            max,
+            offset: max as usize,
+        });
+    }
+    Ok(match read {
+        Ok(_) => (decompressed, None),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => (decompressed, Some(ParseErrorKind::TruncatedStream {
+            compressed_bytes: bytes.len(),
+        })),
+        Err(e) => (decompressed, Some(ParseErrorKind::Decompression(e.to_string()))),
+    })
+}
+
+/// Parses every file directly inside `dir` as an execution log, in file name order so that names
+/// carrying a date or build number read as a sequence of builds. Files that cannot be parsed are
+/// skipped with a note on stderr.
+pub fn parse_logs_dir(dir: &Path) -> AppResult<Vec<(PathBuf, Vec<SpawnExec>)>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.file_type()?.is_file() {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+
+    let mut logs = Vec::new();
+    for path in paths {
+        match parse_log_file(&path) {
+            Ok(spawns) => logs.push((path, spawns)),
+            Err(e) => eprintln!("Skipping {}: {}", path.display(), e),
+        }
+    }
+    if logs.is_empty() {
+        return Err(AppError::LogParsing(format!("no execution logs found in {}", dir.display())));
+    }
+    eprintln!("Read {} execution logs from {}.", logs.len(), dir.display());
+    Ok(logs)
+}
+
+/// Flags spawns whose fields contradict each other.
+fn check_fields(spawns: &[SpawnExec], warnings: &mut Warnings) {
+    for spawn in spawns {
+        if spawn.status.is_empty() && spawn.exit_code != 0 {
+            warnings.push(
+                "exit_code_without_status",
+                "Spawns with a non-zero exit code but no status",
+                format!("{} (exit code {})", spawn.target_label, spawn.exit_code),
+            );
+        }
+        if spawn.cache_hit && spawn.runner.is_empty() {
+            warnings.push("cache_hit_without_runner", "Cache hits without a runner", &spawn.target_label);
+        }
+    }
+}
+
+/// Parses the entries of a log that Bazel may still be writing, ignoring a truncated last entry
+/// (and, for compact logs, the unfinished end of the zstd stream). Unlike `parse_log_file`, nothing
+/// is printed.
+pub fn parse_partial_log(path: &Path) -> AppResult<Vec<SpawnExec>> {
+    let raw_bytes = fs::read(path)?;
+    let format = LogFormat::detect(&raw_bytes);
+    let parsed = match format {
+        LogFormat::Compact => parse_compact_bytes(&raw_bytes, &ParseLimits::default())?,
+        LogFormat::Verbose => parse_verbose_bytes(&raw_bytes, &ParseLimits::default())?,
+    };
+    match parsed.error {
+        // Bazel has not written the rest yet.
+        None
+        | Some(ParseError {
+            kind: ParseErrorKind::Truncated { .. } | ParseErrorKind::TruncatedStream { .. },
+            ..
+        }) => Ok(parsed.spawns),
+        Some(error) => Err(AppError::InvalidLog { format, error }),
+    }
+}
+
+/// The longest prefix of `content` made of complete length-delimited messages.
+fn complete_messages(content: &[u8]) -> &[u8] {
+    let mut rest = content;
+    loop {
+        let mut cursor = rest;
+        let Ok(length) = prost::encoding::decode_varint(&mut cursor) else { break };
+        match usize::try_from(length) {
+            Ok(length) if length <= cursor.len() => rest = &cursor[length..],
+            _ => break,
+        }
+    }
+    &content[..content.len() - rest.len()]
+}
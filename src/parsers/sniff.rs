@@ -0,0 +1,102 @@
+//! Recognizes the other Bazel outputs that get passed where an execution log is expected, so that
+//! the error can say what the file is and which flag writes the log instead.
+
+use flate2::read::GzDecoder;
+use std::fmt;
+use std::io::Read;
+
+/// How much of a file is looked at.
+const SNIFF_BYTES: usize = 64 * 1024;
+
+/// The first bytes of the first event of a binary Build Event Protocol stream: the event ID
+/// (field 1) holding an empty `started` ID (field 3).
+const BUILD_STARTED_ID: [u8; 4] = [0x0a, 0x02, 0x1a, 0x00];
+
+/// A Bazel output that is not an execution log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtherOutput {
+    JsonExecutionLog,
+    JsonBuildEvents,
+    BinaryBuildEvents,
+    Profile,
+    AqueryText,
+    AqueryJson,
+}
+
+impl OtherOutput {
+    /// Recognizes `bytes` as the start of one of the other outputs.
+    pub fn detect(bytes: &[u8]) -> Option<OtherOutput> {
+        let mut head = Vec::new();
+        let head = if bytes.starts_with(&[0x1f, 0x8b]) {
+            // Only profiles are commonly gzipped. A truncated read still leaves enough to look at.
+            let _ = GzDecoder::new(bytes).take(SNIFF_BYTES as u64).read_to_end(&mut head);
+            head.as_slice()
+        } else {
+            &bytes[..bytes.len().min(SNIFF_BYTES)]
+        };
+
+        let mut message = head;
+        if prost::encoding::decode_varint(&mut message).is_ok() && message.starts_with(&BUILD_STARTED_ID) {
+            return Some(OtherOutput::BinaryBuildEvents);
+        }
+        let text = String::from_utf8_lossy(head);
+        let text = text.trim_start();
+        if text.starts_with("action '") {
+            return Some(OtherOutput::AqueryText);
+        }
+        if !text.starts_with(['{', '[']) {
+            return None;
+        }
+        let has_key = |key: &str| text.contains(&format!("\"{}\"", key));
+        if has_key("traceEvents") || (text.starts_with('[') && has_key("ph") && has_key("ts")) {
+            Some(OtherOutput::Profile)
+        } else if text.starts_with("{\"id\":") {
+            Some(OtherOutput::JsonBuildEvents)
+        } else if has_key("artifacts") && has_key("actions") {
+            Some(OtherOutput::AqueryJson)
+        } else if has_key("commandArgs") || has_key("listedOutputs") {
+            Some(OtherOutput::JsonExecutionLog)
+        } else {
+            None
+        }
+    }
+
+    /// What to do with the file instead.
+    pub fn hint(self) -> &'static str {
+        match self {
+            OtherOutput::JsonExecutionLog => {
+                "this tool reads the binary execution logs; run the build with \
+                 --execution_log_compact_file=<file> instead of --execution_log_json_file."
+            }
+            OtherOutput::JsonBuildEvents => {
+                "pass it with --bep next to the execution log, which Bazel writes with \
+                 --execution_log_compact_file=<file>."
+            }
+            OtherOutput::BinaryBuildEvents => {
+                "--bep reads the JSON form written by --build_event_json_file; the execution log is written by \
+                 --execution_log_compact_file=<file>."
+            }
+            OtherOutput::Profile => {
+                "pass it with --profile next to the execution log, which Bazel writes with \
+                 --execution_log_compact_file=<file>."
+            }
+            OtherOutput::AqueryText | OtherOutput::AqueryJson => {
+                "aquery describes actions without running them; run the build with \
+                 --execution_log_compact_file=<file> to record how they ran."
+            }
+        }
+    }
+}
+
+impl fmt::Display for OtherOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OtherOutput::JsonExecutionLog => "a JSON execution log (--execution_log_json_file)",
+            OtherOutput::JsonBuildEvents => "a Build Event Protocol stream (--build_event_json_file)",
+            OtherOutput::BinaryBuildEvents => "a binary Build Event Protocol stream (--build_event_binary_file)",
+            OtherOutput::Profile => "a JSON trace profile (--profile)",
+            OtherOutput::AqueryText => "aquery output (--output=text)",
+            OtherOutput::AqueryJson => "aquery output (--output=jsonproto)",
+        })
+    }
+}
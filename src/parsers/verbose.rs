@@ -0,0 +1,26 @@
+use super::framing::{Entries, ParseError};
+use super::limits::{Budget, LimitExceeded};
+use crate::proto::SpawnExec;
+use prost::Message;
+
+/// Decodes the verbose execution log format (length-delimited SpawnExec protos) up to the first
+/// entry that cannot be read, and the error for that entry.
+pub(super) fn parse_verbose_entries(
+    content: &[u8],
+    budget: &mut Budget,
+) -> Result<(Vec<SpawnExec>, Option<ParseError>), LimitExceeded> {
+    let mut decoded_spawns = Vec::new();
+    for entry in Entries::new(content) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => return Ok((decoded_spawns, Some(e))),
+        };
+        budget.entry(&entry)?;
+        match SpawnExec::decode(entry.message) {
+            Ok(spawn) => decoded_spawns.push(spawn),
+            Err(e) => return Ok((decoded_spawns, Some(entry.error(e)))),
+        }
+        budget.charge(entry.message.len(), entry.offset)?;
+    }
+    Ok((decoded_spawns, None))
+}
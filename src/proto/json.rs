@@ -0,0 +1,129 @@
+//! serde support for the well-known types prost-types leaves without it, in their proto3 JSON
+//! form: durations as `"1.500s"` and timestamps as RFC 3339 strings in UTC.
+
+use prost_types::{Duration, Timestamp};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// `seconds` and `nanos` as `<seconds>[.<fraction>]`, the fraction with 3, 6 or 9 digits.
+fn write_seconds(seconds: i64, nanos: i32) -> String {
+    let nanos = nanos.unsigned_abs();
+    if nanos == 0 {
+        seconds.to_string()
+    } else if nanos.is_multiple_of(1_000_000) {
+        format!("{}.{:03}", seconds, nanos / 1_000_000)
+    } else if nanos.is_multiple_of(1_000) {
+        format!("{}.{:06}", seconds, nanos / 1_000)
+    } else {
+        format!("{}.{:09}", seconds, nanos)
+    }
+}
+
+/// Parses `<seconds>[.<fraction>]` with up to nine fraction digits.
+fn read_seconds(value: &str) -> Option<(i64, i32)> {
+    let (seconds, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let nanos = if fraction.is_empty() { 0 } else { format!("{:0<9}", fraction).parse().ok()? };
+    Some((seconds.parse().ok()?, nanos))
+}
+
+pub(crate) mod duration {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        let Some(duration) = duration else {
+            return serializer.serialize_none();
+        };
+        let sign = if duration.seconds < 0 || duration.nanos < 0 { "-" } else { "" };
+        let seconds = write_seconds(duration.seconds.unsigned_abs() as i64, duration.nanos);
+        serializer.serialize_str(&format!("{}{}s", sign, seconds))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        let Some(value) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        let invalid = || D::Error::custom(format!("invalid duration '{}', expected e.g. \"1.5s\"", value));
+        let unsigned = value.strip_suffix('s').ok_or_else(invalid)?;
+        let (negative, unsigned) = match unsigned.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, unsigned),
+        };
+        let (seconds, nanos) = read_seconds(unsigned).ok_or_else(invalid)?;
+        let sign = if negative { -1 } else { 1 };
+        Ok(Some(Duration {
+            seconds: sign * seconds,
+            nanos: sign as i32 * nanos,
+        }))
+    }
+}
+
+pub(crate) mod timestamp {
+    use super::*;
+
+    /// Days since 1970-01-01 of a proleptic Gregorian date.
+    fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// The proleptic Gregorian date `days` after 1970-01-01.
+    fn civil_from_days(days: i64) -> (i64, i64, i64) {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        (year, month, day)
+    }
+
+    pub(crate) fn serialize<S: Serializer>(timestamp: &Option<Timestamp>, serializer: S) -> Result<S::Ok, S::Error> {
+        let Some(timestamp) = timestamp else {
+            return serializer.serialize_none();
+        };
+        let (year, month, day) = civil_from_days(timestamp.seconds.div_euclid(86_400));
+        let second_of_day = timestamp.seconds.rem_euclid(86_400);
+        let seconds = write_seconds(second_of_day % 60, timestamp.nanos);
+        serializer.serialize_str(&format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{}{}Z",
+            year,
+            month,
+            day,
+            second_of_day / 3600,
+            second_of_day / 60 % 60,
+            if second_of_day % 60 < 10 { "0" } else { "" },
+            seconds
+        ))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Timestamp>, D::Error> {
+        let Some(value) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        let parse = || -> Option<Timestamp> {
+            let (date, time) = value.strip_suffix('Z')?.split_once('T')?;
+            let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+            let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+            let mut time = time.splitn(3, ':');
+            let (hour, minute) = (time.next()?.parse::<i64>().ok()?, time.next()?.parse::<i64>().ok()?);
+            let (second, nanos) = read_seconds(time.next()?)?;
+            Some(Timestamp {
+                seconds: days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second,
+                nanos,
+            })
+        };
+        parse()
+            .ok_or_else(|| D::Error::custom(format!("invalid timestamp '{}', expected RFC 3339 in UTC", value)))
+            .map(Some)
+    }
+}
@@ -1,11 +1,14 @@
 // Auto-generated protobuf bindings
 // This module contains the generated Rust structs from spawn.proto
 
+#[allow(clippy::all)]
 pub mod tools {
     pub mod protos {
         include!(concat!(env!("OUT_DIR"), "/tools.protos.rs"));
     }
 }
 
+pub(crate) mod json;
+
 // Re-export commonly used types for convenience
 pub use tools::protos::*;
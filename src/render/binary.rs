@@ -0,0 +1,20 @@
+use super::{Document, Renderer};
+use std::io::{self, Write};
+
+/// MessagePack encoding of the same structure as the JSON output, for ingestion pipelines.
+pub struct MsgpackRenderer;
+
+impl Renderer for MsgpackRenderer {
+    fn render(&self, document: &Document, out: &mut dyn Write) -> io::Result<()> {
+        rmp_serde::encode::write(out, document).map_err(io::Error::other)
+    }
+}
+
+/// CBOR (RFC 8949) encoding of the same structure as the JSON output.
+pub struct CborRenderer;
+
+impl Renderer for CborRenderer {
+    fn render(&self, document: &Document, out: &mut dyn Write) -> io::Result<()> {
+        ciborium::into_writer(document, out).map_err(io::Error::other)
+    }
+}
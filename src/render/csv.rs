@@ -0,0 +1,55 @@
+use super::{Block, Document, Renderer, Table, Value};
+use std::io::{self, Write};
+
+/// CSV tables with raw (unformatted) values. The output starts with a `# format_version: <n>`
+/// comment line; each table is preceded by a `# <section id>` comment line and separated from
+/// the next by a blank line. Fields and notes are omitted.
+pub struct CsvRenderer;
+
+impl Renderer for CsvRenderer {
+    fn render(&self, document: &Document, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "# format_version: {}", document.format_version)?;
+        for section in &document.sections {
+            for block in &section.blocks {
+                if let Block::Table(table) = block {
+                    writeln!(out)?;
+                    writeln!(out, "# {}", section.id)?;
+                    write_table(table, out)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes one table as a standalone CSV document: a header row of column keys, then the rows.
+pub fn write_table(table: &Table, out: &mut dyn Write) -> io::Result<()> {
+    let headers: Vec<String> = table.columns.iter().map(|c| escape_csv(&c.key)).collect();
+    writeln!(out, "{}", headers.join(","))?;
+    for row in &table.rows {
+        let cells: Vec<String> = row.iter().map(csv_value).collect();
+        writeln!(out, "{}", cells.join(","))?;
+    }
+    Ok(())
+}
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Text(text) => escape_csv(text),
+        Value::Int(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Bar(segments) => {
+            let parts: Vec<String> = segments.iter().map(f64::to_string).collect();
+            escape_csv(&parts.join(";"))
+        }
+    }
+}
+
+fn escape_csv(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,137 @@
+use super::{format_value, Block, Column, ColumnKind, Document, Field, Renderer, Table, Value};
+use std::io::{self, Write};
+
+/// A self-contained HTML page with inline styles, suitable for CI artifacts and links.
+pub struct HtmlRenderer;
+
+const STYLE: &str = "body{font-family:-apple-system,Segoe UI,Helvetica,Arial,sans-serif;margin:2em;color:#24292f}\
+table{border-collapse:collapse;margin-bottom:1.5em;font-size:14px}\
+th,td{border:1px solid #d0d7de;padding:4px 8px;text-align:left}\
+th{background:#f6f8fa}td.num{text-align:right;font-variant-numeric:tabular-nums}\
+.bar{display:flex;height:14px;min-width:240px}.bar span{height:14px}\
+.seg0{background:#8c959f}.seg1{background:#cf222e}.seg2{background:#0969da}\
+.seg3{background:#1a7f37}.seg4{background:#bf8700}.seg5{background:#8250df}\
+dl{display:grid;grid-template-columns:max-content auto;gap:2px 12px}dt{font-weight:600}dd{margin:0}\
+p.note{color:#57606a}code{font-size:12px}\
+details{margin-bottom:.5em}summary{cursor:pointer}details table{margin:.5em 0 1em 1.5em}";
+
+impl Renderer for HtmlRenderer {
+    fn render(&self, document: &Document, out: &mut dyn Write) -> io::Result<()> {
+        let title = escape_html(&document.title);
+        writeln!(
+            out,
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title><style>{}</style></head><body>",
+            title, STYLE
+        )?;
+        writeln!(out, "<h1>{}</h1>", title)?;
+        write_fields(&document.metadata, out)?;
+
+        for section in &document.sections {
+            writeln!(
+                out,
+                "<section id=\"{}\"><h2>{}</h2>",
+                escape_html(&section.id),
+                escape_html(&section.title)
+            )?;
+            for block in &section.blocks {
+                match block {
+                    Block::Text(text) => writeln!(out, "<p class=\"note\">{}</p>", escape_html(text))?,
+                    Block::Fields(fields) => write_fields(fields, out)?,
+                    Block::Table(table) => write_table(table, out)?,
+                }
+            }
+            writeln!(out, "</section>")?;
+        }
+        writeln!(out, "</body></html>")
+    }
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn write_fields(fields: &[Field], out: &mut dyn Write) -> io::Result<()> {
+    if fields.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "<dl>")?;
+    for field in fields {
+        writeln!(
+            out,
+            "<dt>{}</dt><dd>{}</dd>",
+            escape_html(&field.column.title),
+            escape_html(&format_value(&field.column, &field.value))
+        )?;
+    }
+    writeln!(out, "</dl>")
+}
+
+fn write_table(table: &Table, out: &mut dyn Write) -> io::Result<()> {
+    let table = &*table.for_display();
+    let Some(group_column) = table.group_column else {
+        return write_rows(table, &table.rows, None, out);
+    };
+    // One collapsible table per group, without the column the group is named after.
+    for rows in table.groups() {
+        writeln!(
+            out,
+            "<details><summary><b>{}</b> ({} rows)</summary>",
+            escape_html(&format_value(&table.columns[group_column], &rows[0][group_column])),
+            rows.len()
+        )?;
+        write_rows(table, rows, Some(group_column), out)?;
+        writeln!(out, "</details>")?;
+    }
+    Ok(())
+}
+
+fn write_rows(table: &Table, rows: &[Vec<Value>], skip: Option<usize>, out: &mut dyn Write) -> io::Result<()> {
+    let columns: Vec<_> = table.columns.iter().enumerate().filter(|&(i, _)| Some(i) != skip).collect();
+    write!(out, "<table><tr>")?;
+    for (_, column) in &columns {
+        write!(out, "<th>{}</th>", escape_html(&column.title))?;
+    }
+    writeln!(out, "</tr>")?;
+    for row in rows {
+        write!(out, "<tr>")?;
+        for &(i, column) in &columns {
+            write_cell(column, &row[i], out)?;
+        }
+        writeln!(out, "</tr>")?;
+    }
+    writeln!(out, "</table>")
+}
+
+fn write_cell(column: &Column, value: &Value, out: &mut dyn Write) -> io::Result<()> {
+    match (column.kind, value) {
+        (ColumnKind::Bar { .. }, Value::Bar(segments)) => {
+            write!(out, "<td><div class=\"bar\">")?;
+            for (i, fraction) in segments.iter().enumerate() {
+                write!(
+                    out,
+                    "<span class=\"seg{}\" style=\"width:{:.1}%\"></span>",
+                    i % 6,
+                    fraction.clamp(0.0, 1.0) * 100.0
+                )?;
+            }
+            write!(out, "</div></td>")
+        }
+        _ if column.is_numeric() => write!(
+            out,
+            "<td class=\"num\">{}</td>",
+            escape_html(&format_value(column, value))
+        ),
+        _ => write!(out, "<td>{}</td>", escape_html(&format_value(column, value))),
+    }
+}
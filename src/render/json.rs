@@ -0,0 +1,13 @@
+use super::{Document, Renderer};
+use std::io::{self, Write};
+
+/// Pretty-printed JSON. Tables become arrays of row objects keyed by column key, and values
+/// keep their raw units (seconds, bytes, percentages) rather than display formatting.
+pub struct JsonRenderer;
+
+impl Renderer for JsonRenderer {
+    fn render(&self, document: &Document, out: &mut dyn Write) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut *out, document)?;
+        writeln!(out)
+    }
+}
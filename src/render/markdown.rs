@@ -0,0 +1,98 @@
+use super::html::escape_html;
+use super::{format_value, Block, Document, Field, Renderer, Table, Value};
+use std::io::{self, Write};
+
+/// GitHub-flavored Markdown, suitable for PR comments and job summaries.
+pub struct MarkdownRenderer;
+
+impl Renderer for MarkdownRenderer {
+    fn render(&self, document: &Document, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "# {}", escape_markdown(&document.title))?;
+        writeln!(out)?;
+        write_fields(&document.metadata, out)?;
+
+        for section in &document.sections {
+            writeln!(out, "## {}", escape_markdown(&section.title))?;
+            writeln!(out)?;
+            for block in &section.blocks {
+                match block {
+                    Block::Text(text) => {
+                        writeln!(out, "{}", escape_markdown(text))?;
+                        writeln!(out)?;
+                    }
+                    Block::Fields(fields) => write_fields(fields, out)?,
+                    Block::Table(table) => write_table(table, out)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escapes characters that would otherwise be interpreted as Markdown or break table cells.
+pub(crate) fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '|' | '*' | '_' | '`' | '<' | '>' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn write_fields(fields: &[Field], out: &mut dyn Write) -> io::Result<()> {
+    if fields.is_empty() {
+        return Ok(());
+    }
+    for field in fields {
+        writeln!(
+            out,
+            "- **{}:** {}",
+            escape_markdown(&field.column.title),
+            escape_markdown(&format_value(&field.column, &field.value))
+        )?;
+    }
+    writeln!(out)
+}
+
+fn write_table(table: &Table, out: &mut dyn Write) -> io::Result<()> {
+    let table = &*table.for_display();
+    let Some(group_column) = table.group_column else {
+        return write_rows(table, &table.rows, None, out);
+    };
+    // GitHub renders `<details>` as a collapsible block; the blank lines let the table inside be
+    // parsed as Markdown.
+    for rows in table.groups() {
+        writeln!(
+            out,
+            "<details><summary><b>{}</b> ({} rows)</summary>",
+            escape_html(&format_value(&table.columns[group_column], &rows[0][group_column])),
+            rows.len()
+        )?;
+        writeln!(out)?;
+        write_rows(table, rows, Some(group_column), out)?;
+        writeln!(out, "</details>")?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn write_rows(table: &Table, rows: &[Vec<Value>], skip: Option<usize>, out: &mut dyn Write) -> io::Result<()> {
+    let columns: Vec<_> = table.columns.iter().enumerate().filter(|&(i, _)| Some(i) != skip).collect();
+    let headers: Vec<String> = columns.iter().map(|(_, c)| escape_markdown(&c.title)).collect();
+    writeln!(out, "| {} |", headers.join(" | "))?;
+    let alignments: Vec<&str> = columns
+        .iter()
+        .map(|(_, c)| if c.is_numeric() { "---:" } else { "---" })
+        .collect();
+    writeln!(out, "| {} |", alignments.join(" | "))?;
+    for row in rows {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|&(i, column)| escape_markdown(&format_value(column, &row[i])))
+            .collect();
+        writeln!(out, "| {} |", cells.join(" | "))?;
+    }
+    writeln!(out)
+}
@@ -0,0 +1,461 @@
+//! Format-independent report model and the renderers that turn it into output.
+//!
+//! Every report builds a [`Section`] made of tables (a column schema plus rows of raw values),
+//! key/value fields and free-form notes. A [`Renderer`] then decides how values are formatted
+//! and laid out, so reports never deal with column widths or output syntax themselves.
+
+mod binary;
+mod csv;
+mod html;
+mod json;
+mod markdown;
+mod serialize;
+mod slack;
+mod style;
+mod summary_line;
+mod text;
+#[cfg(feature = "xlsx")]
+mod xlsx;
+mod yaml;
+
+pub use self::binary::{CborRenderer, MsgpackRenderer};
+pub use self::csv::{write_table, CsvRenderer};
+pub use self::html::HtmlRenderer;
+pub use self::json::JsonRenderer;
+pub use self::markdown::MarkdownRenderer;
+pub use self::serialize::{report_schema, OLDEST_REPORT_FORMAT_VERSION, REPORT_FORMAT_VERSION};
+pub use self::slack::SlackRenderer;
+pub use self::style::{output_style, set_output_style, styled, to_ascii, NumberFormat, OutputStyle};
+pub use self::summary_line::SummaryLineRenderer;
+pub use self::text::TextRenderer;
+#[cfg(feature = "xlsx")]
+pub use self::xlsx::{write_workbook, Sheet};
+pub use self::yaml::YamlRenderer;
+
+use crate::cli::OutputFormat;
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// Renders a complete [`Document`] in one output format.
+pub trait Renderer {
+    fn render(&self, document: &Document, out: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Returns the renderer for the requested output format, honoring `--ascii` for the formats that
+/// are not binary.
+pub fn renderer_for(format: OutputFormat) -> Box<dyn Renderer> {
+    match format {
+        OutputFormat::Text => styled(Box::new(TextRenderer)),
+        OutputFormat::Json => styled(Box::new(JsonRenderer)),
+        OutputFormat::Yaml => styled(Box::new(YamlRenderer)),
+        OutputFormat::Msgpack => Box::new(MsgpackRenderer),
+        OutputFormat::Cbor => Box::new(CborRenderer),
+        OutputFormat::Markdown => styled(Box::new(MarkdownRenderer)),
+        OutputFormat::Csv => styled(Box::new(CsvRenderer)),
+        OutputFormat::Html => styled(Box::new(HtmlRenderer)),
+        OutputFormat::Slack => styled(Box::new(SlackRenderer)),
+    }
+}
+
+/// A whole report: a title, some metadata about the run and the sections in display order.
+pub struct Document {
+    pub title: String,
+    /// Structure version used by the serde-based formats; see [`REPORT_FORMAT_VERSION`].
+    pub format_version: u32,
+    pub metadata: Vec<Field>,
+    pub sections: Vec<Section>,
+}
+
+impl Document {
+    pub fn new(title: &str) -> Self {
+        Document {
+            title: title.to_string(),
+            format_version: REPORT_FORMAT_VERSION,
+            metadata: Vec::new(),
+            sections: Vec::new(),
+        }
+    }
+
+    pub fn push_metadata(&mut self, column: Column, value: impl Into<Value>) {
+        self.metadata.push(Field {
+            column,
+            value: value.into(),
+        });
+    }
+}
+
+/// One report. `id` is a stable machine-readable name; `title` is shown to humans.
+pub struct Section {
+    pub id: String,
+    pub title: String,
+    pub blocks: Vec<Block>,
+}
+
+impl Section {
+    pub fn new(id: &str, title: impl Into<String>) -> Self {
+        Section {
+            id: id.to_string(),
+            title: title.into(),
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Adds a free-form line, such as a note, a hint or an empty-state message.
+    pub fn push_text(&mut self, text: impl Into<String>) {
+        self.blocks.push(Block::Text(text.into()));
+    }
+
+    /// Adds a key/value field. Consecutive fields are grouped into a single block.
+    pub fn push_field(&mut self, column: Column, value: impl Into<Value>) {
+        let field = Field {
+            column,
+            value: value.into(),
+        };
+        match self.blocks.last_mut() {
+            Some(Block::Fields(fields)) => fields.push(field),
+            _ => self.blocks.push(Block::Fields(vec![field])),
+        }
+    }
+
+    pub fn push_table(&mut self, table: Table) {
+        self.blocks.push(Block::Table(table));
+    }
+
+    /// The value of the field with the given column key, if the section has one.
+    pub fn field(&self, key: &str) -> Option<&Value> {
+        self.blocks.iter().find_map(|block| match block {
+            Block::Fields(fields) => fields.iter().find(|f| f.column.key == key).map(|f| &f.value),
+            _ => None,
+        })
+    }
+}
+
+pub enum Block {
+    Text(String),
+    Fields(Vec<Field>),
+    Table(Table),
+}
+
+/// A single labelled value, rendered e.g. as `Total Actions: 1234` in text output.
+pub struct Field {
+    pub column: Column,
+    pub value: Value,
+}
+
+#[derive(Clone)]
+pub struct Table {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Value>>,
+    /// Index of a column whose runs of equal values are shown as collapsible groups in HTML and
+    /// Markdown output. Other formats show the rows as they are.
+    pub group_column: Option<usize>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Table {
+            columns,
+            rows: Vec::new(),
+            group_column: None,
+        }
+    }
+
+    /// Groups consecutive rows with the same value in the column with key `key`.
+    pub fn group_by(mut self, key: &str) -> Self {
+        self.group_column = self.columns.iter().position(|c| c.key == key);
+        debug_assert!(self.group_column.is_some(), "no column {} to group by", key);
+        self
+    }
+
+    /// The runs of consecutive rows sharing the group column value, or all rows as one run for an
+    /// ungrouped table.
+    pub fn groups(&self) -> Vec<&[Vec<Value>]> {
+        let Some(column) = self.group_column else {
+            return vec![&self.rows];
+        };
+        self.rows
+            .chunk_by(|a, b| a[column] == b[column])
+            .collect()
+    }
+
+    pub fn push_row(&mut self, row: Vec<Value>) {
+        debug_assert_eq!(row.len(), self.columns.len(), "row does not match the table schema");
+        self.rows.push(row);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The table without its machine-only columns, for the formats read by people.
+    pub fn for_display(&self) -> Cow<'_, Table> {
+        if !self.columns.iter().any(|c| c.machine_only) {
+            return Cow::Borrowed(self);
+        }
+        let shown: Vec<usize> = (0..self.columns.len()).filter(|&i| !self.columns[i].machine_only).collect();
+        Cow::Owned(Table {
+            columns: shown.iter().map(|&i| self.columns[i].clone()).collect(),
+            rows: self
+                .rows
+                .iter()
+                .map(|row| shown.iter().map(|&i| row[i].clone()).collect())
+                .collect(),
+            group_column: self.group_column.and_then(|g| shown.iter().position(|&i| i == g)),
+        })
+    }
+}
+
+/// Unit used when displaying a byte count. Values are always stored as raw bytes.
+#[derive(Clone, Copy)]
+pub enum ByteUnit {
+    /// 1024 bytes, displayed as `KB`.
+    Kilobytes,
+    /// 1,048,576 bytes, displayed as `MB`.
+    Megabytes,
+    /// 1,000,000 bytes, displayed as `MB` (used for network transfer figures).
+    DecimalMegabytes,
+}
+
+impl ByteUnit {
+    pub fn divisor(self) -> f64 {
+        match self {
+            ByteUnit::Kilobytes => 1024.0,
+            ByteUnit::Megabytes => 1_048_576.0,
+            ByteUnit::DecimalMegabytes => 1_000_000.0,
+        }
+    }
+
+    pub fn suffix(self) -> &'static str {
+        match self {
+            ByteUnit::Kilobytes => "KB",
+            ByteUnit::Megabytes | ByteUnit::DecimalMegabytes => "MB",
+        }
+    }
+}
+
+/// How the values of a column are interpreted and formatted.
+#[derive(Clone, Copy)]
+pub enum ColumnKind {
+    Text,
+    Integer,
+    Float { precision: usize },
+    /// Seconds as a float.
+    Seconds { precision: usize },
+    /// A byte count.
+    Bytes { unit: ByteUnit, precision: usize },
+    /// A percentage in the range 0-100.
+    Percent { precision: usize },
+    /// A multiplier, displayed with an `x` suffix.
+    Ratio { precision: usize },
+    /// Proportional segments drawn as a bar; each segment uses the matching symbol.
+    Bar {
+        width: usize,
+        symbols: &'static [char],
+    },
+}
+
+/// Describes one column of a table (or one field): a stable key, a display title and a kind.
+#[derive(Clone)]
+pub struct Column {
+    pub key: String,
+    pub title: String,
+    pub kind: ColumnKind,
+    /// Show an explicit `+` on positive numbers (used for deltas).
+    pub signed: bool,
+    /// Only included in machine-readable formats, e.g. IDs for joining with other data.
+    pub machine_only: bool,
+}
+
+impl Column {
+    fn new(key: &str, title: &str, kind: ColumnKind) -> Self {
+        Column {
+            key: key.to_string(),
+            title: title.to_string(),
+            kind,
+            signed: false,
+            machine_only: false,
+        }
+    }
+
+    pub fn text(key: &str, title: &str) -> Self {
+        Self::new(key, title, ColumnKind::Text)
+    }
+
+    pub fn integer(key: &str, title: &str) -> Self {
+        Self::new(key, title, ColumnKind::Integer)
+    }
+
+    pub fn float(key: &str, title: &str, precision: usize) -> Self {
+        Self::new(key, title, ColumnKind::Float { precision })
+    }
+
+    pub fn seconds(key: &str, title: &str, precision: usize) -> Self {
+        Self::new(key, title, ColumnKind::Seconds { precision })
+    }
+
+    pub fn bytes(key: &str, title: &str, unit: ByteUnit, precision: usize) -> Self {
+        Self::new(key, title, ColumnKind::Bytes { unit, precision })
+    }
+
+    pub fn percent(key: &str, title: &str, precision: usize) -> Self {
+        Self::new(key, title, ColumnKind::Percent { precision })
+    }
+
+    pub fn ratio(key: &str, title: &str, precision: usize) -> Self {
+        Self::new(key, title, ColumnKind::Ratio { precision })
+    }
+
+    pub fn bar(key: &str, title: &str, width: usize, symbols: &'static [char]) -> Self {
+        Self::new(key, title, ColumnKind::Bar { width, symbols })
+    }
+
+    /// Marks the column as a delta so positive values are shown with a leading `+`.
+    pub fn signed(mut self) -> Self {
+        self.signed = true;
+        self
+    }
+
+    /// Leaves the column out of the text, Markdown, HTML and Slack output.
+    pub fn machine_only(mut self) -> Self {
+        self.machine_only = true;
+        self
+    }
+
+    /// Returns true if the column holds numbers and should be right-aligned.
+    pub fn is_numeric(&self) -> bool {
+        !matches!(self.kind, ColumnKind::Text | ColumnKind::Bar { .. })
+    }
+}
+
+/// A raw cell value. Formatting is decided by the column kind and the renderer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Text(String),
+    Int(i64),
+    Float(f64),
+    /// Fractions (0.0-1.0 of the full bar width) for each segment of a bar.
+    Bar(Vec<f64>),
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Text(value.to_string())
+    }
+}
+
+impl From<&String> for Value {
+    fn from(value: &String) -> Self {
+        Value::Text(value.clone())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Text(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Int(value.into())
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Value::Int(value.try_into().unwrap_or(i64::MAX))
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        Value::Int(value.try_into().unwrap_or(i64::MAX))
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+/// Durations are stored as float seconds.
+impl From<Duration> for Value {
+    fn from(value: Duration) -> Self {
+        Value::Float(value.as_secs_f64())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl Value {
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(i) => Some(*i as f64),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+}
+
+/// Formats `12345` as `12,345`, or with the separators of `--number-locale`.
+pub(crate) fn group_thousands(value: u64) -> String {
+    output_style().numbers.apply(&value.to_string())
+}
+
+/// Formats a value for human-readable output (text, Markdown, HTML) according to its column.
+pub fn format_value(column: &Column, value: &Value) -> String {
+    let number = match value {
+        Value::Null => return "-".to_string(),
+        Value::Text(text) => return text.clone(),
+        Value::Bar(segments) => return format_bar(column, segments),
+        Value::Int(_) | Value::Float(_) => value.as_f64().unwrap_or_default(),
+    };
+    let sign = if column.signed && number > 0.0 { "+" } else { "" };
+    let numbers = output_style().numbers;
+    let fixed = |precision: usize, number: f64| numbers.apply(&format!("{:.*}", precision, number));
+    match column.kind {
+        ColumnKind::Text => match value {
+            Value::Int(i) => i.to_string(),
+            _ => number.to_string(),
+        },
+        ColumnKind::Integer => match value {
+            Value::Int(i) => format!("{}{}", sign, numbers.apply(&i.to_string())),
+            _ => format!("{}{}", sign, fixed(0, number)),
+        },
+        ColumnKind::Float { precision } => format!("{}{}", sign, fixed(precision, number)),
+        ColumnKind::Seconds { precision } => format!("{}{}s", sign, fixed(precision, number)),
+        ColumnKind::Bytes { unit, precision } => {
+            format!("{}{}{}", sign, fixed(precision, number / unit.divisor()), unit.suffix())
+        }
+        ColumnKind::Percent { precision } => format!("{}{}%", sign, fixed(precision, number)),
+        ColumnKind::Ratio { precision } => format!("{}{}x", sign, fixed(precision, number)),
+        ColumnKind::Bar { .. } => String::new(),
+    }
+}
+
+/// Draws bar segments as repeated symbols, e.g. `QQSSEEEEEEFF`.
+fn format_bar(column: &Column, segments: &[f64]) -> String {
+    let ColumnKind::Bar { width, symbols } = column.kind else {
+        return String::new();
+    };
+    let mut bar = String::new();
+    for (i, fraction) in segments.iter().enumerate() {
+        let symbol = symbols.get(i).or(symbols.last()).copied().unwrap_or('#');
+        let cells = (fraction.clamp(0.0, 1.0) * width as f64).round() as usize;
+        bar.extend(std::iter::repeat_n(symbol, cells));
+    }
+    bar
+}
@@ -0,0 +1,240 @@
+//! Serde support for the report model, shared by every serde-based output format.
+//!
+//! A [`Document`] is converted into the borrowed wire types below before serialization. They
+//! derive both `Serialize` and `JsonSchema`, so the schema printed by `--emit-schema` is the
+//! contract the JSON, YAML, MessagePack and CBOR outputs actually follow.
+
+use super::{Block, ColumnKind, Document, Field, Section, Table, Value};
+use indexmap::IndexMap;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::ser::{Serialize, Serializer};
+use std::borrow::Cow;
+
+/// Version of the serialized report structure, embedded in every document as `format_version`.
+///
+/// Adding sections, fields, table columns or metadata keys is compatible and keeps the version.
+/// Renaming or removing anything, or changing a value's type or unit, bumps it; the previous
+/// version stays available through `--format-version` for one release cycle.
+///
+/// - 1: tables list their columns as plain keys.
+/// - 2: tables describe each column with its key, title, kind and unit.
+pub const REPORT_FORMAT_VERSION: u32 = 2;
+
+/// Oldest version that can still be requested with `--format-version`.
+pub const OLDEST_REPORT_FORMAT_VERSION: u32 = REPORT_FORMAT_VERSION - 1;
+
+/// An analysis report: metadata about the run and one entry per report section.
+#[derive(serde::Serialize, JsonSchema)]
+#[schemars(rename = "Report")]
+struct WireDocument<'a, T> {
+    /// Version of this document structure. Incremented whenever the structure changes.
+    format_version: u32,
+    title: &'a str,
+    /// Values keyed by stable metadata keys, e.g. `log_file`.
+    metadata: IndexMap<&'a str, &'a Value>,
+    sections: Vec<WireSection<'a, T>>,
+}
+
+/// One report section. `id` is stable across releases; `title` is for humans.
+#[derive(serde::Serialize, JsonSchema)]
+#[schemars(rename = "Section")]
+struct WireSection<'a, T> {
+    id: &'a str,
+    title: &'a str,
+    /// Summary values keyed by column key.
+    fields: IndexMap<&'a str, &'a Value>,
+    tables: Vec<T>,
+    /// Free-form notes, hints and empty-state messages.
+    notes: Vec<&'a str>,
+}
+
+/// A table with its column descriptions in display order and one object per row.
+#[derive(serde::Serialize, JsonSchema)]
+#[schemars(rename = "Table")]
+struct WireTable<'a> {
+    columns: Vec<WireColumn<'a>>,
+    rows: Vec<IndexMap<&'a str, &'a Value>>,
+}
+
+/// A table as serialized by format version 1, with its column keys in display order.
+#[derive(serde::Serialize, JsonSchema)]
+#[schemars(rename = "Table")]
+struct WireTableV1<'a> {
+    columns: Vec<&'a str>,
+    rows: Vec<IndexMap<&'a str, &'a Value>>,
+}
+
+/// Describes one table column.
+#[derive(serde::Serialize, JsonSchema)]
+#[schemars(rename = "Column")]
+struct WireColumn<'a> {
+    /// Key of this column in each row.
+    key: &'a str,
+    /// Display title.
+    title: &'a str,
+    kind: WireKind,
+    /// Unit of numeric values, if any. Values are always in the base unit.
+    unit: Option<WireUnit>,
+}
+
+/// JSON type of a column's values.
+#[derive(serde::Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(rename = "ColumnKind")]
+enum WireKind {
+    Text,
+    Integer,
+    Number,
+    /// An array of segment fractions.
+    Bar,
+}
+
+#[derive(serde::Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(rename = "Unit")]
+enum WireUnit {
+    Seconds,
+    Bytes,
+    /// A percentage in the range 0-100.
+    Percent,
+    /// A multiplier.
+    Ratio,
+}
+
+impl<'a> From<&'a super::Column> for WireColumn<'a> {
+    fn from(column: &'a super::Column) -> Self {
+        let (kind, unit) = match column.kind {
+            ColumnKind::Text => (WireKind::Text, None),
+            ColumnKind::Integer => (WireKind::Integer, None),
+            ColumnKind::Float { .. } => (WireKind::Number, None),
+            ColumnKind::Seconds { .. } => (WireKind::Number, Some(WireUnit::Seconds)),
+            ColumnKind::Bytes { .. } => (WireKind::Integer, Some(WireUnit::Bytes)),
+            ColumnKind::Percent { .. } => (WireKind::Number, Some(WireUnit::Percent)),
+            ColumnKind::Ratio { .. } => (WireKind::Number, Some(WireUnit::Ratio)),
+            ColumnKind::Bar { .. } => (WireKind::Bar, None),
+        };
+        WireColumn {
+            key: &column.key,
+            title: &column.title,
+            kind,
+            unit,
+        }
+    }
+}
+
+fn fields_map<'a>(fields: impl IntoIterator<Item = &'a Field>) -> IndexMap<&'a str, &'a Value> {
+    fields
+        .into_iter()
+        .map(|field| (field.column.key.as_str(), &field.value))
+        .collect()
+}
+
+fn rows_map(table: &Table) -> Vec<IndexMap<&str, &Value>> {
+    table
+        .rows
+        .iter()
+        .map(|row| {
+            table
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| (column.key.as_str(), value))
+                .collect()
+        })
+        .collect()
+}
+
+impl<'a> From<&'a Table> for WireTable<'a> {
+    fn from(table: &'a Table) -> Self {
+        WireTable {
+            columns: table.columns.iter().map(WireColumn::from).collect(),
+            rows: rows_map(table),
+        }
+    }
+}
+
+impl<'a> From<&'a Table> for WireTableV1<'a> {
+    fn from(table: &'a Table) -> Self {
+        WireTableV1 {
+            columns: table.columns.iter().map(|c| c.key.as_str()).collect(),
+            rows: rows_map(table),
+        }
+    }
+}
+
+impl<'a, T: From<&'a Table>> From<&'a Section> for WireSection<'a, T> {
+    fn from(section: &'a Section) -> Self {
+        let mut wire = WireSection {
+            id: &section.id,
+            title: &section.title,
+            fields: IndexMap::new(),
+            tables: Vec::new(),
+            notes: Vec::new(),
+        };
+        for block in &section.blocks {
+            match block {
+                Block::Fields(fields) => wire.fields.extend(fields_map(fields)),
+                Block::Table(table) => wire.tables.push(table.into()),
+                Block::Text(text) => wire.notes.push(text),
+            }
+        }
+        wire
+    }
+}
+
+impl<'a, T: From<&'a Table>> From<&'a Document> for WireDocument<'a, T> {
+    fn from(document: &'a Document) -> Self {
+        WireDocument {
+            format_version: document.format_version,
+            title: &document.title,
+            metadata: fields_map(&document.metadata),
+            sections: document.sections.iter().map(WireSection::from).collect(),
+        }
+    }
+}
+
+impl Serialize for Document {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.format_version == 1 {
+            WireDocument::<WireTableV1>::from(self).serialize(serializer)
+        } else {
+            WireDocument::<WireTable>::from(self).serialize(serializer)
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Text(text) => serializer.serialize_str(text),
+            Value::Int(i) => serializer.serialize_i64(*i),
+            Value::Float(f) if f.is_finite() => serializer.serialize_f64(*f),
+            Value::Float(_) => serializer.serialize_none(),
+            Value::Bar(segments) => segments.serialize(serializer),
+        }
+    }
+}
+
+impl JsonSchema for Value {
+    fn schema_name() -> Cow<'static, str> {
+        "Value".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A raw value in base units (seconds, bytes, percent 0-100). Bars are arrays of segment fractions.",
+            "type": ["null", "string", "integer", "number", "array"],
+            "items": { "type": "number" }
+        })
+    }
+}
+
+/// The JSON Schema of the serialized report document in the given format version.
+pub fn report_schema(format_version: u32) -> Schema {
+    if format_version == 1 {
+        schemars::schema_for!(WireDocument<'static, WireTableV1<'static>>)
+    } else {
+        schemars::schema_for!(WireDocument<'static, WireTable<'static>>)
+    }
+}
@@ -0,0 +1,125 @@
+use super::{format_value, text, Block, Document, Field, Renderer, Table};
+use serde_json::{json, Value as Json};
+use std::io::{self, Write};
+
+/// Rows shown per table; Slack messages are meant to be skimmed.
+const MAX_TABLE_ROWS: usize = 5;
+/// Slack rejects messages with more blocks than this.
+const MAX_BLOCKS: usize = 50;
+/// Slack's limit on the text of a section block, and on the number of fields in one.
+const MAX_SECTION_TEXT: usize = 3000;
+const MAX_SECTION_FIELDS: usize = 10;
+
+/// A Slack Block Kit message (`{"text": ..., "blocks": [...]}`) that can be posted as-is to an
+/// incoming webhook. Fields become mrkdwn field grids, tables are shortened to their first rows
+/// and shown as preformatted text, and notes become context blocks.
+pub struct SlackRenderer;
+
+impl Renderer for SlackRenderer {
+    fn render(&self, document: &Document, out: &mut dyn Write) -> io::Result<()> {
+        let mut blocks = vec![json!({
+            "type": "header",
+            "text": { "type": "plain_text", "text": truncate(&document.title, 150) },
+        })];
+        if !document.metadata.is_empty() {
+            let metadata: Vec<String> = document.metadata.iter().map(field_text).collect();
+            blocks.push(context(&metadata.join("  |  ")));
+        }
+
+        for section in &document.sections {
+            let mut section_blocks = vec![
+                json!({ "type": "divider" }),
+                mrkdwn_section(&format!("*{}*", escape(&section.title))),
+            ];
+            for block in &section.blocks {
+                match block {
+                    Block::Fields(fields) => section_blocks.extend(fields_blocks(fields)),
+                    Block::Table(table) if !table.rows.is_empty() => section_blocks.push(table_block(table)?),
+                    Block::Table(_) => {}
+                    Block::Text(text) => section_blocks.push(context(&escape(text))),
+                }
+            }
+            // Leave room for the truncation notice.
+            if blocks.len() + section_blocks.len() >= MAX_BLOCKS {
+                blocks.push(context("_Further sections omitted; see the full report._"));
+                break;
+            }
+            blocks.extend(section_blocks);
+        }
+
+        let message = json!({ "text": document.title, "blocks": blocks });
+        serde_json::to_writer_pretty(&mut *out, &message).map_err(io::Error::from)?;
+        writeln!(out)
+    }
+}
+
+fn field_text(field: &Field) -> String {
+    format!(
+        "*{}:* {}",
+        escape(&field.column.title),
+        escape(&format_value(&field.column, &field.value))
+    )
+}
+
+fn fields_blocks(fields: &[Field]) -> Vec<Json> {
+    fields
+        .chunks(MAX_SECTION_FIELDS)
+        .map(|chunk| {
+            let fields: Vec<Json> = chunk
+                .iter()
+                .map(|field| {
+                    json!({
+                        "type": "mrkdwn",
+                        "text": format!(
+                            "*{}*\n{}",
+                            escape(&field.column.title),
+                            escape(&format_value(&field.column, &field.value))
+                        ),
+                    })
+                })
+                .collect();
+            json!({ "type": "section", "fields": fields })
+        })
+        .collect()
+}
+
+fn table_block(table: &Table) -> io::Result<Json> {
+    let shown = Table {
+        columns: table.columns.clone(),
+        rows: table.rows.iter().take(MAX_TABLE_ROWS).cloned().collect(),
+        group_column: None,
+    };
+    let mut buffer = Vec::new();
+    text::write_table(&shown, &mut buffer)?;
+    let mut rendered = String::from_utf8_lossy(&buffer).into_owned();
+    if table.rows.len() > MAX_TABLE_ROWS {
+        rendered.push_str(&format!("... {} more rows\n", table.rows.len() - MAX_TABLE_ROWS));
+    }
+    // Six characters go to the code fences.
+    let body = truncate(&escape(rendered.trim_end()), MAX_SECTION_TEXT - 6);
+    Ok(mrkdwn_section(&format!("```{}```", body)))
+}
+
+fn mrkdwn_section(text: &str) -> Json {
+    json!({ "type": "section", "text": { "type": "mrkdwn", "text": text } })
+}
+
+fn context(text: &str) -> Json {
+    json!({ "type": "context", "elements": [{ "type": "mrkdwn", "text": text }] })
+}
+
+/// Escapes the three characters Slack treats as control sequences in message text.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Shortens `text` to at most `max` characters, marking the cut with an ellipsis.
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        let mut shortened: String = text.chars().take(max - 1).collect();
+        shortened.push('…');
+        shortened
+    }
+}
@@ -0,0 +1,174 @@
+//! Process-wide output conventions: how numbers are written in the formats read by people, and
+//! whether output is restricted to ASCII for log processors that mangle anything else.
+
+use super::{Document, Renderer};
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::sync::OnceLock;
+
+/// Thousands and decimal separators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Written between groups of three integer digits; `None` leaves numbers ungrouped.
+    pub thousands: Option<char>,
+    pub decimal: char,
+}
+
+impl NumberFormat {
+    /// `1,234.5`, the default.
+    pub const ENGLISH: NumberFormat = NumberFormat {
+        thousands: Some(','),
+        decimal: '.',
+    };
+    /// `1234.5`, as Rust and the machine-readable formats write numbers.
+    pub const PLAIN: NumberFormat = NumberFormat {
+        thousands: None,
+        decimal: '.',
+    };
+
+    /// The conventions of a POSIX locale name such as `de_DE.UTF-8`, by language (and region, for
+    /// Switzerland). `none`, `C` and `POSIX` leave numbers ungrouped.
+    pub fn for_locale(name: &str) -> Option<NumberFormat> {
+        let name = name.split(['.', '@']).next().unwrap_or_default();
+        let (language, region) = name.split_once(['_', '-']).unwrap_or((name, ""));
+        let separators = |thousands, decimal| Some(NumberFormat { thousands: Some(thousands), decimal });
+        match (language.to_ascii_lowercase().as_str(), region.to_ascii_uppercase().as_str()) {
+            ("none" | "c" | "posix", _) => Some(NumberFormat::PLAIN),
+            (_, "CH" | "LI") => separators('’', '.'),
+            ("en" | "ja" | "zh" | "ko" | "he" | "th" | "hi" | "ms" | "fil" | "ga", _) => Some(NumberFormat::ENGLISH),
+            ("de" | "nl" | "it" | "es" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl" | "sr" | "vi", _) => {
+                separators('.', ',')
+            }
+            ("fr", _) => separators('\u{202f}', ','),
+            ("ru" | "pl" | "cs" | "sk" | "sv" | "nb" | "nn" | "no" | "fi" | "uk" | "hu" | "bg" | "lt" | "lv" | "et", _) => {
+                separators('\u{a0}', ',')
+            }
+            _ => None,
+        }
+    }
+
+    /// The conventions of the first of `LC_ALL`, `LC_NUMERIC` and `LANG` that is set, or English.
+    pub fn from_environment() -> NumberFormat {
+        ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| NumberFormat::for_locale(&locale))
+            .unwrap_or(NumberFormat::ENGLISH)
+    }
+
+    /// Groups the integer digits of a number formatted by Rust (`-1234.5`) and swaps in the
+    /// decimal separator. Anything else, such as `NaN`, is returned as is.
+    pub fn apply(&self, formatted: &str) -> String {
+        let (sign, unsigned) = formatted.split_at(usize::from(formatted.starts_with('-')));
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (unsigned, None),
+        };
+        if integer.is_empty() || !integer.bytes().all(|b| b.is_ascii_digit()) {
+            return formatted.to_string();
+        }
+        let mut localized = sign.to_string();
+        for (i, digit) in integer.chars().enumerate() {
+            if let Some(thousands) = self.thousands
+                && i > 0
+                && (integer.len() - i).is_multiple_of(3)
+            {
+                localized.push(thousands);
+            }
+            localized.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            localized.push(self.decimal);
+            localized.push_str(fraction);
+        }
+        localized
+    }
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat::ENGLISH
+    }
+}
+
+/// How every renderer writes its output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputStyle {
+    pub numbers: NumberFormat,
+    /// Replace every non-ASCII character, see [`to_ascii`].
+    pub ascii: bool,
+}
+
+static OUTPUT_STYLE: OnceLock<OutputStyle> = OnceLock::new();
+
+/// Sets the style for the rest of the process. Only the first call has an effect; until then the
+/// default style is used.
+pub fn set_output_style(style: OutputStyle) {
+    let _ = OUTPUT_STYLE.set(style);
+}
+
+pub fn output_style() -> OutputStyle {
+    OUTPUT_STYLE.get().copied().unwrap_or_default()
+}
+
+/// Replaces the characters the reports draw with (bars, sparklines, status icons) by ASCII
+/// look-alikes, and any other non-ASCII character, e.g. in a target label, by `?`.
+pub fn to_ascii(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    let mut ascii = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            _ if c.is_ascii() => ascii.push(c),
+            '…' => ascii.push_str("..."),
+            '·' | '–' | '—' | '─' | '═' => ascii.push('-'),
+            '│' | '║' => ascii.push('|'),
+            '→' => ascii.push_str("->"),
+            '←' => ascii.push_str("<-"),
+            '×' => ascii.push('x'),
+            '≥' => ascii.push_str(">="),
+            '≤' => ascii.push_str("<="),
+            '±' => ascii.push_str("+/-"),
+            'µ' => ascii.push('u'),
+            '‘' | '’' => ascii.push('\''),
+            '“' | '”' => ascii.push('"'),
+            '\u{a0}' | '\u{202f}' | '\u{2009}' => ascii.push(' '),
+            '✅' => ascii.push_str("[ok]"),
+            '❌' => ascii.push_str("[x]"),
+            '▁' => ascii.push('_'),
+            '▂' => ascii.push('.'),
+            '▃' => ascii.push('-'),
+            '▄' => ascii.push('='),
+            '▅' => ascii.push('+'),
+            '▆' => ascii.push('*'),
+            '▇' => ascii.push('%'),
+            '█' => ascii.push('#'),
+            // Emoji presentation selectors and zero-width joiners have no look-alike.
+            '\u{fe0f}' | '\u{200d}' => {}
+            _ => ascii.push('?'),
+        }
+    }
+    Cow::Owned(ascii)
+}
+
+/// Renders through another renderer and rewrites its output with [`to_ascii`].
+pub(super) struct AsciiRenderer(pub(super) Box<dyn Renderer>);
+
+impl Renderer for AsciiRenderer {
+    fn render(&self, document: &Document, out: &mut dyn Write) -> io::Result<()> {
+        let mut rendered = Vec::new();
+        self.0.render(document, &mut rendered)?;
+        out.write_all(to_ascii(&String::from_utf8_lossy(&rendered)).as_bytes())
+    }
+}
+
+/// Wraps `renderer` in an [`AsciiRenderer`] when the output style asks for ASCII.
+pub fn styled(renderer: Box<dyn Renderer>) -> Box<dyn Renderer> {
+    if output_style().ascii {
+        Box::new(AsciiRenderer(renderer))
+    } else {
+        renderer
+    }
+}
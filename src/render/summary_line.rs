@@ -0,0 +1,43 @@
+use super::{group_thousands, output_style, Document, Renderer, Value};
+use std::io::{self, Write};
+
+/// A single status line built from the overall summary, such as
+/// `✅ 12,345 actions · 91.2% cache hits · 14m exec · 0 failures`, for CI status descriptions and
+/// chat messages.
+pub struct SummaryLineRenderer;
+
+impl Renderer for SummaryLineRenderer {
+    fn render(&self, document: &Document, out: &mut dyn Write) -> io::Result<()> {
+        let summary = document
+            .sections
+            .iter()
+            .find(|section| section.id == "overall_summary")
+            .ok_or_else(|| io::Error::other("the summary line needs the summary report"))?;
+        let number = |key: &str| summary.field(key).and_then(Value::as_f64).unwrap_or_default();
+
+        let failures = number("failed_actions") as u64;
+        let icon = if failures == 0 { "✅" } else { "❌" };
+        writeln!(
+            out,
+            "{} {} actions · {}% cache hits · {} exec · {} {}",
+            icon,
+            group_thousands(number("total_actions") as u64),
+            output_style().numbers.apply(&format!("{:.1}", number("cache_hit_rate"))),
+            short_duration(number("execution_time")),
+            group_thousands(failures),
+            if failures == 1 { "failure" } else { "failures" }
+        )
+    }
+}
+
+/// Rounds a duration to the unit that matters at a glance: `42s`, `14m` or `2h05m`.
+fn short_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 3600 {
+        format!("{}m", seconds / 60)
+    } else {
+        format!("{}h{:02}m", seconds / 3600, seconds % 3600 / 60)
+    }
+}
@@ -0,0 +1,106 @@
+use super::{format_value, Block, Column, Document, Field, Renderer, Table};
+use std::io::{self, Write};
+
+/// Plain-text tables for the terminal, padded to the widest value in each column.
+pub struct TextRenderer;
+
+impl Renderer for TextRenderer {
+    fn render(&self, document: &Document, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "========================================")?;
+        writeln!(out, " {}", document.title)?;
+        writeln!(out, "========================================")?;
+        write_fields(&document.metadata, out)?;
+        writeln!(out)?;
+
+        for section in &document.sections {
+            writeln!(out, "--- {} ---", section.title)?;
+            for block in &section.blocks {
+                match block {
+                    Block::Text(text) => writeln!(out, "{}", text)?,
+                    Block::Fields(fields) => write_fields(fields, out)?,
+                    Block::Table(table) => write_table(table, out)?,
+                }
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_fields(fields: &[Field], out: &mut dyn Write) -> io::Result<()> {
+    for field in fields {
+        writeln!(
+            out,
+            "{}: {}",
+            field.column.title,
+            format_value(&field.column, &field.value)
+        )?;
+    }
+    Ok(())
+}
+
+pub(super) fn write_table(table: &Table, out: &mut dyn Write) -> io::Result<()> {
+    let table = &*table.for_display();
+    let cells: Vec<Vec<String>> = table
+        .rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(&table.columns)
+                .map(|(value, column)| format_value(column, value))
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = table
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(column.title.chars().count())
+        })
+        .collect();
+
+    let headers: Vec<String> = table.columns.iter().map(|c| c.title.clone()).collect();
+    write_row(&headers, &table.columns, &widths, out)?;
+
+    let separator_width = widths.iter().sum::<usize>() + 3 * widths.len().saturating_sub(1);
+    writeln!(out, "{}", "-".repeat(separator_width))?;
+
+    for row in &cells {
+        write_row(row, &table.columns, &widths, out)?;
+    }
+    Ok(())
+}
+
+fn write_row(
+    cells: &[String],
+    columns: &[Column],
+    widths: &[usize],
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let last = cells.len().saturating_sub(1);
+    let mut line = String::new();
+    for (i, (cell, column)) in cells.iter().zip(columns).enumerate() {
+        if i > 0 {
+            line.push_str(" | ");
+        }
+        let padding = widths[i].saturating_sub(cell.chars().count());
+        if column.is_numeric() {
+            line.push_str(&" ".repeat(padding));
+            line.push_str(cell);
+        } else {
+            line.push_str(cell);
+            // The last left-aligned column (typically a target label) is not padded.
+            if i != last {
+                line.push_str(&" ".repeat(padding));
+            }
+        }
+    }
+    writeln!(out, "{}", line)
+}
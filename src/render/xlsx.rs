@@ -0,0 +1,120 @@
+use super::{Block, Column, ColumnKind, Field, Section, Table, Value};
+use crate::AppResult;
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use std::path::Path;
+
+/// One worksheet: a name (at most 31 characters) and the sections stacked on it.
+pub struct Sheet<'a> {
+    pub name: &'a str,
+    pub sections: Vec<Section>,
+}
+
+/// Writes a workbook with one worksheet per sheet. Each section starts with its title in bold,
+/// followed by its fields as label/value rows, its tables (header row, then one row per table row)
+/// and its notes. Numbers stay numeric, with a number format matching the column's unit; bar
+/// columns are left out.
+pub fn write_workbook(sheets: &[Sheet], path: &Path) -> AppResult<()> {
+    let mut workbook = Workbook::new();
+    for sheet in sheets {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(sheet.name)?;
+        let mut row = 0;
+        for section in &sheet.sections {
+            row = write_section(worksheet, row, section)? + 1;
+        }
+        worksheet.autofit();
+    }
+    workbook.save(path)?;
+    Ok(())
+}
+
+/// Writes a section starting at `row` and returns the first row after it.
+fn write_section(worksheet: &mut Worksheet, mut row: u32, section: &Section) -> Result<u32, XlsxError> {
+    let bold = Format::new().set_bold();
+    worksheet.write_string_with_format(row, 0, &section.title, &bold)?;
+    row += 1;
+    for block in &section.blocks {
+        match block {
+            Block::Fields(fields) => row = write_fields(worksheet, row, fields)?,
+            Block::Table(table) => row = write_table(worksheet, row, table, &bold)? + 1,
+            Block::Text(text) => {
+                worksheet.write_string(row, 0, text)?;
+                row += 1;
+            }
+        }
+    }
+    Ok(row)
+}
+
+fn write_fields(worksheet: &mut Worksheet, mut row: u32, fields: &[Field]) -> Result<u32, XlsxError> {
+    for field in fields {
+        if matches!(field.column.kind, ColumnKind::Bar { .. }) {
+            continue;
+        }
+        worksheet.write_string(row, 0, &field.column.title)?;
+        write_cell(worksheet, row, 1, &field.column, &field.value)?;
+        row += 1;
+    }
+    Ok(row)
+}
+
+fn write_table(worksheet: &mut Worksheet, mut row: u32, table: &Table, bold: &Format) -> Result<u32, XlsxError> {
+    let columns: Vec<usize> = (0..table.columns.len())
+        .filter(|&i| !matches!(table.columns[i].kind, ColumnKind::Bar { .. }))
+        .collect();
+    for (col, &i) in columns.iter().enumerate() {
+        worksheet.write_string_with_format(row, col as u16, &table.columns[i].title, bold)?;
+    }
+    row += 1;
+    for values in &table.rows {
+        for (col, &i) in columns.iter().enumerate() {
+            if let Some(value) = values.get(i) {
+                write_cell(worksheet, row, col as u16, &table.columns[i], value)?;
+            }
+        }
+        row += 1;
+    }
+    Ok(row)
+}
+
+fn write_cell(worksheet: &mut Worksheet, row: u32, col: u16, column: &Column, value: &Value) -> Result<(), XlsxError> {
+    let number = match value {
+        Value::Null | Value::Bar(_) => return Ok(()),
+        Value::Text(text) => {
+            worksheet.write_string(row, col, text)?;
+            return Ok(());
+        }
+        Value::Int(_) | Value::Float(_) => value.as_f64().unwrap_or_default(),
+    };
+    let (scaled, format) = number_format(column, number);
+    worksheet.write_number_with_format(row, col, scaled, &Format::new().set_num_format(&format))?;
+    Ok(())
+}
+
+/// Scales a raw value into the column's display unit and returns the matching number format.
+fn number_format(column: &Column, number: f64) -> (f64, String) {
+    let decimals = |precision: usize| {
+        if precision == 0 {
+            "0".to_string()
+        } else {
+            format!("0.{}", "0".repeat(precision))
+        }
+    };
+    let (scaled, format) = match column.kind {
+        ColumnKind::Text | ColumnKind::Bar { .. } => (number, "General".to_string()),
+        ColumnKind::Integer => (number, "#,##0".to_string()),
+        ColumnKind::Float { precision } => (number, decimals(precision)),
+        ColumnKind::Seconds { precision } => (number, format!("{}\"s\"", decimals(precision))),
+        ColumnKind::Bytes { unit, precision } => (
+            number / unit.divisor(),
+            format!("{}\" {}\"", decimals(precision), unit.suffix()),
+        ),
+        ColumnKind::Percent { precision } => (number / 100.0, format!("{}%", decimals(precision))),
+        ColumnKind::Ratio { precision } => (number, format!("{}\"x\"", decimals(precision))),
+    };
+    if column.signed && format != "General" {
+        (scaled, format!("+{0};-{0};{0}", format))
+    } else {
+        (scaled, format)
+    }
+}
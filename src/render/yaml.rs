@@ -0,0 +1,11 @@
+use super::{Document, Renderer};
+use std::io::{self, Write};
+
+/// YAML with the same structure and raw values as the JSON output.
+pub struct YamlRenderer;
+
+impl Renderer for YamlRenderer {
+    fn render(&self, document: &Document, out: &mut dyn Write) -> io::Result<()> {
+        serde_yaml::to_writer(&mut *out, document).map_err(io::Error::other)
+    }
+}
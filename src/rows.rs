@@ -0,0 +1,223 @@
+//! A flat, one-row-per-spawn view of an execution log for export to tabular stores.
+//!
+//! The row type is declared through `spawn_row!` so that the BigQuery schema is derived from the
+//! same field list (names, Rust types and doc comments) and cannot drift from the serialized rows;
+//! the same goes for the DataFrame built with the `dataframe` feature.
+
+use crate::commands::analyze::{output_bytes, start_seconds, to_std_duration};
+use crate::filters::normalize_path_keeping_cpu;
+use crate::proto::{SpawnExec, SpawnMetrics};
+use serde::Serialize;
+
+/// One column of a BigQuery table schema, in the JSON form accepted by `bq load --schema`.
+#[derive(Serialize)]
+pub struct BigQueryField {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub field_type: &'static str,
+    pub mode: &'static str,
+    pub description: String,
+}
+
+/// Maps a Rust field type to its BigQuery column type and mode.
+pub trait BigQueryType {
+    const TYPE: &'static str;
+    const MODE: &'static str = "REQUIRED";
+}
+
+impl BigQueryType for String {
+    const TYPE: &'static str = "STRING";
+}
+
+impl BigQueryType for i64 {
+    const TYPE: &'static str = "INTEGER";
+}
+
+impl BigQueryType for f64 {
+    const TYPE: &'static str = "FLOAT";
+}
+
+impl BigQueryType for bool {
+    const TYPE: &'static str = "BOOLEAN";
+}
+
+impl<T: BigQueryType> BigQueryType for Option<T> {
+    const TYPE: &'static str = T::TYPE;
+    const MODE: &'static str = "NULLABLE";
+}
+
+impl<T: BigQueryType> BigQueryType for Vec<T> {
+    const TYPE: &'static str = T::TYPE;
+    const MODE: &'static str = "REPEATED";
+}
+
+macro_rules! spawn_row {
+    ($(#[$meta:meta])* pub struct $name:ident { $($(#[doc = $doc:literal])* pub $field:ident: $ty:ty,)* }) => {
+        $(#[$meta])*
+        pub struct $name {
+            $($(#[doc = $doc])* pub $field: $ty,)*
+        }
+
+        impl $name {
+            /// The BigQuery schema matching the serialized rows, derived from the field list.
+            pub fn bigquery_schema() -> Vec<BigQueryField> {
+                vec![$(BigQueryField {
+                    name: stringify!($field),
+                    field_type: <$ty as BigQueryType>::TYPE,
+                    mode: <$ty as BigQueryType>::MODE,
+                    description: <[&str]>::join(&[$($doc),*], " ").trim().to_string(),
+                },)*]
+            }
+
+            /// The rows as a DataFrame with one column per field, named and typed like the
+            /// serialized rows.
+            #[cfg(feature = "dataframe")]
+            pub fn dataframe(rows: &[$name]) -> polars::prelude::DataFrame {
+                use polars::prelude::{DataFrame, IntoColumn, NamedFrom, Series};
+                let columns = vec![$(Series::new(
+                    stringify!($field).into(),
+                    rows.iter().map(|row| row.$field.clone()).collect::<Vec<$ty>>(),
+                )
+                .into_column(),)*];
+                DataFrame::new(columns).expect("every column has one value per row")
+            }
+        }
+    };
+}
+
+spawn_row! {
+    /// A spawn flattened into scalar columns. Durations are in seconds, sizes in bytes.
+    #[derive(Serialize)]
+    pub struct SpawnRow {
+        /// Stable ID of the spawn across logs, exports and tool versions; see `spawn_id`.
+        pub spawn_id: String,
+        /// Label of the target that owns the action; empty for actions without one.
+        pub target_label: String,
+        /// Action mnemonic, e.g. CppCompile.
+        pub mnemonic: String,
+        /// Runner that produced the result, e.g. remote, linux-sandbox or remote cache hit.
+        pub runner: String,
+        /// Whether the result came from a cache.
+        pub cache_hit: bool,
+        pub remotable: bool,
+        pub cacheable: bool,
+        pub remote_cacheable: bool,
+        /// Failure status; null for successful actions.
+        pub status: Option<String>,
+        pub exit_code: i64,
+        /// Hash of the action digest.
+        pub action_digest: Option<String>,
+        /// First output path, which identifies the action together with label and mnemonic.
+        pub primary_output: Option<String>,
+        pub output_count: i64,
+        /// Sum of the output digest sizes.
+        pub output_bytes: i64,
+        /// Number of inputs recorded in the log (not reconstructed for every log).
+        pub input_count: i64,
+        /// Input size reported by the spawn metrics.
+        pub input_bytes: Option<i64>,
+        /// Start time in seconds since the Unix epoch.
+        pub start_time_seconds: Option<f64>,
+        pub total_seconds: Option<f64>,
+        pub queue_seconds: Option<f64>,
+        pub setup_seconds: Option<f64>,
+        pub upload_seconds: Option<f64>,
+        pub execution_seconds: Option<f64>,
+        pub fetch_seconds: Option<f64>,
+        pub retry_seconds: Option<f64>,
+        pub memory_estimate_bytes: Option<i64>,
+        pub memory_limit_bytes: Option<i64>,
+        pub timeout_millis: i64,
+        /// First command-line argument.
+        pub executable: Option<String>,
+        /// Number of command-line arguments.
+        pub argc: i64,
+    }
+}
+
+/// The first output path, which identifies the action together with label and mnemonic.
+pub fn primary_output(spawn: &SpawnExec) -> Option<&str> {
+    spawn
+        .actual_outputs
+        .first()
+        .map(|f| f.path.as_str())
+        .or_else(|| spawn.listed_outputs.first().map(String::as_str))
+}
+
+/// A stable ID for the spawn: 16 hex digits of the 64-bit FNV-1a hash of its label, mnemonic
+/// and primary output, with slashes and the host CPU normalized (see
+/// [`normalize_path_keeping_cpu`]). Unlike positions or digests it stays the same across builds,
+/// log formats, build machines and versions of this tool, while the same target built for two
+/// CPUs in one log gets two IDs. Reports normalize paths the same way, so an ID from a report, an
+/// export or `show` names the same action in the others.
+pub fn spawn_id(spawn: &SpawnExec) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    let output = normalize_path_keeping_cpu(primary_output(spawn).unwrap_or(""));
+    let parts = [spawn.target_label.as_str(), spawn.mnemonic.as_str(), output.as_str()];
+    let mut hash = OFFSET_BASIS;
+    for (i, part) in parts.iter().enumerate() {
+        // A separator byte keeps ("ab", "c") and ("a", "bc") apart.
+        let separator: &[u8] = if i == 0 { &[] } else { &[0] };
+        for &byte in separator.iter().chain(part.as_bytes()) {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(PRIME);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+fn metric_seconds(
+    spawn: &SpawnExec,
+    phase: fn(&SpawnMetrics) -> Option<&prost_types::Duration>,
+) -> Option<f64> {
+    spawn
+        .metrics
+        .as_ref()
+        .and_then(phase)
+        .map(|d| to_std_duration(d).as_secs_f64())
+}
+
+/// Flattens spawns into a DataFrame with the columns of [`SpawnRow`], for analyses this crate
+/// has no report for.
+#[cfg(feature = "dataframe")]
+pub fn spawns_to_dataframe(spawns: &[SpawnExec]) -> polars::prelude::DataFrame {
+    let rows: Vec<SpawnRow> = spawns.iter().map(SpawnRow::from_spawn).collect();
+    SpawnRow::dataframe(&rows)
+}
+
+impl SpawnRow {
+    pub fn from_spawn(spawn: &SpawnExec) -> Self {
+        let metrics = spawn.metrics.as_ref();
+        SpawnRow {
+            spawn_id: spawn_id(spawn),
+            target_label: spawn.target_label.clone(),
+            mnemonic: spawn.mnemonic.clone(),
+            runner: spawn.runner.clone(),
+            cache_hit: spawn.cache_hit,
+            remotable: spawn.remotable,
+            cacheable: spawn.cacheable,
+            remote_cacheable: spawn.remote_cacheable,
+            status: (!spawn.status.is_empty()).then(|| spawn.status.clone()),
+            exit_code: spawn.exit_code.into(),
+            action_digest: spawn.digest.as_ref().map(|d| d.hash.clone()),
+            primary_output: primary_output(spawn).map(str::to_string),
+            output_count: spawn.actual_outputs.len() as i64,
+            output_bytes: output_bytes(spawn),
+            input_count: spawn.inputs.len() as i64,
+            input_bytes: metrics.map(|m| m.input_bytes),
+            start_time_seconds: start_seconds(spawn),
+            total_seconds: metric_seconds(spawn, |m| m.total_time.as_ref()),
+            queue_seconds: metric_seconds(spawn, |m| m.queue_time.as_ref()),
+            setup_seconds: metric_seconds(spawn, |m| m.setup_time.as_ref()),
+            upload_seconds: metric_seconds(spawn, |m| m.upload_time.as_ref()),
+            execution_seconds: metric_seconds(spawn, |m| m.execution_wall_time.as_ref()),
+            fetch_seconds: metric_seconds(spawn, |m| m.fetch_time.as_ref()),
+            retry_seconds: metric_seconds(spawn, |m| m.retry_time.as_ref()),
+            memory_estimate_bytes: metrics.map(|m| m.memory_estimate_bytes),
+            memory_limit_bytes: metrics.map(|m| m.memory_bytes_limit).filter(|limit| *limit > 0),
+            timeout_millis: spawn.timeout_millis,
+            executable: spawn.command_args.first().cloned(),
+            argc: spawn.command_args.len() as i64,
+        }
+    }
+}
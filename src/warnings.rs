@@ -0,0 +1,96 @@
+//! Data-quality issues found while reading and preparing a log (ignored entries, dangling
+//! references, inconsistent fields), collected into one report section instead of loose prints.
+
+use crate::render::{Column, Section, Table};
+
+/// Examples kept per kind of warning.
+const MAX_EXAMPLES: usize = 3;
+
+#[derive(Debug)]
+struct Warning {
+    kind: &'static str,
+    message: &'static str,
+    count: u64,
+    examples: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Warnings {
+    /// In the order each kind was first seen.
+    warnings: Vec<Warning>,
+}
+
+impl Warnings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one occurrence of `kind`. `message` describes the kind; `example` identifies this
+    /// occurrence, e.g. an entry ID or a label.
+    pub fn push(&mut self, kind: &'static str, message: &'static str, example: impl Into<String>) {
+        self.push_many(kind, message, 1, example);
+    }
+
+    /// Records `count` occurrences of `kind` that share one example.
+    pub fn push_many(&mut self, kind: &'static str, message: &'static str, count: u64, example: impl Into<String>) {
+        let index = match self.warnings.iter().position(|w| w.kind == kind) {
+            Some(index) => index,
+            None => {
+                self.warnings.push(Warning {
+                    kind,
+                    message,
+                    count: 0,
+                    examples: Vec::new(),
+                });
+                self.warnings.len() - 1
+            }
+        };
+        let warning = &mut self.warnings[index];
+        warning.count += count;
+        if warning.examples.len() < MAX_EXAMPLES {
+            warning.examples.push(example.into());
+        }
+    }
+
+    /// Moves the warnings of `other` into this collection.
+    pub fn extend(&mut self, other: Warnings) {
+        for warning in other.warnings {
+            match self.warnings.iter_mut().find(|w| w.kind == warning.kind) {
+                Some(existing) => {
+                    existing.count += warning.count;
+                    existing.examples.extend(warning.examples);
+                    existing.examples.truncate(MAX_EXAMPLES);
+                }
+                None => self.warnings.push(warning),
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    pub fn section(&self) -> Section {
+        let mut section = Section::new("warnings", "Data Quality Warnings");
+        if self.warnings.is_empty() {
+            section.push_text("No data quality issues found.");
+            return section;
+        }
+        let mut table = Table::new(vec![
+            Column::text("kind", "Kind"),
+            Column::integer("count", "Count"),
+            Column::text("message", "Description"),
+            Column::text("examples", "Examples"),
+        ]);
+        for warning in &self.warnings {
+            table.push_row(vec![
+                warning.kind.into(),
+                warning.count.into(),
+                warning.message.into(),
+                warning.examples.join("; ").into(),
+            ]);
+        }
+        section.push_table(table);
+        section
+    }
+}
@@ -0,0 +1,387 @@
+//! Writes spawns as execution logs, for build tools and test harnesses that produce logs of their
+//! own and for the subcommands that rewrite logs.
+//!
+//! Both formats are read back by [`parsers`](crate::parsers) and by Bazel's own tooling. The
+//! compact format cannot carry everything a `SpawnExec` can: `listed_outputs` and symlink targets
+//! are dropped, and every output is written as a file, as the reconstruction reads them back.
+//!
+//! Subcommands that rewrite compact logs without going through spawns, keeping some of their
+//! actions or joining several logs, use [`EntryCompactor`], which keeps every entry type.
+
+use crate::parsers::LogFormat;
+use crate::proto::exec_log_entry::{self as compact, Type as CompactEntryType};
+use crate::proto::{ExecLogEntry, SpawnExec};
+use prost::Message;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{self, Write};
+
+/// Writes length-delimited `SpawnExec` messages, the format of `--execution_log_binary_file`.
+pub struct VerboseWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> VerboseWriter<W> {
+    pub fn new(out: W) -> Self {
+        VerboseWriter { out }
+    }
+
+    pub fn write_spawn(&mut self, spawn: &SpawnExec) -> io::Result<()> {
+        self.out.write_all(&spawn.encode_length_delimited_to_vec())
+    }
+
+    /// Flushes the log and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Writes zstd-compressed `ExecLogEntry` messages, the format of `--execution_log_compact_file`.
+/// Files and input sets shared by several spawns are written once and referenced by ID.
+pub struct CompactWriter<W: Write> {
+    encoder: zstd::stream::write::Encoder<'static, W>,
+    /// The last ID handed out; IDs start at 1, as 0 means "not referenced".
+    last_id: u32,
+    /// IDs of the file entries written so far, by their encoded message.
+    files: HashMap<Vec<u8>, u32>,
+    /// IDs of the input sets written so far, by their members.
+    input_sets: HashMap<Vec<u32>, u32>,
+}
+
+impl<W: Write> CompactWriter<W> {
+    /// Starts a log with an empty invocation entry.
+    pub fn new(out: W) -> io::Result<Self> {
+        Self::with_invocation(out, compact::Invocation::default())
+    }
+
+    /// Starts a log with `invocation` as its first entry.
+    pub fn with_invocation(out: W, invocation: compact::Invocation) -> io::Result<Self> {
+        let mut writer = CompactWriter {
+            encoder: zstd::stream::write::Encoder::new(out, 0)?,
+            last_id: 0,
+            files: HashMap::new(),
+            input_sets: HashMap::new(),
+        };
+        writer.write_entry(0, CompactEntryType::Invocation(invocation))?;
+        Ok(writer)
+    }
+
+    pub fn write_spawn(&mut self, spawn: &SpawnExec) -> io::Result<()> {
+        let mut input_ids = Vec::with_capacity(spawn.inputs.len());
+        let mut tool_ids = Vec::new();
+        for input in &spawn.inputs {
+            let id = self.file_id(&input.path, input.digest.clone())?;
+            input_ids.push(id);
+            if input.is_tool {
+                tool_ids.push(id);
+            }
+        }
+        let input_set_id = self.input_set_id(input_ids)?;
+        let tool_set_id = self.input_set_id(tool_ids)?;
+        let mut outputs = Vec::with_capacity(spawn.actual_outputs.len());
+        for output in &spawn.actual_outputs {
+            outputs.push(compact::Output {
+                r#type: Some(compact::output::Type::OutputId(self.file_id(&output.path, output.digest.clone())?)),
+            });
+        }
+
+        let spawn = compact::Spawn {
+            args: spawn.command_args.clone(),
+            env_vars: spawn.environment_variables.clone(),
+            platform: spawn.platform.clone(),
+            input_set_id,
+            tool_set_id,
+            outputs,
+            target_label: spawn.target_label.clone(),
+            mnemonic: spawn.mnemonic.clone(),
+            exit_code: spawn.exit_code,
+            status: spawn.status.clone(),
+            runner: spawn.runner.clone(),
+            cache_hit: spawn.cache_hit,
+            remotable: spawn.remotable,
+            cacheable: spawn.cacheable,
+            remote_cacheable: spawn.remote_cacheable,
+            digest: spawn.digest.clone(),
+            timeout_millis: spawn.timeout_millis,
+            metrics: spawn.metrics.clone(),
+        };
+        self.write_entry(0, CompactEntryType::Spawn(spawn))
+    }
+
+    /// Ends the zstd stream and returns the underlying writer.
+    pub fn finish(self) -> io::Result<W> {
+        let mut out = self.encoder.finish()?;
+        out.flush()?;
+        Ok(out)
+    }
+
+    fn write_entry(&mut self, id: u32, entry: CompactEntryType) -> io::Result<()> {
+        let entry = ExecLogEntry { id, r#type: Some(entry) };
+        self.encoder.write_all(&entry.encode_length_delimited_to_vec())
+    }
+
+    fn next_id(&mut self) -> u32 {
+        self.last_id += 1;
+        self.last_id
+    }
+
+    fn file_id(&mut self, path: &str, digest: Option<crate::proto::Digest>) -> io::Result<u32> {
+        let file = compact::File {
+            path: path.to_string(),
+            digest,
+        };
+        let key = file.encode_to_vec();
+        if let Some(&id) = self.files.get(&key) {
+            return Ok(id);
+        }
+        let id = self.next_id();
+        self.write_entry(id, CompactEntryType::File(file))?;
+        self.files.insert(key, id);
+        Ok(id)
+    }
+
+    /// The ID of an input set of the given files, or 0 for none.
+    fn input_set_id(&mut self, input_ids: Vec<u32>) -> io::Result<u32> {
+        if input_ids.is_empty() {
+            return Ok(0);
+        }
+        if let Some(&id) = self.input_sets.get(&input_ids) {
+            return Ok(id);
+        }
+        let id = self.next_id();
+        let set = compact::InputSet {
+            input_ids: input_ids.clone(),
+            transitive_set_ids: Vec::new(),
+        };
+        self.write_entry(id, CompactEntryType::InputSet(set))?;
+        self.input_sets.insert(input_ids, id);
+        Ok(id)
+    }
+}
+
+/// Rewrites the entries of compact logs as one minimal log: only the actions kept and the entries
+/// they reference, directly or through input and symlink sets, are written, with IDs renumbered
+/// from 1 in the order the entries are written. Entries that end up identical, such as a file
+/// referenced by two joined logs, are written once.
+pub struct EntryCompactor {
+    invocation: Option<compact::Invocation>,
+    /// The entries to write after the invocation, with their new IDs and references.
+    entries: Vec<ExecLogEntry>,
+    /// New IDs of the entries so far, by their encoded message.
+    ids: HashMap<Vec<u8>, u32>,
+    spawns: usize,
+}
+
+impl Default for EntryCompactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntryCompactor {
+    pub fn new() -> Self {
+        EntryCompactor {
+            invocation: None,
+            entries: Vec::new(),
+            ids: HashMap::new(),
+            spawns: 0,
+        }
+    }
+
+    /// Adds the spawns and symlink actions of one log for which `keep` returns true. Each log's
+    /// IDs are its own; the invocation is the first log's.
+    pub fn add_log(&mut self, entries: &[ExecLogEntry], mut keep: impl FnMut(&ExecLogEntry) -> bool) {
+        let actions = entries.iter().enumerate().filter(|(_, entry)| is_action(entry) && keep(entry));
+        self.add_reached(entries, &positions(entries), actions.map(|(position, _)| position).collect());
+    }
+
+    /// Splits one log into a log per group of its spawns and symlink actions, as `group` sorts them.
+    /// Cheaper than a call to [`add_log`](Self::add_log) per group, as each group's entries are
+    /// only looked at once.
+    pub fn split<K: Ord>(entries: &[ExecLogEntry], mut group: impl FnMut(&ExecLogEntry) -> K) -> BTreeMap<K, Self> {
+        let mut groups: BTreeMap<K, Vec<usize>> = BTreeMap::new();
+        for (position, entry) in entries.iter().enumerate() {
+            if is_action(entry) {
+                groups.entry(group(entry)).or_default().push(position);
+            }
+        }
+        let positions = positions(entries);
+        groups
+            .into_iter()
+            .map(|(key, actions)| {
+                let mut compactor = EntryCompactor::new();
+                compactor.add_reached(entries, &positions, actions);
+                (key, compactor)
+            })
+            .collect()
+    }
+
+    /// Adds the actions at `actions` and the entries they reach, in their order in `entries`.
+    fn add_reached(&mut self, entries: &[ExecLogEntry], positions: &HashMap<u32, usize>, actions: Vec<usize>) {
+        if self.invocation.is_none() {
+            self.invocation = entries.iter().find_map(|entry| match &entry.r#type {
+                Some(CompactEntryType::Invocation(invocation)) => Some(invocation.clone()),
+                _ => None,
+            });
+        }
+
+        let mut reached: HashSet<usize> = actions.iter().copied().collect();
+        let mut pending = actions;
+        while let Some(position) = pending.pop() {
+            for id in references(&entries[position]) {
+                if let Some(&referenced) = positions.get(&id)
+                    && reached.insert(referenced)
+                {
+                    pending.push(referenced);
+                }
+            }
+        }
+        let mut reached: Vec<usize> = reached.into_iter().collect();
+        reached.sort_unstable();
+
+        let mut new_ids = HashMap::new();
+        for position in reached {
+            let mut entry = entries[position].clone();
+            // References the log does not resolve become 0, "not referenced", and are then left out.
+            for id in references_mut(&mut entry) {
+                *id = new_ids.get(id).copied().unwrap_or(0);
+            }
+            drop_unresolved(&mut entry);
+            let Some(r#type) = entry.r#type else { continue };
+            if matches!(r#type, CompactEntryType::Spawn(_) | CompactEntryType::SymlinkAction(_)) {
+                self.spawns += usize::from(matches!(r#type, CompactEntryType::Spawn(_)));
+                self.entries.push(ExecLogEntry { id: 0, r#type: Some(r#type) });
+                continue;
+            }
+            let mut key = Vec::new();
+            r#type.encode(&mut key);
+            let next_id = self.ids.len() as u32 + 1;
+            let id = *self.ids.entry(key).or_insert_with(|| next_id);
+            if id == next_id {
+                self.entries.push(ExecLogEntry { id, r#type: Some(r#type) });
+            }
+            new_ids.insert(entry.id, id);
+        }
+    }
+
+    /// Number of spawns added so far.
+    pub fn spawns(&self) -> usize {
+        self.spawns
+    }
+
+    /// Writes the log, zstd-compressed, and returns `out`.
+    pub fn finish<W: Write>(self, out: W) -> io::Result<W> {
+        let mut encoder = zstd::stream::write::Encoder::new(out, 0)?;
+        let invocation = ExecLogEntry {
+            id: 0,
+            r#type: Some(CompactEntryType::Invocation(self.invocation.unwrap_or_default())),
+        };
+        for entry in std::iter::once(invocation).chain(self.entries) {
+            encoder.write_all(&entry.encode_length_delimited_to_vec())?;
+        }
+        let mut out = encoder.finish()?;
+        out.flush()?;
+        Ok(out)
+    }
+}
+
+fn is_action(entry: &ExecLogEntry) -> bool {
+    matches!(entry.r#type, Some(CompactEntryType::Spawn(_) | CompactEntryType::SymlinkAction(_)))
+}
+
+/// Positions of the entries with IDs, by ID.
+fn positions(entries: &[ExecLogEntry]) -> HashMap<u32, usize> {
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.id != 0)
+        .map(|(position, entry)| (entry.id, position))
+        .collect()
+}
+
+/// The IDs of the entries `entry` refers to.
+fn references(entry: &ExecLogEntry) -> Vec<u32> {
+    match &entry.r#type {
+        Some(CompactEntryType::Spawn(spawn)) => {
+            let outputs = spawn.outputs.iter().filter_map(|output| match output.r#type {
+                Some(compact::output::Type::OutputId(id)) => Some(id),
+                _ => None,
+            });
+            [spawn.input_set_id, spawn.tool_set_id].into_iter().chain(outputs).collect()
+        }
+        Some(CompactEntryType::InputSet(set)) => set.input_ids.iter().chain(&set.transitive_set_ids).copied().collect(),
+        Some(CompactEntryType::SymlinkEntrySet(set)) => {
+            set.direct_entries.values().chain(&set.transitive_set_ids).copied().collect()
+        }
+        Some(CompactEntryType::RunfilesTree(tree)) => vec![tree.input_set_id, tree.symlinks_id, tree.root_symlinks_id],
+        _ => Vec::new(),
+    }
+}
+
+/// [`references`], to rewrite them.
+fn references_mut(entry: &mut ExecLogEntry) -> Vec<&mut u32> {
+    match &mut entry.r#type {
+        Some(CompactEntryType::Spawn(spawn)) => {
+            let outputs = spawn.outputs.iter_mut().filter_map(|output| match &mut output.r#type {
+                Some(compact::output::Type::OutputId(id)) => Some(id),
+                _ => None,
+            });
+            [&mut spawn.input_set_id, &mut spawn.tool_set_id].into_iter().chain(outputs).collect()
+        }
+        Some(CompactEntryType::InputSet(set)) => set.input_ids.iter_mut().chain(&mut set.transitive_set_ids).collect(),
+        Some(CompactEntryType::SymlinkEntrySet(set)) => {
+            set.direct_entries.values_mut().chain(&mut set.transitive_set_ids).collect()
+        }
+        Some(CompactEntryType::RunfilesTree(tree)) => {
+            vec![&mut tree.input_set_id, &mut tree.symlinks_id, &mut tree.root_symlinks_id]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Leaves out the members of sets and the outputs whose IDs are 0.
+fn drop_unresolved(entry: &mut ExecLogEntry) {
+    match &mut entry.r#type {
+        Some(CompactEntryType::Spawn(spawn)) => spawn
+            .outputs
+            .retain(|output| !matches!(output.r#type, Some(compact::output::Type::OutputId(0)))),
+        Some(CompactEntryType::InputSet(set)) => {
+            set.input_ids.retain(|&id| id != 0);
+            set.transitive_set_ids.retain(|&id| id != 0);
+        }
+        Some(CompactEntryType::SymlinkEntrySet(set)) => {
+            set.direct_entries.retain(|_, id| *id != 0);
+            set.transitive_set_ids.retain(|&id| id != 0);
+        }
+        _ => {}
+    }
+}
+
+/// Writes `spawns` as one MessagePack array of maps with the proto3 JSON field names, which other
+/// processes load without decoding and reconstructing the log again (in Rust with
+/// [`read_spawns_msgpack`](crate::parsers::read_spawns_msgpack)).
+pub fn write_spawns_msgpack<W: Write>(spawns: &[SpawnExec], mut out: W) -> io::Result<W> {
+    rmp_serde::encode::write_named(&mut out, spawns).map_err(io::Error::other)?;
+    out.flush()?;
+    Ok(out)
+}
+
+/// Writes `spawns` as a log in `format` and returns `out`.
+pub fn write_spawns<W: Write>(spawns: &[SpawnExec], format: LogFormat, out: W) -> io::Result<W> {
+    match format {
+        LogFormat::Verbose => {
+            let mut writer = VerboseWriter::new(out);
+            for spawn in spawns {
+                writer.write_spawn(spawn)?;
+            }
+            writer.finish()
+        }
+        LogFormat::Compact => {
+            let mut writer = CompactWriter::new(out)?;
+            for spawn in spawns {
+                writer.write_spawn(spawn)?;
+            }
+            writer.finish()
+        }
+    }
+}
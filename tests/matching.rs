@@ -0,0 +1,64 @@
+//! Cross-log matching: exact keys first, then the looser fallbacks, each spawn paired at most once.
+
+use bzl_exec_log_parser::matching::{match_spawns, only_new, only_old, MatchKind};
+use bzl_exec_log_parser::proto::{File, SpawnExec};
+
+fn spawn(label: &str, mnemonic: &str, output: &str) -> SpawnExec {
+    SpawnExec {
+        target_label: label.to_string(),
+        mnemonic: mnemonic.to_string(),
+        actual_outputs: vec![File {
+            path: output.to_string(),
+            ..File::default()
+        }],
+        ..SpawnExec::default()
+    }
+}
+
+#[test]
+fn exact_keys_win_over_fallbacks() {
+    let old = vec![
+        spawn("//app:lib", "CppCompile", "bazel-out/k8-fastbuild/bin/app/lib.o"),
+        spawn("//app:bin", "CppLink", "bazel-out/k8-fastbuild/bin/app/bin"),
+        spawn("//tools:gen", "Genrule", "bazel-out/k8-fastbuild/bin/tools/gen.h"),
+        spawn("//gone:x", "Javac", "bazel-out/k8-fastbuild/bin/gone/x.jar"),
+    ];
+    let new = vec![
+        spawn("//tools:generate", "Genrule", "bazel-out/k8-fastbuild/bin/tools/gen.h"),
+        spawn("//app:bin", "CppLink", "bazel-out/k8-opt/bin/app/bin"),
+        spawn("//app:lib", "CppCompile", "bazel-out/k8-fastbuild/bin/app/lib.o"),
+        spawn("//added:y", "Javac", "bazel-out/k8-fastbuild/bin/added/y.jar"),
+    ];
+
+    let pairs = match_spawns(&old, &new);
+    let kinds: Vec<(&str, &str, MatchKind)> = pairs
+        .iter()
+        .map(|p| (p.old.target_label.as_str(), p.new.target_label.as_str(), p.kind))
+        .collect();
+    // In the order of the new log.
+    assert_eq!(
+        kinds,
+        [
+            ("//tools:gen", "//tools:generate", MatchKind::Output),
+            ("//app:bin", "//app:bin", MatchKind::Configuration),
+            ("//app:lib", "//app:lib", MatchKind::Exact),
+        ]
+    );
+    assert_eq!(only_old(&old, &pairs).iter().map(|s| s.target_label.as_str()).collect::<Vec<_>>(), ["//gone:x"]);
+    assert_eq!(only_new(&new, &pairs).iter().map(|s| s.target_label.as_str()).collect::<Vec<_>>(), ["//added:y"]);
+}
+
+#[test]
+fn duplicate_keys_pair_in_log_order() {
+    let attempt = |runner: &str| SpawnExec {
+        runner: runner.to_string(),
+        ..spawn("//app:test", "TestRunner", "bazel-out/k8-fastbuild/testlogs/app/test/test.log")
+    };
+    let old = vec![attempt("first"), attempt("second")];
+    let new = vec![attempt("first"), attempt("second"), attempt("third")];
+
+    let pairs = match_spawns(&old, &new);
+    let runners: Vec<(&str, &str)> = pairs.iter().map(|p| (p.old.runner.as_str(), p.new.runner.as_str())).collect();
+    assert_eq!(runners, [("first", "first"), ("second", "second")]);
+    assert_eq!(only_new(&new, &pairs)[0].runner, "third");
+}
@@ -0,0 +1,289 @@
+//! Round trips between the formats through the writer API: compact logs reconstruct to the
+//! spawns they were written from and write back byte for byte, and verbose logs survive a detour
+//! through JSON and MessagePack unchanged. Compacting a log keeps the spawns chosen and only the entries they
+//! reference, and merging compact logs keeps the entries spawns cannot carry.
+
+use bzl_exec_log_parser::parsers::{
+    parse_compact_bytes, parse_verbose_bytes, read_compact_entries, read_spawns_msgpack, ParseLimits,
+};
+use bzl_exec_log_parser::proto::exec_log_entry::{SymlinkAction, Type as CompactEntryType};
+use bzl_exec_log_parser::proto::{
+    platform, Digest, EnvironmentVariable, ExecLogEntry, File, Platform, SpawnExec, SpawnMetrics,
+};
+use bzl_exec_log_parser::writer::{write_spawns_msgpack, CompactWriter, EntryCompactor, VerboseWriter};
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn text() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9_./:@-]{0,16}"
+}
+
+fn path() -> impl Strategy<Value = String> {
+    "(bazel-out/k8-fastbuild/bin/)?[a-z]{1,6}(/[a-z0-9_]{1,8}){0,3}(\\.[a-z]{1,3})?"
+}
+
+fn digest() -> impl Strategy<Value = Digest> {
+    ("[0-9a-f]{64}", 0..1_i64 << 40, prop_oneof![Just(String::new()), Just("SHA-256".to_string())]).prop_map(
+        |(hash, size_bytes, hash_function_name)| Digest {
+            hash,
+            size_bytes,
+            hash_function_name,
+        },
+    )
+}
+
+fn duration() -> impl Strategy<Value = prost_types::Duration> {
+    (0..100_000_i64, 0..1_000_000_000_i32).prop_map(|(seconds, nanos)| prost_types::Duration { seconds, nanos })
+}
+
+fn metrics() -> impl Strategy<Value = SpawnMetrics> {
+    (
+        vec(option::of(duration()), 11),
+        vec(0..1_i64 << 40, 9),
+        option::of((0..4_000_000_000_i64, 0..1_000_000_000_i32)),
+    )
+        .prop_map(|(durations, sizes, start)| {
+            let mut durations = durations.into_iter();
+            let mut next_duration = || durations.next().unwrap();
+            SpawnMetrics {
+                total_time: next_duration(),
+                parse_time: next_duration(),
+                network_time: next_duration(),
+                fetch_time: next_duration(),
+                queue_time: next_duration(),
+                setup_time: next_duration(),
+                upload_time: next_duration(),
+                execution_wall_time: next_duration(),
+                process_outputs_time: next_duration(),
+                retry_time: next_duration(),
+                time_limit: next_duration(),
+                input_bytes: sizes[0],
+                input_files: sizes[1],
+                memory_estimate_bytes: sizes[2],
+                input_bytes_limit: sizes[3],
+                input_files_limit: sizes[4],
+                output_bytes_limit: sizes[5],
+                output_files_limit: sizes[6],
+                memory_bytes_limit: sizes[7],
+                start_time: start.map(|(seconds, nanos)| prost_types::Timestamp { seconds, nanos }),
+            }
+        })
+}
+
+/// A spawn as the compact format records it: no listed outputs or symlink targets, and each input
+/// path once.
+fn compact_spawn() -> impl Strategy<Value = SpawnExec> {
+    (
+        (vec(text(), 0..4), vec((text(), text()), 0..3), option::of(vec((text(), text()), 0..3))),
+        (vec((path(), option::of(digest()), any::<bool>()), 0..6), vec((path(), option::of(digest())), 0..3)),
+        (text(), text(), text(), text(), any::<i32>()),
+        (any::<[bool; 4]>(), 0..1_000_000_i64, option::of(digest()), option::of(metrics())),
+    )
+        .prop_map(|((args, env, platform), (inputs, outputs), (mnemonic, runner, status, target_label, exit_code), (flags, timeout_millis, digest, metrics))| {
+            let mut seen = HashSet::new();
+            SpawnExec {
+                command_args: args,
+                environment_variables: env.into_iter().map(|(name, value)| EnvironmentVariable { name, value }).collect(),
+                platform: platform.map(|properties| Platform {
+                    properties: properties.into_iter().map(|(name, value)| platform::Property { name, value }).collect(),
+                }),
+                inputs: inputs
+                    .into_iter()
+                    .filter(|(path, _, _)| seen.insert(path.clone()))
+                    .map(|(path, digest, is_tool)| File {
+                        path,
+                        digest,
+                        is_tool,
+                        ..File::default()
+                    })
+                    .collect(),
+                listed_outputs: Vec::new(),
+                remotable: flags[0],
+                cacheable: flags[1],
+                timeout_millis,
+                mnemonic,
+                actual_outputs: outputs
+                    .into_iter()
+                    .map(|(path, digest)| File {
+                        path,
+                        digest,
+                        ..File::default()
+                    })
+                    .collect(),
+                runner,
+                cache_hit: flags[2],
+                status,
+                exit_code,
+                remote_cacheable: flags[3],
+                target_label,
+                digest,
+                metrics,
+            }
+        })
+}
+
+/// Any spawn, with the fields the compact format drops.
+fn verbose_spawn() -> impl Strategy<Value = SpawnExec> {
+    (compact_spawn(), vec(path(), 0..3), vec(text(), 0..3)).prop_map(|(mut spawn, listed_outputs, symlinks)| {
+        spawn.listed_outputs = listed_outputs;
+        for (output, target) in spawn.actual_outputs.iter_mut().zip(symlinks) {
+            output.symlink_target_path = target;
+        }
+        spawn
+    })
+}
+
+fn write_compact(spawns: &[SpawnExec]) -> Vec<u8> {
+    let mut writer = CompactWriter::new(Vec::new()).unwrap();
+    for spawn in spawns {
+        writer.write_spawn(spawn).unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+fn write_verbose(spawns: &[SpawnExec]) -> Vec<u8> {
+    let mut writer = VerboseWriter::new(Vec::new());
+    for spawn in spawns {
+        writer.write_spawn(spawn).unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+proptest! {
+    #[test]
+    fn compact_logs_reconstruct_and_write_back(spawns in vec(compact_spawn(), 0..8)) {
+        let written = write_compact(&spawns);
+        let parsed = parse_compact_bytes(&written, &ParseLimits::default()).unwrap();
+        prop_assert_eq!(parsed.error, None);
+        prop_assert_eq!(&parsed.spawns, &spawns);
+        prop_assert_eq!(write_compact(&parsed.spawns), written);
+    }
+
+    #[test]
+    fn compacted_logs_keep_the_chosen_spawns_with_contiguous_ids(
+        spawns in vec(compact_spawn(), 0..8),
+        kept in any::<u8>(),
+    ) {
+        let written = write_compact(&spawns);
+        let entries = read_compact_entries(&written, &ParseLimits::default()).unwrap();
+
+        // The writer's logs are already minimal.
+        let mut compactor = EntryCompactor::new();
+        compactor.add_log(&entries, |_| true);
+        prop_assert_eq!(compactor.finish(Vec::new()).unwrap(), written);
+
+        let mut compactor = EntryCompactor::new();
+        let mut index = 0;
+        compactor.add_log(&entries, |_| {
+            index += 1;
+            kept & (1 << (index - 1)) != 0
+        });
+        let compacted = compactor.finish(Vec::new()).unwrap();
+        let parsed = parse_compact_bytes(&compacted, &ParseLimits::default()).unwrap();
+        let expected: Vec<_> =
+            spawns.iter().enumerate().filter(|(i, _)| kept & (1 << i) != 0).map(|(_, spawn)| spawn.clone()).collect();
+        prop_assert_eq!(parsed.error, None);
+        prop_assert_eq!(parsed.spawns, expected);
+        let ids: Vec<u32> = read_compact_entries(&compacted, &ParseLimits::default())
+            .unwrap()
+            .iter()
+            .map(|entry| entry.id)
+            .filter(|&id| id != 0)
+            .collect();
+        let contiguous: Vec<u32> = (1..=ids.len() as u32).collect();
+        prop_assert_eq!(ids, contiguous);
+    }
+
+    #[test]
+    fn verbose_logs_survive_json(spawns in vec(verbose_spawn(), 0..8)) {
+        let written = write_verbose(&spawns);
+        let parsed = parse_verbose_bytes(&written, &ParseLimits::default()).unwrap();
+        prop_assert_eq!(parsed.error, None);
+        let json = serde_json::to_string(&parsed.spawns).unwrap();
+        let decoded: Vec<SpawnExec> = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(&decoded, &spawns);
+        prop_assert_eq!(write_verbose(&decoded), written);
+    }
+
+    #[test]
+    fn spawns_survive_msgpack(spawns in vec(verbose_spawn(), 0..8)) {
+        let exported = write_spawns_msgpack(&spawns, Vec::new()).unwrap();
+        prop_assert_eq!(read_spawns_msgpack(&exported).unwrap(), spawns);
+    }
+}
+
+#[test]
+fn json_uses_the_proto3_forms_of_durations_and_timestamps() {
+    let metrics = SpawnMetrics {
+        total_time: Some(prost_types::Duration { seconds: 1, nanos: 500_000_000 }),
+        start_time: Some(prost_types::Timestamp { seconds: 1_700_000_000, nanos: 123_000_000 }),
+        ..SpawnMetrics::default()
+    };
+    let json = serde_json::to_value(&metrics).unwrap();
+    assert_eq!(json["totalTime"], "1.500s");
+    assert_eq!(json["startTime"], "2023-11-14T22:13:20.123Z");
+    assert!(json.get("queueTime").is_none());
+}
+
+/// A compact log of `spawns` followed by a symlink action of `//pkg:link`.
+fn compact_log_with_symlink(spawns: &[SpawnExec]) -> Vec<u8> {
+    let mut entries = read_compact_entries(&write_compact(spawns), &ParseLimits::default()).unwrap();
+    entries.push(ExecLogEntry {
+        id: 0,
+        r#type: Some(CompactEntryType::SymlinkAction(SymlinkAction {
+            input_path: "bazel-out/k8-fastbuild/bin/pkg/tool".to_string(),
+            output_path: "bazel-out/k8-fastbuild/bin/pkg/link".to_string(),
+            target_label: "//pkg:link".to_string(),
+            mnemonic: "Symlink".to_string(),
+        })),
+    });
+    let mut compactor = EntryCompactor::new();
+    compactor.add_log(&entries, |_| true);
+    compactor.finish(Vec::new()).unwrap()
+}
+
+#[test]
+fn merged_compact_logs_keep_symlink_actions() {
+    let spawns: Vec<SpawnExec> = ["a", "b"]
+        .iter()
+        .map(|name| SpawnExec {
+            command_args: vec!["cc".to_string(), format!("{}.cc", name)],
+            target_label: format!("//pkg:{}", name),
+            mnemonic: "CppCompile".to_string(),
+            digest: Some(Digest { hash: format!("{:0>64}", name), size_bytes: 140, ..Digest::default() }),
+            ..SpawnExec::default()
+        })
+        .collect();
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let logs = ["merge-attempt-1.log.zst", "merge-attempt-2.log.zst"].map(|name| directory.join(name));
+    for log in &logs {
+        fs::write(log, compact_log_with_symlink(&spawns)).unwrap();
+    }
+
+    for (dedup, copies) in [(None, 2), (Some("first"), 1)] {
+        let out = directory.join(format!("merged-{}.log.zst", copies));
+        let mut command = Command::new(env!("CARGO_BIN_EXE_bzl-exec-log-analyzer"));
+        command.arg("merge").args(&logs).arg("-o").arg(&out);
+        if let Some(dedup) = dedup {
+            command.args(["--dedup", dedup]);
+        }
+        let output = command.output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+        let merged = fs::read(&out).unwrap();
+        let entries = read_compact_entries(&merged, &ParseLimits::default()).unwrap();
+        let symlinks = entries
+            .iter()
+            .filter(|entry| matches!(entry.r#type, Some(CompactEntryType::SymlinkAction(_))))
+            .count();
+        assert_eq!(symlinks, copies);
+        let parsed = parse_compact_bytes(&merged, &ParseLimits::default()).unwrap();
+        let expected: Vec<SpawnExec> = spawns.iter().cycle().take(spawns.len() * copies).cloned().collect();
+        assert_eq!(parsed.spawns, expected);
+    }
+}
@@ -0,0 +1,151 @@
+//! The in-memory parsers on damaged and forged logs: they return the spawns before the damage and
+//! say where it is instead of failing or panicking.
+
+use bzl_exec_log_parser::parsers::{parse_compact_bytes, parse_verbose_bytes, LogFormat, OtherOutput, ParseErrorKind, ParseLimits};
+use bzl_exec_log_parser::AppError;
+use std::fs;
+use std::path::Path;
+
+fn fixture(name: &str) -> Vec<u8> {
+    fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/windows").join(name)).unwrap()
+}
+
+#[test]
+fn intact_logs_parse_completely() {
+    let verbose = parse_verbose_bytes(&fixture("exec.log"), &ParseLimits::default()).unwrap();
+    assert_eq!(verbose.spawns.len(), 10);
+    assert_eq!(verbose.error, None);
+    let compact = parse_compact_bytes(&fixture("exec.log.zst"), &ParseLimits::default()).unwrap();
+    assert_eq!(compact.spawns.len(), 10);
+    assert_eq!(compact.error, None);
+}
+
+#[test]
+fn truncated_logs_keep_the_complete_entries() {
+    let log = fixture("exec.log");
+    for cut in 0..log.len() {
+        let parsed = parse_verbose_bytes(&log[..cut], &ParseLimits::default()).unwrap();
+        assert!(parsed.spawns.len() < 10);
+        // A cut between two entries leaves a shorter but valid log.
+        if let Some(error) = parsed.error {
+            assert_eq!(error.entry_index, parsed.spawns.len());
+            assert!(error.offset < cut);
+        }
+    }
+
+    let log = fixture("exec.log.zst");
+    for cut in 1..log.len() {
+        let parsed = parse_compact_bytes(&log[..cut], &ParseLimits::default()).unwrap();
+        let error = parsed.error.unwrap();
+        assert_eq!(error.kind, ParseErrorKind::TruncatedStream { compressed_bytes: cut }, "cut at {}", cut);
+    }
+}
+
+#[test]
+fn forged_length_prefixes_are_rejected_before_decoding() {
+    // A length prefix of u64::MAX followed by a few bytes.
+    let mut log = fixture("exec.log");
+    let first_entry = {
+        let parsed = parse_verbose_bytes(&log, &ParseLimits::default()).unwrap();
+        prost::Message::encoded_len(&parsed.spawns[0])
+    };
+    let end = first_entry + prost::encoding::encoded_len_varint(first_entry as u64);
+    log.truncate(end);
+    log.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0x0a, 0x00]);
+
+    let parsed = parse_verbose_bytes(&log, &ParseLimits::default()).unwrap();
+    assert_eq!(parsed.spawns.len(), 1);
+    let error = parsed.error.unwrap();
+    assert_eq!(error.offset, end);
+    assert_eq!(error.entry_index, 1);
+    assert_eq!(error.kind, ParseErrorKind::Truncated { length: u64::MAX, available: 2 });
+}
+
+#[test]
+fn garbage_is_reported_at_the_first_entry() {
+    let parsed = parse_verbose_bytes(&[0x05, 0xff, 0xff, 0xff, 0xff, 0xff], &ParseLimits::default()).unwrap();
+    assert!(parsed.spawns.is_empty());
+    assert!(matches!(parsed.error.unwrap().kind, ParseErrorKind::InvalidEntry(_)));
+
+    let parsed = parse_verbose_bytes(&[0xff; 16], &ParseLimits::default()).unwrap();
+    assert_eq!(parsed.error.unwrap().kind, ParseErrorKind::InvalidLengthPrefix);
+
+    let parsed = parse_compact_bytes(b"not a zstd stream", &ParseLimits::default()).unwrap();
+    assert!(matches!(parsed.error.unwrap().kind, ParseErrorKind::Decompression(_)));
+}
+
+#[test]
+fn limits_abort_parsing() {
+    let verbose = fixture("exec.log");
+    let compact = fixture("exec.log.zst");
+    let exceeded = |result: Result<_, AppError>| match result {
+        Err(AppError::LimitExceeded(e)) => e.limit,
+        other => panic!("expected a limit to be exceeded, got {:?}", other.map(|_| ())),
+    };
+
+    let limits = ParseLimits {
+        max_entries: Some(5),
+        ..ParseLimits::default()
+    };
+    assert_eq!(exceeded(parse_verbose_bytes(&verbose, &limits)), "max_entries");
+    assert_eq!(exceeded(parse_compact_bytes(&compact, &limits)), "max_entries");
+
+    let limits = ParseLimits {
+        max_entry_bytes: Some(64),
+        ..ParseLimits::default()
+    };
+    assert_eq!(exceeded(parse_verbose_bytes(&verbose, &limits)), "max_entry_bytes");
+
+    let limits = ParseLimits {
+        max_decompressed_bytes: Some(1024),
+        ..ParseLimits::default()
+    };
+    assert_eq!(exceeded(parse_compact_bytes(&compact, &limits)), "max_decompressed_bytes");
+
+    let limits = ParseLimits {
+        max_memory_bytes: Some(2048),
+        ..ParseLimits::default()
+    };
+    assert_eq!(exceeded(parse_verbose_bytes(&verbose, &limits)), "max_memory_bytes");
+    assert_eq!(exceeded(parse_compact_bytes(&compact, &limits)), "max_memory_bytes");
+
+    // Limits the log stays within change nothing.
+    let limits = ParseLimits {
+        max_entries: Some(1000),
+        max_entry_bytes: Some(1 << 20),
+        max_decompressed_bytes: Some(1 << 20),
+        max_memory_bytes: Some(1 << 20),
+    };
+    assert_eq!(parse_compact_bytes(&compact, &limits).unwrap().spawns.len(), 10);
+}
+
+#[test]
+fn errors_explain_where_the_log_breaks() {
+    let log = fixture("exec.log.zst");
+    let error = AppError::InvalidLog {
+        format: LogFormat::Compact,
+        error: parse_compact_bytes(&log[..1200], &ParseLimits::default()).unwrap().error.unwrap(),
+    };
+    let hint = error.hint().unwrap();
+    assert!(hint.starts_with("looks like a compact log, but the zstd frame is truncated at byte 1,200"), "{}", hint);
+
+    let parsed = parse_verbose_bytes(&[0x05, 0xff, 0xff, 0xff, 0xff, 0xff], &ParseLimits::default()).unwrap();
+    assert!(parsed.error.unwrap().hint(LogFormat::Verbose).contains("--execution_log_binary_file"));
+}
+
+#[test]
+fn other_bazel_outputs_are_recognized() {
+    let outputs: [(&[u8], OtherOutput); 6] = [
+        (b"{\"id\":{\"started\":{}},\"children\":[{\"progress\":{}}]}\n", OtherOutput::JsonBuildEvents),
+        (&[0x06, 0x0a, 0x02, 0x1a, 0x00, 0x12, 0x00], OtherOutput::BinaryBuildEvents),
+        (b"{\"otherData\":{},\"traceEvents\":[]}", OtherOutput::Profile),
+        (b"action 'Compiling app/main.cc'\n  Mnemonic: CppCompile\n", OtherOutput::AqueryText),
+        (b"{\n  \"artifacts\": [],\n  \"actions\": []\n}", OtherOutput::AqueryJson),
+        (b"{\n  \"commandArgs\": [\"/bin/true\"],\n  \"mnemonic\": \"Genrule\"\n}", OtherOutput::JsonExecutionLog),
+    ];
+    for (bytes, output) in outputs {
+        assert_eq!(OtherOutput::detect(bytes), Some(output), "{}", String::from_utf8_lossy(bytes));
+    }
+    assert_eq!(OtherOutput::detect(&fixture("exec.log")), None);
+    assert_eq!(OtherOutput::detect(&fixture("exec.log.zst")), None);
+}
@@ -0,0 +1,279 @@
+//! Synthetic logs shaped like those of a Windows (MSVC) build: drive-letter tool paths, MSVC `/option`
+//! arguments, `.exe`, `.dll` and `.lib` outputs and an `x64_windows-*` output directory. The
+//! fixtures hold the same ten spawns in the verbose and the compact format.
+//! See `tests/fixtures/windows/README.md` for what the synthetic fixtures were modeled on.
+
+use bzl_exec_log_parser::filters::normalize_path;
+use bzl_exec_log_parser::findings::hermeticity_findings;
+use bzl_exec_log_parser::parsers::{parse_log_file, LogFormat};
+use bzl_exec_log_parser::proto::SpawnExec;
+use bzl_exec_log_parser::rows::spawn_id;
+use bzl_exec_log_parser::writer::write_spawns;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/windows").join(name)
+}
+
+/// Runs the analyzer and returns its standard output.
+fn run(args: &[&str]) -> String {
+    let output =
+        Command::new(env!("CARGO_BIN_EXE_bzl-exec-log-analyzer")).args(args).output().expect("the analyzer runs");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Runs the analyzer and returns its JSON output.
+fn analyze(args: &[&str]) -> Value {
+    serde_json::from_str(&run(&[args, &["--output", "json"]].concat())).expect("the output is JSON")
+}
+
+/// Every `spawn_id` anywhere in a JSON value.
+fn spawn_ids(value: &Value, ids: &mut BTreeSet<String>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                match field.as_str() {
+                    Some(id) if key == "spawn_id" => {
+                        ids.insert(id.to_string());
+                    }
+                    _ => spawn_ids(field, ids),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| spawn_ids(item, ids)),
+        _ => {}
+    }
+}
+
+/// The spawn IDs of `export ndjson`, one per spawn.
+fn exported_spawn_ids(log: &Path) -> BTreeSet<String> {
+    let mut ids = BTreeSet::new();
+    for line in run(&["export", "ndjson", log.to_str().unwrap()]).lines() {
+        spawn_ids(&serde_json::from_str(line).unwrap(), &mut ids);
+    }
+    ids
+}
+
+fn section<'a>(report: &'a Value, id: &str) -> &'a Value {
+    report["sections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|section| section["id"] == id)
+        .unwrap_or_else(|| panic!("no section {}", id))
+}
+
+/// The values of one column of a section's first table.
+fn column<'a>(report: &'a Value, section_id: &str, key: &str) -> Vec<&'a str> {
+    section(report, section_id)["tables"][0]["rows"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row[key].as_str().unwrap_or_default())
+        .collect()
+}
+
+#[test]
+fn verbose_and_compact_logs_parse_alike() {
+    let mut verbose = parse_log_file(&fixture("exec.log")).unwrap();
+    let compact = parse_log_file(&fixture("exec.log.zst")).unwrap();
+    assert_eq!(verbose.len(), 10);
+    // The compact format records only the produced outputs.
+    for spawn in &mut verbose {
+        spawn.listed_outputs.clear();
+    }
+    assert_eq!(verbose, compact);
+}
+
+#[test]
+fn paths_normalize_to_the_linux_form() {
+    assert_eq!(
+        normalize_path(r"bazel-out\x64_windows-fastbuild\bin\app\hello.exe"),
+        "bazel-out/fastbuild/bin/app/hello.exe"
+    );
+    assert_eq!(
+        normalize_path("bazel-out/x64_windows-opt-exec-ST-d57f47055a04/bin/tools/gen.obj"),
+        normalize_path("bazel-out/k8-opt-exec-ST-d57f47055a04/bin/tools/gen.obj")
+    );
+    assert_eq!(normalize_path(r"C:\Users\builder\sdk"), "C:/Users/builder/sdk");
+}
+
+/// `spawn` with its output directories renamed from `x64_windows-` to `<cpu>-`.
+fn for_cpu(mut spawn: SpawnExec, cpu: &str) -> SpawnExec {
+    for file in &mut spawn.actual_outputs {
+        file.path = file.path.replace("x64_windows-", &format!("{}-", cpu));
+    }
+    for path in &mut spawn.listed_outputs {
+        *path = path.replace("x64_windows-", &format!("{}-", cpu));
+    }
+    spawn
+}
+
+fn write_log(name: &str, spawns: &[SpawnExec]) -> PathBuf {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.log", name));
+    fs::write(&path, write_spawns(spawns, LogFormat::Verbose, Vec::new()).unwrap()).unwrap();
+    path
+}
+
+/// The Windows fixture as if built with the output directories of `cpu`, written as a verbose log.
+fn with_cpu(cpu: &str) -> PathBuf {
+    let spawns: Vec<SpawnExec> =
+        parse_log_file(&fixture("exec.log")).unwrap().into_iter().map(|spawn| for_cpu(spawn, cpu)).collect();
+    write_log(&format!("windows_as_{}", cpu), &spawns)
+}
+
+/// The spawn IDs the analyzer reports for a log, in its JSON output.
+fn reported_spawn_ids(log: &Path, extra: &[&str]) -> BTreeSet<String> {
+    let mut ids = BTreeSet::new();
+    spawn_ids(&analyze(&[&[log.to_str().unwrap(), "--top-n", "100"], extra].concat()), &mut ids);
+    ids
+}
+
+#[test]
+fn spawn_ids_do_not_depend_on_the_build_machine() {
+    let windows = exported_spawn_ids(&fixture("exec.log"));
+    let linux = with_cpu("k8");
+    assert_eq!(exported_spawn_ids(&linux), windows);
+    assert_eq!(reported_spawn_ids(&linux, &[]), windows);
+    let spawn = |log: &Path| parse_log_file(log).unwrap().swap_remove(4);
+    assert_eq!(spawn_id(&spawn(&linux)), spawn_id(&spawn(&fixture("exec.log"))));
+}
+
+#[test]
+fn spawn_ids_tell_cpus_apart() {
+    let arm64 = with_cpu("android-arm64-v8a");
+    let x86_64 = with_cpu("android-x86_64");
+    let exported = exported_spawn_ids(&arm64);
+    // Only //gen:config, which records no outputs, has the same ID for both CPUs.
+    assert_eq!(exported.intersection(&exported_spawn_ids(&x86_64)).count(), 1);
+    assert_eq!(reported_spawn_ids(&arm64, &[]), exported);
+    assert_eq!(reported_spawn_ids(&arm64, &["--raw-paths"]), exported);
+    // Grouping across logs still folds them together.
+    let output = |log: &Path| parse_log_file(log).unwrap()[4].actual_outputs[0].path.clone();
+    assert_eq!(normalize_path(&output(&arm64)), normalize_path(&output(&x86_64)));
+}
+
+#[test]
+fn show_finds_actions_by_the_spawn_ids_of_reports_and_exports() {
+    let log = with_cpu("android-arm64-v8a");
+    for id in reported_spawn_ids(&log, &[]) {
+        let report = analyze(&["show", log.to_str().unwrap(), &id]);
+        assert_eq!(report["metadata"]["matches"], 1, "{}", id);
+    }
+}
+
+#[test]
+fn reports_and_exports_give_the_same_spawn_ids() {
+    for log in ["exec.log", "exec.log.zst"] {
+        let path = fixture(log);
+        let exported = exported_spawn_ids(&path);
+        assert_eq!(exported.len(), 10);
+        for paths in [None, Some("--raw-paths")] {
+            let args = [&[path.to_str().unwrap(), "--top-n", "100"][..], paths.as_slice()].concat();
+            let mut reported = BTreeSet::new();
+            spawn_ids(&analyze(&args), &mut reported);
+            assert_eq!(reported, exported, "{} {:?}", log, paths);
+        }
+    }
+}
+
+#[test]
+fn msvc_options_are_not_absolute_paths() {
+    let spawns = parse_log_file(&fixture("exec.log")).unwrap();
+    let absolute: Vec<_> = hermeticity_findings(&spawns)
+        .into_iter()
+        .filter(|finding| finding.rule.id == "hermeticity/absolute-path")
+        .collect();
+    assert_eq!(absolute.len(), 1, "only the /I include directory under C:\\Users is machine-specific");
+    assert_eq!(absolute[0].target_label, "//app:hello");
+    assert!(absolute[0].message.contains(r"C:\Users\builder\sdk\include"));
+}
+
+#[test]
+fn configurations_fold_the_host_cpu() {
+    for log in ["exec.log", "exec.log.zst"] {
+        let report = analyze(&[fixture(log).to_str().unwrap(), "--only", "configurations"]);
+        let configurations = column(&report, "configurations", "configuration");
+        assert!(configurations.contains(&"x86_64-fastbuild"), "{:?}", configurations);
+        assert!(configurations.contains(&"x86_64-opt-exec-ST-d57f47055a04"), "{:?}", configurations);
+
+        let raw = analyze(&[fixture(log).to_str().unwrap(), "--only", "configurations", "--raw-paths"]);
+        assert!(column(&raw, "configurations", "configuration").contains(&"x64_windows-fastbuild"));
+    }
+
+    // The same build on a Linux machine lands in the same configurations, so nothing is duplicated.
+    let spawns = parse_log_file(&fixture("exec.log")).unwrap();
+    let linux = spawns.iter().map(|spawn| for_cpu(spawn.clone(), "k8"));
+    let both: Vec<SpawnExec> = spawns.iter().cloned().chain(linux).collect();
+    let report = analyze(&[write_log("windows_and_linux", &both).to_str().unwrap(), "--only", "configurations"]);
+    let configurations = column(&report, "configurations", "configuration");
+    assert_eq!(configurations.len(), 3, "{:?}", configurations);
+    assert!(section(&report, "cross_configuration_duplicates")["tables"].as_array().is_none_or(Vec::is_empty));
+}
+
+#[test]
+fn targets_built_for_two_cpus_are_duplicates() {
+    // The target configuration built for two Android CPUs, the tools once for the host.
+    let mut spawns = Vec::new();
+    for spawn in parse_log_file(&fixture("exec.log")).unwrap() {
+        if spawn.actual_outputs.iter().any(|file| file.path.contains("x64_windows-fastbuild")) {
+            spawns.push(for_cpu(spawn.clone(), "android-arm64-v8a"));
+            spawns.push(for_cpu(spawn, "android-x86_64"));
+        } else {
+            spawns.push(spawn);
+        }
+    }
+    let report = analyze(&[write_log("two_android_cpus", &spawns).to_str().unwrap(), "--only", "configurations"]);
+    let configurations = column(&report, "configurations", "configuration");
+    for name in ["android-arm64-v8a-fastbuild", "android-x86_64-fastbuild", "x86_64-opt-exec-ST-d57f47055a04"] {
+        assert!(configurations.contains(&name), "{} missing from {:?}", name, configurations);
+    }
+    let targets = column(&report, "cross_configuration_duplicates", "target");
+    assert!(targets.contains(&"//app:hello"), "{:?}", targets);
+    for row in section(&report, "cross_configuration_duplicates")["tables"][0]["rows"].as_array().unwrap() {
+        assert_eq!(row["configurations"], "android-arm64-v8a-fastbuild, android-x86_64-fastbuild");
+    }
+}
+
+#[test]
+fn rule_classes_recognize_windows_outputs() {
+    let report = analyze(&[fixture("exec.log").to_str().unwrap(), "--only", "rule-classes"]);
+    let classes = column(&report, "rule_classes", "rule_class");
+    for class in [
+        "cc_binary / cc_test (link)",
+        "cc_library / cc_shared_library (shared)",
+        "cc_library (static archive)",
+    ] {
+        assert!(classes.contains(&class), "{} missing from {:?}", class, classes);
+    }
+}
+
+#[test]
+fn genrules_run_by_msys_bash_are_audited() {
+    let report = analyze(&[fixture("exec.log").to_str().unwrap(), "--only", "genrule-audit"]);
+    assert_eq!(section(&report, "genrule_audit")["fields"]["actions"], 2);
+    let patterns = column(&report, "genrule_audit", "pattern");
+    assert!(patterns.contains(&"timestamp"), "{:?}", patterns);
+    assert!(patterns.contains(&"absolute path"), "{:?}", patterns);
+}
+
+#[test]
+fn packages_group_windows_actions() {
+    let report = analyze(&[fixture("exec.log").to_str().unwrap(), "--only", "package-mnemonics"]);
+    let packages = column(&report, "packages", "package");
+    assert_eq!(packages.first(), Some(&"//app"));
+    assert!(packages.contains(&"//lib"));
+}
+
+#[test]
+fn show_finds_outputs_by_linux_or_windows_path() {
+    for pattern in [r"bazel-out\x64_windows-fastbuild\bin\app\hello.exe", "bazel-out/k8-fastbuild/bin/app/hello.exe"] {
+        let report = analyze(&["show", fixture("exec.log").to_str().unwrap(), pattern]);
+        assert_eq!(report["metadata"]["matches"], 1, "{}", pattern);
+    }
+}